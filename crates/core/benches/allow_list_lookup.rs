@@ -0,0 +1,93 @@
+//! Measures the time taken to filter the `DATA` messages of a log with a subscription allow list.
+//!
+//! The log is synthetic, with many small messages spread across many subscriptions, so that the
+//! per-message cost of consulting the allow list is as visible as possible.
+//!
+//! Run with `cargo bench --bench allow_list_lookup`.
+
+use std::time::Instant;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const MAGIC: [u8; 7] = [b'U', b'L', b'o', b'g', 0x01, 0x12, 0x35];
+const SUBSCRIPTIONS: u16 = 200;
+const MESSAGES: usize = 2_000_000;
+const RUNS: usize = 5;
+
+fn push_message(bytes: &mut Vec<u8>, msg_type: u8, payload: &[u8]) {
+    bytes.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    bytes.push(msg_type);
+    bytes.extend_from_slice(payload);
+}
+
+/// Builds a log with `SUBSCRIPTIONS` topics, and `MESSAGES` data messages cycling through them.
+fn build_log() -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(1);
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    push_message(&mut bytes, b'B', &[0; 40]);
+
+    for i in 0..SUBSCRIPTIONS {
+        push_message(
+            &mut bytes,
+            b'F',
+            format!("topic_{i}:uint64_t timestamp;uint8_t value;").as_bytes(),
+        );
+    }
+
+    for i in 0..SUBSCRIPTIONS {
+        let mut payload = vec![0];
+        // Spread the msg_ids out, as loggers with many topics do.
+        payload.extend_from_slice(&(i * 7).to_le_bytes());
+        payload.extend_from_slice(format!("topic_{i}").as_bytes());
+        push_message(&mut bytes, b'A', &payload);
+    }
+
+    for i in 0..MESSAGES {
+        let msg_id = (i % usize::from(SUBSCRIPTIONS)) as u16 * 7;
+        let mut payload = msg_id.to_le_bytes().to_vec();
+        payload.extend_from_slice(&(i as u64).to_le_bytes());
+        payload.push(i as u8);
+        push_message(&mut bytes, b'D', &payload);
+    }
+
+    bytes
+}
+
+fn main() -> Result<(), ULogError> {
+    let bytes = build_log();
+    // Allow every tenth topic, so most messages are rejected by the filter.
+    let allowed: Vec<String> = (0..SUBSCRIPTIONS)
+        .step_by(10)
+        .map(|i| format!("topic_{i}"))
+        .collect();
+
+    let mut best_ms = f64::MAX;
+    let mut decoded = 0;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+
+        decoded = 0;
+        let parser = ULogParserBuilder::new(bytes.as_slice())
+            .set_subscription_allow_list(allowed.iter().cloned())
+            .build()?;
+        for msg_res in parser {
+            if let UlogMessage::LoggedData(_) = msg_res? {
+                decoded += 1;
+            }
+        }
+
+        best_ms = best_ms.min(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    println!("messages: {MESSAGES}, decoded: {decoded}");
+    println!(
+        "best of {RUNS}: {best_ms:.2} ms ({:.1} ns per message)",
+        best_ms * 1e6 / MESSAGES as f64
+    );
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Compares the number of heap allocations, and the time taken, to decode every `LoggedData`
+//! message of a log with the heap parser and with `ULogParser::for_each_arena_batch()`.
+//!
+//! Run with `cargo bench --features arena --bench arena_allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SAMPLE_LOG: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../tests/fixtures/test_data/input/sample_log_small.ulg"
+);
+
+fn builder() -> Result<ULogParserBuilder<BufReader<File>>, ULogError> {
+    Ok(ULogParserBuilder::new(BufReader::new(File::open(
+        SAMPLE_LOG,
+    )?)))
+}
+
+/// Runs `f`, returning its result with the number of allocations made and the elapsed time.
+fn measure<T>(f: impl FnOnce() -> Result<T, ULogError>) -> Result<(T, usize, f64), ULogError> {
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f()?;
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    Ok((
+        result,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations_before,
+        elapsed,
+    ))
+}
+
+fn main() -> Result<(), ULogError> {
+    let (heap_records, heap_allocations, heap_ms) = measure(|| {
+        let mut count = 0usize;
+        for msg_res in builder()?.build()? {
+            if let UlogMessage::LoggedData(_) = msg_res? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    })?;
+
+    let (arena_records, arena_allocations, arena_ms) = measure(|| {
+        let mut count = 0usize;
+        builder()?.build()?.for_each_arena_batch(|records| {
+            count += records.len();
+            Ok(())
+        })?;
+        Ok(count)
+    })?;
+
+    assert_eq!(heap_records, arena_records);
+
+    println!("records decoded: {heap_records}");
+    println!("heap:  {heap_allocations:>9} allocations, {heap_ms:>8.2} ms");
+    println!("arena: {arena_allocations:>9} allocations, {arena_ms:>8.2} ms");
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Extraction of the actuator outputs as an `ndarray` matrix, for ESC and motor analysis.
+
+use std::io::Read;
+
+use ndarray::Array2;
+
+use crate::errors::ULogError;
+use crate::model::inst::FieldValue;
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// The topic holding the values sent to the actuators.
+const ACTUATOR_OUTPUTS_TOPIC: &str = "actuator_outputs";
+
+impl<R: Read> ULogParser<R> {
+    /// Returns the timestamps and outputs of the first instance of `actuator_outputs`, as a
+    /// `(samples × channels)` matrix with one row per record.
+    ///
+    /// The channels are the elements of the `output` field.  If the topic has a `noutputs` field,
+    /// only the channels active in any record are kept, i.e. the matrix has as many columns as the
+    /// largest `noutputs`.  Records without an `output` array are skipped.  Any allow list set on
+    /// the parser is replaced by the actuator outputs topic.
+    pub fn actuator_outputs(mut self) -> Result<(Vec<u64>, Array2<f32>), ULogError> {
+        self.set_allowed_subscription_names([ACTUATOR_OUTPUTS_TOPIC.to_string()]);
+
+        let mut timestamps = Vec::new();
+        let mut outputs: Vec<Vec<f32>> = Vec::new();
+        let mut active_channels: Option<usize> = None;
+
+        while let Some(msg_res) = self.next() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            if self.get_subscription_ref(data.msg_id)?.multi_id != 0 {
+                continue;
+            }
+
+            let Some(FieldValue::ArrayF32(output)) =
+                data.data.find_field("output").map(|f| &f.value)
+            else {
+                continue;
+            };
+
+            if let Some(count) = data
+                .data
+                .find_field("noutputs")
+                .and_then(|f| f.value.as_i64())
+            {
+                let count = usize::try_from(count).unwrap_or(0);
+                active_channels = Some(active_channels.map_or(count, |max| max.max(count)));
+            }
+
+            timestamps.push(data.timestamp);
+            outputs.push(output.to_vec());
+        }
+
+        let channels = outputs.iter().map(Vec::len).min().unwrap_or(0);
+        let channels = active_channels.map_or(channels, |active| active.min(channels));
+
+        let values = outputs
+            .iter()
+            .flat_map(|output| &output[..channels])
+            .copied()
+            .collect();
+        let matrix = Array2::from_shape_vec((outputs.len(), channels), values)
+            .map_err(|err| ULogError::InternalError(err.to_string()))?;
+
+        Ok((timestamps, matrix))
+    }
+}
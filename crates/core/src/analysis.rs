@@ -0,0 +1,844 @@
+//! Whole-file analyses which consume a `ULogParser` and summarise its contents per subscription.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::layout::{field_range, type_size};
+use crate::model::def;
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::{LoggedData, UlogMessage};
+use crate::parser::{ULogMessageType, ULogParser};
+use crate::writer::data_timestamp;
+
+/// The format definitions of a log, together with one example record per topic.
+#[derive(Debug, Clone)]
+pub struct LogPreview {
+    /// Format definitions, in the order they appear in the log.
+    pub formats: Vec<def::Format>,
+    /// The first `LoggedData` record of each subscription, keyed by subscription name.
+    pub samples: HashMap<String, LoggedData>,
+}
+
+/// The time span covered by the records of a single subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopicCoverage {
+    /// The earliest timestamp, in microseconds.
+    pub first_timestamp: u64,
+    /// The latest timestamp, in microseconds.
+    pub last_timestamp: u64,
+    /// The number of records.
+    pub count: u64,
+}
+
+/// A period in which the logger lost data because its write buffer overflowed, as found by
+/// [`ULogParser::logger_overflows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggerOverflow {
+    /// The timestamp of the record written just before the overflow, in microseconds, or 0 if
+    /// there was none.
+    pub timestamp: u64,
+    /// The duration of the data lost, in milliseconds.
+    pub duration_ms: u16,
+}
+
+/// A sample of the PX4 logger's own status, from the `logger_status` topic, as read by
+/// [`ULogParser::logger_status`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoggerStatus {
+    /// The log written: 0 for the full log, 1 for the mission log.
+    pub log_type: u8,
+    /// The backend written to: 1 for the SD card, 2 for MAVLink, 3 for both.
+    pub backend: u8,
+    pub total_written_kb: f32,
+    pub write_rate_kb_s: f32,
+    /// The number of times the write buffer overflowed so far.
+    pub dropouts: u32,
+    /// The number of messages lost between the logger and MAVLink so far.
+    pub message_gaps: u32,
+    pub buffer_used_bytes: u32,
+    pub buffer_size_bytes: u32,
+    /// The number of topics logged.
+    pub num_messages: u8,
+}
+
+impl LoggerStatus {
+    /// Returns the fraction of the write buffer in use, from 0 to 1, or `None` if the buffer
+    /// size is not known.
+    pub fn buffer_usage(&self) -> Option<f64> {
+        (self.buffer_size_bytes > 0)
+            .then(|| f64::from(self.buffer_used_bytes) / f64::from(self.buffer_size_bytes))
+    }
+}
+
+/// What a parser would decode with its current configuration, as computed by
+/// [`ULogParser::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePlan {
+    /// Every subscription in the log, sorted by name.
+    pub subscriptions: Vec<SubscriptionPlan>,
+    /// The total of the `estimated_output_bytes` of the subscriptions.
+    pub estimated_output_bytes: u64,
+}
+
+/// What a parser would decode of a single subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionPlan {
+    /// The name of the subscription, formatted as for [`ULogParser::bytes_per_subscription`].
+    pub name: String,
+    /// Whether the subscription passes the allow list, so its records will be decoded.
+    pub decoded: bool,
+    /// The top-level fields included in each record.
+    pub fields: Vec<String>,
+    /// The top-level fields which are dropped or skipped while decoding, e.g. padding.
+    pub ignored_fields: Vec<String>,
+    /// The number of records in the log.
+    pub message_count: u64,
+    /// The payload bytes of the records, as for [`ULogParser::bytes_per_subscription`].
+    pub payload_bytes: u64,
+    /// The payload bytes of the included fields, or 0 if the subscription is not decoded.
+    pub estimated_output_bytes: u64,
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Describes what the parser would decode with its current configuration, without decoding
+    /// any records.
+    ///
+    /// Like [`ULogParser::bytes_per_subscription`] this is a cheap scan of the message headers.
+    /// The allow list decides which subscriptions are decoded, and the timestamp, padding and
+    /// array size options decide which of their fields are kept.  This allows a configuration to
+    /// be tuned before committing to a full parse.
+    pub fn plan(mut self) -> Result<ParsePlan, ULogError> {
+        // Keep the allow list, but stop decoding while scanning.
+        let mut filter = self.suspend_data_decoding();
+
+        let mut subscriptions = Vec::new();
+        let mut stats_by_msg_id: HashMap<u16, (u64, u64)> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            match msg_res? {
+                UlogMessage::AddSubscription(sub) => {
+                    filter.update_ids(&sub);
+                    subscriptions.push(sub);
+                }
+                UlogMessage::Ignored {
+                    msg_type,
+                    message_contents,
+                } if matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA) => {
+                    let msg_id = u16::from_le_bytes([message_contents[0], message_contents[1]]);
+                    let stats = stats_by_msg_id.entry(msg_id).or_insert((0, 0));
+                    stats.0 += 1;
+                    stats.1 += message_contents.len() as u64 - 2;
+                }
+                _ => {}
+            }
+        }
+
+        let mut plans = Vec::new();
+        for sub in subscriptions {
+            let format = self.get_format(&sub.message_name)?;
+            let (message_count, payload_bytes) =
+                stats_by_msg_id.get(&sub.msg_id).copied().unwrap_or((0, 0));
+
+            let mut fields = Vec::new();
+            let mut ignored_fields = Vec::new();
+            let mut ignored_bytes = 0;
+
+            for field in &format.fields {
+                let ignored = if field.name.starts_with("_padding") {
+                    !self.include_padding
+                } else if field.name == "timestamp" {
+                    !self.include_timestamp
+                } else {
+                    field
+                        .r#type
+                        .array_size
+                        .zip(self.max_array_size)
+                        .is_some_and(|(size, max)| size > max)
+                };
+
+                if ignored {
+                    ignored_fields.push(field.name.clone());
+                    ignored_bytes +=
+                        type_size(&field.r#type, &self.formats, &self.opaque_type_sizes)? as u64;
+                } else {
+                    fields.push(field.name.clone());
+                }
+            }
+
+            let decoded = filter.is_allowed(sub.msg_id);
+            plans.push(SubscriptionPlan {
+                name: self.subscription_display_name(&sub),
+                decoded,
+                fields,
+                ignored_fields,
+                message_count,
+                payload_bytes,
+                estimated_output_bytes: if decoded {
+                    payload_bytes.saturating_sub(message_count * ignored_bytes)
+                } else {
+                    0
+                },
+            });
+        }
+
+        plans.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(ParsePlan {
+            estimated_output_bytes: plans.iter().map(|plan| plan.estimated_output_bytes).sum(),
+            subscriptions: plans,
+        })
+    }
+
+    /// Sums the payload bytes of the `LoggedData` messages of each subscription.
+    ///
+    /// The payload excludes the 3 byte message header and the 2 byte `msg_id`.  Messages are not
+    /// decoded, so this is a cheap scan even for large files.  Keys are the topic names, suffixed
+    /// with the `multi_id` (e.g. `actuator_outputs.01`) for topics logged with several instances.
+    pub fn bytes_per_subscription(mut self) -> Result<HashMap<String, u64>, ULogError> {
+        // Ignore every subscription, so LoggedData arrives as raw bytes without being decoded.
+        self.set_allowed_subscription_names(Vec::new());
+        self.drop_ignored = false;
+
+        let mut bytes_per_msg_id: HashMap<u16, u64> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::Ignored {
+                msg_type,
+                message_contents,
+            } = msg_res?
+            {
+                if !matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA) {
+                    continue;
+                }
+
+                let msg_id = u16::from_le_bytes([message_contents[0], message_contents[1]]);
+                *bytes_per_msg_id.entry(msg_id).or_insert(0) += message_contents.len() as u64 - 2;
+            }
+        }
+
+        self.per_subscription(bytes_per_msg_id)
+    }
+
+    /// Computes the earliest and latest timestamps, and the number of records, of each
+    /// subscription in a single pass.
+    ///
+    /// Keys are formatted as for [`ULogParser::bytes_per_subscription`].
+    pub fn coverage(mut self) -> Result<HashMap<String, TopicCoverage>, ULogError> {
+        let mut coverage_by_msg_id: HashMap<u16, TopicCoverage> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::LoggedData(data) = msg_res? {
+                coverage_by_msg_id
+                    .entry(data.msg_id)
+                    .and_modify(|coverage| {
+                        coverage.first_timestamp = coverage.first_timestamp.min(data.timestamp);
+                        coverage.last_timestamp = coverage.last_timestamp.max(data.timestamp);
+                        coverage.count += 1;
+                    })
+                    .or_insert(TopicCoverage {
+                        first_timestamp: data.timestamp,
+                        last_timestamp: data.timestamp,
+                        count: 1,
+                    });
+            }
+        }
+
+        self.per_subscription(coverage_by_msg_id)
+    }
+
+    /// Computes the jitter of each subscription: the standard deviation, in microseconds, of the
+    /// intervals between consecutive records.
+    ///
+    /// The intervals are accumulated in a single pass using Welford's algorithm, so no timestamps
+    /// are retained.  Subscriptions with fewer than two records have no intervals and are not
+    /// included.  Keys are formatted as for [`ULogParser::bytes_per_subscription`].
+    pub fn jitter(mut self) -> Result<HashMap<String, f64>, ULogError> {
+        let mut intervals_by_msg_id: HashMap<u16, IntervalStats> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::LoggedData(data) = msg_res? {
+                intervals_by_msg_id
+                    .entry(data.msg_id)
+                    .or_default()
+                    .push(data.timestamp);
+            }
+        }
+
+        let jitter_by_msg_id = intervals_by_msg_id
+            .into_iter()
+            .filter(|(_, stats)| stats.count > 0)
+            .map(|(msg_id, stats)| (msg_id, stats.std_dev()))
+            .collect();
+
+        self.per_subscription(jitter_by_msg_id)
+    }
+
+    /// Returns the subscription with the most records, e.g. to choose a primary clock.
+    ///
+    /// Ties are broken by the name which sorts first, so the result is deterministic.  Returns
+    /// `None` if the log has no records.  The name is formatted as for
+    /// [`ULogParser::bytes_per_subscription`].
+    pub fn highest_rate_subscription(self) -> Result<Option<String>, ULogError> {
+        let coverage = self.coverage()?;
+
+        Ok(coverage
+            .into_iter()
+            .max_by(|(name_a, a), (name_b, b)| a.count.cmp(&b.count).then(name_b.cmp(name_a)))
+            .map(|(name, _)| name))
+    }
+
+    /// Lists the topics whose format has the field `field_name`, e.g. every topic with a
+    /// `timestamp`, sorted by name.
+    ///
+    /// `field_name` may be a `/` separated path into a nested format, e.g. `accel/x`.  Only the
+    /// formats are examined, so records are skipped without being decoded.  A topic logged with
+    /// several instances is listed once.
+    pub fn topics_with_field(mut self, field_name: &str) -> Result<Vec<String>, ULogError> {
+        let mut topics = Vec::new();
+        for topic in self.subscribed_topics()? {
+            let Some(format) = self.formats.get(&topic) else {
+                continue;
+            };
+            if field_range(format, field_name, &self.formats, &self.opaque_type_sizes)?.is_some() {
+                topics.push(topic);
+            }
+        }
+
+        Ok(topics)
+    }
+
+    /// Returns a minimal set of topics which together have every field of `fields`, to pass to
+    /// `set_subscription_allow_list()`.
+    ///
+    /// Fields are named as for [`ULogParser::topics_with_field`], and fields which no topic has
+    /// are left out.  Topics are picked greedily, by the most fields not yet covered, and those
+    /// whose fields are all covered by the others are then dropped, so no topic of the set can
+    /// be removed without losing a field.
+    pub fn subscriptions_for_fields<S: AsRef<str>>(
+        mut self,
+        fields: &[S],
+    ) -> Result<HashSet<String>, ULogError> {
+        // The indices of the requested fields of each topic which has any.
+        let mut candidates: Vec<(String, BTreeSet<usize>)> = Vec::new();
+        for topic in self.subscribed_topics()? {
+            let Some(format) = self.formats.get(&topic) else {
+                continue;
+            };
+
+            let mut covered = BTreeSet::new();
+            for (index, field) in fields.iter().enumerate() {
+                if field_range(
+                    format,
+                    field.as_ref(),
+                    &self.formats,
+                    &self.opaque_type_sizes,
+                )?
+                .is_some()
+                {
+                    covered.insert(index);
+                }
+            }
+            if !covered.is_empty() {
+                candidates.push((topic, covered));
+            }
+        }
+
+        let mut uncovered: BTreeSet<usize> = candidates
+            .iter()
+            .flat_map(|(_, covered)| covered.iter().copied())
+            .collect();
+        let mut chosen: Vec<(String, BTreeSet<usize>)> = Vec::new();
+
+        while !uncovered.is_empty() {
+            // Ties go to the first topic by name, so the result is deterministic.
+            let mut best = 0;
+            let mut best_count = 0;
+            for (index, (_, covered)) in candidates.iter().enumerate() {
+                let count = covered.intersection(&uncovered).count();
+                if count > best_count {
+                    best = index;
+                    best_count = count;
+                }
+            }
+
+            let (topic, covered) = candidates.remove(best);
+            uncovered.retain(|index| !covered.contains(index));
+            chosen.push((topic, covered));
+        }
+
+        let mut index = 0;
+        while index < chosen.len() {
+            let redundant = chosen[index].1.iter().all(|field| {
+                chosen
+                    .iter()
+                    .enumerate()
+                    .any(|(other, (_, covered))| other != index && covered.contains(field))
+            });
+
+            if redundant {
+                chosen.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        Ok(chosen.into_iter().map(|(topic, _)| topic).collect())
+    }
+
+    /// Reads the rest of the log without decoding any records, and returns the names of the
+    /// topics subscribed to.
+    fn subscribed_topics(&mut self) -> Result<BTreeSet<String>, ULogError> {
+        self.set_allowed_subscription_names(Vec::new());
+        self.drop_ignored = true;
+
+        let mut subscribed: BTreeSet<String> = BTreeSet::new();
+        for msg_res in self.by_ref() {
+            if let UlogMessage::AddSubscription(sub) = msg_res? {
+                subscribed.insert(sub.message_name);
+            }
+        }
+
+        Ok(subscribed)
+    }
+
+    /// Reads the format definitions and the first record of every topic.
+    ///
+    /// Parsing stops as soon as every subscription seen so far has a record, without reading the
+    /// remainder of the log.  Subscriptions which are only added after that point are therefore
+    /// not included.  Keys are formatted as for [`ULogParser::bytes_per_subscription`].
+    pub fn preview(mut self) -> Result<LogPreview, ULogError> {
+        let mut formats = Vec::new();
+        let mut samples_by_msg_id: HashMap<u16, LoggedData> = HashMap::new();
+        let mut subscribed: HashSet<u16> = HashSet::new();
+
+        for msg_res in self.by_ref() {
+            match msg_res? {
+                UlogMessage::FormatDefinition(format) => formats.push(format),
+                UlogMessage::AddSubscription(sub) => {
+                    subscribed.insert(sub.msg_id);
+                }
+                UlogMessage::LoggedData(data) => {
+                    samples_by_msg_id.entry(data.msg_id).or_insert(data);
+
+                    if samples_by_msg_id.len() == subscribed.len() {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(LogPreview {
+            formats,
+            samples: self.per_subscription(samples_by_msg_id)?,
+        })
+    }
+
+    /// Finds the overflows of the logger, in the order they were logged.
+    ///
+    /// When the PX4 logger cannot write its buffer out quickly enough it drops data, and logs a
+    /// `DROPOUT` message with the duration of the gap.  These messages carry no timestamp, so
+    /// each overflow is placed at the timestamp of the record written just before it, which
+    /// allows gaps in the data to be correlated with logger saturation.
+    pub fn logger_overflows(mut self) -> Result<Vec<LoggerOverflow>, ULogError> {
+        let mut overflows = Vec::new();
+        let mut last_timestamp = 0;
+
+        for msg_res in self.by_ref() {
+            match msg_res? {
+                UlogMessage::LoggedData(data) => last_timestamp = data.timestamp,
+                UlogMessage::DropoutMark(dropout) => overflows.push(LoggerOverflow {
+                    timestamp: last_timestamp,
+                    duration_ms: dropout.duration(),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(overflows)
+    }
+
+    /// Reads the status of the logger over time from the `logger_status` topic, as
+    /// `(timestamp, status)` pairs in the order they were logged.
+    ///
+    /// The buffer usage shows how close the logger came to overflowing, ahead of the dropouts
+    /// found by [`ULogParser::logger_overflows`].  The full and mission logs report separately,
+    /// distinguished by `log_type`.  Fields which older PX4 releases do not log are 0.  Only
+    /// `logger_status` is decoded, overriding any allow list the parser was built with.
+    pub fn logger_status(mut self) -> Result<Vec<(u64, LoggerStatus)>, ULogError> {
+        self.set_allowed_subscription_names(["logger_status".to_string()]);
+
+        let mut statuses = Vec::new();
+
+        for msg_res in self.by_ref() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            let integer = |name: &str| {
+                data.data
+                    .find_field(name)
+                    .and_then(|field| field.value.as_i64())
+            };
+            let u8_field = |name: &str| integer(name).and_then(|v| u8::try_from(v).ok());
+            let u32_field = |name: &str| integer(name).and_then(|v| u32::try_from(v).ok());
+            #[allow(clippy::cast_possible_truncation)]
+            let f32_field = |name: &str| {
+                data.data
+                    .find_field(name)
+                    .and_then(|field| field.value.as_f64())
+                    .map(|v| v as f32)
+            };
+
+            statuses.push((
+                data.timestamp,
+                LoggerStatus {
+                    log_type: u8_field("type").unwrap_or_default(),
+                    backend: u8_field("backend").unwrap_or_default(),
+                    total_written_kb: f32_field("total_written_kb").unwrap_or_default(),
+                    write_rate_kb_s: f32_field("write_rate_kb_s").unwrap_or_default(),
+                    dropouts: u32_field("dropouts").unwrap_or_default(),
+                    message_gaps: u32_field("message_gaps").unwrap_or_default(),
+                    buffer_used_bytes: u32_field("buffer_used_bytes").unwrap_or_default(),
+                    buffer_size_bytes: u32_field("buffer_size_bytes").unwrap_or_default(),
+                    num_messages: u8_field("num_messages").unwrap_or_default(),
+                },
+            ));
+        }
+
+        Ok(statuses)
+    }
+
+    /// Returns the entries of `expected` which are not logged as a `Parameter` anywhere in the
+    /// log, in the order given.
+    ///
+    /// This is a completeness check, e.g. that a log records every parameter a tuning tool
+    /// relies on.  Parameters changed in flight count as logged, while those only listed with a
+    /// `DefaultParameter` do not.  Records are not decoded, so this is a cheap scan.
+    pub fn missing_parameters(mut self, expected: &[&str]) -> Result<Vec<String>, ULogError> {
+        self.set_allowed_subscription_names(Vec::new());
+
+        for msg_res in self.by_ref() {
+            msg_res?;
+        }
+
+        Ok(expected
+            .iter()
+            .filter(|key| !self.parameters().contains_key(**key))
+            .map(|key| (*key).to_string())
+            .collect())
+    }
+
+    /// Counts the messages of each type in consecutive time buckets of `bucket_us` microseconds,
+    /// e.g. to plot how the logging rate of each kind of message changes over a flight.
+    ///
+    /// Buckets are keyed by their start time, and message types by their type byte, e.g. `b'D'`
+    /// for records.  The timestamps of the records are the clock: every other message is counted
+    /// in the bucket of the record before it, and those before the first record in its bucket.
+    /// Buckets without messages are left out.  Records are not decoded, so this is a cheap scan.
+    /// Returns `ULogError::InvalidConfiguration` if `bucket_us` is 0.
+    pub fn type_timeline(
+        mut self,
+        bucket_us: u64,
+    ) -> Result<BTreeMap<u64, BTreeMap<u8, u64>>, ULogError> {
+        if bucket_us == 0 {
+            return Err(ULogError::InvalidConfiguration(
+                "the bucket width must be positive".to_string(),
+            ));
+        }
+
+        // Records are only needed for their timestamps, which `Ignored` records keep.
+        self.set_allowed_subscription_names(Vec::new());
+        self.drop_ignored = false;
+
+        let mut timeline: BTreeMap<u64, BTreeMap<u8, u64>> = BTreeMap::new();
+        // The counts of the messages before the first record.
+        let mut leading: BTreeMap<u8, u64> = BTreeMap::new();
+        let mut bucket = None;
+
+        while let Some(msg_res) = self.next() {
+            let msg = msg_res?;
+            let Some(type_byte) = msg.type_byte() else {
+                continue;
+            };
+
+            let timestamp =
+                data_timestamp(&msg, &self.formats, &self.opaque_type_sizes, |msg_id| {
+                    self.subscriptions
+                        .get(&msg_id)
+                        .map(|sub| sub.message_name.as_str())
+                });
+            if let Some(timestamp) = timestamp {
+                let start = timestamp - timestamp % bucket_us;
+                if bucket.is_none() {
+                    timeline.insert(start, std::mem::take(&mut leading));
+                }
+                bucket = Some(start);
+            }
+
+            let counts = match bucket {
+                Some(start) => timeline.entry(start).or_default(),
+                None => &mut leading,
+            };
+            *counts.entry(type_byte).or_insert(0) += 1;
+        }
+
+        // A log without records has a single bucket.
+        if bucket.is_none() && !leading.is_empty() {
+            timeline.insert(0, leading);
+        }
+
+        Ok(timeline)
+    }
+
+    /// Counts how often each of `fields` holds the smallest or largest value of its type, which
+    /// for a sensor reading repeatedly suggests it is saturated.
+    ///
+    /// Fields are given as the subscription, named as for
+    /// [`ULogParser::bytes_per_subscription`], followed by the `/` separated path of the field,
+    /// e.g. `sensor_accel.01/x`, and the counts are keyed the same way.  Each element of an array
+    /// field is counted separately.  Floats count at `f32::MIN` and `f32::MAX` (or their `f64`
+    /// equivalents), while bools, chars and fields never logged count 0.  Only the named
+    /// subscriptions are decoded, overriding any allow list the parser was built with.
+    pub fn saturation_report(mut self, fields: &[&str]) -> Result<HashMap<String, u64>, ULogError> {
+        let targets = fields
+            .iter()
+            .map(|field| {
+                field.split_once('/').ok_or_else(|| {
+                    ULogError::InvalidConfiguration(format!(
+                        "{field} does not name a subscription and a field"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.set_allowed_subscription_names(
+            targets
+                .iter()
+                .map(|(subscription, _)| topic_name(subscription).to_string()),
+        );
+
+        let mut counts: HashMap<String, u64> = fields
+            .iter()
+            .map(|field| ((*field).to_string(), 0))
+            .collect();
+        let mut names_by_msg_id: HashMap<u16, String> = HashMap::new();
+
+        while let Some(msg_res) = self.next() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            let name = match names_by_msg_id.entry(data.msg_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let sub = self.get_subscription(data.msg_id)?;
+                    entry.insert(self.subscription_display_name(&sub))
+                }
+            };
+
+            for (field, (subscription, path)) in fields.iter().zip(&targets) {
+                if *subscription != name.as_str() {
+                    continue;
+                }
+                if let Some(found) = data.data.find_field(path) {
+                    // Unwrap is safe: every field has an entry.
+                    *counts.get_mut(*field).unwrap() += saturated_count(&found.value);
+                }
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Counts the scalar values decoded from every record of the log, as a rough measure of its
+    /// information content.
+    ///
+    /// Each element of an array counts as a value, and nested formats count the values of their
+    /// fields.  Only the fields yielded by the parser are counted, so the `timestamp` and padding
+    /// fields count if `include_timestamp()` and `include_padding()` are set, and records outside
+    /// the allow list, or arrays skipped by `skip_arrays_larger_than()`, do not count.
+    pub fn total_scalar_samples(mut self) -> Result<u64, ULogError> {
+        let mut total = 0;
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::LoggedData(data) = msg_res? {
+                total += scalar_count(&data.data);
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Decodes the records of a single subscription, keyed by their `timestamp`.
+    ///
+    /// The subscription is named as for [`ULogParser::bytes_per_subscription`], e.g.
+    /// `actuator_outputs.01` for an instance of a multi-instance topic.  When several records
+    /// share a timestamp the one logged last is kept.  Only the named subscription is decoded,
+    /// overriding any allow list the parser was built with.
+    pub fn collect_timed(
+        mut self,
+        subscription: &str,
+    ) -> Result<BTreeMap<u64, LoggedData>, ULogError> {
+        let mut records = BTreeMap::new();
+        self.for_each_record(subscription, |data| {
+            records.insert(data.timestamp, data);
+            Ok(())
+        })?;
+
+        Ok(records)
+    }
+
+    /// Reads the rest of the log, calling `f` with each record of the subscription named as for
+    /// [`ULogParser::bytes_per_subscription`].  Only that subscription is decoded, overriding any
+    /// allow list the parser was built with.
+    pub(crate) fn for_each_record(
+        &mut self,
+        subscription: &str,
+        mut f: impl FnMut(LoggedData) -> Result<(), ULogError>,
+    ) -> Result<(), ULogError> {
+        self.set_allowed_subscription_names([topic_name(subscription).to_string()]);
+
+        let mut matches_by_msg_id: HashMap<u16, bool> = HashMap::new();
+
+        while let Some(msg_res) = self.next() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            let matches = match matches_by_msg_id.get(&data.msg_id) {
+                Some(matches) => *matches,
+                None => {
+                    let sub = self.get_subscription(data.msg_id)?;
+                    let matches = self.subscription_display_name(&sub) == subscription;
+                    matches_by_msg_id.insert(data.msg_id, matches);
+                    matches
+                }
+            };
+
+            if matches {
+                f(data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-keys a map of per-msg_id results by subscription name.  Subscriptions removed during the
+    /// log keep their name.
+    pub(crate) fn per_subscription<T>(
+        &self,
+        by_msg_id: HashMap<u16, T>,
+    ) -> Result<HashMap<String, T>, ULogError> {
+        by_msg_id
+            .into_iter()
+            .map(|(msg_id, value)| {
+                let sub = self.last_known_subscription(msg_id)?;
+                Ok((self.subscription_display_name(sub), value))
+            })
+            .collect()
+    }
+}
+
+/// Returns the topic of a subscription named as for [`ULogParser::bytes_per_subscription`], i.e.
+/// the name without any instance suffix.
+fn topic_name(subscription: &str) -> &str {
+    match subscription.rsplit_once('.') {
+        Some((name, instance)) if instance.parse::<u8>().is_ok() => name,
+        _ => subscription,
+    }
+}
+
+/// The number of values of a field, or of the elements of an array field, which are the smallest
+/// or largest value of their type.
+fn saturated_count(value: &FieldValue) -> u64 {
+    macro_rules! count {
+        ($values:expr, $ty:ty) => {
+            $values
+                .iter()
+                .filter(|v| **v == <$ty>::MIN || **v == <$ty>::MAX)
+                .count() as u64
+        };
+    }
+
+    match value {
+        FieldValue::ScalarU8(v) => count!([*v], u8),
+        FieldValue::ScalarU16(v) => count!([*v], u16),
+        FieldValue::ScalarU32(v) => count!([*v], u32),
+        FieldValue::ScalarU64(v) => count!([*v], u64),
+        FieldValue::ScalarI8(v) => count!([*v], i8),
+        FieldValue::ScalarI16(v) => count!([*v], i16),
+        FieldValue::ScalarI32(v) => count!([*v], i32),
+        FieldValue::ScalarI64(v) => count!([*v], i64),
+        FieldValue::ScalarF32(v) => count!([*v], f32),
+        FieldValue::ScalarF64(v) => count!([*v], f64),
+        FieldValue::ArrayU8(v) => count!(v, u8),
+        FieldValue::ArrayU16(v) => count!(v, u16),
+        FieldValue::ArrayU32(v) => count!(v, u32),
+        FieldValue::ArrayU64(v) => count!(v, u64),
+        FieldValue::ArrayI8(v) => count!(v, i8),
+        FieldValue::ArrayI16(v) => count!(v, i16),
+        FieldValue::ArrayI32(v) => count!(v, i32),
+        FieldValue::ArrayI64(v) => count!(v, i64),
+        FieldValue::ArrayF32(v) => count!(v, f32),
+        FieldValue::ArrayF64(v) => count!(v, f64),
+        _ => 0,
+    }
+}
+
+/// The number of scalar values in a decoded format, including those of nested formats.
+fn scalar_count(format: &inst::Format) -> u64 {
+    format
+        .fields
+        .iter()
+        .map(|field| match &field.value {
+            FieldValue::ScalarOther(nested) => scalar_count(nested),
+            FieldValue::ArrayOther(elements) => elements.iter().map(scalar_count).sum(),
+            FieldValue::ArrayU8(v) => v.len() as u64,
+            FieldValue::ArrayU16(v) => v.len() as u64,
+            FieldValue::ArrayU32(v) => v.len() as u64,
+            FieldValue::ArrayU64(v) => v.len() as u64,
+            FieldValue::ArrayI8(v) => v.len() as u64,
+            FieldValue::ArrayI16(v) => v.len() as u64,
+            FieldValue::ArrayI32(v) => v.len() as u64,
+            FieldValue::ArrayI64(v) => v.len() as u64,
+            FieldValue::ArrayF32(v) => v.len() as u64,
+            FieldValue::ArrayF64(v) => v.len() as u64,
+            FieldValue::ArrayBool(v) => v.len() as u64,
+            FieldValue::ArrayChar(v) => v.len() as u64,
+            FieldValue::Skipped(_) => 0,
+            // Scalars, and opaque values which count as one.
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Running mean and variance of the intervals between timestamps, using Welford's algorithm.
+#[derive(Default)]
+struct IntervalStats {
+    last_timestamp: Option<u64>,
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl IntervalStats {
+    fn push(&mut self, timestamp: u64) {
+        if let Some(last_timestamp) = self.last_timestamp {
+            let interval = timestamp as f64 - last_timestamp as f64;
+
+            self.count += 1;
+            let delta = interval - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (interval - self.mean);
+        }
+
+        self.last_timestamp = Some(timestamp);
+    }
+
+    fn std_dev(&self) -> f64 {
+        (self.m2 / self.count as f64).sqrt()
+    }
+}
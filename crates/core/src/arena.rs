@@ -0,0 +1,423 @@
+//! Decoding of `LoggedData` into records allocated from a bump arena.
+//!
+//! Decoding a `LoggedData` message onto the heap allocates every field name, every array, and a
+//! copy of the format definition.  For batch processing of large logs this churn dominates, so
+//! [`ULogParser::for_each_arena_batch`] instead decodes records into a bump arena which is
+//! reset after each batch.  The records borrow from the arena, so they can only be used within
+//! the callback that receives the batch.
+//!
+//! Every allocation of the records, their field lists, arrays and names, goes through the
+//! [`RecordArena`] trait, so [`ULogParser::for_each_arena_batch_in`] can decode into an allocator
+//! of the caller's choosing, such as a preallocated region on an embedded target.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use allocator_api2::alloc::Allocator;
+use allocator_api2::vec::Vec as AllocVec;
+use bumpalo::Bump;
+
+use crate::errors::ULogError;
+use crate::field_helpers::{parse_data_field, ParseFromBuf};
+use crate::message_buf::MessageBuf;
+use crate::model::{def, inst, msg};
+use crate::parser::{ULogMessageType, ULogParser};
+
+/// The default number of records per batch passed to [`ULogParser::for_each_arena_batch`].
+pub const DEFAULT_ARENA_BATCH_SIZE: usize = 1024;
+
+/// A region of memory that arena records are decoded into.
+///
+/// The records of a batch are allocated through `allocator()`, using the `Allocator` trait of
+/// the `allocator-api2` crate, the stable counterpart of `std::alloc::Allocator`.  They are not
+/// dropped or deallocated individually, so the arena must release them all at once in
+/// `reset()`, which is called before the first batch and after each batch.
+///
+/// It is implemented for `bumpalo::Bump`.
+pub trait RecordArena {
+    type Alloc<'a>: Allocator + Copy
+    where
+        Self: 'a;
+
+    /// Returns the allocator that the records of a batch are allocated from.
+    fn allocator(&self) -> Self::Alloc<'_>;
+
+    /// Releases the records of the last batch.
+    fn reset(&mut self);
+}
+
+impl RecordArena for Bump {
+    type Alloc<'a> = &'a Bump;
+
+    fn allocator(&self) -> &Bump {
+        self
+    }
+
+    fn reset(&mut self) {
+        Bump::reset(self);
+    }
+}
+
+/// A `LoggedData` message decoded into an arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaRecord<'a> {
+    pub msg_id: u16,
+    pub timestamp: u64,
+    /// The name of the message format.
+    pub name: &'a str,
+    pub fields: &'a [ArenaField<'a>],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaField<'a> {
+    pub name: &'a str,
+    pub value: ArenaValue<'a>,
+}
+
+/// The arena counterpart of `inst::FieldValue`, with arrays and nested formats as slices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+    // Typed scalars
+    ScalarU8(u8),
+    ScalarU16(u16),
+    ScalarU32(u32),
+    ScalarU64(u64),
+    ScalarI8(i8),
+    ScalarI16(i16),
+    ScalarI32(i32),
+    ScalarI64(i64),
+    ScalarF32(f32),
+    ScalarF64(f64),
+    ScalarBool(bool),
+    ScalarChar(char),
+    ScalarOther(&'a [ArenaField<'a>]),
+
+    // Typed arrays
+    ArrayU8(&'a [u8]),
+    ArrayU16(&'a [u16]),
+    ArrayU32(&'a [u32]),
+    ArrayU64(&'a [u64]),
+    ArrayI8(&'a [i8]),
+    ArrayI16(&'a [i16]),
+    ArrayI32(&'a [i32]),
+    ArrayI64(&'a [i64]),
+    ArrayF32(&'a [f32]),
+    ArrayF64(&'a [f64]),
+    ArrayBool(&'a [bool]),
+    ArrayChar(&'a [char]),
+    ArrayOther(&'a [&'a [ArenaField<'a>]]),
+}
+
+impl PartialEq<inst::FieldValue> for ArenaValue<'_> {
+    fn eq(&self, other: &inst::FieldValue) -> bool {
+        use inst::FieldValue as Heap;
+        use ArenaValue::*;
+
+        match (self, other) {
+            (ScalarU8(a), Heap::ScalarU8(b)) => a == b,
+            (ScalarU16(a), Heap::ScalarU16(b)) => a == b,
+            (ScalarU32(a), Heap::ScalarU32(b)) => a == b,
+            (ScalarU64(a), Heap::ScalarU64(b)) => a == b,
+            (ScalarI8(a), Heap::ScalarI8(b)) => a == b,
+            (ScalarI16(a), Heap::ScalarI16(b)) => a == b,
+            (ScalarI32(a), Heap::ScalarI32(b)) => a == b,
+            (ScalarI64(a), Heap::ScalarI64(b)) => a == b,
+            (ScalarF32(a), Heap::ScalarF32(b)) => a == b,
+            (ScalarF64(a), Heap::ScalarF64(b)) => a == b,
+            (ScalarBool(a), Heap::ScalarBool(b)) => a == b,
+            (ScalarChar(a), Heap::ScalarChar(b)) => a == b,
+            (ScalarOther(a), Heap::ScalarOther(b)) => *a == b.fields.as_slice(),
+            (ArrayU8(a), Heap::ArrayU8(b)) => *a == b.as_slice(),
+            (ArrayU16(a), Heap::ArrayU16(b)) => *a == b.as_slice(),
+            (ArrayU32(a), Heap::ArrayU32(b)) => *a == b.as_slice(),
+            (ArrayU64(a), Heap::ArrayU64(b)) => *a == b.as_slice(),
+            (ArrayI8(a), Heap::ArrayI8(b)) => *a == b.as_slice(),
+            (ArrayI16(a), Heap::ArrayI16(b)) => *a == b.as_slice(),
+            (ArrayI32(a), Heap::ArrayI32(b)) => *a == b.as_slice(),
+            (ArrayI64(a), Heap::ArrayI64(b)) => *a == b.as_slice(),
+            (ArrayF32(a), Heap::ArrayF32(b)) => *a == b.as_slice(),
+            (ArrayF64(a), Heap::ArrayF64(b)) => *a == b.as_slice(),
+            (ArrayBool(a), Heap::ArrayBool(b)) => *a == b.as_slice(),
+            (ArrayChar(a), Heap::ArrayChar(b)) => *a == b.as_slice(),
+            (ArrayOther(a), Heap::ArrayOther(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| *a == b.fields.as_slice())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<inst::Field> for ArenaField<'_> {
+    fn eq(&self, other: &inst::Field) -> bool {
+        self.name == other.name && self.value == other.value
+    }
+}
+
+impl PartialEq<msg::LoggedData> for ArenaRecord<'_> {
+    fn eq(&self, other: &msg::LoggedData) -> bool {
+        self.msg_id == other.msg_id
+            && self.timestamp == other.timestamp
+            && self.name == other.data.name
+            && self.fields == other.data.fields.as_slice()
+    }
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Decodes the `LoggedData` messages of the log into arena allocated records, calling `f` with
+    /// each batch of records.
+    ///
+    /// Batches hold up to `ULogParserBuilder::arena_batch_size()` records, and the arena is reset
+    /// after each call to `f`, so memory use is bounded by the size of a batch.  Other messages
+    /// are read but not passed to `f`.  Parsing stops at the first error, whether returned by
+    /// the parser or by `f`.
+    ///
+    /// Records are decoded as with the heap parser, including the subscription allow list and
+    /// the `include_timestamp()` and `include_padding()` options.  The bad timestamp policy and
+    /// `add_index_field()` are not applied.
+    pub fn for_each_arena_batch<F>(self, f: F) -> Result<(), ULogError>
+    where
+        F: FnMut(&[ArenaRecord<'_>]) -> Result<(), ULogError>,
+    {
+        self.for_each_arena_batch_in(&mut Bump::new(), f)
+    }
+
+    /// Like `for_each_arena_batch()`, but decodes the records into a caller supplied arena.
+    ///
+    /// This allows the records to be decoded into a region allocated up front, e.g. a `Bump`
+    /// created with `Bump::with_capacity()`, or a custom [`RecordArena`], which is reused for every
+    /// batch.  If the region is large enough for a batch, decoding allocates no further memory
+    /// for the records.  An allocation failure of the arena aborts, as for the global allocator.
+    pub fn for_each_arena_batch_in<A, F>(mut self, arena: &mut A, mut f: F) -> Result<(), ULogError>
+    where
+        A: RecordArena,
+        F: FnMut(&[ArenaRecord<'_>]) -> Result<(), ULogError>,
+    {
+        let batch_size = self.arena_batch_size.max(1);
+        let mut filter = self.suspend_data_decoding();
+        let mut finished = false;
+
+        arena.reset();
+
+        while !finished {
+            {
+                let alloc = arena.allocator();
+                let mut records = AllocVec::with_capacity_in(batch_size, alloc);
+
+                while records.len() < batch_size {
+                    let Some(msg_res) = self.next() else {
+                        finished = true;
+                        break;
+                    };
+
+                    match msg_res? {
+                        msg::UlogMessage::AddSubscription(sub) => filter.update_ids(&sub),
+                        msg::UlogMessage::Ignored {
+                            msg_type,
+                            message_contents,
+                        } if matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA) => {
+                            let mut message_buf = MessageBuf::from_vec(message_contents);
+                            let msg_id = message_buf.take_u16()?;
+
+                            if filter.is_allowed(msg_id) {
+                                records.push(self.decode_in_arena(msg_id, message_buf, alloc)?);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                if !records.is_empty() {
+                    f(&records)?;
+                }
+            }
+
+            arena.reset();
+        }
+
+        Ok(())
+    }
+
+    fn decode_in_arena<'a, A: Allocator + Copy + 'a>(
+        &self,
+        msg_id: u16,
+        mut message_buf: MessageBuf,
+        alloc: A,
+    ) -> Result<ArenaRecord<'a>, ULogError> {
+        let sub = self.get_subscription_ref(msg_id)?;
+        let format = self
+            .formats
+            .get(&sub.message_name)
+            .ok_or_else(|| ULogError::UndefinedFormat(sub.message_name.clone()))?;
+
+        let decoder = ArenaDecoder {
+            formats: &self.formats,
+            include_padding: self.include_padding,
+            alloc,
+        };
+
+        let mut timestamp = None;
+        let mut fields = AllocVec::with_capacity_in(format.fields.len(), alloc);
+
+        for field in decoder.decode_fields(format, &mut message_buf)? {
+            if field.name == "timestamp" {
+                match field.value {
+                    ArenaValue::ScalarU64(value) => timestamp = Some(value),
+                    ArenaValue::ScalarU32(value) => timestamp = Some(u64::from(value)),
+                    _ => {}
+                }
+
+                if !self.include_timestamp {
+                    continue;
+                }
+            }
+
+            fields.push(*field);
+        }
+
+        Ok(ArenaRecord {
+            msg_id,
+            timestamp: timestamp.ok_or(ULogError::MissingTimestamp)?,
+            name: alloc_str(&format.name, alloc),
+            fields: fields.leak(),
+        })
+    }
+}
+
+/// Copies a string into an arena.
+fn alloc_str<'a, A: Allocator + 'a>(s: &str, alloc: A) -> &'a str {
+    let mut bytes = AllocVec::with_capacity_in(s.len(), alloc);
+    bytes.extend_from_slice(s.as_bytes());
+    // SAFETY: the bytes were copied from a `str`, so they are valid UTF-8.
+    unsafe { std::str::from_utf8_unchecked(bytes.leak()) }
+}
+
+/// Decodes field values into an arena, mirroring `ULogParser::parse_data_message_sub()`.
+///
+/// The vectors are leaked into the arena, which releases them on reset.
+struct ArenaDecoder<'f, A> {
+    formats: &'f HashMap<String, def::Format>,
+    include_padding: bool,
+    alloc: A,
+}
+
+impl<'a, A: Allocator + Copy + 'a> ArenaDecoder<'_, A> {
+    fn decode_fields(
+        &self,
+        format: &def::Format,
+        message_buf: &mut MessageBuf,
+    ) -> Result<&'a [ArenaField<'a>], ULogError> {
+        let mut fields = AllocVec::with_capacity_in(format.fields.len(), self.alloc);
+
+        for field in &format.fields {
+            let value = if field.name.starts_with("_padding") {
+                match self.decode_padding(field, message_buf)? {
+                    Some(value) => value,
+                    None => continue,
+                }
+            } else {
+                self.decode_value(&field.r#type, message_buf)?
+            };
+
+            fields.push(ArenaField {
+                name: alloc_str(&field.name, self.alloc),
+                value,
+            });
+        }
+
+        Ok(fields.leak())
+    }
+
+    fn decode_padding(
+        &self,
+        field: &def::Field,
+        message_buf: &mut MessageBuf,
+    ) -> Result<Option<ArenaValue<'a>>, ULogError> {
+        let Some(array_size) = field.r#type.array_size else {
+            return Ok(None);
+        };
+
+        if array_size > message_buf.len() {
+            return Ok(None);
+        }
+
+        if self.include_padding {
+            let mut bytes = AllocVec::with_capacity_in(array_size, self.alloc);
+            bytes.extend_from_slice(message_buf.advance(array_size)?);
+            let bytes = bytes.leak();
+            Ok(Some(ArenaValue::ArrayU8(bytes)))
+        } else {
+            message_buf.skip(array_size)?;
+            Ok(None)
+        }
+    }
+
+    fn decode_value(
+        &self,
+        type_expr: &def::TypeExpr,
+        message_buf: &mut MessageBuf,
+    ) -> Result<ArenaValue<'a>, ULogError> {
+        use def::BaseType::*;
+        use ArenaValue::*;
+
+        let Some(array_size) = type_expr.array_size else {
+            return Ok(match &type_expr.base_type {
+                UINT8 => ScalarU8(parse_data_field(message_buf)?),
+                UINT16 => ScalarU16(parse_data_field(message_buf)?),
+                UINT32 => ScalarU32(parse_data_field(message_buf)?),
+                UINT64 => ScalarU64(parse_data_field(message_buf)?),
+                INT8 => ScalarI8(parse_data_field(message_buf)?),
+                INT16 => ScalarI16(parse_data_field(message_buf)?),
+                INT32 => ScalarI32(parse_data_field(message_buf)?),
+                INT64 => ScalarI64(parse_data_field(message_buf)?),
+                FLOAT => ScalarF32(parse_data_field(message_buf)?),
+                DOUBLE => ScalarF64(parse_data_field(message_buf)?),
+                BOOL => ScalarBool(parse_data_field(message_buf)?),
+                CHAR => ScalarChar(parse_data_field(message_buf)?),
+                OTHER(type_name) => {
+                    ScalarOther(self.decode_fields(self.format(type_name)?, message_buf)?)
+                }
+            });
+        };
+
+        Ok(match &type_expr.base_type {
+            UINT8 => ArrayU8(self.decode_array(array_size, message_buf)?),
+            UINT16 => ArrayU16(self.decode_array(array_size, message_buf)?),
+            UINT32 => ArrayU32(self.decode_array(array_size, message_buf)?),
+            UINT64 => ArrayU64(self.decode_array(array_size, message_buf)?),
+            INT8 => ArrayI8(self.decode_array(array_size, message_buf)?),
+            INT16 => ArrayI16(self.decode_array(array_size, message_buf)?),
+            INT32 => ArrayI32(self.decode_array(array_size, message_buf)?),
+            INT64 => ArrayI64(self.decode_array(array_size, message_buf)?),
+            FLOAT => ArrayF32(self.decode_array(array_size, message_buf)?),
+            DOUBLE => ArrayF64(self.decode_array(array_size, message_buf)?),
+            BOOL => ArrayBool(self.decode_array(array_size, message_buf)?),
+            CHAR => ArrayChar(self.decode_array(array_size, message_buf)?),
+            OTHER(type_name) => {
+                let child_format = self.format(type_name)?;
+                let mut elements = AllocVec::with_capacity_in(array_size, self.alloc);
+                for _ in 0..array_size {
+                    elements.push(self.decode_fields(child_format, message_buf)?);
+                }
+                ArrayOther(elements.leak())
+            }
+        })
+    }
+
+    fn decode_array<T: ParseFromBuf>(
+        &self,
+        array_size: usize,
+        message_buf: &mut MessageBuf,
+    ) -> Result<&'a [T], ULogError> {
+        let mut array = AllocVec::with_capacity_in(array_size, self.alloc);
+        for _ in 0..array_size {
+            array.push(parse_data_field(message_buf)?);
+        }
+        Ok(array.leak())
+    }
+
+    fn format(&self, type_name: &str) -> Result<&def::Format, ULogError> {
+        self.formats
+            .get(type_name)
+            .ok_or_else(|| ULogError::UndefinedFormat(type_name.to_owned()))
+    }
+}
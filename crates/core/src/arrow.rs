@@ -0,0 +1,353 @@
+//! Export of the records of a log into Apache Arrow record batches, e.g. for Polars or Parquet.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+use ::arrow::array::{
+    ArrayRef, BinaryBuilder, BooleanBuilder, FixedSizeListArray, Float32Builder, Float64Builder,
+    Int16Builder, Int32Builder, Int64Builder, Int8Builder, RecordBatch, RecordBatchOptions,
+    StringBuilder, StructArray, UInt16Builder, UInt32Builder, UInt64Builder, UInt8Builder,
+};
+use ::arrow::datatypes::{DataType, Field, Fields, Schema};
+use ::arrow::error::ArrowError;
+
+use crate::errors::ULogError;
+use crate::model::def::{self, BaseType};
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+impl From<ArrowError> for ULogError {
+    fn from(err: ArrowError) -> Self {
+        ULogError::InternalError(err.to_string())
+    }
+}
+
+/// Reads a log into one Arrow `RecordBatch` per subscription.
+///
+/// Batches are keyed as in [`ULogParser::bytes_per_subscription`], e.g. `actuator_outputs.01`,
+/// and only subscriptions with at least one record are included.  The schema of each batch is
+/// derived from the format definitions of the log, with one row per record:
+///
+/// - Scalar fields become columns of the matching Arrow type, e.g. `float` becomes `Float32`.
+/// - Fixed-size array fields become `FixedSizeList` columns.
+/// - Nested formats become `Struct` columns, which keep all of their fields.
+/// - `char` fields and arrays become `Utf8` columns, with trailing NULs removed.
+/// - Fields of opaque types become `Binary` columns.
+///
+/// The `timestamp` field is included as configured by `ULogParserBuilder::include_timestamp()`,
+/// while padding fields, which carry no data and may be omitted at the end of a record, are
+/// never included.  Every record is buffered until the log has been read, so memory use is
+/// proportional to the size of the log.
+pub fn to_record_batches<R: Read>(
+    mut parser: ULogParser<R>,
+) -> Result<HashMap<String, RecordBatch>, ULogError> {
+    let mut tables: HashMap<u16, TableBuilder> = HashMap::new();
+
+    while let Some(msg_res) = parser.next() {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+
+        let table = match tables.get_mut(&data.msg_id) {
+            Some(table) => table,
+            None => {
+                let sub = parser.get_subscription(data.msg_id)?;
+                let table = TableBuilder::new(
+                    parser.subscription_display_name(&sub),
+                    &parser.get_format(&sub.message_name)?,
+                    &parser,
+                );
+                tables.entry(data.msg_id).or_insert(table)
+            }
+        };
+
+        table.append(&data.data)?;
+    }
+
+    tables
+        .into_values()
+        .map(|mut table| Ok((table.name.clone(), table.finish()?)))
+        .collect()
+}
+
+/// The columns of a single subscription.
+struct TableBuilder {
+    name: String,
+    fields: Vec<Field>,
+    columns: Vec<ColumnBuilder>,
+    num_rows: usize,
+}
+
+impl TableBuilder {
+    fn new<R: Read>(name: String, format: &def::Format, parser: &ULogParser<R>) -> Self {
+        let (fields, columns) = format_columns(
+            format
+                .fields
+                .iter()
+                .filter(|field| field.name != "timestamp" || parser.include_timestamp),
+            &parser.formats,
+        );
+
+        TableBuilder {
+            name,
+            fields,
+            columns,
+            num_rows: 0,
+        }
+    }
+
+    fn append(&mut self, format: &inst::Format) -> Result<(), ULogError> {
+        append_fields(&self.fields, &mut self.columns, format)?;
+        self.num_rows += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ULogError> {
+        let columns = self.columns.iter_mut().map(ColumnBuilder::finish).collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(self.num_rows));
+
+        Ok(RecordBatch::try_new_with_options(
+            Arc::new(Schema::new(self.fields.clone())),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+/// Returns the fields and columns of the given format fields, without padding.
+fn format_columns<'a>(
+    fields: impl IntoIterator<Item = &'a def::Field>,
+    formats: &HashMap<String, def::Format>,
+) -> (Vec<Field>, Vec<ColumnBuilder>) {
+    fields
+        .into_iter()
+        .filter(|field| !is_padding(&field.name))
+        .map(|field| {
+            let column = ColumnBuilder::new(&field.r#type, formats);
+            (Field::new(&field.name, column.data_type(), false), column)
+        })
+        .unzip()
+}
+
+/// Appends the fields of a record, or of a nested format, to their columns.
+fn append_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+    columns: &mut [ColumnBuilder],
+    format: &inst::Format,
+) -> Result<(), ULogError> {
+    let values: Vec<&inst::Field> = format
+        .fields
+        .iter()
+        .filter(|field| !is_padding(&field.name))
+        .collect();
+
+    if values.len() != columns.len() {
+        return Err(ULogError::TypeMismatch(format!(
+            "format {} has {} fields, expected {}",
+            format.name,
+            values.len(),
+            columns.len()
+        )));
+    }
+
+    for ((field, column), value) in fields.into_iter().zip(columns).zip(values) {
+        if value.name != *field.name() {
+            return Err(ULogError::TypeMismatch(format!(
+                "field {} of format {}, expected {}",
+                value.name,
+                format.name,
+                field.name()
+            )));
+        }
+        column.append(&value.value)?;
+    }
+
+    Ok(())
+}
+
+enum ColumnBuilder {
+    UInt8(UInt8Builder),
+    UInt16(UInt16Builder),
+    UInt32(UInt32Builder),
+    UInt64(UInt64Builder),
+    Int8(Int8Builder),
+    Int16(Int16Builder),
+    Int32(Int32Builder),
+    Int64(Int64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    Boolean(BooleanBuilder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    FixedSizeList {
+        size: i32,
+        values: Box<ColumnBuilder>,
+    },
+    Struct {
+        fields: Fields,
+        columns: Vec<ColumnBuilder>,
+    },
+}
+
+impl ColumnBuilder {
+    fn new(type_expr: &def::TypeExpr, formats: &HashMap<String, def::Format>) -> Self {
+        match (&type_expr.base_type, type_expr.array_size) {
+            (BaseType::CHAR, Some(_)) => ColumnBuilder::Utf8(StringBuilder::new()),
+            // Opaque values are decoded as a single run of bytes, arrays included.
+            (BaseType::OTHER(name), _) if !formats.contains_key(name) => {
+                ColumnBuilder::Binary(BinaryBuilder::new())
+            }
+            (base_type, Some(size)) => ColumnBuilder::FixedSizeList {
+                size: size as i32,
+                values: Box::new(Self::scalar(base_type, formats)),
+            },
+            (base_type, None) => Self::scalar(base_type, formats),
+        }
+    }
+
+    fn scalar(base_type: &BaseType, formats: &HashMap<String, def::Format>) -> Self {
+        match base_type {
+            BaseType::UINT8 => ColumnBuilder::UInt8(UInt8Builder::new()),
+            BaseType::UINT16 => ColumnBuilder::UInt16(UInt16Builder::new()),
+            BaseType::UINT32 => ColumnBuilder::UInt32(UInt32Builder::new()),
+            BaseType::UINT64 => ColumnBuilder::UInt64(UInt64Builder::new()),
+            BaseType::INT8 => ColumnBuilder::Int8(Int8Builder::new()),
+            BaseType::INT16 => ColumnBuilder::Int16(Int16Builder::new()),
+            BaseType::INT32 => ColumnBuilder::Int32(Int32Builder::new()),
+            BaseType::INT64 => ColumnBuilder::Int64(Int64Builder::new()),
+            BaseType::FLOAT => ColumnBuilder::Float32(Float32Builder::new()),
+            BaseType::DOUBLE => ColumnBuilder::Float64(Float64Builder::new()),
+            BaseType::BOOL => ColumnBuilder::Boolean(BooleanBuilder::new()),
+            BaseType::CHAR => ColumnBuilder::Utf8(StringBuilder::new()),
+            BaseType::OTHER(name) => match formats.get(name) {
+                Some(format) => {
+                    let (fields, columns) = format_columns(&format.fields, formats);
+                    ColumnBuilder::Struct {
+                        fields: fields.into(),
+                        columns,
+                    }
+                }
+                None => ColumnBuilder::Binary(BinaryBuilder::new()),
+            },
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            ColumnBuilder::UInt8(_) => DataType::UInt8,
+            ColumnBuilder::UInt16(_) => DataType::UInt16,
+            ColumnBuilder::UInt32(_) => DataType::UInt32,
+            ColumnBuilder::UInt64(_) => DataType::UInt64,
+            ColumnBuilder::Int8(_) => DataType::Int8,
+            ColumnBuilder::Int16(_) => DataType::Int16,
+            ColumnBuilder::Int32(_) => DataType::Int32,
+            ColumnBuilder::Int64(_) => DataType::Int64,
+            ColumnBuilder::Float32(_) => DataType::Float32,
+            ColumnBuilder::Float64(_) => DataType::Float64,
+            ColumnBuilder::Boolean(_) => DataType::Boolean,
+            ColumnBuilder::Utf8(_) => DataType::Utf8,
+            ColumnBuilder::Binary(_) => DataType::Binary,
+            ColumnBuilder::FixedSizeList { size, values } => {
+                DataType::FixedSizeList(Arc::new(list_item(values)), *size)
+            }
+            ColumnBuilder::Struct { fields, .. } => DataType::Struct(fields.clone()),
+        }
+    }
+
+    fn append(&mut self, value: &FieldValue) -> Result<(), ULogError> {
+        match (self, value) {
+            (ColumnBuilder::UInt8(builder), FieldValue::ScalarU8(v)) => builder.append_value(*v),
+            (ColumnBuilder::UInt16(builder), FieldValue::ScalarU16(v)) => builder.append_value(*v),
+            (ColumnBuilder::UInt32(builder), FieldValue::ScalarU32(v)) => builder.append_value(*v),
+            (ColumnBuilder::UInt64(builder), FieldValue::ScalarU64(v)) => builder.append_value(*v),
+            (ColumnBuilder::Int8(builder), FieldValue::ScalarI8(v)) => builder.append_value(*v),
+            (ColumnBuilder::Int16(builder), FieldValue::ScalarI16(v)) => builder.append_value(*v),
+            (ColumnBuilder::Int32(builder), FieldValue::ScalarI32(v)) => builder.append_value(*v),
+            (ColumnBuilder::Int64(builder), FieldValue::ScalarI64(v)) => builder.append_value(*v),
+            (ColumnBuilder::Float32(builder), FieldValue::ScalarF32(v)) => builder.append_value(*v),
+            (ColumnBuilder::Float64(builder), FieldValue::ScalarF64(v)) => builder.append_value(*v),
+            (ColumnBuilder::Boolean(builder), FieldValue::ScalarBool(v)) => {
+                builder.append_value(*v)
+            }
+            (ColumnBuilder::Utf8(builder), FieldValue::ScalarChar(v)) => {
+                builder.append_value(v.to_string());
+            }
+            (ColumnBuilder::Utf8(builder), FieldValue::ArrayChar(chars)) => {
+                let text: String = chars.iter().collect();
+                builder.append_value(text.trim_end_matches('\0'));
+            }
+            (ColumnBuilder::Binary(builder), FieldValue::Opaque(bytes)) => {
+                builder.append_value(bytes);
+            }
+            (ColumnBuilder::Struct { fields, columns }, FieldValue::ScalarOther(format)) => {
+                append_fields(fields.iter().map(AsRef::as_ref), columns, format)?;
+            }
+            (ColumnBuilder::FixedSizeList { size, values }, FieldValue::ArrayOther(elements))
+                if elements.len() == *size as usize =>
+            {
+                let ColumnBuilder::Struct { fields, columns } = values.as_mut() else {
+                    return Err(mismatch(value));
+                };
+                for element in elements {
+                    append_fields(fields.iter().map(AsRef::as_ref), columns, element)?;
+                }
+            }
+            (ColumnBuilder::FixedSizeList { size, values }, _) => match value.to_scalars() {
+                Some(elements) if elements.len() == *size as usize => {
+                    for element in &elements {
+                        values.append(element)?;
+                    }
+                }
+                _ => return Err(mismatch(value)),
+            },
+            _ => return Err(mismatch(value)),
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ColumnBuilder::UInt8(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::UInt16(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::UInt32(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::UInt64(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Int8(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Int16(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Int32(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Int64(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Float32(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Float64(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Boolean(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Utf8(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Binary(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::FixedSizeList { size, values } => Arc::new(FixedSizeListArray::new(
+                Arc::new(list_item(values)),
+                *size,
+                values.finish(),
+                None,
+            )),
+            ColumnBuilder::Struct { fields, columns } => Arc::new(StructArray::new(
+                fields.clone(),
+                columns.iter_mut().map(ColumnBuilder::finish).collect(),
+                None,
+            )),
+        }
+    }
+}
+
+fn is_padding(name: &str) -> bool {
+    name.starts_with("_padding")
+}
+
+fn list_item(values: &ColumnBuilder) -> Field {
+    Field::new_list_field(values.data_type(), false)
+}
+
+/// Reports a value which does not match the type of its column, such as an array skipped with
+/// `ULogParserBuilder::skip_arrays_larger_than()`.
+fn mismatch(value: &FieldValue) -> ULogError {
+    ULogError::TypeMismatch(format!("cannot append {value:?} to an Arrow column"))
+}
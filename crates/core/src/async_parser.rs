@@ -0,0 +1,141 @@
+//! Parsing of logs from a `tokio::io::AsyncRead`, e.g. a network socket or an object store stream.
+//!
+//! The bytes of each message are read asynchronously into a buffer, and then decoded by a regular
+//! `ULogParser`, so the two parsers yield exactly the same messages for the same options.
+
+use std::collections::VecDeque;
+use std::io::{self, Read};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::builder::ULogParserBuilder;
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::{Step, ULogParser, ULOG_MESSAGE_HEADER_SIZE};
+
+/// The size of the file header, which precedes the first message.
+const FILE_HEADER_SIZE: usize = 16;
+
+/// The most bytes requested from the async reader at once.
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// The reader of the `ULogParser` wrapped by an [`AsyncULogParser`]: the bytes read from the
+/// async reader which have not been parsed yet.
+pub struct AsyncFeed<R> {
+    source: R,
+    buffer: VecDeque<u8>,
+    chunk: Vec<u8>,
+    eof: bool,
+}
+
+impl<R> Read for AsyncFeed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.buffer.read(buf)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncFeed<R> {
+    fn new(source: R) -> Self {
+        AsyncFeed {
+            source,
+            buffer: VecDeque::new(),
+            chunk: vec![0; READ_CHUNK_SIZE],
+            eof: false,
+        }
+    }
+
+    /// Reads from the source until at least `len` bytes are buffered, or the source ends.
+    async fn fill_to(&mut self, len: usize) -> Result<(), ULogError> {
+        while self.buffer.len() < len && !self.eof {
+            let n = self.source.read(&mut self.chunk).await?;
+            if n == 0 {
+                self.eof = true;
+            }
+            self.buffer.extend(&self.chunk[..n]);
+        }
+
+        Ok(())
+    }
+}
+
+/// A parser which reads its log from a `tokio::io::AsyncRead`, created by
+/// [`ULogParserBuilder::build_async`].
+///
+/// Call [`AsyncULogParser::next_message`] in a loop, as for the `Iterator` impl of `ULogParser`.
+pub struct AsyncULogParser<R: AsyncRead + Unpin> {
+    parser: ULogParser<AsyncFeed<R>>,
+}
+
+impl<R: AsyncRead + Unpin> ULogParserBuilder<R> {
+    /// Builds an [`AsyncULogParser`] over the async reader, with the options of this builder.
+    ///
+    /// The `read_ahead` and `recover_on_error` options are ignored, as the feed is filled with
+    /// exactly one message at a time.
+    pub fn build_async(self) -> Result<AsyncULogParser<R>, ULogError> {
+        let mut parser = self.build_with(AsyncFeed::new)?;
+        parser.datastream.set_read_ahead(0);
+        parser.recover_on_error = false;
+
+        Ok(AsyncULogParser { parser })
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncULogParser<R> {
+    /// Reads and decodes the next message, returning `None` at the end of the log.
+    ///
+    /// Only the bytes of the next message are read before it is decoded, so messages are yielded
+    /// as soon as they arrive.
+    pub async fn next_message(&mut self) -> Option<Result<UlogMessage, ULogError>> {
+        if self.parser.reached_max_records() {
+            return None;
+        }
+
+        let result = self.next_decoded().await;
+        self.parser.finish_message(result).transpose()
+    }
+
+    /// Returns the synchronous parser which decodes the messages, e.g. to query the metadata
+    /// seen so far.
+    pub fn parser(&self) -> &ULogParser<AsyncFeed<R>> {
+        &self.parser
+    }
+
+    /// Reads and decodes the next message, as `next_sub()` does for the synchronous parser.
+    async fn next_decoded(&mut self) -> Result<Option<UlogMessage>, ULogError> {
+        loop {
+            if let Some(msg) = self.parser.pending.pop_front() {
+                return Ok(Some(msg));
+            }
+
+            self.fill().await?;
+
+            match self.parser.next_step()? {
+                None => return Ok(None),
+                Some(Step::Yield(msg)) => return Ok(Some(msg)),
+                Some(Step::Skip) => {}
+            }
+        }
+    }
+
+    /// Buffers the bytes of the next message, preceded by the file header if it has not been
+    /// read yet.
+    async fn fill(&mut self) -> Result<(), ULogError> {
+        let start = if self.parser.expects_file_header() {
+            FILE_HEADER_SIZE
+        } else {
+            0
+        };
+
+        let feed = self.parser.datastream.get_mut();
+        feed.fill_to(start + ULOG_MESSAGE_HEADER_SIZE).await?;
+
+        // A truncated header is left for the parser to report.
+        if feed.buffer.len() < start + ULOG_MESSAGE_HEADER_SIZE {
+            return Ok(());
+        }
+
+        let msg_size = u16::from_le_bytes([feed.buffer[start], feed.buffer[start + 1]]);
+        feed.fill_to(start + ULOG_MESSAGE_HEADER_SIZE + msg_size as usize)
+            .await
+    }
+}
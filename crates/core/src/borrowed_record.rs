@@ -0,0 +1,116 @@
+//! Records which borrow their fields from the source buffer instead of decoding them.
+//!
+//! A `LoggedData` message owns every field of its record, including a `Vec` for each array.  A
+//! [`BorrowedRecord`], returned by `ULogParser::next_borrowed()`, only holds the location of the
+//! record in the buffer, and each accessor reads a single field from there.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::layout::field_layout;
+use crate::message_buf::{cast_array, PlainArrayElement};
+use crate::model::def::{self, BaseType, TypeExpr};
+
+/// A primitive type which can be read from a [`BorrowedRecord`].
+pub trait BorrowedScalar: PlainArrayElement {
+    /// The ULog type of fields holding values of this type.
+    const BASE_TYPE: BaseType;
+}
+
+macro_rules! impl_borrowed_scalar {
+    ($($ty:ty => $base_type:ident),*) => {
+        $(
+            impl BorrowedScalar for $ty {
+                const BASE_TYPE: BaseType = BaseType::$base_type;
+            }
+        )*
+    };
+}
+
+impl_borrowed_scalar!(
+    u8 => UINT8, u16 => UINT16, u32 => UINT32, u64 => UINT64,
+    i8 => INT8, i16 => INT16, i32 => INT32, i64 => INT64,
+    f32 => FLOAT, f64 => DOUBLE
+);
+
+/// A `DATA` message whose fields are read on demand from the buffer holding the log.
+///
+/// Fields are addressed by name, with `/` separated paths for fields of nested formats, e.g.
+/// `accel/x`.  The accessors return `None` if there is no such field, or if it is not of the
+/// requested type: `get::<f32>()` only reads `float` fields, and `get::<u64>()` only `uint64_t`
+/// fields.
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedRecord<'a> {
+    pub msg_id: u16,
+    pub multi_id: u8,
+    pub message_name: &'a str,
+    // The encoded fields, following the msg_id.
+    payload: &'a [u8],
+    format: &'a def::Format,
+    formats: &'a HashMap<String, def::Format>,
+    opaque_sizes: &'a HashMap<String, usize>,
+}
+
+impl<'a> BorrowedRecord<'a> {
+    pub(crate) fn new(
+        msg_id: u16,
+        multi_id: u8,
+        payload: &'a [u8],
+        format: &'a def::Format,
+        formats: &'a HashMap<String, def::Format>,
+        opaque_sizes: &'a HashMap<String, usize>,
+    ) -> Self {
+        BorrowedRecord {
+            msg_id,
+            multi_id,
+            message_name: &format.name,
+            payload,
+            format,
+            formats,
+            opaque_sizes,
+        }
+    }
+
+    /// Returns the encoded fields of the record, as they appear in the buffer.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the value of the `timestamp` field, if the format has one.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.get("timestamp")
+    }
+
+    /// Returns the encoded bytes of the field `name`.
+    pub fn field_bytes(&self, name: &str) -> Option<&'a [u8]> {
+        self.field(name).map(|(_, bytes)| bytes)
+    }
+
+    /// Returns the value of the scalar field `name`.
+    pub fn get<T: BorrowedScalar>(&self, name: &str) -> Option<T> {
+        let (type_expr, bytes) = self.field(name)?;
+        (type_expr.is_scalar() && type_expr.base_type == T::BASE_TYPE)
+            .then(|| T::from_le_slice(bytes))
+    }
+
+    /// Returns the value of the `bool` field `name`.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        let (type_expr, bytes) = self.field(name)?;
+        (type_expr.is_scalar() && type_expr.base_type == BaseType::BOOL).then(|| bytes[0] != 0)
+    }
+
+    /// Returns the values of the array field `name`.
+    ///
+    /// The values are borrowed from the buffer if it holds them suitably aligned, which is always
+    /// the case for `u8` and `i8` arrays, and are otherwise decoded into a copy.
+    pub fn get_array<T: BorrowedScalar>(&self, name: &str) -> Option<Cow<'a, [T]>> {
+        let (type_expr, bytes) = self.field(name)?;
+        (!type_expr.is_scalar() && type_expr.base_type == T::BASE_TYPE).then(|| cast_array(bytes))
+    }
+
+    fn field(&self, name: &str) -> Option<(&'a TypeExpr, &'a [u8])> {
+        let (type_expr, range) =
+            field_layout(self.format, name, self.formats, self.opaque_sizes).ok()??;
+        Some((type_expr, self.payload.get(range)?))
+    }
+}
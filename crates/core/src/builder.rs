@@ -1,8 +1,16 @@
-use std::collections::HashSet;
-use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, StdinLock};
+use std::sync::Arc;
+use std::time::Instant;
+
+use regex::Regex;
 
 use crate::errors::ULogError;
-use crate::parser::ULogParser;
+use crate::header_codec::HeaderCodec;
+use crate::parser::{
+    BadTimestampPolicy, FieldTransform, ParserConfig, SubscriptionFilter, TimeFilter, ULogParser,
+    DEFAULT_NAME_INSTANCE_PATTERN,
+};
 
 pub struct ULogParserBuilder<R> {
     reader: R,
@@ -10,9 +18,36 @@ pub struct ULogParserBuilder<R> {
     include_timestamp: bool,
     include_padding: bool,
     allowed_subscription_names: Option<HashSet<String>>,
+    allowed_subscription_globs: Option<Vec<String>>,
+    drop_ignored: bool,
+    time_filter: Option<TimeFilter>,
+    bad_timestamp_policy: BadTimestampPolicy,
+    add_index_field: bool,
+    read_appended_data: bool,
+    resync_on_error: bool,
+    recover_on_error: bool,
+    error_on_dropout: bool,
+    require_sorted: bool,
+    collect_warnings: bool,
+    fold_resubscriptions: bool,
+    growing: bool,
+    max_subscriptions: Option<usize>,
+    max_records: Option<u64>,
+    deadline: Option<Instant>,
+    max_array_size: Option<usize>,
+    opaque_type_sizes: HashMap<String, usize>,
+    transforms: HashMap<String, Vec<(String, FieldTransform)>>,
+    split_name_instance: bool,
+    name_instance_pattern: String,
+    raw_payload: bool,
+    pipelined: bool,
+    read_ahead: usize,
+    header_codec: Option<Arc<dyn HeaderCodec>>,
+    #[cfg(feature = "arena")]
+    arena_batch_size: usize,
 }
 
-impl<R: Read> ULogParserBuilder<R> {
+impl<R> ULogParserBuilder<R> {
     // Start the builder with a mandatory reader
     #[must_use]
     pub fn new(reader: R) -> Self {
@@ -22,6 +57,33 @@ impl<R: Read> ULogParserBuilder<R> {
             include_timestamp: false,
             include_padding: false,
             allowed_subscription_names: None,
+            allowed_subscription_globs: None,
+            drop_ignored: false,
+            time_filter: None,
+            bad_timestamp_policy: BadTimestampPolicy::default(),
+            add_index_field: false,
+            read_appended_data: false,
+            resync_on_error: false,
+            recover_on_error: false,
+            error_on_dropout: false,
+            require_sorted: false,
+            collect_warnings: false,
+            fold_resubscriptions: false,
+            growing: false,
+            max_subscriptions: None,
+            max_records: None,
+            deadline: None,
+            max_array_size: None,
+            opaque_type_sizes: HashMap::new(),
+            transforms: HashMap::new(),
+            split_name_instance: false,
+            name_instance_pattern: DEFAULT_NAME_INSTANCE_PATTERN.to_string(),
+            raw_payload: false,
+            pipelined: false,
+            read_ahead: 0,
+            header_codec: None,
+            #[cfg(feature = "arena")]
+            arena_batch_size: crate::arena::DEFAULT_ARENA_BATCH_SIZE,
         }
     }
 
@@ -50,7 +112,8 @@ impl<R: Read> ULogParserBuilder<R> {
     /// Specifying only the required messages in this allow list can greatly improve parser performance.
     ///
     /// Any `LoggedData` messages not included in this allow list will be emitted as raw bytes in a
-    /// `UlogMessage::Ignored` variant, so no messages are lost.
+    /// `UlogMessage::Ignored` variant, so no messages are lost.  The raw bytes are the complete
+    /// message contents, starting with the little endian `msg_id`.
     ///
     /// # Parameters
     /// - `subs`: An iterable collection of string-like items representing the names of `LoggedData` messages
@@ -66,18 +129,475 @@ impl<R: Read> ULogParserBuilder<R> {
         self
     }
 
+    /// Sets glob patterns matching the names of the `LoggedData` messages that the parser will
+    /// return, e.g. `vehicle_*` or `sensor_gyro*` for every instance of a topic.
+    ///
+    /// Patterns support `*` for any run of characters, `?` for a single character, and
+    /// `{a,b}` for alternatives, e.g. `vehicle_{gps,magnetometer}`.  They must match the whole
+    /// name.  Each subscription is matched once, when it is added, so this costs no more per
+    /// message than `set_subscription_allow_list()`.
+    ///
+    /// If both an allow list and patterns are set, a subscription is parsed if it is in the list
+    /// or matches any of the patterns.  `build()` returns `ULogError::InvalidConfiguration` if a
+    /// pattern has unbalanced braces.
+    #[must_use]
+    pub fn set_subscription_allow_list_glob<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_subscription_globs = Some(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Skips the `LoggedData` messages outside the allow list entirely, instead of yielding them as
+    /// `UlogMessage::Ignored`.
+    ///
+    /// This saves copying their raw bytes for consumers which never look at them.  Such messages
+    /// are lost, so a stream parsed with this option cannot be written back out unchanged.
+    /// Methods which scan the raw bytes of every subscription, such as
+    /// `ULogParser::bytes_per_subscription()`, are unaffected.
+    #[must_use]
+    pub fn drop_ignored(mut self, drop: bool) -> Self {
+        self.drop_ignored = drop;
+        self
+    }
+
+    /// Only decodes the `LoggedData` messages whose timestamp satisfies `predicate`, skipping the
+    /// others entirely.
+    ///
+    /// This generalises a time range, e.g. to select several disjoint windows of a flight.  The
+    /// timestamp is read from the raw bytes of each record before it is decoded, so rejected
+    /// records cost little more than reading them.  Records of subscriptions outside the allow
+    /// list are filtered as well.
+    #[must_use]
+    pub fn filter_time(mut self, predicate: impl Fn(u64) -> bool + Send + Sync + 'static) -> Self {
+        self.time_filter = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Sets how `LoggedData` messages with an implausible timestamp are handled.
+    ///
+    /// A timestamp is considered implausible if it is zero after non-zero timestamps were seen for
+    /// the same subscription, or if it jumps backwards by a large amount.
+    /// See [`BadTimestampPolicy`] for the available options.  Defaults to `BadTimestampPolicy::Include`.
+    #[must_use]
+    pub fn on_bad_timestamp(mut self, policy: BadTimestampPolicy) -> Self {
+        self.bad_timestamp_policy = policy;
+        self
+    }
+
+    /// Appends a synthetic `_index: u64` field to every `LoggedData` message.
+    ///
+    /// The index counts the samples of each subscription independently, starting at 0, which is
+    /// useful for joining data downstream when timestamps are unreliable.
+    ///
+    /// ⚠️ The extra field is not part of the original message, so messages parsed with this option
+    /// enabled will not re-encode to the original bytes.
+    #[must_use]
+    pub fn add_index_field(mut self, add: bool) -> Self {
+        self.add_index_field = add;
+        self
+    }
+
+    /// Continues parsing the sections appended to the log, at the offsets given in the flag bits.
+    ///
+    /// By default the parser stops at the first appended offset, as the bytes before it may end
+    /// with a partially written message.  With this option enabled the parser skips any such
+    /// partial message instead, and yields a `UlogMessage::AppendedSectionStart` marker before
+    /// the messages of each appended section, producing a single continuous stream.
+    #[must_use]
+    pub fn read_appended_data(mut self, read: bool) -> Self {
+        self.read_appended_data = read;
+        self
+    }
+
+    /// Makes `ULogParser::parse_all()` resynchronize after an error in the data section.
+    ///
+    /// This is a best-effort repair for damaged files, such as files corrupted by a text mode
+    /// transfer which replaced `\n` with `\r\n`.  After a message fails to parse, the parser skips
+    /// ahead to just after the next SYNC message and continues from there.  Each resync is recorded
+    /// in the returned errors as `ULogError::Resynchronized`, with the number of bytes skipped.
+    /// Messages of unknown type are treated as errors, as they are the usual sign of corruption.
+    ///
+    /// Logs without SYNC messages cannot be recovered this way: the rest of the log is skipped.
+    #[must_use]
+    pub fn resync_on_error(mut self, resync: bool) -> Self {
+        self.resync_on_error = resync;
+        self
+    }
+
+    /// Skips over corrupt messages in the data section, rather than returning an error.
+    ///
+    /// This is a best-effort extraction for damaged files, e.g. logs written by a flight
+    /// controller which lost power.  A message is corrupt if its type is unknown, its size does
+    /// not suit its type (e.g. a `DATA` message of a different size than its format), or it fails
+    /// to parse.  The parser then scans forward for the next plausible message, one whose header
+    /// and contents are valid and which is followed by another valid header, and resumes there.
+    /// The skipped bytes are yielded as a `UlogMessage::Corrupt` marker, so no data is silently
+    /// lost.  A truncated final message is yielded as a marker too, ending the log.
+    ///
+    /// Checking every message costs a little speed, so this is off by default.  It takes
+    /// precedence over `resync_on_error()`, and is ignored by async parsers.
+    #[must_use]
+    pub fn recover_on_error(mut self, recover: bool) -> Self {
+        self.recover_on_error = recover;
+        self
+    }
+
+    /// Stops with `ULogError::DataDropout` at the first dropout, rather than yielding it as a
+    /// `UlogMessage::DropoutMark`.
+    ///
+    /// This suits strict pipelines, where any data lost by the logger invalidates the log.
+    /// Dropouts carry no timestamp, so the error reports the timestamp of the last record yielded
+    /// before it, or 0 if there was none.  The file is not parsed any further.
+    #[must_use]
+    pub fn error_on_dropout(mut self, error: bool) -> Self {
+        self.error_on_dropout = error;
+        self
+    }
+
+    /// Stops with `ULogError::OutOfOrder` at the first record whose timestamp is before that of
+    /// the record yielded before it, whatever their topics.
+    ///
+    /// This suits pipelines which require the records of a log to be sorted by timestamp, rather
+    /// than only the records of each topic.  Equal timestamps are allowed.  The error reports the
+    /// byte offset of the record, and the file is not parsed any further.
+    #[must_use]
+    pub fn require_sorted(mut self, require: bool) -> Self {
+        self.require_sorted = require;
+        self
+    }
+
+    /// Collects the recoverable issues met while parsing, such as skipped corrupt bytes or a
+    /// reused msg_id, so they can be read from `ULogParser::warnings()`.
+    ///
+    /// Parsing is unchanged: the issues are tolerated as usual, and only recorded.  The
+    /// warnings are kept until the parser is dropped, which matters for a long-running stream
+    /// with many issues.
+    #[must_use]
+    pub fn collect_warnings(mut self, collect: bool) -> Self {
+        self.collect_warnings = collect;
+        self
+    }
+
+    /// Yields the records of a topic instance with the msg_id of its first subscription, even if
+    /// the topic was subscribed again under another msg_id.
+    ///
+    /// A log made of several segments, e.g. where the logger removed its subscriptions and added
+    /// them again, may give a topic a different msg_id in each segment.  With this option every
+    /// record of the topic carries the same msg_id, so anything which groups records by msg_id,
+    /// such as `group_by_subscription()`, merges the segments.  Topics are matched by name and
+    /// `multi_id`.  `get_subscription()` still returns the latest subscription to a msg_id, so
+    /// use the format name of a folded record to identify its topic.
+    #[must_use]
+    pub fn fold_resubscriptions(mut self, fold: bool) -> Self {
+        self.fold_resubscriptions = fold;
+        self
+    }
+
+    /// Parses a log which is still being written, e.g. to tail a flight in progress.
+    ///
+    /// The last message of such a log may only be partly written.  With this option, the
+    /// iterator ends before a message cut off by the end of the file instead of decoding it, and
+    /// `ULogParser::refresh()` continues from that message once more of the file is written.  The
+    /// file header must be complete when parsing starts.
+    #[must_use]
+    pub fn growing(mut self, growing: bool) -> Self {
+        self.growing = growing;
+        self
+    }
+
+    /// Limits the number of subscriptions a log may declare.
+    ///
+    /// If the log declares more than `max` subscriptions, the parser returns
+    /// `ULogError::TooManySubscriptions` and stops.  This bounds the memory used for untrusted
+    /// input, such as uploaded files.  By default there is no limit.
+    #[must_use]
+    pub fn max_subscriptions(mut self, max: usize) -> Self {
+        self.max_subscriptions = Some(max);
+        self
+    }
+
+    /// Stops parsing after `max` `LoggedData` records have been yielded.
+    ///
+    /// Once the limit is reached the iterator returns `None`, without reading the rest of the
+    /// file.  Only decoded records count towards it: other messages, and records left `Ignored`
+    /// by the allow list, are yielded as usual until then.  This bounds the memory used by
+    /// collectors of untrusted input.  By default there is no limit.
+    #[must_use]
+    pub fn max_records(mut self, max: u64) -> Self {
+        self.max_records = Some(max);
+        self
+    }
+
+    /// Sets a wall-clock deadline for parsing.
+    ///
+    /// Once the deadline has passed the parser returns `ULogError::Timeout`, and the iterator is
+    /// exhausted.  This bounds the time spent on adversarial or unexpectedly large input.
+    /// The clock is only checked every few hundred messages, so the deadline may be overrun by the
+    /// time it takes to parse that many messages.
+    #[must_use]
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Leaves array fields of `LoggedData` messages with more than `max` elements undecoded.
+    ///
+    /// This speeds up previews of logs with huge arrays, such as image buffers.  The bytes of such
+    /// a field are skipped, and its value is `FieldValue::Skipped` with the number of elements.
+    ///
+    /// ⚠️ Skipped fields cannot be re-encoded, so messages parsed with this option enabled cannot be
+    /// written back out.
+    #[must_use]
+    pub fn skip_arrays_larger_than(mut self, max: usize) -> Self {
+        self.max_array_size = Some(max);
+        self
+    }
+
+    /// Decodes fields of the given unknown base types as opaque bytes, instead of failing.
+    ///
+    /// A type name in a format definition which is neither a primitive nor a defined format, such
+    /// as a vendor specific `float16`, normally fails decoding with `ULogError::UndefinedFormat`.
+    /// Given the encoded size in bytes of each such type, the bytes of its fields are kept as
+    /// `FieldValue::Opaque` so that the remaining fields of the message can still be decoded.
+    #[must_use]
+    pub fn opaque_type_sizes<I, S>(mut self, sizes: I) -> Self
+    where
+        I: IntoIterator<Item = (S, usize)>,
+        S: Into<String>,
+    {
+        self.opaque_type_sizes = sizes
+            .into_iter()
+            .map(|(name, size)| (name.into(), size))
+            .collect();
+        self
+    }
+
+    /// Applies `f` to the values of a numeric field of a subscription after decoding, e.g. to
+    /// convert radians to degrees.
+    ///
+    /// `field` may be a `/` separated path into a nested format, e.g. `accel/x`, and the transform
+    /// applies to every instance of the subscription.  Each element of an array field is
+    /// transformed.  Float fields keep their type, while integer fields become `f64`.  Several
+    /// transforms of the same field are applied in the order they were registered.
+    ///
+    /// ⚠️ Transformed records no longer hold the logged values, so they will not re-encode to the
+    /// original bytes.
+    #[must_use]
+    pub fn transform(mut self, subscription: &str, field: &str, f: FieldTransform) -> Self {
+        self.transforms
+            .entry(subscription.to_string())
+            .or_default()
+            .push((field.to_string(), f));
+        self
+    }
+
+    /// Splits subscription names which encode the instance, such as `sensor_gyro_1`, into the
+    /// base name and the `multi_id`.
+    ///
+    /// Some tools log each instance of a topic under its own name instead of setting the
+    /// `multi_id`.  With this option enabled, a subscription with a `multi_id` of 0 whose name
+    /// matches the pattern set by `name_instance_pattern()` is renamed to the base name, and
+    /// given the instance as its `multi_id`, so it merges with the other instances of the topic.
+    /// If only the unsplit name has a format definition, it is also registered under the base name.
+    /// A subscription whose format differs from that of the base name, as compared by
+    /// `ULogParser::formats_equal()`, keeps its name.
+    ///
+    /// ⚠️ The `AddSubscription` messages yielded are the renamed subscriptions, so they will not
+    /// re-encode to the original bytes.
+    #[must_use]
+    pub fn split_name_instance(mut self, split: bool) -> Self {
+        self.split_name_instance = split;
+        self
+    }
+
+    /// Sets the regular expression used by `split_name_instance()`.
+    ///
+    /// The pattern must have two capture groups: the base name, followed by the instance number.
+    /// The default is `^(.+)_(\d+)$`, i.e. an underscore separated numeric suffix.
+    #[must_use]
+    pub fn name_instance_pattern(mut self, pattern: &str) -> Self {
+        self.name_instance_pattern = pattern.to_string();
+        self
+    }
+
+    /// Attaches a copy of the original payload bytes to each `LoggedData` record, as `raw_payload`.
+    ///
+    /// This is intended for debugging decoding mismatches, by comparing a record against the
+    /// bytes it was decoded from.  Each record then holds its payload twice, so this is off by
+    /// default.
+    #[must_use]
+    pub fn with_raw_payload(mut self, include: bool) -> Self {
+        self.raw_payload = include;
+        self
+    }
+
+    /// Decodes `LoggedData` messages on a pool of worker threads, while the parser keeps reading
+    /// ahead on the calling thread.
+    ///
+    /// This overlaps reading with decoding, and works with any reader.  Up to 1024 messages are
+    /// read ahead of the message being yielded, and messages are still yielded in file order, so
+    /// the output is exactly that of the serial parser.  It is only worthwhile when decoding is
+    /// the bottleneck, e.g. for large logs read from a fast disk, so this is off by default.
+    ///
+    /// With `recover_on_error(true)`, records are decoded on the calling thread, so that corrupt
+    /// ones can be recovered from.
+    #[must_use]
+    pub fn pipelined(mut self, pipelined: bool) -> Self {
+        self.pipelined = pipelined;
+        self
+    }
+
+    /// Reads up to `bytes` ahead of the current message, rather than only the bytes asked for.
+    ///
+    /// The parser reads each message with several small reads, which cost a syscall each on an
+    /// unbuffered reader such as a `File`.  A window of e.g. 64 KiB serves most of them from
+    /// memory, so sequential parsing makes far fewer reads.  Messages larger than the window are
+    /// read directly.  The default of 0 reads only the bytes asked for, which suits readers that
+    /// already buffer, e.g. a `BufReader` or a slice.  Async parsers always use 0.
+    #[must_use]
+    pub fn read_ahead(mut self, bytes: usize) -> Self {
+        self.read_ahead = bytes;
+        self
+    }
+
+    /// Reads the header of each message with `codec`, for logs written by a fork of the format
+    /// which encodes message sizes differently.
+    ///
+    /// Only the parser's own reads use the codec: `recover_on_error()` and the async parser still
+    /// expect the standard header.  `ULogParser::next_borrowed()` only reads records in place
+    /// with the standard header, so with a codec it parses every message with `next()` instead,
+    /// and returns no records.
+    #[must_use]
+    pub fn header_codec(mut self, codec: impl HeaderCodec + 'static) -> Self {
+        self.header_codec = Some(Arc::new(codec));
+        self
+    }
+
+    /// Sets the number of records per batch decoded by `ULogParser::for_each_arena_batch()`.
+    ///
+    /// Larger batches amortise the cost of resetting the arena, at the cost of holding more
+    /// records in memory at once.  The default is 1024.
+    #[cfg(feature = "arena")]
+    #[must_use]
+    pub fn arena_batch_size(mut self, batch_size: usize) -> Self {
+        self.arena_batch_size = batch_size;
+        self
+    }
+
     // Final method to build the `ULogParser`
-    pub fn build(self) -> Result<ULogParser<R>, ULogError> {
-        let result = ULogParser::new(self.reader);
+    pub fn build(self) -> Result<ULogParser<R>, ULogError>
+    where
+        R: Read,
+    {
+        self.build_with(|reader| reader)
+    }
+
+    /// Builds a parser over `wrap(reader)`, with the options of this builder.
+    pub(crate) fn build_with<S: Read>(
+        self,
+        wrap: impl FnOnce(R) -> S,
+    ) -> Result<ULogParser<S>, ULogError> {
+        let result = ULogParser::new(wrap(self.reader));
 
         match result {
             Ok(mut parser) => {
+                parser.config = ParserConfig {
+                    include_header: self.include_header,
+                    include_timestamp: self.include_timestamp,
+                    include_padding: self.include_padding,
+                    allowed_subscription_names: self.allowed_subscription_names.clone(),
+                    allowed_subscription_globs: self.allowed_subscription_globs.clone(),
+                    drop_ignored: self.drop_ignored,
+                    time_filter: self.time_filter.is_some(),
+                    bad_timestamp_policy: self.bad_timestamp_policy,
+                    add_index_field: self.add_index_field,
+                    read_appended_data: self.read_appended_data,
+                    resync_on_error: self.resync_on_error,
+                    recover_on_error: self.recover_on_error,
+                    error_on_dropout: self.error_on_dropout,
+                    require_sorted: self.require_sorted,
+                    collect_warnings: self.collect_warnings,
+                    fold_resubscriptions: self.fold_resubscriptions,
+                    growing: self.growing,
+                    max_subscriptions: self.max_subscriptions,
+                    max_records: self.max_records,
+                    deadline: self.deadline,
+                    max_array_size: self.max_array_size,
+                    opaque_type_sizes: self.opaque_type_sizes.clone(),
+                    transformed_fields: transformed_fields(&self.transforms),
+                    name_instance_pattern: self
+                        .split_name_instance
+                        .then(|| self.name_instance_pattern.clone()),
+                    raw_payload: self.raw_payload,
+                    pipelined: self.pipelined,
+                    read_ahead: self.read_ahead,
+                    custom_header_codec: self.header_codec.is_some(),
+                };
+
                 parser.include_header = self.include_header;
                 parser.include_timestamp = self.include_timestamp;
                 parser.include_padding = self.include_padding;
+                parser.drop_ignored = self.drop_ignored;
+                parser.time_filter = self.time_filter;
+                parser.bad_timestamp_policy = self.bad_timestamp_policy;
+                parser.add_index_field = self.add_index_field;
+                parser.read_appended_data = self.read_appended_data;
+                parser.resync_on_error = self.resync_on_error;
+                parser.recover_on_error = self.recover_on_error;
+                parser.error_on_dropout = self.error_on_dropout;
+                parser.require_sorted = self.require_sorted;
+                parser.collect_warnings = self.collect_warnings;
+                parser.fold_resubscriptions = self.fold_resubscriptions;
+                parser.growing = self.growing;
+                parser.max_subscriptions = self.max_subscriptions;
+                parser.max_records = self.max_records;
+                parser.deadline = self.deadline;
+                parser.max_array_size = self.max_array_size;
+                parser.opaque_type_sizes = self.opaque_type_sizes;
+                parser.transforms = self.transforms;
+                parser.raw_payload = self.raw_payload;
+                parser.pipelined = self.pipelined;
+                parser.datastream.set_read_ahead(self.read_ahead);
+                parser.header_codec = self.header_codec;
+
+                if self.split_name_instance {
+                    let pattern = Regex::new(&self.name_instance_pattern).map_err(|err| {
+                        ULogError::InvalidConfiguration(format!(
+                            "Invalid name instance pattern: {err}"
+                        ))
+                    })?;
 
-                if let Some(allowed_subscr) = self.allowed_subscription_names {
-                    parser.set_allowed_subscription_names(allowed_subscr);
+                    if pattern.captures_len() != 3 {
+                        return Err(ULogError::InvalidConfiguration(format!(
+                            "The name instance pattern {} must have exactly two capture groups",
+                            self.name_instance_pattern
+                        )));
+                    }
+
+                    parser.name_instance_pattern = Some(pattern);
+                }
+                #[cfg(feature = "arena")]
+                {
+                    parser.arena_batch_size = self.arena_batch_size;
+                }
+
+                if self.allowed_subscription_names.is_some()
+                    || self.allowed_subscription_globs.is_some()
+                {
+                    let patterns = self
+                        .allowed_subscription_globs
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|glob| glob_to_regex(glob))
+                        .collect::<Result<_, _>>()?;
+
+                    parser.subscription_filter = SubscriptionFilter::new(
+                        self.allowed_subscription_names.unwrap_or_default(),
+                    )
+                    .with_patterns(patterns);
                 }
 
                 Ok(parser)
@@ -86,3 +606,67 @@ impl<R: Read> ULogParserBuilder<R> {
         }
     }
 }
+
+/// Lists the fields with a transform, sorted by subscription.
+fn transformed_fields(
+    transforms: &HashMap<String, Vec<(String, FieldTransform)>>,
+) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = transforms
+        .iter()
+        .flat_map(|(subscription, fields)| {
+            fields
+                .iter()
+                .map(move |(field, _)| (subscription.clone(), field.clone()))
+        })
+        .collect();
+    // Stable, so the fields of each subscription keep the order they were registered in.
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    fields
+}
+
+impl ULogParserBuilder<StdinLock<'static>> {
+    /// Starts the builder with the process's standard input as the reader.
+    ///
+    /// Useful for CLI tools that receive a ULOG file through a pipe, e.g. `cat x.ulg | mytool`.
+    /// The stdin lock is already buffered, so no extra `BufReader` is needed.
+    #[must_use]
+    pub fn from_stdin() -> Self {
+        ULogParserBuilder::new(io::stdin().lock())
+    }
+}
+
+/// Translates a glob pattern of `set_subscription_allow_list_glob()` into a regex matching the
+/// whole name.
+fn glob_to_regex(glob: &str) -> Result<Regex, ULogError> {
+    let unbalanced =
+        || ULogError::InvalidConfiguration(format!("Unbalanced braces in the pattern {glob}"));
+
+    let mut pattern = String::from("^");
+    let mut depth = 0usize;
+
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '{' => {
+                depth += 1;
+                pattern.push_str("(?:");
+            }
+            '}' => {
+                depth = depth.checked_sub(1).ok_or_else(unbalanced)?;
+                pattern.push(')');
+            }
+            ',' if depth > 0 => pattern.push('|'),
+            c => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+
+    if depth > 0 {
+        return Err(unbalanced());
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).map_err(|err| {
+        ULogError::InvalidConfiguration(format!("Invalid subscription pattern {glob}: {err}"))
+    })
+}
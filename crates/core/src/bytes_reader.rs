@@ -0,0 +1,284 @@
+//! Reading logs held in a `bytes::Bytes` buffer, e.g. an upload received by a network service.
+//!
+//! The parser reads from the buffer directly, so the log never needs to be copied into a `Vec`.
+//! The bytes of decoded fields can also be taken as `Bytes` slices of the buffer, which share its
+//! reference count rather than copying the data, or records can be read without decoding them at
+//! all with `ULogParser::next_borrowed()`.
+
+use std::io::{self, Read};
+use std::mem;
+use std::ops::Range;
+
+use bytes::Bytes;
+
+use crate::borrowed_record::BorrowedRecord;
+use crate::builder::ULogParserBuilder;
+use crate::errors::ULogError;
+use crate::layout::field_range;
+use crate::message_buf::MessageBuf;
+use crate::model::msg::LoggedData;
+use crate::parser::{ULogMessageType, ULogParser};
+
+/// A `Read` implementation over a `Bytes` buffer, which keeps the whole buffer so that slices of
+/// it can be handed out.
+#[derive(Debug, Clone)]
+pub struct BytesReader {
+    source: Bytes,
+    position: usize,
+}
+
+impl BytesReader {
+    pub fn new(source: Bytes) -> Self {
+        BytesReader {
+            source,
+            position: 0,
+        }
+    }
+
+    /// Returns the whole buffer, including the bytes already read.
+    pub fn source(&self) -> &Bytes {
+        &self.source
+    }
+
+    pub(crate) fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+}
+
+impl Read for BytesReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.source[self.position.min(self.source.len())..];
+        let n = buf.len().min(remaining.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Returns the offset of the first `MultiInfo` message in `bytes` whose key names a hardfault
+/// field, e.g. `char[512] hardfault_plain`.
+fn find_hardfault_marker(bytes: &[u8]) -> Option<usize> {
+    const MARKER: &[u8] = b" hardfault_";
+
+    let mut from = 0;
+    while let Some(found) = bytes[from..]
+        .windows(MARKER.len())
+        .position(|window| window == MARKER)
+    {
+        let marker = from + found;
+        from = marker + 1;
+
+        // The key starts with its type, e.g. `char[512]`, and is preceded by the 3 byte message
+        // header, the `is_continued` flag and the key length.
+        let Some(type_start) = bytes[..marker].iter().rposition(|&b| b == b'c') else {
+            continue;
+        };
+        if !bytes[type_start..marker].starts_with(b"char[") || type_start < 5 {
+            continue;
+        }
+        let start = type_start - 5;
+        let key_len = usize::from(bytes[type_start - 1]);
+        let is_multi_info = matches!(
+            ULogMessageType::from(bytes[start + 2]),
+            ULogMessageType::INFO_MULTIPLE
+        );
+        if is_multi_info && key_len > marker - type_start + MARKER.len() {
+            return Some(start);
+        }
+    }
+
+    None
+}
+
+impl ULogParserBuilder<BytesReader> {
+    /// Starts the builder with a `Bytes` buffer holding a complete log as the reader.
+    #[must_use]
+    pub fn from_bytes_crate(bytes: Bytes) -> Self {
+        ULogParserBuilder::new(BytesReader::new(bytes))
+    }
+}
+
+impl ULogParser<BytesReader> {
+    /// Returns the file header and the definitions section exactly as they appear in the source
+    /// buffer, i.e. everything before the first subscription.
+    ///
+    /// Together with [`crate::writer::ULogWriter::write_raw_header`], this lets a tool rewrite the
+    /// data of a log while keeping its header byte for byte.  The definitions are read if the
+    /// parser has not reached them yet, but are still yielded by the iterator, so a transcoder
+    /// should skip the messages before the first `AddSubscription`.  If the log has no
+    /// subscriptions, the whole buffer is returned.
+    pub fn raw_header(&mut self) -> Result<Bytes, ULogError> {
+        self.buffer_definitions()?;
+
+        let source = self.datastream.get_ref().source();
+        let end = self.data_section_offset.unwrap_or(source.len());
+
+        Ok(source.slice(..end))
+    }
+
+    /// Returns the data appended to the log, such as the hardfault dump PX4 writes after a crash,
+    /// as a slice of the source buffer.
+    ///
+    /// The dump runs from the first appended data offset of the `FlagBits` message to the end of
+    /// the buffer.  If the log is flagged as having appended data but records no offset, the dump
+    /// is found by its marker instead: PX4 writes it as `MultiInfo` messages whose key starts with
+    /// `hardfault_`, e.g. `char[512] hardfault_plain`.  These messages can also be decoded with
+    /// `read_appended_data(true)`.  Returns `None` if the log has no appended data.
+    pub fn crash_dump(&self) -> Result<Option<Bytes>, ULogError> {
+        let source = self.datastream.get_ref().source();
+
+        // The FLAG_BITS message directly follows the 16 byte file header.
+        let Some(&[low, high, msg_type]) = source.get(16..19) else {
+            return Ok(None);
+        };
+        if !matches!(ULogMessageType::from(msg_type), ULogMessageType::FLAG_BITS) {
+            return Ok(None);
+        }
+        let end = 19 + usize::from(u16::from_le_bytes([low, high]));
+        let Some(contents) = source.get(19..end) else {
+            return Err(ULogError::UnexpectedEndOfFile);
+        };
+
+        let flag_bits = self.parse_flag_bits(MessageBuf::from_vec(contents.to_vec()))?;
+        if !flag_bits.has_data_appended() {
+            return Ok(None);
+        }
+
+        let offset = flag_bits
+            .appended_data_offsets
+            .iter()
+            .find(|&&offset| offset > 0)
+            .map(|&offset| offset as usize)
+            .or_else(|| find_hardfault_marker(&source[end..]).map(|offset| end + offset));
+
+        Ok(offset
+            .filter(|&offset| offset < source.len())
+            .map(|offset| source.slice(offset..)))
+    }
+
+    /// Returns the bytes of the field `name` of `record` as a slice of the source buffer, without
+    /// copying them.
+    ///
+    /// `record` must be the `LoggedData` message most recently yielded by the parser, since only
+    /// the location of the current message is known.  Fields of nested formats are addressed by
+    /// `/` separated paths, e.g. `accel/x`.  Returns `None` if there is no such field, or if
+    /// `record` is not the current message.
+    pub fn field_slice(&self, record: &LoggedData, name: &str) -> Option<Bytes> {
+        let source = self.datastream.get_ref().source();
+
+        let header = source.get(self.message_offset..self.message_offset + 3)?;
+        let msg_size = usize::from(u16::from_le_bytes([header[0], header[1]]));
+        if !matches!(ULogMessageType::from(header[2]), ULogMessageType::DATA) || msg_size < 2 {
+            return None;
+        }
+
+        let payload_start = self.message_offset + 3;
+        let msg_id = source.get(payload_start..payload_start + 2)?;
+        if u16::from_le_bytes([msg_id[0], msg_id[1]]) != record.msg_id {
+            return None;
+        }
+
+        let range = field_range(
+            &record.data.def_format,
+            name,
+            &self.formats,
+            &self.opaque_type_sizes,
+        )
+        .ok()??;
+        let start = payload_start + 2 + range.start;
+        let end = payload_start + 2 + range.end;
+
+        (end <= payload_start + msg_size && end <= source.len()).then(|| source.slice(start..end))
+    }
+
+    /// Returns the next record of an allowed subscription, borrowing its fields from the source
+    /// buffer rather than decoding them.
+    ///
+    /// The record borrows the parser, so it must be dropped before the next call.  Other messages
+    /// are parsed as by `next()`, registering subscriptions and formats, but are not returned.
+    /// Records are read as they appear in the log: options which rewrite, renumber or drop
+    /// decoded records, such as `transform()`, `bad_timestamp_policy()` or
+    /// `fold_resubscriptions()`, do not apply to them.  Returns `None` at the end of the log.
+    ///
+    /// Records are read on the calling thread even if the parser is `pipelined`.  Once `next()`
+    /// has started the pipeline, messages may be queued in it, so this returns
+    /// `ULogError::InvalidConfiguration` rather than skip them.
+    pub fn next_borrowed(&mut self) -> Option<Result<BorrowedRecord<'_>, ULogError>> {
+        if self.pipeline.is_some() {
+            return Some(Err(ULogError::InvalidConfiguration(
+                "next_borrowed() cannot follow next() on a pipelined parser".to_string(),
+            )));
+        }
+
+        let (msg_id, payload) = loop {
+            match self.peek_data_message() {
+                Some((msg_id, payload, allowed)) => {
+                    if let Err(err) = self.bypass_message(allowed) {
+                        return Some(Err(err));
+                    }
+                    self.datastream.advance(payload.end - self.message_offset);
+
+                    if allowed {
+                        break (msg_id, payload);
+                    }
+                }
+                None => {
+                    // Parse serially, so that no records are queued in the pipeline.
+                    let pipelined = mem::replace(&mut self.pipelined, false);
+                    let next = self.next();
+                    self.pipelined = pipelined;
+
+                    if let Err(err) = next? {
+                        return Some(Err(err));
+                    }
+                }
+            }
+        };
+
+        // Unwraps are safe: checked by `peek_data_message()`.
+        let sub = self.get_subscription_ref(msg_id).unwrap();
+        let format = self.formats.get(&sub.message_name).unwrap();
+        let source = self.datastream.get_ref().source();
+
+        Some(Ok(BorrowedRecord::new(
+            msg_id,
+            sub.multi_id,
+            &source[payload],
+            format,
+            &self.formats,
+            &self.opaque_type_sizes,
+        )))
+    }
+
+    /// Returns the msg_id and payload range of the next message if it is a complete `DATA`
+    /// message of a known subscription which can be read in place, and whether the subscription is
+    /// allowed.
+    fn peek_data_message(&self) -> Option<(u16, Range<usize>, bool)> {
+        let source = self.datastream.get_ref().source();
+        let start = self.datastream.num_bytes_read;
+
+        let header = source.get(start..start + 3)?;
+        let msg_size = usize::from(u16::from_le_bytes([header[0], header[1]]));
+        if !matches!(ULogMessageType::from(header[2]), ULogMessageType::DATA) || msg_size < 2 {
+            return None;
+        }
+
+        let end = start + 3 + msg_size;
+        if end > source.len() || !self.can_bypass_parsing(3 + msg_size) {
+            return None;
+        }
+
+        let msg_id = u16::from_le_bytes([source[start + 3], source[start + 4]]);
+        let sub = self.get_subscription_ref(msg_id).ok()?;
+        if !self.formats.contains_key(&sub.message_name) {
+            // Leave the error to `next()`.
+            return None;
+        }
+
+        Some((
+            msg_id,
+            start + 5..end,
+            self.subscription_filter.is_allowed(msg_id),
+        ))
+    }
+}
@@ -0,0 +1,50 @@
+//! Checksums of the data of each topic, to detect which topics differ between copies of a log.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crc32fast::Hasher;
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::{ULogMessageType, ULogParser};
+
+impl<R: Read> ULogParser<R> {
+    /// Computes the CRC-32 of the payloads of each subscription, concatenated in file order.
+    ///
+    /// Payloads are as for [`ULogParser::bytes_per_subscription`], so the checksum of a topic only
+    /// changes if its own records were altered, added, removed or reordered, whatever happened to
+    /// the rest of the log.  Messages are not decoded, so this is a cheap scan.  Keys are
+    /// formatted as for [`ULogParser::bytes_per_subscription`].
+    pub fn subscription_crc(mut self) -> Result<HashMap<String, u32>, ULogError> {
+        self.set_allowed_subscription_names(Vec::new());
+        self.drop_ignored = false;
+
+        let mut hashers_by_msg_id: HashMap<u16, Hasher> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::Ignored {
+                msg_type,
+                message_contents,
+            } = msg_res?
+            {
+                if !matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA) {
+                    continue;
+                }
+
+                let msg_id = u16::from_le_bytes([message_contents[0], message_contents[1]]);
+                hashers_by_msg_id
+                    .entry(msg_id)
+                    .or_default()
+                    .update(&message_contents[2..]);
+            }
+        }
+
+        let checksums = hashers_by_msg_id
+            .into_iter()
+            .map(|(msg_id, hasher)| (msg_id, hasher.finalize()))
+            .collect();
+
+        self.per_subscription(checksums)
+    }
+}
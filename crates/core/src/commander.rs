@@ -0,0 +1,93 @@
+//! Extraction of the flight controller's state machine transitions, for incident analysis.
+
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+const VEHICLE_STATUS_TOPIC: &str = "vehicle_status";
+const COMMANDER_STATE_TOPIC: &str = "commander_state";
+
+/// A change of one of the commander states.
+///
+/// The states are the raw values of the PX4 enums, e.g. `vehicle_status_s::ARMING_STATE_ARMED`,
+/// since their numbering differs between PX4 versions.  `from` is `None` for the first record of
+/// the topic, which gives the initial state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChange {
+    /// `vehicle_status.arming_state` changed.
+    Arming { from: Option<u8>, to: u8 },
+    /// `vehicle_status.nav_state` changed.
+    Nav { from: Option<u8>, to: u8 },
+    /// `commander_state.main_state` changed, i.e. the flight mode requested by the pilot.
+    Main { from: Option<u8>, to: u8 },
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Returns the timeline of arming state, navigation state and main state changes, as
+    /// `(timestamp, change)` pairs sorted by timestamp.
+    ///
+    /// The states are read from the first instance of `vehicle_status` and `commander_state`.
+    /// Topics or fields missing from the log are ignored.  Any allow list set on the parser is
+    /// replaced by these topics.
+    pub fn state_transitions(mut self) -> Result<Vec<(u64, StateChange)>, ULogError> {
+        self.set_allowed_subscription_names([
+            VEHICLE_STATUS_TOPIC.to_string(),
+            COMMANDER_STATE_TOPIC.to_string(),
+        ]);
+
+        let mut transitions = Vec::new();
+        let mut arming_state = None;
+        let mut nav_state = None;
+        let mut main_state = None;
+
+        while let Some(msg_res) = self.next() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            if self.get_subscription_ref(data.msg_id)?.multi_id != 0 {
+                continue;
+            }
+
+            let state = |name: &str| {
+                data.data
+                    .find_field(name)
+                    .and_then(|f| f.value.as_i64())
+                    .and_then(|value| u8::try_from(value).ok())
+            };
+
+            let mut track =
+                |current: &mut Option<u8>,
+                 name: &str,
+                 change: fn(Option<u8>, u8) -> StateChange| {
+                    if let Some(to) = state(name) {
+                        if *current != Some(to) {
+                            transitions.push((data.timestamp, change(*current, to)));
+                            *current = Some(to);
+                        }
+                    }
+                };
+
+            if data.data.name == VEHICLE_STATUS_TOPIC {
+                track(&mut arming_state, "arming_state", |from, to| {
+                    StateChange::Arming { from, to }
+                });
+                track(&mut nav_state, "nav_state", |from, to| StateChange::Nav {
+                    from,
+                    to,
+                });
+            } else {
+                track(&mut main_state, "main_state", |from, to| {
+                    StateChange::Main { from, to }
+                });
+            }
+        }
+
+        // Records of different topics are not necessarily logged in timestamp order.
+        transitions.sort_by_key(|(timestamp, _)| *timestamp);
+
+        Ok(transitions)
+    }
+}
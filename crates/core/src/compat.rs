@@ -0,0 +1,152 @@
+//! A cheap pre-flight check of whether a log can be parsed, from its first few bytes only.
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::model::msg::FlagBits;
+use crate::model::MAGIC;
+use crate::parser::ULogMessageType;
+
+/// The highest ULOG file version this crate knows how to parse.
+pub const MAX_SUPPORTED_VERSION: u8 = 1;
+
+/// The size of the file header: the magic bytes, the version and the start timestamp.
+const FILE_HEADER_SIZE: usize = 16;
+
+/// The size of a FLAG_BITS message, including its 3 byte message header.
+const FLAG_BITS_MESSAGE_SIZE: usize = 3 + 40;
+
+/// A feature which a reader must understand to parse a log, as declared by its incompat flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequiredFeature {
+    /// Data is appended to the log at the offsets given in the FLAG_BITS message, e.g. a crash dump.
+    AppendedData,
+    /// An incompat flag bit which this crate does not know, such as a compression scheme introduced
+    /// by a later version of the format.  `byte` and `bit` locate the flag within `incompat_flags`.
+    Unknown { byte: usize, bit: u8 },
+}
+
+impl RequiredFeature {
+    /// Returns true if this crate can parse logs which require this feature.
+    pub fn is_supported(&self) -> bool {
+        matches!(self, RequiredFeature::AppendedData)
+    }
+}
+
+/// A reason why a log cannot be parsed by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompatProblem {
+    /// Fewer bytes were given than are needed to read the file header and FLAG_BITS message.
+    Truncated,
+    /// The file does not start with the ULOG magic bytes.
+    InvalidMagicBits,
+    /// The file version is newer than `MAX_SUPPORTED_VERSION`.
+    UnsupportedVersion(u8),
+    /// The log requires a feature which this crate does not support.
+    UnsupportedFeature(RequiredFeature),
+}
+
+/// The result of `compatibility_check()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatReport {
+    /// The file version, or `None` if the header could not be read.
+    pub version: Option<u8>,
+    /// The features declared by the incompat flags, in flag bit order.
+    pub required_features: Vec<RequiredFeature>,
+    /// Everything which prevents the log from being parsed.  Empty if the log can be parsed.
+    pub problems: Vec<CompatProblem>,
+}
+
+impl CompatReport {
+    /// Returns true if this crate can fully parse the log.
+    pub fn can_parse(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Checks whether this crate can parse a log, given the bytes at the start of the file.
+///
+/// Only the file header and the FLAG_BITS message which follows it are examined, so `bytes` need
+/// only hold the first 59 bytes of the file.  This allows incompatible files to be rejected, e.g.
+/// by an upload gate, without starting a parse which is bound to fail.
+///
+/// Logs without a FLAG_BITS message, as written by older loggers, require no features.
+pub fn compatibility_check(bytes: &[u8]) -> CompatReport {
+    let mut report = CompatReport {
+        version: None,
+        required_features: Vec::new(),
+        problems: Vec::new(),
+    };
+
+    if bytes.len() < FILE_HEADER_SIZE {
+        report.problems.push(CompatProblem::Truncated);
+        return report;
+    }
+
+    if bytes[0..7] != MAGIC {
+        report.problems.push(CompatProblem::InvalidMagicBits);
+        return report;
+    }
+
+    let version = bytes[7];
+    report.version = Some(version);
+    if version > MAX_SUPPORTED_VERSION {
+        report
+            .problems
+            .push(CompatProblem::UnsupportedVersion(version));
+    }
+
+    let message = &bytes[FILE_HEADER_SIZE..];
+    if message.len() < 3 {
+        report.problems.push(CompatProblem::Truncated);
+        return report;
+    }
+
+    if !matches!(
+        ULogMessageType::from(message[2]),
+        ULogMessageType::FLAG_BITS
+    ) {
+        return report;
+    }
+
+    if message.len() < FLAG_BITS_MESSAGE_SIZE {
+        report.problems.push(CompatProblem::Truncated);
+        return report;
+    }
+
+    let payload = &message[3..FLAG_BITS_MESSAGE_SIZE];
+    let flag_bits = FlagBits {
+        // Unwraps are safe: the slices are exactly 8 bytes long.
+        compat_flags: payload[0..8].try_into().unwrap(),
+        incompat_flags: payload[8..16].try_into().unwrap(),
+        appended_data_offsets: [
+            LittleEndian::read_u64(&payload[16..24]),
+            LittleEndian::read_u64(&payload[24..32]),
+            LittleEndian::read_u64(&payload[32..40]),
+        ],
+    };
+
+    if flag_bits.has_data_appended() {
+        report.required_features.push(RequiredFeature::AppendedData);
+    }
+
+    for (byte, &flags) in flag_bits.incompat_flags.iter().enumerate() {
+        for bit in 0..8u8 {
+            // Bit 0 of the first byte is the DATA_APPENDED flag handled above.
+            if flags & (1 << bit) != 0 && (byte, bit) != (0, 0) {
+                report
+                    .required_features
+                    .push(RequiredFeature::Unknown { byte, bit });
+            }
+        }
+    }
+
+    for &feature in &report.required_features {
+        if !feature.is_supported() {
+            report
+                .problems
+                .push(CompatProblem::UnsupportedFeature(feature));
+        }
+    }
+
+    report
+}
@@ -0,0 +1,57 @@
+//! A heuristic estimate of how well the data of each topic compresses, for storage planning.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::{ULogMessageType, ULogParser};
+
+/// The number of payload bytes of each subscription which are compressed to estimate its ratio.
+const SAMPLE_BYTES: usize = 64 * 1024;
+
+impl<R: Read> ULogParser<R> {
+    /// Estimates the compression ratio of each subscription: its payload size divided by the
+    /// size after compression, so higher values mean more compressible data.
+    ///
+    /// The first 64 KiB of the payloads of each subscription, concatenated in file order, are
+    /// compressed with LZ4.  This is a heuristic: other compressors, and larger samples, typically
+    /// achieve better ratios.  Messages are not decoded, so this is a cheap scan.  Keys are
+    /// formatted as for [`ULogParser::bytes_per_subscription`].
+    pub fn compressibility_estimate(mut self) -> Result<HashMap<String, f64>, ULogError> {
+        self.set_allowed_subscription_names(Vec::new());
+        self.drop_ignored = false;
+
+        let mut samples_by_msg_id: HashMap<u16, Vec<u8>> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::Ignored {
+                msg_type,
+                message_contents,
+            } = msg_res?
+            {
+                if !matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA) {
+                    continue;
+                }
+
+                let msg_id = u16::from_le_bytes([message_contents[0], message_contents[1]]);
+                let sample = samples_by_msg_id.entry(msg_id).or_default();
+
+                let payload = &message_contents[2..];
+                let take = payload.len().min(SAMPLE_BYTES - sample.len());
+                sample.extend_from_slice(&payload[..take]);
+            }
+        }
+
+        let ratios = samples_by_msg_id
+            .into_iter()
+            .filter(|(_, sample)| !sample.is_empty())
+            .map(|(msg_id, sample)| {
+                let compressed = lz4_flex::compress(&sample);
+                (msg_id, sample.len() as f64 / compressed.len() as f64)
+            })
+            .collect();
+
+        self.per_subscription(ratios)
+    }
+}
@@ -0,0 +1,81 @@
+//! Export of the vehicle configuration, i.e. the info messages and parameters, as TOML.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use toml::{Table, Value};
+
+use crate::errors::ULogError;
+use crate::model::inst::{FieldValue, ParameterValue};
+use crate::model::msg::{InfoValue, UlogMessage};
+use crate::parser::ULogParser;
+
+/// Reads a log and returns its configuration as a TOML document, for archiving alongside it.
+///
+/// The document has an `[info]` table holding the `Info` messages, and a `[params]` table holding
+/// the parameters.  Parameters changed during the flight take their last value, so the snapshot
+/// is the configuration the vehicle ended the flight with.  Integers, floats, bools and strings
+/// are rendered as the corresponding TOML types, and arrays as TOML arrays.
+///
+/// `uint64_t` values above `i64::MAX` do not fit a TOML integer, and are rendered as floats.
+pub fn config_toml<R: Read>(mut parser: ULogParser<R>) -> Result<String, ULogError> {
+    let mut info = BTreeMap::new();
+    let mut params = BTreeMap::new();
+
+    for msg_res in parser.by_ref() {
+        match msg_res? {
+            UlogMessage::Info(msg) => {
+                let value = match msg.info_value() {
+                    InfoValue::Str(s) => Some(Value::String(s)),
+                    InfoValue::Scalar(value) => toml_value(&value),
+                    InfoValue::Array(_, elements) => elements
+                        .iter()
+                        .map(toml_value)
+                        .collect::<Option<Vec<_>>>()
+                        .map(Value::Array),
+                };
+
+                if let Some(value) = value {
+                    info.insert(msg.key, value);
+                }
+            }
+            UlogMessage::Parameter(param) => {
+                let value = match param.value {
+                    ParameterValue::INT32(v) => Value::Integer(i64::from(v)),
+                    ParameterValue::FLOAT(v) => Value::Float(natural_f64(v)),
+                };
+                params.insert(param.key, value);
+            }
+            _ => {}
+        }
+    }
+
+    let mut document = Table::new();
+    document.insert("info".to_string(), Value::Table(info.into_iter().collect()));
+    document.insert(
+        "params".to_string(),
+        Value::Table(params.into_iter().collect()),
+    );
+
+    Ok(document.to_string())
+}
+
+/// Converts a scalar field value, returning `None` for nested formats and arrays.
+fn toml_value(value: &FieldValue) -> Option<Value> {
+    match value {
+        FieldValue::ScalarBool(v) => Some(Value::Boolean(*v)),
+        FieldValue::ScalarChar(v) => Some(Value::String(v.to_string())),
+        FieldValue::ScalarF32(v) => Some(Value::Float(natural_f64(*v))),
+        _ => value
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| value.as_f64().map(Value::Float)),
+    }
+}
+
+/// Widens an `f32` to the `f64` with the same shortest decimal representation, so that e.g. 0.1
+/// is rendered as `0.1` rather than `0.10000000149011612`.
+fn natural_f64(v: f32) -> f64 {
+    // Unwrap is safe: the `Display` output of a float always parses as a float.
+    v.to_string().parse().unwrap()
+}
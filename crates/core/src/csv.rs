@@ -0,0 +1,182 @@
+//! Export of the records of a log as a single long-format CSV table, for plotting libraries
+//! which expect tidy data, and of its parameters in the layout of PX4's tooling.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+
+use crate::errors::ULogError;
+use crate::model::inst::{ArrayMode, FieldValue, ParameterValue};
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// Writes every numeric value of a log to `writer` as CSV, with one row per value, and returns
+/// the writer.
+///
+/// Rows have the columns `timestamp,subscription,field,value`, in the order of the records.
+/// Subscriptions are named as in [`ULogParser::bytes_per_subscription`], e.g.
+/// `actuator_outputs.01`, and fields as by `inst::Format::flatten_fields()` with `mode`, e.g.
+/// `accel/x`, or `output.03` with `ArrayMode::Expand`.  With `ArrayMode::Nested` an array is
+/// written as a single value listing its elements, e.g. `"[1,2,3]"`, while arrays of nested
+/// formats are still written field by field, e.g. `esc.00/rpm`.  Only integer and float values
+/// are written: bools, chars and opaque fields are left out, as is the `timestamp` field, which
+/// is the first column of every row.
+pub fn export_long_csv<R: Read, W: Write>(
+    mut parser: ULogParser<R>,
+    mut writer: W,
+    mode: ArrayMode,
+) -> Result<W, ULogError> {
+    // The name of each subscription, keyed by msg_id.
+    let mut names: HashMap<u16, String> = HashMap::new();
+
+    writeln!(writer, "timestamp,subscription,field,value")?;
+
+    while let Some(msg_res) = parser.next() {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+
+        let subscription = match names.get(&data.msg_id) {
+            Some(name) => name,
+            None => {
+                let sub = parser.get_subscription(data.msg_id)?;
+                let name = quote(&parser.subscription_display_name(&sub));
+                names.entry(data.msg_id).or_insert(name)
+            }
+        };
+
+        for (field, value) in data.data.flatten_columns(mode) {
+            if field == "timestamp" {
+                continue;
+            }
+            if let Some(value) = numeric_text(&value) {
+                writeln!(
+                    writer,
+                    "{},{subscription},{},{}",
+                    data.timestamp,
+                    quote(&field),
+                    quote(&value)
+                )?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(writer)
+}
+
+/// Writes the initial parameters of a log to `writer` as CSV, and returns the writer.
+///
+/// The layout is that of pyulog's `ulog_params --initial`, so the output can replace it in
+/// existing scripts: one `name,value` row per parameter, sorted by name, with no header row.
+/// Floats are formatted as by Python's `str()`, e.g. `1.0` or `0.10000000149011612`.  Only the
+/// definitions section is read, so parameters changed in flight are left out.
+pub fn export_params_csv<R: Read, W: Write>(
+    parser: ULogParser<R>,
+    mut writer: W,
+) -> Result<W, ULogError> {
+    let mut parameters = BTreeMap::new();
+
+    for msg_res in parser {
+        match msg_res? {
+            UlogMessage::Parameter(param) => {
+                parameters.insert(param.key, param.value);
+            }
+            // The first subscription starts the data section.
+            UlogMessage::AddSubscription(_) => break,
+            _ => {}
+        }
+    }
+
+    for (name, value) in parameters {
+        let value = match value {
+            ParameterValue::INT32(v) => v.to_string(),
+            ParameterValue::FLOAT(v) => python_float_text(f64::from(v)),
+        };
+        writeln!(writer, "{},{value}", quote(&name))?;
+    }
+
+    writer.flush()?;
+    Ok(writer)
+}
+
+/// Formats a float as Python's `str()` does: the fewest digits which read back as the same
+/// value, always with a decimal point or an exponent, and in scientific notation if the decimal
+/// exponent is below -4 or above 15.
+fn python_float_text(value: f64) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+
+    // The shortest form gives the number of digits, but may round a tie up where Python rounds
+    // it to even, so the digits are rounded again at that precision.
+    let shortest = format!("{:e}", value.abs());
+    let precision = shortest
+        .split('e')
+        .next()
+        .unwrap_or_default()
+        .len()
+        .saturating_sub(2);
+    let scientific = format!("{:.precision$e}", value.abs());
+
+    // Unwraps are safe: `{:e}` always writes a mantissa and a valid exponent.
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let digits = mantissa.replace('.', "");
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+
+    if !(-4..16).contains(&exponent) {
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+        return format!("{sign}{mantissa}e{exponent_sign}{:02}", exponent.abs());
+    }
+
+    if exponent < 0 {
+        let zeros = "0".repeat(exponent.unsigned_abs() as usize - 1);
+        return format!("{sign}0.{zeros}{digits}");
+    }
+
+    let integer_len = exponent as usize + 1;
+    if digits.len() <= integer_len {
+        format!("{sign}{digits:0<integer_len$}.0")
+    } else {
+        let (integer, fraction) = digits.split_at(integer_len);
+        format!("{sign}{integer}.{fraction}")
+    }
+}
+
+/// Formats an integer or float scalar, keeping the full precision of its type, or an array of
+/// them as a bracketed list.
+fn numeric_text(value: &FieldValue) -> Option<String> {
+    if let Some(elements) = value.to_scalars() {
+        let elements = elements
+            .iter()
+            .map(numeric_text)
+            .collect::<Option<Vec<_>>>()?;
+        return Some(format!("[{}]", elements.join(",")));
+    }
+
+    match value {
+        FieldValue::ScalarU8(v) => Some(v.to_string()),
+        FieldValue::ScalarU16(v) => Some(v.to_string()),
+        FieldValue::ScalarU32(v) => Some(v.to_string()),
+        FieldValue::ScalarU64(v) => Some(v.to_string()),
+        FieldValue::ScalarI8(v) => Some(v.to_string()),
+        FieldValue::ScalarI16(v) => Some(v.to_string()),
+        FieldValue::ScalarI32(v) => Some(v.to_string()),
+        FieldValue::ScalarI64(v) => Some(v.to_string()),
+        FieldValue::ScalarF32(v) => Some(v.to_string()),
+        FieldValue::ScalarF64(v) => Some(v.to_string()),
+        _ => None,
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn quote(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
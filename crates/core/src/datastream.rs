@@ -1,4 +1,5 @@
-use std::io::{ErrorKind, Read};
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
 
 use byteorder::{ByteOrder, LittleEndian};
 
@@ -9,6 +10,12 @@ pub struct DataStream<R: Read> {
     reader: R,
     pub(crate) num_bytes_read: usize,
     pub(crate) eof: bool,
+    // Bytes handed back with `unread()` or read ahead, which are read before the reader.
+    pushback: VecDeque<u8>,
+    // The most bytes requested from the reader at once when reading ahead, or 0 to read only the
+    // bytes asked for.
+    read_ahead: usize,
+    read_ahead_buf: Vec<u8>,
 }
 
 impl<R: Read> DataStream<R> {
@@ -17,9 +24,55 @@ impl<R: Read> DataStream<R> {
             reader,
             num_bytes_read: 0,
             eof: false,
+            pushback: VecDeque::new(),
+            read_ahead: 0,
+            read_ahead_buf: Vec::new(),
         }
     }
 
+    /// Buffers up to `num_bytes` ahead of the current position, so that small reads are served
+    /// from a single larger read of the reader.  0 disables reading ahead.
+    pub(crate) fn set_read_ahead(&mut self, num_bytes: usize) {
+        self.read_ahead = num_bytes;
+        self.read_ahead_buf = vec![0; num_bytes];
+    }
+
+    /// Returns the capacity of the buffers of bytes read ahead or handed back.
+    pub(crate) fn buffered_capacity(&self) -> usize {
+        self.pushback.capacity() + self.read_ahead_buf.capacity()
+    }
+
+    #[cfg(feature = "bytes")]
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Pushes `bytes` back onto the front of the stream, so they are read again next.
+    pub(crate) fn unread(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter().rev() {
+            self.pushback.push_front(byte);
+        }
+        self.num_bytes_read -= bytes.len();
+
+        if !bytes.is_empty() {
+            self.eof = false;
+        }
+    }
+
+    // Fills the start of `buf` from the pushback, returning the number of bytes filled.
+    fn read_pushback(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.pushback.len());
+        for (dst, src) in buf[..n].iter_mut().zip(self.pushback.drain(..n)) {
+            *dst = src;
+        }
+        n
+    }
+
     pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<usize, ULogError> {
         log::trace!(
             "datastream read from:  [{:04X}-{:04X}]",
@@ -28,21 +81,53 @@ impl<R: Read> DataStream<R> {
         );
         self.num_bytes_read += buf.len();
 
-        match self.reader.read_exact(buf) {
-            Ok(()) => Ok(buf.len()),
-            Err(err) => match err.kind() {
+        let mut filled = self.read_pushback(buf);
+        let remaining = buf.len() - filled;
+
+        // Reads larger than the window go straight to the reader, as buffering gains nothing.
+        if remaining > 0 && remaining < self.read_ahead {
+            self.fill_read_ahead(remaining).map_err(ULogError::Io)?;
+            filled += self.read_pushback(&mut buf[filled..]);
+        }
+
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
                 // Eof is not technically an error, so signal it by reporting 0 bytes read and setting eof true.
-                ErrorKind::UnexpectedEof => {
+                // The position only counts the bytes actually read, e.g. of a truncated message.
+                Ok(0) => {
                     self.eof = true;
-                    Ok(0)
+                    self.num_bytes_read -= buf.len() - filled;
+                    return Ok(0);
                 }
-                _ => Err(ULogError::Io(err)),
-            },
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(ULogError::Io(err)),
+            }
         }
+
+        Ok(buf.len())
+    }
+
+    // Reads ahead into the pushback until it holds at least `min_len` bytes, or the reader ends.
+    fn fill_read_ahead(&mut self, min_len: usize) -> std::io::Result<()> {
+        while self.pushback.len() < min_len {
+            match self.reader.read(&mut self.read_ahead_buf) {
+                Ok(0) => break,
+                Ok(n) => self.pushback.extend(&self.read_ahead_buf[..n]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
     }
 
     /// Skips the specified number of bytes in the underlying reader.
     pub fn skip(&mut self, num_bytes: usize) -> Result<usize, ULogError> {
+        let from_pushback = num_bytes.min(self.pushback.len());
+        self.pushback.drain(..from_pushback);
+        self.num_bytes_read += from_pushback;
+
+        let num_bytes = num_bytes - from_pushback;
         let mut total_skipped = 0;
         while total_skipped < num_bytes {
             // Calculate how many bytes remaining to skip
@@ -62,7 +147,7 @@ impl<R: Read> DataStream<R> {
             total_skipped += bytes_read;
         }
         self.num_bytes_read += total_skipped;
-        Ok(total_skipped)
+        Ok(from_pushback + total_skipped)
     }
 
     pub fn read_u8(&mut self) -> Result<u8, ULogError> {
@@ -134,6 +219,50 @@ impl<R: Read> DataStream<R> {
     }
 }
 
+#[cfg(feature = "bytes")]
+impl DataStream<crate::bytes_reader::BytesReader> {
+    /// Moves `num_bytes` past the current position without reading them.
+    ///
+    /// Any bytes read ahead are copies of the buffer at the current position, so they are dropped
+    /// rather than drained.
+    pub(crate) fn advance(&mut self, num_bytes: usize) {
+        self.pushback.clear();
+        self.num_bytes_read += num_bytes;
+        self.reader.set_position(self.num_bytes_read);
+    }
+}
+
+impl<R: Read + Seek> DataStream<R> {
+    /// Skips the specified number of bytes by seeking, without reading them.
+    pub(crate) fn seek_forward(&mut self, num_bytes: usize) -> Result<(), ULogError> {
+        let from_pushback = num_bytes.min(self.pushback.len());
+        self.pushback.drain(..from_pushback);
+        self.num_bytes_read += from_pushback;
+
+        let num_bytes = num_bytes - from_pushback;
+        if num_bytes > 0 {
+            self.reader.seek(SeekFrom::Current(num_bytes as i64))?;
+            self.num_bytes_read += num_bytes;
+        }
+        Ok(())
+    }
+
+    /// Returns the length of the underlying stream.  The position is left at the end, so it
+    /// must be restored with `seek_to()`.
+    pub(crate) fn stream_len(&mut self) -> Result<usize, ULogError> {
+        Ok(self.reader.seek(SeekFrom::End(0))? as usize)
+    }
+
+    /// Moves to the absolute byte `offset`, discarding any bytes pushed back or read ahead.
+    pub(crate) fn seek_to(&mut self, offset: usize) -> Result<(), ULogError> {
+        self.pushback.clear();
+        self.reader.seek(SeekFrom::Start(offset as u64))?;
+        self.num_bytes_read = offset;
+        self.eof = false;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{Seek, SeekFrom, Write};
@@ -355,4 +484,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_datastream_unread() -> Result<(), ULogError> {
+        let mut datastream = DataStream::new(&[1u8, 2, 3, 4, 5, 6][..]);
+
+        assert_eq!(datastream.read_u16()?, 0x0201);
+        datastream.unread(&[9, 2]);
+        assert_eq!(datastream.num_bytes_read, 0);
+
+        // Pushed back bytes come first, and reads may span both them and the reader.
+        let mut buf = [0; 3];
+        datastream.read_exact(&mut buf)?;
+        assert_eq!(buf, [9, 2, 3]);
+
+        datastream.unread(&[3]);
+        assert_eq!(datastream.skip(2)?, 2);
+        assert_eq!(datastream.read_u8()?, 5);
+        assert_eq!(datastream.num_bytes_read, 5);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,175 @@
+//! Delta encoding of the record stream: each record reduced to the fields which changed.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::model::inst::{ArrayMode, FieldValue};
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// A `LoggedData` record reduced to the fields which changed since the previous record of the
+/// same subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDelta {
+    pub msg_id: u16,
+    pub timestamp: u64,
+    /// The changed fields, as `(column name, new value)` pairs in field order.  Column names are
+    /// those of `inst::Format::flatten_fields()` with `ArrayMode::Expand`, e.g. `accel/x` or
+    /// `output.03`, so a change to one array element only includes that element.
+    pub changed: Vec<(String, FieldValue)>,
+}
+
+/// The records of a log as deltas, created by [`ULogParser::changed_fields`].
+pub struct ChangedFields<R: Read> {
+    parser: ULogParser<R>,
+    epsilon: f64,
+    // The flattened fields of the previous record of each subscription.
+    previous: HashMap<u16, Vec<(String, FieldValue)>>,
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Converts the `LoggedData` records into [`FieldDelta`]s, holding only the fields which
+    /// changed since the previous record of the same subscription.
+    ///
+    /// The first record of each subscription includes every field.  Floats are considered
+    /// unchanged if they differ by no more than `epsilon`; other values must be equal.  A record
+    /// in which nothing changed is still yielded, with no fields, so that its timestamp is kept.
+    /// All other messages are skipped.
+    pub fn changed_fields(self, epsilon: f64) -> ChangedFields<R> {
+        ChangedFields {
+            parser: self,
+            epsilon,
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Lists the fields of each subscription whose value never changed, e.g. to find candidates
+    /// to move into parameters or info messages.
+    ///
+    /// Every record is compared against the first record of its subscription in a single pass,
+    /// with floats allowed to differ by up to `epsilon`.  Fields are named as in
+    /// [`FieldDelta::changed`], and listed in field order.  Keys are formatted as for
+    /// [`ULogParser::bytes_per_subscription`].
+    pub fn constant_fields(
+        mut self,
+        epsilon: f64,
+    ) -> Result<HashMap<String, Vec<String>>, ULogError> {
+        // The flattened fields of the first record of each subscription, and whether each is
+        // still constant.
+        let mut first_by_msg_id: HashMap<u16, Vec<(String, FieldValue, bool)>> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            let fields = data.data.flatten_fields(ArrayMode::Expand);
+
+            match first_by_msg_id.get_mut(&data.msg_id) {
+                Some(first) => {
+                    for (index, (name, first_value, constant)) in first.iter_mut().enumerate() {
+                        if !*constant {
+                            continue;
+                        }
+
+                        // Look up by position, falling back to the name if the layout changed.
+                        let value = match fields.get(index) {
+                            Some((field_name, value)) if field_name == name => Some(value),
+                            _ => fields
+                                .iter()
+                                .find(|(field_name, _)| field_name == name)
+                                .map(|(_, value)| value),
+                        };
+
+                        *constant =
+                            value.is_some_and(|value| approx_eq(first_value, value, epsilon));
+                    }
+                }
+                None => {
+                    first_by_msg_id.insert(
+                        data.msg_id,
+                        fields
+                            .into_iter()
+                            .map(|(name, value)| (name, value, true))
+                            .collect(),
+                    );
+                }
+            }
+        }
+
+        let constant_by_msg_id = first_by_msg_id
+            .into_iter()
+            .map(|(msg_id, first)| {
+                let constant = first
+                    .into_iter()
+                    .filter(|(_, _, constant)| *constant)
+                    .map(|(name, _, _)| name)
+                    .collect();
+                (msg_id, constant)
+            })
+            .collect();
+
+        self.per_subscription(constant_by_msg_id)
+    }
+}
+
+impl<R: Read> Iterator for ChangedFields<R> {
+    type Item = Result<FieldDelta, ULogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let data = match self.parser.next()? {
+                Ok(UlogMessage::LoggedData(data)) => data,
+                Ok(_) => continue,
+                Err(err) => return Some(Err(err)),
+            };
+
+            let fields = data.data.flatten_fields(ArrayMode::Expand);
+
+            let changed = match self.previous.get(&data.msg_id) {
+                Some(previous) => fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, (name, value))| {
+                        // Compare by position, falling back to the name if the layout changed.
+                        let previous_value = match previous.get(*index) {
+                            Some((previous_name, previous_value)) if previous_name == name => {
+                                Some(previous_value)
+                            }
+                            _ => previous
+                                .iter()
+                                .find(|(previous_name, _)| previous_name == name)
+                                .map(|(_, previous_value)| previous_value),
+                        };
+
+                        previous_value.is_none_or(|previous_value| {
+                            !approx_eq(previous_value, value, self.epsilon)
+                        })
+                    })
+                    .map(|(_, field)| field.clone())
+                    .collect(),
+                None => fields.clone(),
+            };
+
+            self.previous.insert(data.msg_id, fields);
+
+            return Some(Ok(FieldDelta {
+                msg_id: data.msg_id,
+                timestamp: data.timestamp,
+                changed,
+            }));
+        }
+    }
+}
+
+/// Compares two values, allowing floats to differ by up to `epsilon`.  NaNs are equal to NaNs.
+fn approx_eq(a: &FieldValue, b: &FieldValue, epsilon: f64) -> bool {
+    let close = |a: f64, b: f64| (a.is_nan() && b.is_nan()) || (a - b).abs() <= epsilon;
+
+    match (a, b) {
+        (FieldValue::ScalarF32(a), FieldValue::ScalarF32(b)) => close(f64::from(*a), f64::from(*b)),
+        (FieldValue::ScalarF64(a), FieldValue::ScalarF64(b)) => close(*a, *b),
+        _ => a == b,
+    }
+}
@@ -0,0 +1,203 @@
+//! A `serde::Deserializer` over decoded records, to populate any `#[derive(Deserialize)]` struct.
+//!
+//! Fields map to struct fields by name, so `#[serde(rename)]`, `#[serde(default)]` and the other
+//! serde attributes apply as usual.  Nested formats deserialize as structs, arrays as sequences
+//! or fixed-size arrays, and `char` arrays as strings.
+
+use std::fmt::Display;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, Deserialize, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::errors::ULogError;
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::LoggedData;
+
+impl de::Error for ULogError {
+    fn custom<T: Display>(msg: T) -> Self {
+        ULogError::Deserialize(msg.to_string())
+    }
+}
+
+/// Deserializes a `LoggedData` record into `T`.
+pub fn from_record<'a, T: Deserialize<'a>>(data: &'a LoggedData) -> Result<T, ULogError> {
+    T::deserialize(RecordDeserializer::new(data))
+}
+
+/// Deserializes the fields of a record as a map from field names to values.
+///
+/// The `timestamp` field is always present, even if the parser was built without
+/// `include_timestamp(true)`.
+pub struct RecordDeserializer<'a> {
+    format: &'a inst::Format,
+    timestamp: Option<u64>,
+}
+
+impl<'a> RecordDeserializer<'a> {
+    pub fn new(data: &'a LoggedData) -> Self {
+        let has_timestamp = data.data.fields.iter().any(|f| f.name == "timestamp");
+
+        RecordDeserializer {
+            format: &data.data,
+            timestamp: (!has_timestamp).then_some(data.timestamp),
+        }
+    }
+
+    fn nested(format: &'a inst::Format) -> Self {
+        RecordDeserializer {
+            format,
+            timestamp: None,
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for RecordDeserializer<'de> {
+    type Error = ULogError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ULogError> {
+        let timestamp = self
+            .timestamp
+            .map(|timestamp| ("timestamp", ValueDeserializer::Timestamp(timestamp)));
+        let fields = self
+            .format
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), ValueDeserializer::Field(&field.value)));
+
+        let mut map = MapDeserializer::new(timestamp.into_iter().chain(fields));
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ULogError> for RecordDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Deserializes the value of a single field.
+enum ValueDeserializer<'a> {
+    Field(&'a FieldValue),
+    // The timestamp of a record whose `timestamp` field was removed by the parser.
+    Timestamp(u64),
+}
+
+impl<'de> ValueDeserializer<'de> {
+    fn visit_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ULogError> {
+        fn visit<'de, V, I>(visitor: V, iter: I) -> Result<V::Value, ULogError>
+        where
+            V: Visitor<'de>,
+            I: Iterator,
+            I::Item: IntoDeserializer<'de, ULogError>,
+        {
+            let mut seq = SeqDeserializer::new(iter);
+            let value = visitor.visit_seq(&mut seq)?;
+            seq.end()?;
+            Ok(value)
+        }
+
+        match self {
+            ValueDeserializer::Field(FieldValue::ArrayU8(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayU16(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayU32(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayU64(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayI8(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayI16(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayI32(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayI64(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayF32(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayF64(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayBool(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayChar(v)) => visit(visitor, v.iter().copied()),
+            ValueDeserializer::Field(FieldValue::ArrayOther(v)) => {
+                visit(visitor, v.iter().map(RecordDeserializer::nested))
+            }
+            value => de::Deserializer::deserialize_any(value, visitor),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = ULogError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ULogError> {
+        let value = match self {
+            ValueDeserializer::Field(value) => value,
+            ValueDeserializer::Timestamp(timestamp) => return visitor.visit_u64(timestamp),
+        };
+
+        match value {
+            FieldValue::ScalarU8(v) => visitor.visit_u8(*v),
+            FieldValue::ScalarU16(v) => visitor.visit_u16(*v),
+            FieldValue::ScalarU32(v) => visitor.visit_u32(*v),
+            FieldValue::ScalarU64(v) => visitor.visit_u64(*v),
+            FieldValue::ScalarI8(v) => visitor.visit_i8(*v),
+            FieldValue::ScalarI16(v) => visitor.visit_i16(*v),
+            FieldValue::ScalarI32(v) => visitor.visit_i32(*v),
+            FieldValue::ScalarI64(v) => visitor.visit_i64(*v),
+            FieldValue::ScalarF32(v) => visitor.visit_f32(*v),
+            FieldValue::ScalarF64(v) => visitor.visit_f64(*v),
+            FieldValue::ScalarBool(v) => visitor.visit_bool(*v),
+            FieldValue::ScalarChar(v) => visitor.visit_char(*v),
+            FieldValue::ScalarOther(nested) => {
+                RecordDeserializer::nested(nested).deserialize_any(visitor)
+            }
+            FieldValue::ArrayChar(v) => {
+                let string: String = v.iter().collect();
+                visitor.visit_str(string.trim_end_matches('\0'))
+            }
+            FieldValue::Skipped(_) => visitor.visit_unit(),
+            FieldValue::Opaque(v) => visitor.visit_borrowed_bytes(v),
+            _ => self.visit_seq(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ULogError> {
+        visitor.visit_some(self)
+    }
+
+    // `char` arrays are strings by default, but can also be read as a sequence of chars.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ULogError> {
+        self.visit_seq(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ULogError> {
+        self.visit_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ULogError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ULogError> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
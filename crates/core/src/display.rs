@@ -44,6 +44,9 @@ impl std::fmt::Display for inst::FieldValue {
                 let formatted: Vec<String> = arr.iter().map(|f| format!("{{{f}}}")).collect();
                 write!(f, "[{}]", formatted.join(", "))
             }
+
+            FieldValue::Skipped(len) => write!(f, "<{len} elements skipped>"),
+            FieldValue::Opaque(bytes) => write!(f, "<{} opaque bytes>", bytes.len()),
         }
     }
 }
@@ -140,12 +143,25 @@ impl fmt::Display for msg::Info {
                 let formatted: Vec<String> = arr.iter().map(|fmt| format!("{{{fmt}}}")).collect();
                 write!(f, "[{}]", formatted.join(", "))?;
             }
+
+            FieldValue::Skipped(len) => write!(f, "<{len} elements skipped>")?,
+            FieldValue::Opaque(bytes) => write!(f, "<{} opaque bytes>", bytes.len())?,
         }
 
         Ok(())
     }
 }
 
+impl fmt::Display for msg::InfoValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            msg::InfoValue::Scalar(value) => write!(f, "{value}"),
+            msg::InfoValue::Array(_, elements) => fmt_array(elements, f),
+            msg::InfoValue::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
 impl fmt::Display for msg::MultiInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}: ", self.key)?;
@@ -194,6 +210,9 @@ impl fmt::Display for msg::MultiInfo {
                 let formatted: Vec<String> = arr.iter().map(|fmt| format!("{{{fmt}}}")).collect();
                 write!(f, "[{}]", formatted.join(", "))?;
             }
+
+            FieldValue::Skipped(len) => write!(f, "<{len} elements skipped>")?,
+            FieldValue::Opaque(bytes) => write!(f, "<{} opaque bytes>", bytes.len())?,
         }
 
         Ok(())
@@ -231,11 +250,14 @@ impl fmt::Display for def::Field {
 
 impl fmt::Display for def::TypeExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self.array_size {
-            None => {
+        match (self.array_size, self.matrix_shape) {
+            (_, Some((rows, columns))) => {
+                write!(f, "{}[{}][{}]", self.base_type, rows, columns)
+            }
+            (None, None) => {
                 write!(f, "{}", self.base_type)
             }
-            Some(array_size) => {
+            (Some(array_size), None) => {
                 write!(f, "{}[{}]", self.base_type, array_size)
             }
         }
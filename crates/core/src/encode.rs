@@ -1,4 +1,4 @@
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 
 use crate::model::msg::{LoggedData, UlogMessage};
 use crate::model::{def, inst, msg};
@@ -19,6 +19,8 @@ impl Encode for UlogMessage {
                 writer.write_all(&header.to_bytes())?;
                 Ok(())
             }
+            // Markers only, which have no representation in the file.
+            UlogMessage::AppendedSectionStart { .. } | UlogMessage::Corrupt { .. } => Ok(()),
             other => {
                 // Wrap in Message struct with size and type prefix
                 let mut content_buf = Vec::new();
@@ -36,7 +38,7 @@ impl Encode for UlogMessage {
 
 impl UlogMessage {
     // Return the message type code as u8
-    fn message_type(&self) -> ULogMessageType {
+    pub(crate) fn message_type(&self) -> ULogMessageType {
         match self {
             UlogMessage::FlagBits(_) => ULogMessageType::FLAG_BITS,
             UlogMessage::FormatDefinition(_) => ULogMessageType::FORMAT,
@@ -46,18 +48,37 @@ impl UlogMessage {
             UlogMessage::MultiInfo(_) => ULogMessageType::INFO_MULTIPLE,
             UlogMessage::Parameter(_) => ULogMessageType::PARAMETER,
             UlogMessage::DefaultParameter(_) => ULogMessageType::PARAMETER_DEFAULT,
+            // The parser yields tagged strings as `LoggedString`, with a tag.
+            UlogMessage::LoggedString(logged_string) if logged_string.tag.is_some() => {
+                ULogMessageType::LOGGING_TAGGED
+            }
             UlogMessage::LoggedString(_) => ULogMessageType::LOGGING,
             UlogMessage::TaggedLoggedString(_) => ULogMessageType::LOGGING_TAGGED,
             UlogMessage::DropoutMark(_) => ULogMessageType::DROPOUT,
             UlogMessage::Unhandled { msg_type, .. } | UlogMessage::Ignored { msg_type, .. } => {
                 ULogMessageType::from(*msg_type)
             }
-            UlogMessage::Header(_) => unreachable!("Handled separately"),
+            UlogMessage::Header(_)
+            | UlogMessage::AppendedSectionStart { .. }
+            | UlogMessage::Corrupt { .. } => unreachable!("Handled separately"),
+        }
+    }
+
+    /// Returns the ULOG message type byte of the message, e.g. `b'D'` for `LoggedData`.
+    ///
+    /// Returns `None` for the file header and for the `AppendedSectionStart` and `Corrupt`
+    /// markers, which are not messages in the file.
+    pub fn type_byte(&self) -> Option<u8> {
+        match self {
+            UlogMessage::Header(_)
+            | UlogMessage::AppendedSectionStart { .. }
+            | UlogMessage::Corrupt { .. } => None,
+            _ => Some(u8::from(self.message_type())),
         }
     }
 
     // Encode the inner content bytes without prefix (size/type)
-    fn encode_content<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+    pub(crate) fn encode_content<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         match self {
             UlogMessage::FlagBits(flag_bits) => flag_bits.encode(writer),
             UlogMessage::FormatDefinition(format) => format.encode(writer),
@@ -76,7 +97,9 @@ impl UlogMessage {
             | UlogMessage::Ignored {
                 message_contents, ..
             } => writer.write_all(message_contents),
-            UlogMessage::Header(_) => unreachable!("Handled separately"),
+            UlogMessage::Header(_)
+            | UlogMessage::AppendedSectionStart { .. }
+            | UlogMessage::Corrupt { .. } => unreachable!("Handled separately"),
         }
     }
 }
@@ -242,7 +265,9 @@ impl Encode for def::TypeExpr {
     fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         self.base_type.encode(writer)?;
 
-        if let Some(array_size) = self.array_size {
+        if let Some((rows, columns)) = self.matrix_shape {
+            writer.write_all(format!("[{rows}][{columns}]").as_bytes())?;
+        } else if let Some(array_size) = self.array_size {
             writer.write_all(b"[")?;
             writer.write_all(array_size.to_string().as_bytes())?;
             writer.write_all(b"]")?;
@@ -381,6 +406,11 @@ impl Encode for inst::FieldValue {
                 }
                 Ok(())
             }
+            Skipped(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "A skipped field has no value to encode",
+            )),
+            Opaque(bytes) => writer.write_all(bytes),
         }
     }
 }
@@ -404,6 +434,61 @@ impl<T: Encode> Encode for &T {
     }
 }
 
+// ------------------------ EncodedReader ------------------------
+
+/// A `Read` adapter which serializes messages from an iterator on demand.
+///
+/// Each message is encoded only once the bytes of the previous one have been read, so the
+/// complete stream is never held in memory.  Reading the adapter to the end yields the same bytes
+/// as calling `encode` on each message in turn.
+pub struct EncodedReader<I> {
+    messages: I,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<I, T> EncodedReader<I>
+where
+    I: Iterator<Item = T>,
+    T: Encode,
+{
+    pub fn new<M: IntoIterator<IntoIter = I>>(messages: M) -> Self {
+        EncodedReader {
+            messages: messages.into_iter(),
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<I, T> Read for EncodedReader<I>
+where
+    I: Iterator<Item = T>,
+    T: Encode,
+{
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+
+        // Refill from the next message, skipping any which encode to nothing.
+        while self.pos == self.buf.len() {
+            let Some(message) = self.messages.next() else {
+                return Ok(0);
+            };
+
+            self.buf.clear();
+            self.pos = 0;
+            message.encode(&mut self.buf)?;
+        }
+
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 // Convenience method to encode to Vec<u8>
 trait EncodeToVec {
     fn encode_to_vec(&self) -> io::Result<Vec<u8>>;
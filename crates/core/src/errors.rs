@@ -50,6 +50,9 @@ pub enum ULogError {
     #[error("Invalid MultiInfo message. {0}")]
     InvalindMultiInfo(String),
 
+    #[error("MultiInfo continuation for key {0} without a preceding message")]
+    DanglingContinuation(String),
+
     #[error("Invalid Default Parameter Type")]
     InvalidDefaultParameterType,
 
@@ -61,4 +64,55 @@ pub enum ULogError {
 
     #[error("Invalid parser configuration: {0}")]
     InvalidConfiguration(String),
+
+    #[error("Skipped {skipped_bytes} bytes to resynchronize at the next SYNC message")]
+    Resynchronized { skipped_bytes: u64 },
+
+    #[error("The log declares more than the maximum of {0} subscriptions")]
+    TooManySubscriptions(usize),
+
+    #[error("Parsing did not finish before the deadline")]
+    Timeout,
+
+    #[error(
+        "Implausible timestamp {timestamp} for msg_id {msg_id} (previous timestamp {previous})"
+    )]
+    ImplausibleTimestamp {
+        msg_id: u16,
+        timestamp: u64,
+        previous: u64,
+    },
+
+    #[error("Data dropout of {duration_ms} ms after timestamp {timestamp}")]
+    DataDropout { timestamp: u64, duration_ms: u16 },
+
+    #[error("Record at offset {offset} has timestamp {cur}, before the previous record's {prev}")]
+    OutOfOrder { prev: u64, cur: u64, offset: u64 },
+
+    #[error("Cannot write message: {0}")]
+    InvalidMessageOrder(String),
+
+    #[error("Message of {0} bytes exceeds the maximum ULOG message size")]
+    MessageTooLarge(usize),
+
+    #[error("Layout mismatch: {0}")]
+    LayoutMismatch(String),
+
+    #[error("Deserialization error: {0}")]
+    Deserialize(String),
+
+    #[error("Field {field} of format {format} is at offset {offset}, which is not a multiple of its alignment {alignment}")]
+    MisalignedField {
+        format: String,
+        field: String,
+        offset: usize,
+        alignment: usize,
+    },
+
+    #[error("Format {format} has a size of {size} bytes, which is not a multiple of its alignment {alignment}")]
+    MisalignedFormatSize {
+        format: String,
+        size: usize,
+        alignment: usize,
+    },
 }
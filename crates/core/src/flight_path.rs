@@ -0,0 +1,47 @@
+//! Conversion of the vehicle position to `geo` geometries, for GIS analysis.
+
+use std::io::Read;
+
+use geo_types::{Coord, LineString};
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// The topic holding the fused global position estimate.
+const GLOBAL_POSITION_TOPIC: &str = "vehicle_global_position";
+
+impl<R: Read> ULogParser<R> {
+    /// Returns the flight path as a `geo::LineString`, with one point per record of the first
+    /// instance of `vehicle_global_position`.
+    ///
+    /// Following the GeoJSON convention of `geo`, each point is `(x: lon, y: lat)` in degrees.
+    /// Records without a `lat` and `lon` field are skipped, as are records whose position is not
+    /// a number.  Any allow list set on the parser is replaced by the position topic.
+    pub fn flight_path(mut self) -> Result<LineString<f64>, ULogError> {
+        self.set_allowed_subscription_names([GLOBAL_POSITION_TOPIC.to_string()]);
+
+        let mut coords = Vec::new();
+
+        while let Some(msg_res) = self.next() {
+            let UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            if self.get_subscription_ref(data.msg_id)?.multi_id != 0 {
+                continue;
+            }
+
+            let lat = data.data.find_field("lat").and_then(|f| f.value.as_f64());
+            let lon = data.data.find_field("lon").and_then(|f| f.value.as_f64());
+
+            if let (Some(lat), Some(lon)) = (lat, lon) {
+                if lat.is_finite() && lon.is_finite() {
+                    coords.push(Coord { x: lon, y: lat });
+                }
+            }
+        }
+
+        Ok(LineString::new(coords))
+    }
+}
@@ -24,6 +24,12 @@ pub(crate) fn parse_format(message_buf: MessageBuf) -> Result<def::Format, ULogE
     while !token_list.is_empty() {
         fields.push(parse_field(&mut token_list)?);
 
+        // The separator after the last field may be left out, but only directly after a complete
+        // field: a string cut within a field fails in `parse_field()`.  A cut within the last
+        // field name leaves a shorter name, which cannot be told apart from a complete one.
+        if token_list.is_empty() {
+            break;
+        }
         match token_list.consume_one()? {
             Token::Semicolon => {}
             token => {
@@ -53,13 +59,26 @@ pub(crate) fn parse_field(token_list: &mut TokenList) -> Result<def::Field, ULog
     };
 
     let mut array_size: Option<usize> = None;
+    let mut matrix_shape: Option<(usize, usize)> = None;
 
     if token_list.peek() == Some(&Token::LBrace) {
-        array_size = match token_list.consume_three()? {
-            (Token::LBrace, Token::Number(size), Token::RBrace) => { Some(size) }
-            (token1, token2, token3) => { Err(ULogError::ParseError(format!(
-                "Invalid array definition. Expected [LBrace, Number, RBrace], got: [{token1:?}, {token2:?}, {token3:?}]"
-            )))? }
+        array_size = Some(parse_array_dimension(token_list)?);
+    }
+
+    // A second dimension declares a row major matrix, e.g. `float[3][4]`.
+    if let (Some(rows), Some(&Token::LBrace)) = (array_size, token_list.peek()) {
+        let columns = parse_array_dimension(token_list)?;
+        array_size = Some(rows.checked_mul(columns).ok_or_else(|| {
+            ULogError::ParseError(format!(
+                "Invalid array definition. The size of {base_type}[{rows}][{columns}] overflows"
+            ))
+        })?);
+        matrix_shape = Some((rows, columns));
+
+        if token_list.peek() == Some(&Token::LBrace) {
+            return Err(ULogError::ParseError(format!(
+                "Unsupported array definition. At most two dimensions are supported, got: {base_type}[{rows}][{columns}][..]"
+            )));
         }
     }
 
@@ -75,10 +94,20 @@ pub(crate) fn parse_field(token_list: &mut TokenList) -> Result<def::Field, ULog
         r#type: def::TypeExpr {
             base_type,
             array_size,
+            matrix_shape,
         },
     })
 }
 
+fn parse_array_dimension(token_list: &mut TokenList) -> Result<usize, ULogError> {
+    match token_list.consume_three()? {
+        (Token::LBrace, Token::Number(size), Token::RBrace) => Ok(size),
+        (token1, token2, token3) => Err(ULogError::ParseError(format!(
+            "Invalid array definition. Expected [LBrace, Number, RBrace], got: [{token1:?}, {token2:?}, {token3:?}]"
+        ))),
+    }
+}
+
 impl def::BaseType {
     pub fn from_string(string: &str) -> def::BaseType {
         match string {
@@ -126,6 +155,7 @@ mod tests {
                     r#type: def::TypeExpr {
                         base_type: def::BaseType::UINT64,
                         array_size: None,
+                        matrix_shape: None,
                     },
                 },
                 def::Field {
@@ -133,6 +163,7 @@ mod tests {
                     r#type: def::TypeExpr {
                         base_type: def::BaseType::BOOL,
                         array_size: None,
+                        matrix_shape: None,
                     },
                 },
                 def::Field {
@@ -140,6 +171,7 @@ mod tests {
                     r#type: def::TypeExpr {
                         base_type: def::BaseType::UINT8,
                         array_size: Some(8),
+                        matrix_shape: None,
                     },
                 },
             ],
@@ -167,4 +199,85 @@ mod tests {
 
         assert_eq!(re_emitted_bytes, input);
     }
+
+    #[test]
+    fn test_parse_matrix_field() {
+        let input = b"my_format:float[3][4] rotation;";
+        let parsed_format = parse_format(MessageBuf::from_vec(input.to_vec())).unwrap();
+
+        let r#type = &parsed_format.fields[0].r#type;
+        assert_eq!(r#type.array_size, Some(12));
+        assert_eq!(r#type.matrix_shape, Some((3, 4)));
+
+        let mut re_emitted_bytes = Vec::new();
+        parsed_format.encode(&mut re_emitted_bytes).unwrap();
+        assert_eq!(re_emitted_bytes, input);
+    }
+
+    #[test]
+    fn test_parse_whitespace_variants() {
+        let expected = parse_format(MessageBuf::from_vec(
+            b"my_format:uint64_t timestamp;float[3] xyz;".to_vec(),
+        ))
+        .unwrap();
+
+        let inputs: [&[u8]; 5] = [
+            b"my_format:uint64_t timestamp;float[3] xyz",
+            b"my_format: uint64_t timestamp; float[3] xyz; ",
+            b"my_format :\tuint64_t  timestamp ;\n float [ 3 ] xyz ;\n",
+            b"my_format:uint64_t timestamp;float[3] xyz;\0\0",
+            b"my_format:uint64_t timestamp;float[3] xyz\0",
+        ];
+        for input in inputs {
+            let parsed_format = parse_format(MessageBuf::from_vec(input.to_vec())).unwrap();
+            assert_eq!(
+                parsed_format,
+                expected,
+                "{:?}",
+                String::from_utf8_lossy(input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_malformed_formats_fail() {
+        let inputs: [&[u8]; 9] = [
+            // Missing separator between fields.
+            b"my_format:uint64_t timestamp float x;",
+            // Empty field.
+            b"my_format:uint64_t timestamp;;float x;",
+            // Missing field name.
+            b"my_format:uint64_t timestamp;float;",
+            // Unterminated array.
+            b"my_format:uint64_t timestamp;float[3 xyz;",
+            b"my_format:uint64_t timestamp;float x;?",
+            // Truncated within the last field, before its name.
+            b"my_format:uint64_t timestamp;floa",
+            b"my_format:uint64_t timestamp;float[",
+            b"my_format:uint64_t timestamp;float[3",
+            b"my_format:uint64_t timestamp;float[3]\0",
+        ];
+        for input in inputs {
+            let result = parse_format(MessageBuf::from_vec(input.to_vec()));
+            assert!(result.is_err(), "{:?}", String::from_utf8_lossy(input));
+        }
+    }
+
+    #[test]
+    fn test_parse_three_dimensional_array_fails() {
+        let input = b"my_format:float[2][3][4] cube;";
+        let result = parse_format(MessageBuf::from_vec(input.to_vec()));
+
+        assert!(
+            matches!(result, Err(ULogError::ParseError(ref msg)) if msg.contains("At most two dimensions"))
+        );
+    }
+
+    #[test]
+    fn test_parse_overflowing_matrix_fails() {
+        let input = b"my_format:float[4294967296][4294967296] huge;";
+        let result = parse_format(MessageBuf::from_vec(input.to_vec()));
+
+        assert!(matches!(result, Err(ULogError::ParseError(ref msg)) if msg.contains("overflows")));
+    }
 }
@@ -0,0 +1,189 @@
+//! Reorders the message stream so that the data of each subscription is contiguous.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::vec;
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::{ULogMessageType, ULogParser};
+
+/// The messages of a log, reordered by `ULogParser::group_by_subscription()`.
+///
+/// All messages other than data are yielded first, in file order, followed by the `DATA`
+/// messages of each subscription in turn.  Subscriptions are ordered by their first sample, and
+/// samples keep their file order within a subscription.  `DATA` messages excluded by the
+/// subscription allow list are grouped along with the rest, as `UlogMessage::Ignored`.
+pub struct GroupedMessages<R: Read> {
+    parser: ULogParser<R>,
+    other: vec::IntoIter<UlogMessage>,
+    groups: VecDeque<Group>,
+}
+
+enum Group {
+    Buffered(vec::IntoIter<UlogMessage>),
+    // Raw `DATA` message contents, each prefixed with its u16 length.
+    Spilled(BufReader<File>),
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Reads the whole log and groups the output by subscription; see `GroupedMessages`.
+    ///
+    /// Every decoded message is buffered until the log has been read, so memory use is
+    /// proportional to the size of the log, and typically several times larger. Use
+    /// `group_by_subscription_spilled()` for files which do not comfortably fit in memory.
+    pub fn group_by_subscription(mut self) -> Result<GroupedMessages<R>, ULogError> {
+        let mut other = Vec::new();
+        let mut groups: Vec<Vec<UlogMessage>> = Vec::new();
+        let mut group_index: HashMap<u16, usize> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            let msg = msg_res?;
+
+            let msg_id = match &msg {
+                UlogMessage::LoggedData(data) => data.msg_id,
+                UlogMessage::Ignored {
+                    msg_type,
+                    message_contents,
+                } if is_data(*msg_type) => raw_msg_id(message_contents),
+                _ => {
+                    other.push(msg);
+                    continue;
+                }
+            };
+
+            let index = *group_index.entry(msg_id).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[index].push(msg);
+        }
+
+        Ok(GroupedMessages {
+            parser: self,
+            other: other.into_iter(),
+            groups: groups
+                .into_iter()
+                .map(|group| Group::Buffered(group.into_iter()))
+                .collect(),
+        })
+    }
+
+    /// Like `group_by_subscription()`, but spills the data to files created by `spill_file`.
+    ///
+    /// The raw bytes of each subscription are written to a file of its own, and only decoded as
+    /// the grouped messages are iterated.  Only the non-data messages are kept in memory.  The
+    /// files must be empty, and opened for both reading and writing, e.g. anonymous temporary
+    /// files created by `tempfile::tempfile`, which are deleted when the `GroupedMessages` is
+    /// dropped.
+    pub fn group_by_subscription_spilled<F>(
+        mut self,
+        mut spill_file: F,
+    ) -> Result<GroupedMessages<R>, ULogError>
+    where
+        F: FnMut() -> io::Result<File>,
+    {
+        let filter = self.suspend_data_decoding();
+
+        let mut other = Vec::new();
+        let mut files: Vec<BufWriter<File>> = Vec::new();
+        let mut file_index: HashMap<u16, usize> = HashMap::new();
+
+        for msg_res in self.by_ref() {
+            match msg_res? {
+                UlogMessage::Ignored {
+                    msg_type,
+                    message_contents,
+                } if is_data(msg_type) => {
+                    let index = match file_index.get(&raw_msg_id(&message_contents)) {
+                        Some(&index) => index,
+                        None => {
+                            files.push(BufWriter::new(spill_file()?));
+                            file_index.insert(raw_msg_id(&message_contents), files.len() - 1);
+                            files.len() - 1
+                        }
+                    };
+
+                    // Contents always fit, as a message size is itself a u16.
+                    let file = &mut files[index];
+                    file.write_all(&(message_contents.len() as u16).to_le_bytes())?;
+                    file.write_all(&message_contents)?;
+                }
+                msg => other.push(msg),
+            }
+        }
+
+        self.resume_data_decoding(filter);
+
+        let groups = files
+            .into_iter()
+            .map(|file| {
+                let mut file = file.into_inner().map_err(io::IntoInnerError::into_error)?;
+                file.seek(SeekFrom::Start(0))?;
+                Ok(Group::Spilled(BufReader::new(file)))
+            })
+            .collect::<Result<_, ULogError>>()?;
+
+        Ok(GroupedMessages {
+            parser: self,
+            other: other.into_iter(),
+            groups,
+        })
+    }
+}
+
+impl<R: Read> GroupedMessages<R> {
+    fn next_grouped(&mut self) -> Result<Option<UlogMessage>, ULogError> {
+        if let Some(msg) = self.other.next() {
+            return Ok(Some(msg));
+        }
+
+        while let Some(group) = self.groups.front_mut() {
+            match group {
+                Group::Buffered(messages) => {
+                    if let Some(msg) = messages.next() {
+                        return Ok(Some(msg));
+                    }
+                }
+                Group::Spilled(file) => {
+                    let mut len = [0u8; 2];
+                    match file.read_exact(&mut len) {
+                        Ok(()) => {
+                            let mut contents = vec![0u8; u16::from_le_bytes(len) as usize];
+                            file.read_exact(&mut contents)?;
+
+                            if let Some(msg) = self.parser.decode_data(contents)? {
+                                return Ok(Some(msg));
+                            }
+                            continue;
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            }
+
+            // The front group is exhausted.
+            self.groups.pop_front();
+        }
+
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for GroupedMessages<R> {
+    type Item = Result<UlogMessage, ULogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_grouped().transpose()
+    }
+}
+
+fn is_data(msg_type: u8) -> bool {
+    matches!(ULogMessageType::from(msg_type), ULogMessageType::DATA)
+}
+
+fn raw_msg_id(message_contents: &[u8]) -> u16 {
+    u16::from_le_bytes([message_contents[0], message_contents[1]])
+}
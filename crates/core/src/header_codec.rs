@@ -0,0 +1,19 @@
+//! Decoding of the header which precedes each message, for logs written by forks of the format.
+
+use crate::errors::ULogError;
+use crate::parser::ULogMessageHeader;
+
+/// Reads the header of each message: the size of its contents and its type.
+///
+/// The standard header is a little-endian `u16` size followed by the type byte, which the parser
+/// reads unless it is built with `ULogParserBuilder::header_codec()`.  A codec only changes how
+/// the header is read; the file header and the message contents are parsed as usual.
+pub trait HeaderCodec: Send + Sync {
+    /// Reads a header, taking its bytes one at a time from `next_byte`, which returns `None` at
+    /// the end of the stream.  Returns `Ok(None)` if the stream ends before the header is
+    /// complete.
+    fn read_header(
+        &self,
+        next_byte: &mut dyn FnMut() -> Result<Option<u8>, ULogError>,
+    ) -> Result<Option<ULogMessageHeader>, ULogError>;
+}
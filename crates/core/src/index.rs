@@ -0,0 +1,63 @@
+//! An index of the records of a log, built by [`ULogParser::build_index`], for random access by
+//! subscription and timestamp.
+//!
+//! [`ULogParser::build_index`]: crate::parser::ULogParser::build_index
+
+use std::collections::HashMap;
+
+/// The location of a single `LoggedData` record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// The timestamp of the record, in microseconds.
+    pub timestamp: u64,
+    /// The byte offset of the message in the file, as passed to
+    /// [`ULogParser::seek_to_offset`](crate::parser::ULogParser::seek_to_offset).
+    pub offset: u64,
+}
+
+/// The timestamps and byte offsets of the records of each subscription of a log.
+#[derive(Debug, Clone, Default)]
+pub struct LogIndex {
+    // Sorted by timestamp, then offset.
+    entries: HashMap<u16, Vec<IndexEntry>>,
+    msg_ids: HashMap<String, u16>,
+}
+
+impl LogIndex {
+    /// Returns the msg_id of the subscription with the given name, formatted as for
+    /// [`ULogParser::bytes_per_subscription`](crate::parser::ULogParser::bytes_per_subscription).
+    pub fn msg_id(&self, name: &str) -> Option<u16> {
+        self.msg_ids.get(name).copied()
+    }
+
+    /// Returns the records of the subscription, sorted by timestamp.
+    pub fn entries(&self, msg_id: u16) -> &[IndexEntry] {
+        self.entries.get(&msg_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the records of the subscription with a timestamp in `[start, end)`, sorted by
+    /// timestamp.
+    pub fn entries_in_range(&self, msg_id: u16, start: u64, end: u64) -> &[IndexEntry] {
+        let entries = self.entries(msg_id);
+        let first = entries.partition_point(|entry| entry.timestamp < start);
+        let last = entries.partition_point(|entry| entry.timestamp < end);
+
+        &entries[first..last.max(first)]
+    }
+
+    pub(crate) fn push(&mut self, msg_id: u16, entry: IndexEntry) {
+        self.entries.entry(msg_id).or_default().push(entry);
+    }
+
+    pub(crate) fn add_name(&mut self, name: String, msg_id: u16) {
+        self.msg_ids.insert(name, msg_id);
+    }
+
+    /// Sorts the records once they have all been pushed, in file order.
+    pub(crate) fn finish(&mut self) {
+        for entries in self.entries.values_mut() {
+            // Stable, so records with equal timestamps stay in file order.
+            entries.sort_by_key(|entry| entry.timestamp);
+        }
+    }
+}
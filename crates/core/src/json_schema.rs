@@ -0,0 +1,138 @@
+//! Export of format definitions as JSON Schema documents.
+
+use std::io::Read;
+
+use serde_json::{json, Map, Value};
+
+use crate::errors::ULogError;
+use crate::model::def;
+use crate::model::inst::ArrayMode;
+use crate::parser::{ULogParser, INDEX_FIELD_NAME};
+
+/// The JSON Schema dialect of the generated documents.
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl<R: Read> ULogParser<R> {
+    /// Returns a JSON Schema describing the records of the topic `message_name` as JSON objects.
+    ///
+    /// Each field becomes a required property, and nested formats become nested objects.  With
+    /// `ArrayMode::Nested` arrays become JSON arrays of exactly `array_size` items, and `char`
+    /// arrays become strings.  With `ArrayMode::Expand` each element of an array becomes a
+    /// property of its own, named as by `inst::Format::flatten_fields()`, e.g. `gyro.00`.
+    /// The properties match the fields of the `LoggedData` records yielded with the current
+    /// parser options, so the `timestamp` and padding fields are only included if enabled.
+    ///
+    /// The format and any nested formats must have been seen already, so this is typically called
+    /// once the definitions section has been read.
+    pub fn topic_json_schema(
+        &self,
+        message_name: &str,
+        mode: ArrayMode,
+    ) -> Result<Value, ULogError> {
+        let format = self.get_format(message_name)?;
+
+        let mut schema = self.object_schema(&format, true, mode)?;
+        if self.add_index_field {
+            schema["properties"][INDEX_FIELD_NAME] = integer_schema(0, u64::MAX);
+            schema["required"]
+                .as_array_mut()
+                // Unwrap is safe: `object_schema()` always returns a `required` array.
+                .unwrap()
+                .push(json!(INDEX_FIELD_NAME));
+        }
+
+        schema["$schema"] = json!(SCHEMA_DIALECT);
+        schema["title"] = json!(message_name);
+
+        Ok(schema)
+    }
+
+    fn object_schema(
+        &self,
+        format: &def::Format,
+        top_level: bool,
+        mode: ArrayMode,
+    ) -> Result<Value, ULogError> {
+        // Mirror `LoggedData::filter_fields()`, and the skipping of padding while decoding.
+        let fields: Vec<&def::Field> = format
+            .fields
+            .iter()
+            .filter(|field| {
+                if field.name.starts_with("_padding") {
+                    self.include_padding
+                } else {
+                    !top_level || field.name != "timestamp" || self.include_timestamp
+                }
+            })
+            .collect();
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for field in &fields {
+            match (mode, field.r#type.array_size) {
+                (ArrayMode::Expand, Some(array_size)) => {
+                    let element_schema = self.base_type_schema(&field.r#type.base_type, mode)?;
+                    for index in 0..array_size {
+                        let name = format!("{}.{index:02}", field.name);
+                        properties.insert(name.clone(), element_schema.clone());
+                        required.push(name);
+                    }
+                }
+                _ => {
+                    properties.insert(field.name.clone(), self.type_schema(&field.r#type, mode)?);
+                    required.push(field.name.clone());
+                }
+            }
+        }
+
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }))
+    }
+
+    fn type_schema(&self, type_expr: &def::TypeExpr, mode: ArrayMode) -> Result<Value, ULogError> {
+        match (type_expr.array_size, &type_expr.base_type) {
+            (Some(array_size), def::BaseType::CHAR) => Ok(json!({
+                "type": "string",
+                "maxLength": array_size,
+            })),
+            (Some(array_size), base_type) => Ok(json!({
+                "type": "array",
+                "items": self.base_type_schema(base_type, mode)?,
+                "minItems": array_size,
+                "maxItems": array_size,
+            })),
+            (None, base_type) => self.base_type_schema(base_type, mode),
+        }
+    }
+
+    fn base_type_schema(
+        &self,
+        base_type: &def::BaseType,
+        mode: ArrayMode,
+    ) -> Result<Value, ULogError> {
+        use def::BaseType::*;
+
+        Ok(match base_type {
+            UINT8 => integer_schema(0, u8::MAX as u64),
+            UINT16 => integer_schema(0, u16::MAX as u64),
+            UINT32 => integer_schema(0, u32::MAX as u64),
+            UINT64 => integer_schema(0, u64::MAX),
+            INT8 => integer_schema(i64::from(i8::MIN), i8::MAX as u64),
+            INT16 => integer_schema(i64::from(i16::MIN), i16::MAX as u64),
+            INT32 => integer_schema(i64::from(i32::MIN), i32::MAX as u64),
+            INT64 => integer_schema(i64::MIN, i64::MAX as u64),
+            FLOAT | DOUBLE => json!({ "type": "number" }),
+            BOOL => json!({ "type": "boolean" }),
+            CHAR => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+            OTHER(type_name) => self.object_schema(&self.get_format(type_name)?, false, mode)?,
+        })
+    }
+}
+
+fn integer_schema(minimum: i64, maximum: u64) -> Value {
+    json!({ "type": "integer", "minimum": minimum, "maximum": maximum })
+}
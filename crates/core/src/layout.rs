@@ -0,0 +1,368 @@
+//! Byte layout of formats on the wire: the size of each type, and the offset of each field.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::ops::Range;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::errors::ULogError;
+use crate::model::def;
+use crate::model::msg::{self, LoggedData};
+use crate::parser::ULogParser;
+
+impl def::BaseType {
+    /// Returns the encoded size in bytes of a primitive type, or `None` for nested formats.
+    pub fn primitive_size(&self) -> Option<usize> {
+        use def::BaseType::*;
+        match self {
+            UINT8 | INT8 | BOOL | CHAR => Some(1),
+            UINT16 | INT16 => Some(2),
+            UINT32 | INT32 | FLOAT => Some(4),
+            UINT64 | INT64 | DOUBLE => Some(8),
+            OTHER(_) => None,
+        }
+    }
+}
+
+/// Returns the encoded size of a value of type `type_expr`, resolving nested formats in `formats`
+/// and other type names in `opaque_sizes`, see `ULogParserBuilder::opaque_type_sizes()`.
+///
+/// Returns `ULogError::ParseError` if a format contains itself, directly or through other formats.
+pub(crate) fn type_size(
+    type_expr: &def::TypeExpr,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<usize, ULogError> {
+    type_size_in(type_expr, formats, opaque_sizes, &mut Vec::new())
+}
+
+/// Returns the encoded size of a complete format, including any padding fields.
+pub(crate) fn format_size(
+    format: &def::Format,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<usize, ULogError> {
+    format_size_in(format, formats, opaque_sizes, &mut Vec::new())
+}
+
+// `visiting` holds the names of the formats being sized, outermost first.
+fn type_size_in<'a>(
+    type_expr: &def::TypeExpr,
+    formats: &'a HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+    visiting: &mut Vec<&'a str>,
+) -> Result<usize, ULogError> {
+    let element_size = match &type_expr.base_type {
+        def::BaseType::OTHER(name) => match formats.get(name) {
+            Some(format) => format_size_in(format, formats, opaque_sizes, visiting)?,
+            None => *opaque_sizes
+                .get(name)
+                .ok_or_else(|| ULogError::UndefinedFormat(name.clone()))?,
+        },
+        primitive => primitive.primitive_size().unwrap_or_default(),
+    };
+
+    element_size
+        .checked_mul(type_expr.array_size.unwrap_or(1))
+        .ok_or_else(|| {
+            ULogError::ParseError(format!(
+                "Invalid array definition. The size of {type_expr} overflows"
+            ))
+        })
+}
+
+fn format_size_in<'a>(
+    format: &'a def::Format,
+    formats: &'a HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+    visiting: &mut Vec<&'a str>,
+) -> Result<usize, ULogError> {
+    enter_format(format, visiting)?;
+    let size = format
+        .fields
+        .iter()
+        .map(|field| type_size_in(&field.r#type, formats, opaque_sizes, visiting))
+        .sum();
+    visiting.pop();
+
+    size
+}
+
+/// Pushes `format` onto the formats being visited, or returns an error if it is already being
+/// visited, i.e. it contains itself.
+fn enter_format<'a>(format: &'a def::Format, visiting: &mut Vec<&'a str>) -> Result<(), ULogError> {
+    if visiting.contains(&format.name.as_str()) {
+        return Err(ULogError::ParseError(format!(
+            "format {} contains itself: {} -> {}",
+            format.name,
+            visiting.join(" -> "),
+            format.name
+        )));
+    }
+    visiting.push(&format.name);
+
+    Ok(())
+}
+
+/// Returns the number of trailing `_padding` fields of a format, which are not written to the log.
+pub(crate) fn trailing_padding_count(format: &def::Format) -> usize {
+    format
+        .fields
+        .iter()
+        .rev()
+        .take_while(|field| field.name.starts_with("_padding"))
+        .count()
+}
+
+/// Returns the size of a `DATA` payload of `format`, i.e. its encoded size without any trailing
+/// padding.  See `ULogParser::format_byte_size()`.
+pub(crate) fn payload_size(
+    format: &def::Format,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<usize, ULogError> {
+    format.fields[..format.fields.len() - trailing_padding_count(format)]
+        .iter()
+        .map(|field| type_size(&field.r#type, formats, opaque_sizes))
+        .sum()
+}
+
+/// Decodes a `timestamp` field from its raw bytes.  A few legacy topics declare a `uint32_t`
+/// timestamp, which is widened.
+pub(crate) fn decode_timestamp(bytes: &[u8]) -> Option<u64> {
+    match bytes.len() {
+        8 => Some(LittleEndian::read_u64(bytes)),
+        4 => Some(u64::from(LittleEndian::read_u32(bytes))),
+        _ => None,
+    }
+}
+
+/// Returns the natural alignment of a value of type `type_expr`: the size of a primitive, or the
+/// largest alignment of the fields of a nested format.  The alignment of an opaque type is not
+/// known, so it is taken as 1.
+fn type_alignment(
+    type_expr: &def::TypeExpr,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<usize, ULogError> {
+    type_alignment_in(type_expr, formats, opaque_sizes, &mut Vec::new())
+}
+
+fn type_alignment_in<'a>(
+    type_expr: &def::TypeExpr,
+    formats: &'a HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+    visiting: &mut Vec<&'a str>,
+) -> Result<usize, ULogError> {
+    match &type_expr.base_type {
+        def::BaseType::OTHER(name) => {
+            let Some(format) = formats.get(name) else {
+                return match opaque_sizes.contains_key(name) {
+                    true => Ok(1),
+                    false => Err(ULogError::UndefinedFormat(name.clone())),
+                };
+            };
+
+            enter_format(format, visiting)?;
+            let alignment = format.fields.iter().try_fold(1, |alignment, field| {
+                Ok(alignment.max(type_alignment_in(
+                    &field.r#type,
+                    formats,
+                    opaque_sizes,
+                    visiting,
+                )?))
+            });
+            visiting.pop();
+
+            alignment
+        }
+        primitive => Ok(primitive.primitive_size().unwrap_or(1)),
+    }
+}
+
+/// Returns the byte range of a field within an encoded instance of `format`.
+///
+/// Fields of nested formats are addressed by `/` separated paths, e.g. `accel/x`.
+pub(crate) fn field_range(
+    format: &def::Format,
+    path: &str,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<Option<Range<usize>>, ULogError> {
+    Ok(field_layout(format, path, formats, opaque_sizes)?.map(|(_, range)| range))
+}
+
+/// Returns the type of a field along with its byte range, as for `field_range()`.
+pub(crate) fn field_layout<'a>(
+    format: &'a def::Format,
+    path: &str,
+    formats: &'a HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+) -> Result<Option<(&'a def::TypeExpr, Range<usize>)>, ULogError> {
+    let (name, rest) = match path.split_once('/') {
+        Some((name, rest)) => (name, Some(rest)),
+        None => (path, None),
+    };
+
+    let mut offset = 0;
+    for field in &format.fields {
+        let size = type_size(&field.r#type, formats, opaque_sizes)?;
+
+        if field.name == name {
+            return match (rest, &field.r#type.base_type) {
+                (None, _) => Ok(Some((&field.r#type, offset..offset + size))),
+                // Only scalar nested formats can be descended into.
+                (Some(rest), def::BaseType::OTHER(nested)) if field.r#type.is_scalar() => {
+                    // The fields of an opaque type are not known.
+                    if opaque_sizes.contains_key(nested) && !formats.contains_key(nested) {
+                        return Ok(None);
+                    }
+                    let nested = formats
+                        .get(nested)
+                        .ok_or_else(|| ULogError::UndefinedFormat(nested.clone()))?;
+                    Ok(field_layout(nested, rest, formats, opaque_sizes)?.map(
+                        |(type_expr, range)| (type_expr, range.start + offset..range.end + offset),
+                    ))
+                }
+                (Some(_), _) => Ok(None),
+            };
+        }
+
+        offset += size;
+    }
+
+    Ok(None)
+}
+
+/// Returns true if two formats have the same fields, comparing nested formats by their fields
+/// rather than by their names.
+pub(crate) fn formats_structurally_equal(
+    a: &def::Format,
+    b: &def::Format,
+    formats: &HashMap<String, def::Format>,
+) -> bool {
+    a.fields.len() == b.fields.len()
+        && a.fields.iter().zip(&b.fields).all(|(field_a, field_b)| {
+            let (type_a, type_b) = (&field_a.r#type, &field_b.r#type);
+
+            field_a.name == field_b.name
+                && type_a.array_size == type_b.array_size
+                && type_a.matrix_shape == type_b.matrix_shape
+                && match (&type_a.base_type, &type_b.base_type) {
+                    (def::BaseType::OTHER(name_a), def::BaseType::OTHER(name_b)) => {
+                        match (formats.get(name_a), formats.get(name_b)) {
+                            (Some(nested_a), Some(nested_b)) => {
+                                formats_structurally_equal(nested_a, nested_b, formats)
+                            }
+                            _ => false,
+                        }
+                    }
+                    (base_a, base_b) => base_a == base_b,
+                }
+        })
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Returns true if two subscriptions have structurally identical formats.
+    ///
+    /// The resolved field lists are compared by name, type and array size, with nested formats
+    /// compared by their own fields, so topics whose formats differ only in name are equal.  This
+    /// identifies subscriptions whose records can safely be merged, e.g. the `multi_id` instances
+    /// of a topic.  Returns false if either format has not been defined.
+    pub fn formats_equal(&self, sub_a: &msg::Subscription, sub_b: &msg::Subscription) -> bool {
+        match (
+            self.formats.get(&sub_a.message_name),
+            self.formats.get(&sub_b.message_name),
+        ) {
+            (Some(format_a), Some(format_b)) => {
+                formats_structurally_equal(format_a, format_b, &self.formats)
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks that every field of `format`, and of any nested formats, is naturally aligned.
+    ///
+    /// The PX4 logger writes the fields of a topic in the order of its C struct, which the message
+    /// generator sorts by size and pads with explicit `_padding` fields, so the packed ULOG layout
+    /// is identical to the aligned in-memory layout.  A field at an offset which is not a multiple
+    /// of its alignment means the two layouts differ, which usually indicates a firmware bug.
+    ///
+    /// The size of the format must also be a multiple of its alignment, i.e. of the largest
+    /// alignment of its fields, so that it is padded up to the next element of an array.  A
+    /// missing trailing `_padding` field thus makes the layouts differ as well.
+    ///
+    /// Returns `ULogError::MisalignedField` for the first misaligned field, and
+    /// `ULogError::MisalignedFormatSize` if the format is not padded to its alignment.
+    pub fn validate_format_layout(&self, format: &def::Format) -> Result<(), ULogError> {
+        let mut offset = 0;
+        let mut format_alignment = 1;
+
+        for field in &format.fields {
+            let alignment = type_alignment(&field.r#type, &self.formats, &self.opaque_type_sizes)?;
+            format_alignment = format_alignment.max(alignment);
+
+            if offset % alignment != 0 {
+                return Err(ULogError::MisalignedField {
+                    format: format.name.clone(),
+                    field: field.name.clone(),
+                    offset,
+                    alignment,
+                });
+            }
+
+            if let def::BaseType::OTHER(name) = &field.r#type.base_type {
+                if let Some(nested) = self.formats.get(name) {
+                    self.validate_format_layout(nested)?;
+                }
+            }
+
+            offset += type_size(&field.r#type, &self.formats, &self.opaque_type_sizes)?;
+        }
+
+        if offset % format_alignment != 0 {
+            return Err(ULogError::MisalignedFormatSize {
+                format: format.name.clone(),
+                size: offset,
+                alignment: format_alignment,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the size in bytes of a `DATA` payload of `format`, following the 2 byte `msg_id`.
+    ///
+    /// Fields are packed without implicit alignment, so the size is the sum of the field sizes,
+    /// with nested formats resolved and explicit `_padding` fields included.  Per the ULOG spec,
+    /// padding at the end of a top-level format is not written to the log, so trailing `_padding`
+    /// fields are excluded; those of nested formats are kept, as they are followed by other data.
+    ///
+    /// Returns `ULogError::UndefinedFormat` if a nested format has not been defined.
+    pub fn format_byte_size(&self, format: &def::Format) -> Result<usize, ULogError> {
+        payload_size(format, &self.formats, &self.opaque_type_sizes)
+    }
+
+    /// Returns the raw bytes of the field `name` of `record`, within its encoded `payload`.
+    ///
+    /// `payload` is the contents of the `DATA` message that `record` was decoded from, following
+    /// the 2 byte `msg_id`.  Fields of nested formats are addressed by `/` separated paths, e.g.
+    /// `accel/x`.  Returns `None` if there is no such field, or if the payload is too short to
+    /// contain it.
+    pub fn field_bytes<'a>(
+        &self,
+        record: &LoggedData,
+        payload: &'a [u8],
+        name: &str,
+    ) -> Option<&'a [u8]> {
+        let range = field_range(
+            &record.data.def_format,
+            name,
+            &self.formats,
+            &self.opaque_type_sizes,
+        )
+        .ok()??;
+        payload.get(range)
+    }
+}
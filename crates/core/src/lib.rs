@@ -1,16 +1,67 @@
 #![allow(clippy::needless_return)]
+#[cfg(feature = "ndarray")]
+pub mod actuator_outputs;
+pub mod analysis;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "tokio")]
+pub mod async_parser;
+#[cfg(feature = "bytes")]
+pub mod borrowed_record;
 pub mod builder;
+#[cfg(feature = "bytes")]
+pub mod bytes_reader;
+#[cfg(feature = "crc32")]
+pub mod checksum;
+pub mod commander;
+pub mod compat;
+#[cfg(feature = "lz4")]
+pub mod compressibility;
+#[cfg(feature = "toml")]
+pub mod config;
+pub mod csv;
 pub mod datastream;
+pub mod delta;
+#[cfg(feature = "serde")]
+pub mod deserializer;
 mod display;
 pub mod encode;
 pub mod errors;
 mod field_helpers;
+#[cfg(feature = "geo")]
+pub mod flight_path;
 mod formats;
+pub mod grouping;
+pub mod header_codec;
+pub mod index;
+#[cfg(feature = "json_schema")]
+pub mod json_schema;
+pub mod layout;
+pub mod log_builder;
+pub mod memory;
+pub mod merge;
 pub mod message_buf;
+pub mod metadata;
 pub mod model;
+pub mod packed;
 #[allow(clippy::redundant_else)]
 pub mod parser;
+mod pipeline;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "prost-reflect")]
+pub mod protobuf;
+mod recovery;
+pub mod scalar_buffer;
+#[cfg(feature = "rusqlite")]
+pub mod sqlite;
+#[cfg(feature = "futures")]
+pub mod stream;
 mod tokenizer;
+pub mod warnings;
+pub mod writer;
 
 #[cfg(feature = "macros")]
 pub use yule_log_macros::{ULogData, ULogMessages};
@@ -0,0 +1,307 @@
+//! Emitting a ULOG file from scratch, e.g. for a simulator which generates synthetic logs.
+//!
+//! [`ULogWriter`] writes messages as they are, so the caller must build every format, field and
+//! padding value itself.  A [`ULogBuilder`] instead takes formats as their definition strings,
+//! assigns the msg_id of each subscription, and checks each record against its format before
+//! writing it.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::errors::ULogError;
+use crate::formats::parse_format;
+use crate::layout::format_size;
+use crate::message_buf::MessageBuf;
+use crate::model::def::{self, BaseType};
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::{self, FileHeader, UlogMessage};
+use crate::writer::ULogWriter;
+
+/// Builds a valid ULOG file from formats, subscriptions and records.
+///
+/// The file header, with its magic bytes, and the `FlagBits` message are written automatically.
+/// The header timestamp is that of the first record.  Formats must be defined before the first
+/// subscription, since they belong to the definitions section of the file.
+///
+/// ```
+/// use yule_log::log_builder::ULogBuilder;
+/// use yule_log::model::inst::FieldValue;
+///
+/// let mut builder = ULogBuilder::new(Vec::new())?;
+/// builder.format("sensor_baro:uint64_t timestamp;float pressure;")?;
+/// let msg_id = builder.subscription("sensor_baro", 0)?;
+/// builder.record(msg_id, 1_000, [FieldValue::ScalarF32(1013.25)])?;
+/// let bytes = builder.finish()?;
+/// # Ok::<(), yule_log::errors::ULogError>(())
+/// ```
+pub struct ULogBuilder<W: Write> {
+    writer: ULogWriter<W>,
+    formats: HashMap<String, def::Format>,
+    subscriptions: HashMap<u16, msg::Subscription>,
+    next_msg_id: u16,
+}
+
+impl<W: Write> ULogBuilder<W> {
+    /// Creates a builder writing a version 1 ULOG file to `writer`.
+    pub fn new(writer: W) -> Result<Self, ULogError> {
+        let mut writer = ULogWriter::new(writer).backfill_header_timestamp(true);
+        writer.write_header(&FileHeader {
+            version: 1,
+            timestamp: 0,
+        })?;
+
+        Ok(ULogBuilder {
+            writer,
+            formats: HashMap::new(),
+            subscriptions: HashMap::new(),
+            next_msg_id: 0,
+        })
+    }
+
+    /// Defines a format from its definition string, e.g.
+    /// `vehicle_attitude:uint64_t timestamp;float[4] q;`.
+    pub fn format(&mut self, definition: &str) -> Result<(), ULogError> {
+        let format = parse_format(MessageBuf::from_vec(definition.as_bytes().to_vec()))?;
+        self.define_format(format)
+    }
+
+    /// Defines a format.
+    pub fn define_format(&mut self, format: def::Format) -> Result<(), ULogError> {
+        if !self.subscriptions.is_empty() {
+            return Err(ULogError::InvalidMessageOrder(format!(
+                "format {} was defined after the first subscription",
+                format.name
+            )));
+        }
+
+        self.writer
+            .write_message(&UlogMessage::FormatDefinition(format.clone()))?;
+        self.formats.insert(format.name.clone(), format);
+
+        Ok(())
+    }
+
+    /// Subscribes to instance `multi_id` of the format `message_name`, and returns the msg_id
+    /// which its records are written with.
+    ///
+    /// The format must have a `uint64_t timestamp` field, and the formats of its nested fields
+    /// must be defined.
+    pub fn subscription(&mut self, message_name: &str, multi_id: u8) -> Result<u16, ULogError> {
+        let format = self
+            .formats
+            .get(message_name)
+            .ok_or_else(|| ULogError::UndefinedFormat(message_name.to_string()))?;
+
+        let has_timestamp = format.fields.iter().any(|field| {
+            field.name == "timestamp"
+                && field.r#type.base_type == BaseType::UINT64
+                && field.r#type.array_size.is_none()
+        });
+        if !has_timestamp {
+            return Err(ULogError::MissingTimestamp);
+        }
+        format_size(format, &self.formats, &HashMap::new())?;
+
+        let msg_id = self.next_msg_id;
+        self.next_msg_id = msg_id
+            .checked_add(1)
+            .ok_or_else(|| ULogError::TooManySubscriptions(usize::from(u16::MAX) + 1))?;
+
+        let sub = msg::Subscription {
+            multi_id,
+            msg_id,
+            message_name: message_name.to_string(),
+        };
+        self.writer
+            .write_message(&UlogMessage::AddSubscription(sub.clone()))?;
+        self.subscriptions.insert(msg_id, sub);
+
+        Ok(msg_id)
+    }
+
+    /// Writes a record of the subscription `msg_id`.
+    ///
+    /// `values` holds the value of each field of the format, in order, except for the `timestamp`
+    /// field and `_padding` fields, which are filled in.  Each value must have the type of its
+    /// field: a `FieldValue::ScalarF32` for a `float` field, a `FieldValue::ArrayU8` of 4
+    /// elements for a `uint8_t[4]` field, and a value returned by [`ULogBuilder::nested`] for a
+    /// field of a nested format.
+    pub fn record(
+        &mut self,
+        msg_id: u16,
+        timestamp: u64,
+        values: impl IntoIterator<Item = FieldValue>,
+    ) -> Result<(), ULogError> {
+        let sub = self
+            .subscriptions
+            .get(&msg_id)
+            .ok_or(ULogError::UndefinedSubscription(msg_id))?;
+
+        let data = self.instance(&sub.message_name, Some(timestamp), values)?;
+        self.writer
+            .write_message(&UlogMessage::LoggedData(msg::LoggedData {
+                timestamp,
+                msg_id,
+                data,
+                raw_payload: None,
+            }))
+    }
+
+    /// Returns the value of a field of the nested format `format_name`, from the value of each of
+    /// its fields as for [`ULogBuilder::record`].  Only `_padding` fields are filled in.
+    pub fn nested(
+        &self,
+        format_name: &str,
+        values: impl IntoIterator<Item = FieldValue>,
+    ) -> Result<FieldValue, ULogError> {
+        self.instance(format_name, None, values)
+            .map(FieldValue::ScalarOther)
+    }
+
+    /// Builds an instance of the format `format_name`, checking each value against its field.
+    fn instance(
+        &self,
+        format_name: &str,
+        timestamp: Option<u64>,
+        values: impl IntoIterator<Item = FieldValue>,
+    ) -> Result<inst::Format, ULogError> {
+        let format = self
+            .formats
+            .get(format_name)
+            .ok_or_else(|| ULogError::UndefinedFormat(format_name.to_string()))?;
+
+        let mut values = values.into_iter();
+        let mut fields = Vec::with_capacity(format.fields.len());
+        for field in &format.fields {
+            let value = match timestamp {
+                Some(timestamp) if field.name == "timestamp" => FieldValue::ScalarU64(timestamp),
+                _ if field.name.starts_with("_padding") => match field.r#type.array_size {
+                    Some(size) => FieldValue::ArrayU8(vec![0; size]),
+                    None => FieldValue::ScalarU8(0),
+                },
+                _ => values.next().ok_or_else(|| {
+                    ULogError::TypeMismatch(format!(
+                        "no value for field {} of format {}",
+                        field.name, format.name
+                    ))
+                })?,
+            };
+
+            if !value_matches(&field.r#type, &value, &self.formats) {
+                return Err(ULogError::TypeMismatch(format!(
+                    "{value:?} is not a valid value for field {} of format {}",
+                    field.name, format.name
+                )));
+            }
+
+            fields.push(inst::Field {
+                name: field.name.clone(),
+                r#type: field.r#type.clone(),
+                value,
+            });
+        }
+
+        if values.next().is_some() {
+            return Err(ULogError::TypeMismatch(format!(
+                "more values than fields of format {}",
+                format.name
+            )));
+        }
+
+        Ok(inst::Format {
+            timestamp,
+            name: format.name.clone(),
+            fields,
+            multi_id_index: None,
+            def_format: format.clone(),
+        })
+    }
+
+    /// Writes any other message, such as an `Info`, a `Parameter` or a `LoggedString`.
+    pub fn message(&mut self, message: &UlogMessage) -> Result<(), ULogError> {
+        match message {
+            UlogMessage::FormatDefinition(format) => self.define_format(format.clone()),
+            UlogMessage::AddSubscription(_) | UlogMessage::LoggedData(_) => {
+                Err(ULogError::InvalidMessageOrder(
+                    "subscriptions and records are written with subscription() and record()"
+                        .to_string(),
+                ))
+            }
+            message => self.writer.write_message(message),
+        }
+    }
+
+    /// Flushes the log and returns the inner writer.
+    pub fn finish(self) -> Result<W, ULogError> {
+        self.writer.finish()
+    }
+}
+
+/// Returns whether `value` can be encoded as a value of type `type_expr`.
+fn value_matches(
+    type_expr: &def::TypeExpr,
+    value: &FieldValue,
+    formats: &HashMap<String, def::Format>,
+) -> bool {
+    use BaseType::*;
+    use FieldValue::*;
+
+    let Some(array_size) = type_expr.array_size else {
+        return match (&type_expr.base_type, value) {
+            (OTHER(name), ScalarOther(nested)) => nested_matches(name, nested, formats),
+            (base_type, value) => matches!(
+                (base_type, value),
+                (UINT8, ScalarU8(_))
+                    | (UINT16, ScalarU16(_))
+                    | (UINT32, ScalarU32(_))
+                    | (UINT64, ScalarU64(_))
+                    | (INT8, ScalarI8(_))
+                    | (INT16, ScalarI16(_))
+                    | (INT32, ScalarI32(_))
+                    | (INT64, ScalarI64(_))
+                    | (FLOAT, ScalarF32(_))
+                    | (DOUBLE, ScalarF64(_))
+                    | (BOOL, ScalarBool(_))
+                    | (CHAR, ScalarChar(_))
+            ),
+        };
+    };
+
+    let len = match (&type_expr.base_type, value) {
+        (UINT8, ArrayU8(v)) => v.len(),
+        (UINT16, ArrayU16(v)) => v.len(),
+        (UINT32, ArrayU32(v)) => v.len(),
+        (UINT64, ArrayU64(v)) => v.len(),
+        (INT8, ArrayI8(v)) => v.len(),
+        (INT16, ArrayI16(v)) => v.len(),
+        (INT32, ArrayI32(v)) => v.len(),
+        (INT64, ArrayI64(v)) => v.len(),
+        (FLOAT, ArrayF32(v)) => v.len(),
+        (DOUBLE, ArrayF64(v)) => v.len(),
+        (BOOL, ArrayBool(v)) => v.len(),
+        (CHAR, ArrayChar(v)) => v.len(),
+        (OTHER(name), ArrayOther(v)) if v.iter().all(|f| nested_matches(name, f, formats)) => {
+            v.len()
+        }
+        _ => return false,
+    };
+    len == array_size
+}
+
+/// Returns whether `nested` holds a value for every field of the format `name`.
+fn nested_matches(
+    name: &str,
+    nested: &inst::Format,
+    formats: &HashMap<String, def::Format>,
+) -> bool {
+    let Some(format) = formats.get(name) else {
+        return false;
+    };
+
+    nested.fields.len() == format.fields.len()
+        && format
+            .fields
+            .iter()
+            .zip(&nested.fields)
+            .all(|(field, value)| value_matches(&field.r#type, &value.value, formats))
+}
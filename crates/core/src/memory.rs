@@ -0,0 +1,139 @@
+//! Estimates of the memory retained by a parser, returned by `ULogParser::memory_report()`.
+//!
+//! Apart from the message being parsed, the parser only retains its options, its definitions,
+//! the metadata of the log and a few values per subscription, all of which are bounded by the
+//! number of distinct formats, keys and subscriptions rather than by the length of the log.  The
+//! exceptions are opt-in: the warnings of `collect_warnings()`, and the `MultiInfo` messages
+//! logged in the data section, which `ULogParser::flush()` releases.
+
+use std::mem::{size_of, size_of_val};
+
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::UlogMessage;
+use crate::model::{def, msg};
+
+/// The approximate number of bytes retained by a parser, by kind of state.
+///
+/// The sizes count the entries of each collection, with their strings and arrays, but not the
+/// overhead of the allocator or of the hash tables.  Messages already yielded are owned by the
+/// caller and not counted, and neither are the queues of a pipelined parser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// The options which name topics or types: the subscription allow list, the field transforms
+    /// and the opaque type sizes.
+    pub options: usize,
+    /// The formats and subscriptions.
+    pub definitions: usize,
+    /// The `Info` and `MultiInfo` messages, the parameters and the offsets of appended data.
+    pub metadata: usize,
+    /// The values tracked per subscription, e.g. the last timestamp of each.
+    pub per_subscription: usize,
+    /// The warnings collected by `collect_warnings()`.
+    pub warnings: usize,
+    /// The read buffers and the messages parsed but not yet yielded.
+    pub buffers: usize,
+}
+
+impl MemoryReport {
+    /// Returns the total number of bytes retained.
+    pub fn total(&self) -> usize {
+        self.options
+            + self.definitions
+            + self.metadata
+            + self.per_subscription
+            + self.warnings
+            + self.buffers
+    }
+}
+
+pub(crate) fn def_format_bytes(format: &def::Format) -> usize {
+    let fields: usize = format
+        .fields
+        .iter()
+        .map(|field| size_of::<def::Field>() + field.name.len() + type_expr_bytes(&field.r#type))
+        .sum();
+
+    size_of::<def::Format>() + format.name.len() + fields
+}
+
+fn type_expr_bytes(type_expr: &def::TypeExpr) -> usize {
+    match &type_expr.base_type {
+        def::BaseType::OTHER(name) => name.len(),
+        _ => 0,
+    }
+}
+
+pub(crate) fn subscription_bytes(sub: &msg::Subscription) -> usize {
+    size_of::<msg::Subscription>() + sub.message_name.len()
+}
+
+pub(crate) fn info_bytes(key: &str, type_expr: &def::TypeExpr, value: &FieldValue) -> usize {
+    key.len() + type_expr_bytes(type_expr) + size_of::<FieldValue>() + field_value_bytes(value)
+}
+
+/// Returns the size of a message, with its strings and arrays, e.g. of a message parsed ahead of
+/// being yielded.
+pub(crate) fn message_bytes(message: &UlogMessage) -> usize {
+    let contents = match message {
+        UlogMessage::FormatDefinition(format) => def_format_bytes(format),
+        UlogMessage::LoggedData(data) => {
+            inst_format_bytes(&data.data) + data.raw_payload.as_ref().map_or(0, Vec::len)
+        }
+        UlogMessage::AddSubscription(sub) => sub.message_name.len(),
+        UlogMessage::Info(info) => info_bytes(&info.key, &info.r#type, &info.value),
+        UlogMessage::MultiInfo(multi_info) => {
+            info_bytes(&multi_info.key, &multi_info.r#type, &multi_info.value)
+        }
+        UlogMessage::Parameter(param) => param.key.len() + type_expr_bytes(&param.r#type),
+        UlogMessage::DefaultParameter(param) => param.key.len() + type_expr_bytes(&param.r#type),
+        UlogMessage::LoggedString(string) | UlogMessage::TaggedLoggedString(string) => {
+            string.msg.len()
+        }
+        UlogMessage::Unhandled {
+            message_contents, ..
+        }
+        | UlogMessage::Ignored {
+            message_contents, ..
+        } => message_contents.len(),
+        _ => 0,
+    };
+
+    size_of::<UlogMessage>() + contents
+}
+
+/// Returns the heap size of a decoded value, i.e. of its array elements or nested fields.
+fn field_value_bytes(value: &FieldValue) -> usize {
+    match value {
+        FieldValue::ScalarOther(format) => inst_format_bytes(format),
+        FieldValue::ArrayU8(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayU16(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayU32(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayU64(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayI8(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayI16(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayI32(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayI64(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayF32(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayF64(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayBool(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayChar(v) => size_of_val(v.as_slice()),
+        FieldValue::ArrayOther(v) => v.iter().map(inst_format_bytes).sum(),
+        FieldValue::Opaque(v) => v.len(),
+        _ => 0,
+    }
+}
+
+fn inst_format_bytes(format: &inst::Format) -> usize {
+    let fields: usize = format
+        .fields
+        .iter()
+        .map(|field| {
+            size_of::<inst::Field>()
+                + field.name.len()
+                + type_expr_bytes(&field.r#type)
+                + field_value_bytes(&field.value)
+        })
+        .sum();
+
+    format.name.len() + def_format_bytes(&format.def_format) + fields
+}
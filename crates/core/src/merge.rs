@@ -0,0 +1,101 @@
+//! Merges the message streams of several logs into a single stream ordered by timestamp.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// The messages of several logs, merged by `merge_by_timestamp()`.
+///
+/// Each item is tagged with the index of the parser it came from, its source id, which
+/// distinguishes topics with the same name in different logs.
+pub struct MergedMessages<R: Read> {
+    sources: Vec<Source<R>>,
+    // The next message of each source which has one, keyed by its timestamp and source id.
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+struct Source<R: Read> {
+    parser: ULogParser<R>,
+    next: Option<Result<UlogMessage, ULogError>>,
+    last_timestamp: u64,
+}
+
+/// Merges the messages of several parsers into a single stream, in timestamp order.
+///
+/// Messages without a timestamp of their own, such as definitions and parameters, take the
+/// timestamp of the previous timestamped message from the same log (or 0), so they keep their
+/// position relative to it.  Messages with equal timestamps are ordered by source id, and each
+/// log's own messages always keep their file order.
+///
+/// Only the next message of each log is held in memory, so logs of any size can be merged.  A log
+/// stops contributing messages after its first error, which is yielded in its place.
+pub fn merge_by_timestamp<R: Read>(parsers: Vec<ULogParser<R>>) -> MergedMessages<R> {
+    let mut merged = MergedMessages {
+        sources: parsers
+            .into_iter()
+            .map(|parser| Source {
+                parser,
+                next: None,
+                last_timestamp: 0,
+            })
+            .collect(),
+        heap: BinaryHeap::new(),
+    };
+
+    for source_id in 0..merged.sources.len() {
+        merged.advance(source_id);
+    }
+
+    merged
+}
+
+impl<R: Read> MergedMessages<R> {
+    /// Reads the next message of a source, and queues it by its timestamp.
+    fn advance(&mut self, source_id: usize) {
+        let source = &mut self.sources[source_id];
+
+        let Some(msg_res) = source.parser.next() else {
+            return;
+        };
+
+        if let Ok(msg) = &msg_res {
+            if let Some(timestamp) = message_timestamp(msg) {
+                // Clamp jumps backwards, so a glitch does not pull the rest of this log ahead.
+                source.last_timestamp = source.last_timestamp.max(timestamp);
+            }
+        }
+
+        source.next = Some(msg_res);
+        self.heap.push(Reverse((source.last_timestamp, source_id)));
+    }
+}
+
+impl<R: Read> Iterator for MergedMessages<R> {
+    type Item = (usize, Result<UlogMessage, ULogError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, source_id)) = self.heap.pop()?;
+
+        // Unwrap is safe: a source is only queued once its next message has been read.
+        let msg_res = self.sources[source_id].next.take().unwrap();
+
+        if msg_res.is_ok() {
+            self.advance(source_id);
+        }
+
+        Some((source_id, msg_res))
+    }
+}
+
+fn message_timestamp(msg: &UlogMessage) -> Option<u64> {
+    match msg {
+        UlogMessage::LoggedData(data) => Some(data.timestamp),
+        UlogMessage::LoggedString(logged_string)
+        | UlogMessage::TaggedLoggedString(logged_string) => Some(logged_string.timestamp),
+        _ => None,
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::borrow::Cow;
+use std::mem::{align_of, size_of};
 
 use byteorder::{ByteOrder, LittleEndian};
 
@@ -105,6 +106,11 @@ impl MessageBuf {
         self.len() == 0
     }
 
+    /// Returns the remaining bytes starting from the current index, without consuming them.
+    pub fn remaining_bytes(&self) -> &[u8] {
+        &self.buf[self.current_index..]
+    }
+
     /// Consumes the `MessageBuf` and returns the remaining bytes starting from the current index.
     ///
     /// After calling this method, the `MessageBuf` is invalidated and can no longer be used.
@@ -235,6 +241,47 @@ impl MessageBuf {
         self.take_u8().map(|val| val != 0)
     }
 
+    /// Takes the next `array_size` `bool` values from the buffer and advances the index.
+    ///
+    /// The bytes are converted in a single pass, with the same rule as `take_bool()`: any nonzero
+    /// byte is `true`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the values or an error message if there are
+    /// not enough remaining bytes in the buffer.
+    pub fn take_bool_array(&mut self, array_size: usize) -> Result<Vec<bool>, ULogError> {
+        self.advance(array_size)
+            .map(|bytes| bytes.iter().map(|&byte| byte != 0).collect())
+    }
+
+    /// Takes the next `array_size` values of a primitive array from the buffer and advances the
+    /// index, borrowing them from the buffer where possible.
+    ///
+    /// The values are returned as `Cow::Borrowed` when their bytes can be used in place: on
+    /// little-endian targets, when the array starts at an address aligned for `T`.  `u8` and `i8`
+    /// arrays are therefore always borrowed.  Otherwise the values are copied into
+    /// `Cow::Owned`.  `bool` and `char` arrays are not supported, since not every byte is a valid
+    /// value of these types; use `take_bool_array()`, or `take_u8()` for each element, instead.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the values or an error message if there are
+    /// not enough remaining bytes in the buffer.
+    pub fn take_array_ref<T: PlainArrayElement>(
+        &mut self,
+        array_size: usize,
+    ) -> Result<Cow<'_, [T]>, ULogError> {
+        let size = array_size.checked_mul(size_of::<T>()).ok_or_else(|| {
+            ULogError::ParseError(format!(
+                "MessageBuf: Array of {array_size} values is too large"
+            ))
+        })?;
+        let bytes = self.advance(size)?;
+
+        Ok(cast_array(bytes))
+    }
+
     /// Advances the internal index by the given size and returns the
     /// corresponding byte slice from the buffer.
     ///
@@ -275,3 +322,52 @@ impl MessageBuf {
         self.advance(size).map(|_| ()) // Discard the result of advance
     }
 }
+
+/// Reinterprets the little-endian encoding of an array as a slice of `T` if it is suitably
+/// aligned, or else decodes a copy of it.  Any bytes after the last whole value are ignored.
+pub(crate) fn cast_array<T: PlainArrayElement>(bytes: &[u8]) -> Cow<'_, [T]> {
+    let in_place = (cfg!(target_endian = "little") || size_of::<T>() == 1)
+        && bytes.as_ptr().align_offset(align_of::<T>()) == 0;
+
+    if in_place {
+        // SAFETY: the bytes are aligned for `T`, the slice covers only whole values in the native
+        // byte order, and `PlainArrayElement` guarantees every bit pattern is valid.
+        Cow::Borrowed(unsafe {
+            std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), bytes.len() / size_of::<T>())
+        })
+    } else {
+        Cow::Owned(
+            bytes
+                .chunks_exact(size_of::<T>())
+                .map(T::from_le_slice)
+                .collect(),
+        )
+    }
+}
+
+/// A primitive type which `MessageBuf::take_array_ref()` can borrow from the buffer.
+///
+/// # Safety
+///
+/// Every bit pattern of the size of `Self` must be a valid value, and `from_le_slice` must decode
+/// the little-endian encoding of a value.  This holds for the integer and float primitives, but
+/// not for `bool` or `char`.
+pub unsafe trait PlainArrayElement: Copy {
+    /// Decodes a value from exactly `size_of::<Self>()` little-endian bytes.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_plain_array_element {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl PlainArrayElement for $ty {
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    // Unwrap is safe: callers pass exactly `size_of::<Self>()` bytes.
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_plain_array_element!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
@@ -0,0 +1,206 @@
+//! Typed helpers for the well known `Info` and `MultiInfo` metadata written by PX4.
+
+use std::fmt::Write;
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::model::inst::FieldValue;
+use crate::model::msg::{InfoValue, UlogMessage};
+use crate::parser::ULogParser;
+
+/// The well known `Info` metadata of a log, as returned by [`ULogParser::metadata`].
+///
+/// Each field is named after its info key, and is `None` if the key has not been seen or has an
+/// unexpected type.  The version fields ending in `_release` are PX4's encoded release numbers,
+/// e.g. `0x010b02ff` for v1.11.2.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogMetadata {
+    pub sys_name: Option<String>,
+    pub sys_mcu: Option<String>,
+    pub sys_os_name: Option<String>,
+    pub sys_os_ver: Option<String>,
+    pub sys_os_ver_release: Option<u32>,
+    pub sys_toolchain: Option<String>,
+    pub sys_toolchain_ver: Option<String>,
+    pub sys_uuid: Option<String>,
+    pub ver_hw: Option<String>,
+    pub ver_hw_subtype: Option<String>,
+    pub ver_sw: Option<String>,
+    pub ver_sw_branch: Option<String>,
+    pub ver_sw_release: Option<u32>,
+    pub log_uuid: Option<String>,
+    /// The file name of the log being replayed, if this log was written by a replay.
+    pub replay: Option<String>,
+    /// The offset of local time from UTC in seconds.
+    pub time_ref_utc: Option<i32>,
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Returns the well known `Info` metadata seen so far, e.g. `sys_name` and `ver_sw`.
+    ///
+    /// The info messages are in the definitions section, so the metadata is complete once the
+    /// first message of the data section has been read.
+    pub fn metadata(&self) -> LogMetadata {
+        LogMetadata {
+            sys_name: self.str_info("sys_name"),
+            sys_mcu: self.str_info("sys_mcu"),
+            sys_os_name: self.str_info("sys_os_name"),
+            sys_os_ver: self.str_info("sys_os_ver"),
+            sys_os_ver_release: self.int_info("sys_os_ver_release"),
+            sys_toolchain: self.str_info("sys_toolchain"),
+            sys_toolchain_ver: self.str_info("sys_toolchain_ver"),
+            sys_uuid: self.sys_uuid(),
+            ver_hw: self.str_info("ver_hw"),
+            ver_hw_subtype: self.str_info("ver_hw_subtype"),
+            ver_sw: self.str_info("ver_sw"),
+            ver_sw_branch: self.str_info("ver_sw_branch"),
+            ver_sw_release: self.int_info("ver_sw_release"),
+            log_uuid: self.log_uuid(),
+            replay: self.str_info("replay"),
+            time_ref_utc: self.int_info("time_ref_utc"),
+        }
+    }
+
+    fn str_info(&self, key: &str) -> Option<String> {
+        match self.info().get(key)? {
+            InfoValue::Str(value) => Some(value.clone()),
+            _ => {
+                log::warn!("Ignoring {key} with unexpected non-string type.");
+                None
+            }
+        }
+    }
+
+    fn int_info<T: TryFrom<i64>>(&self, key: &str) -> Option<T> {
+        let value = match self.info().get(key)? {
+            InfoValue::Scalar(value) => value.as_i64(),
+            _ => None,
+        };
+
+        match value.and_then(|value| T::try_from(value).ok()) {
+            Some(value) => Some(value),
+            None => {
+                log::warn!("Ignoring {key} with unexpected type or value.");
+                None
+            }
+        }
+    }
+
+    /// Reads messages until an `Info` message with the given key, and returns its value.
+    ///
+    /// Parsing stops right after the message, so a quick probe of e.g. `sys_name` reads only the
+    /// start of the definitions section.  The messages read are not returned, but their info and
+    /// parameters are recorded as usual, and the parser can carry on from where it stopped.  If
+    /// the key has already been seen, its latest value is returned without reading any further.
+    /// Returns `None` if the log ends without the key.
+    pub fn parse_until_info(&mut self, key: &str) -> Result<Option<InfoValue>, ULogError> {
+        if let Some(value) = self.info().get(key) {
+            return Ok(Some(value.clone()));
+        }
+
+        for msg_res in self.by_ref() {
+            if let UlogMessage::Info(info) = msg_res? {
+                if info.key == key {
+                    return Ok(Some(info.info_value()));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the unique id of the log, from the `log_uuid` info key.
+    ///
+    /// See [`ULogParser::sys_uuid`] for how the value is formatted.
+    pub fn log_uuid(&self) -> Option<String> {
+        self.uuid_info("log_uuid")
+    }
+
+    /// Returns the unique id of the vehicle, from the `sys_uuid` info key.
+    ///
+    /// The id may be logged either as a `char` array, which is returned as is, or as a `uint8_t`
+    /// array, which is returned as lowercase hex.  Returns `None` if the key has not been seen (so
+    /// far) or has any other type.
+    pub fn sys_uuid(&self) -> Option<String> {
+        self.uuid_info("sys_uuid")
+    }
+
+    fn uuid_info(&self, key: &str) -> Option<String> {
+        let info = self.get_info(key)?;
+
+        match &info.value {
+            FieldValue::ArrayChar(chars) => {
+                let uuid: String = chars.iter().collect();
+                Some(uuid.trim_end_matches('\0').to_string())
+            }
+            FieldValue::ArrayU8(bytes) => Some(bytes.iter().fold(String::new(), |mut hex, b| {
+                // Writing to a String cannot fail.
+                let _ = write!(hex, "{b:02x}");
+                hex
+            })),
+            _ => {
+                log::warn!("Ignoring {key} with unexpected type {}.", info.r#type);
+                None
+            }
+        }
+    }
+
+    /// Returns the offset of local time from UTC in microseconds, from the `time_ref_utc` info key.
+    ///
+    /// PX4 logs the offset in seconds, as set by the `SDLOG_UTC_OFFSET` parameter, so that a UTC
+    /// time plus the offset gives the local wall-clock time.  Returns `None` if the key has not
+    /// been seen (so far) or is not an integer.
+    pub fn utc_offset_us(&self) -> Option<i64> {
+        let info = self.get_info("time_ref_utc")?;
+
+        match info.value.as_i64() {
+            Some(seconds) => seconds.checked_mul(1_000_000),
+            None => {
+                log::warn!(
+                    "Ignoring time_ref_utc with unexpected type {}.",
+                    info.r#type
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the performance counters logged by PX4 as `(name, value)` pairs.
+    ///
+    /// PX4 writes one counter per `MultiInfo` message under the keys `perf_counter_preflight`
+    /// and `perf_counter_postflight`.  Each line looks like
+    /// `vehicle_imu: gyro update interval: 7612 events, 2443.69 avg, ...`, and is split at the last
+    /// `": "` into the counter name and its readable value.
+    ///
+    /// The postflight counters are only written at the end of the log, so call this after the
+    /// stream has been fully consumed.
+    pub fn perf_counters(&self) -> Vec<(String, String)> {
+        let mut counters = Vec::new();
+
+        for key in ["perf_counter_preflight", "perf_counter_postflight"] {
+            let Some(entries) = self.multi_info().get(key) else {
+                continue;
+            };
+
+            for entry in entries {
+                let FieldValue::ArrayChar(chars) = &entry.value else {
+                    log::warn!(
+                        "Ignoring {key} entry with non-string type {}.",
+                        entry.r#type
+                    );
+                    continue;
+                };
+
+                let line: String = chars.iter().collect();
+                let line = line.trim_end_matches(['\0', '\n']);
+
+                match line.rsplit_once(": ") {
+                    Some((name, value)) => counters.push((name.to_string(), value.to_string())),
+                    None => counters.push((line.to_string(), String::new())),
+                }
+            }
+        }
+
+        counters
+    }
+}
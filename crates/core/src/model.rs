@@ -1,3 +1,5 @@
+use crate::errors::ULogError;
+
 pub(crate) const MAGIC: [u8; 7] = [b'U', b'L', b'o', b'g', 0x01, 0x12, 0x35];
 
 pub mod msg {
@@ -23,10 +25,25 @@ pub mod msg {
             msg_type: u8,
             message_contents: Vec<u8>,
         },
+        /// A message which was read but not decoded, e.g. the `DATA` message of a subscription
+        /// outside the allow list.  The contents of a `DATA` message start with its `msg_id`,
+        /// so the message can be attributed to its subscription and re-encoded without loss.
         Ignored {
             msg_type: u8,
             message_contents: Vec<u8>,
         },
+        /// Marks the start of a section appended to the log at the given file offset.
+        /// Only emitted when `ULogParserBuilder::read_appended_data()` is enabled.
+        AppendedSectionStart {
+            offset: u64,
+        },
+        /// Marks `len` bytes from the file offset `offset` which were skipped because they do not
+        /// form valid messages.  Only emitted when `ULogParserBuilder::recover_on_error()` is
+        /// enabled.
+        Corrupt {
+            offset: u64,
+            len: u64,
+        },
     }
 
     #[derive(Debug, Copy, Clone)]
@@ -106,6 +123,36 @@ pub mod msg {
         pub value: inst::FieldValue,
     }
 
+    /// The value of an `Info` or `MultiInfo` message, distinguishing strings, scalars and arrays.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum InfoValue {
+        /// A single value, e.g. `uint32_t ver_sw_release`.
+        Scalar(inst::FieldValue),
+        /// An array of any type other than `char`, e.g. `uint8_t[16] uuid`, as scalar elements.
+        Array(def::BaseType, Vec<inst::FieldValue>),
+        /// A `char` array, e.g. `char[10] sys_name`, with any trailing NUL padding removed.
+        Str(String),
+    }
+
+    impl InfoValue {
+        pub(crate) fn new(r#type: &def::TypeExpr, value: &inst::FieldValue) -> Self {
+            match (value, value.to_scalars()) {
+                (inst::FieldValue::ArrayChar(chars), _) => {
+                    let s: String = chars.iter().collect();
+                    InfoValue::Str(s.trim_end_matches('\0').to_string())
+                }
+                (_, Some(elements)) => InfoValue::Array(r#type.base_type.clone(), elements),
+                (scalar, None) => InfoValue::Scalar(scalar.clone()),
+            }
+        }
+    }
+
+    impl Info {
+        pub fn info_value(&self) -> InfoValue {
+            InfoValue::new(&self.r#type, &self.value)
+        }
+    }
+
     #[derive(Debug, Clone)]
     pub struct MultiInfo {
         pub is_continued: bool,
@@ -114,6 +161,12 @@ pub mod msg {
         pub value: inst::FieldValue,
     }
 
+    impl MultiInfo {
+        pub fn info_value(&self) -> InfoValue {
+            InfoValue::new(&self.r#type, &self.value)
+        }
+    }
+
     #[derive(Debug)]
     pub struct Parameter {
         pub key: String,
@@ -152,6 +205,37 @@ pub mod msg {
         pub timestamp: u64,
         pub msg_id: u16,
         pub data: inst::Format,
+        /// The payload this record was decoded from, following the `msg_id`.  Only set when the
+        /// parser was built with `with_raw_payload(true)`.
+        pub(crate) raw_payload: Option<Vec<u8>>,
+    }
+
+    impl LoggedData {
+        /// Returns the payload this record was decoded from, following the `msg_id`.
+        ///
+        /// Only available when the parser was built with `with_raw_payload(true)`.
+        pub fn raw_payload(&self) -> Option<&[u8]> {
+            self.raw_payload.as_deref()
+        }
+
+        /// Returns the value of a named time field, such as `timestamp_sample`.
+        ///
+        /// Topics may carry several time fields besides the leading `timestamp`, at any position.
+        /// Fields of nested formats are addressed by `/` separated paths, e.g. `gps/timestamp`.
+        /// `uint64_t`, `uint32_t` and non-negative `int64_t` fields are accepted.  The top-level
+        /// `timestamp` is always available, even when it has been filtered from the fields.
+        pub fn timestamp_field(&self, name: &str) -> Option<u64> {
+            match self.data.find_field(name) {
+                Some(field) => match field.value {
+                    inst::FieldValue::ScalarU64(v) => Some(v),
+                    inst::FieldValue::ScalarU32(v) => Some(u64::from(v)),
+                    inst::FieldValue::ScalarI64(v) => u64::try_from(v).ok(),
+                    _ => None,
+                },
+                None if name == "timestamp" => Some(self.timestamp),
+                None => None,
+            }
+        }
     }
 
     #[derive(Debug, Copy, Clone)]
@@ -159,6 +243,13 @@ pub mod msg {
         pub(crate) duration: u16,
     }
 
+    impl Dropout {
+        /// The duration of the dropout, in milliseconds.
+        pub fn duration(&self) -> u16 {
+            self.duration
+        }
+    }
+
     impl TryFrom<u8> for LogLevel {
         type Error = ULogError;
 
@@ -206,7 +297,13 @@ pub mod def {
     #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     pub struct TypeExpr {
         pub base_type: BaseType,
+        /// The total number of elements, for both one and two dimensional arrays.
         pub array_size: Option<usize>,
+        /// The `(rows, columns)` of a two dimensional array declared as `float[3][4]`.
+        ///
+        /// The elements are stored in row major order and decoded into a flat array of
+        /// `array_size` elements. Use `inst::Field::to_matrix` to split them into rows.
+        pub(crate) matrix_shape: Option<(usize, usize)>,
     }
 
     #[allow(clippy::upper_case_acronyms)]
@@ -254,7 +351,18 @@ pub mod inst {
         pub value: FieldValue,
     }
 
-    #[derive(Debug, Clone)]
+    /// Controls how array fields are represented when a `Format` is flattened into columns.
+    #[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+    pub enum ArrayMode {
+        /// One column per element, suffixed with the element index, e.g. `gyro.00`, `gyro.01`.
+        /// Arrays of nested formats expand into the columns of each element, e.g. `esc.00/rpm`.
+        #[default]
+        Expand,
+        /// A single column holding the whole array value, e.g. `gyro`.
+        Nested,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
     pub enum ParameterValue {
         INT32(i32),
         FLOAT(f32),
@@ -291,6 +399,13 @@ pub mod inst {
         ArrayBool(Vec<bool>),
         ArrayChar(Vec<char>),
         ArrayOther(Vec<inst::Format>),
+
+        /// An array field which was not decoded, holding its number of elements.
+        /// See `ULogParserBuilder::skip_arrays_larger_than()`.
+        Skipped(usize),
+        /// The raw bytes of a field whose base type is not known to this crate.
+        /// See `ULogParserBuilder::opaque_type_sizes()`.
+        Opaque(Vec<u8>),
     }
 }
 
@@ -314,9 +429,271 @@ impl inst::FieldValue {
             _ => None, // not an array
         }
     }
+
+    /// Best-effort conversion of a numeric scalar to `f64`.
+    ///
+    /// Integers are converted with `as`, so 64 bit integers above 2^53 lose precision.
+    /// Returns `None` for bools, chars, nested formats and arrays.
+    pub fn as_f64(&self) -> Option<f64> {
+        use inst::FieldValue::*;
+        match *self {
+            ScalarU8(v) => Some(f64::from(v)),
+            ScalarU16(v) => Some(f64::from(v)),
+            ScalarU32(v) => Some(f64::from(v)),
+            #[allow(clippy::cast_precision_loss)]
+            ScalarU64(v) => Some(v as f64),
+            ScalarI8(v) => Some(f64::from(v)),
+            ScalarI16(v) => Some(f64::from(v)),
+            ScalarI32(v) => Some(f64::from(v)),
+            #[allow(clippy::cast_precision_loss)]
+            ScalarI64(v) => Some(v as f64),
+            ScalarF32(v) => Some(f64::from(v)),
+            ScalarF64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Best-effort conversion of an integer scalar to `i64`.
+    ///
+    /// Floats are never truncated, so this returns `None` for floats as well as for `u64` values
+    /// above `i64::MAX`, bools, chars, nested formats and arrays.
+    pub fn as_i64(&self) -> Option<i64> {
+        use inst::FieldValue::*;
+        match *self {
+            ScalarU8(v) => Some(i64::from(v)),
+            ScalarU16(v) => Some(i64::from(v)),
+            ScalarU32(v) => Some(i64::from(v)),
+            ScalarU64(v) => i64::try_from(v).ok(),
+            ScalarI8(v) => Some(i64::from(v)),
+            ScalarI16(v) => Some(i64::from(v)),
+            ScalarI32(v) => Some(i64::from(v)),
+            ScalarI64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Applies `f` to a numeric scalar, or to each element of a numeric array.
+    ///
+    /// Floats keep their type, while integers become `f64`.  Returns `None` for bools, chars and
+    /// nested formats.
+    pub(crate) fn map_numeric(&self, f: fn(f64) -> f64) -> Option<inst::FieldValue> {
+        use inst::FieldValue::*;
+        #[allow(clippy::cast_possible_truncation)]
+        let map_f32 = |v: f32| f(f64::from(v)) as f32;
+
+        match self {
+            ScalarF32(v) => Some(ScalarF32(map_f32(*v))),
+            ScalarF64(v) => Some(ScalarF64(f(*v))),
+            ArrayF32(values) => Some(ArrayF32(values.iter().map(|v| map_f32(*v)).collect())),
+            ArrayF64(values) => Some(ArrayF64(values.iter().map(|v| f(*v)).collect())),
+            _ => match self.to_scalars() {
+                Some(elements) => elements
+                    .iter()
+                    .map(|element| element.as_f64().map(f))
+                    .collect::<Option<Vec<_>>>()
+                    .map(ArrayF64),
+                None => self.as_f64().map(|v| ScalarF64(f(v))),
+            },
+        }
+    }
+
+    /// Appends the elements of the array `other` to this array, which must be of the same type.
+    pub(crate) fn append(&mut self, other: &inst::FieldValue) -> Result<(), ULogError> {
+        use inst::FieldValue::*;
+        match (&mut *self, other) {
+            (ArrayU8(a), ArrayU8(b)) => a.extend_from_slice(b),
+            (ArrayU16(a), ArrayU16(b)) => a.extend_from_slice(b),
+            (ArrayU32(a), ArrayU32(b)) => a.extend_from_slice(b),
+            (ArrayU64(a), ArrayU64(b)) => a.extend_from_slice(b),
+            (ArrayI8(a), ArrayI8(b)) => a.extend_from_slice(b),
+            (ArrayI16(a), ArrayI16(b)) => a.extend_from_slice(b),
+            (ArrayI32(a), ArrayI32(b)) => a.extend_from_slice(b),
+            (ArrayI64(a), ArrayI64(b)) => a.extend_from_slice(b),
+            (ArrayF32(a), ArrayF32(b)) => a.extend_from_slice(b),
+            (ArrayF64(a), ArrayF64(b)) => a.extend_from_slice(b),
+            (ArrayBool(a), ArrayBool(b)) => a.extend_from_slice(b),
+            (ArrayChar(a), ArrayChar(b)) => a.extend_from_slice(b),
+            (ArrayOther(a), ArrayOther(b)) => a.extend_from_slice(b),
+            (base, other) => {
+                return Err(ULogError::TypeMismatch(format!(
+                    "Cannot append {other:?} to {base:?}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+// Strict conversions to primitives: the variant must match the target type exactly.
+macro_rules! impl_tryfrom_scalar {
+    ($ty:ty, $variant:ident) => {
+        impl TryFrom<&inst::FieldValue> for $ty {
+            type Error = ULogError;
+
+            fn try_from(value: &inst::FieldValue) -> Result<Self, Self::Error> {
+                match value {
+                    inst::FieldValue::$variant(v) => Ok(*v),
+                    other => Err(ULogError::TypeMismatch(format!(
+                        "Expected {} but got {:?}",
+                        stringify!($ty),
+                        other
+                    ))),
+                }
+            }
+        }
+
+        impl TryFrom<inst::FieldValue> for $ty {
+            type Error = ULogError;
+
+            fn try_from(value: inst::FieldValue) -> Result<Self, Self::Error> {
+                <$ty>::try_from(&value)
+            }
+        }
+    };
+}
+
+impl_tryfrom_scalar!(u8, ScalarU8);
+impl_tryfrom_scalar!(u16, ScalarU16);
+impl_tryfrom_scalar!(u32, ScalarU32);
+impl_tryfrom_scalar!(u64, ScalarU64);
+impl_tryfrom_scalar!(i8, ScalarI8);
+impl_tryfrom_scalar!(i16, ScalarI16);
+impl_tryfrom_scalar!(i32, ScalarI32);
+impl_tryfrom_scalar!(i64, ScalarI64);
+impl_tryfrom_scalar!(f32, ScalarF32);
+impl_tryfrom_scalar!(f64, ScalarF64);
+impl_tryfrom_scalar!(bool, ScalarBool);
+impl_tryfrom_scalar!(char, ScalarChar);
+
+impl inst::Field {
+    /// Splits a two dimensional array field into its rows.
+    ///
+    /// Returns `None` if the field was not declared with two dimensions.
+    pub fn to_matrix(&self) -> Option<Vec<Vec<inst::FieldValue>>> {
+        let (_, columns) = self.r#type.matrix_shape?;
+        let scalars = self.value.to_scalars()?;
+
+        Some(scalars.chunks(columns).map(<[_]>::to_vec).collect())
+    }
 }
 
 impl inst::Format {
+    /// Flattens the fields into `(column name, value)` pairs, recursing into nested formats.
+    ///
+    /// Column names are paths relative to this format, with nested fields separated by `/`,
+    /// e.g. `accel/x`.  Array fields are named according to `mode`.
+    pub fn flatten_fields(&self, mode: inst::ArrayMode) -> Vec<(String, inst::FieldValue)> {
+        let mut flattened = Vec::new();
+        self.flatten_fields_sub(None, mode, &mut flattened);
+        flattened
+    }
+
+    /// Returns the field at a `/` separated path, e.g. `accel/x`, descending into scalar nested
+    /// formats.
+    pub fn find_field(&self, path: &str) -> Option<&inst::Field> {
+        let (name, rest) = match path.split_once('/') {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (path, None),
+        };
+
+        let field = self.fields.iter().find(|field| field.name == name)?;
+
+        match (rest, &field.value) {
+            (None, _) => Some(field),
+            (Some(rest), inst::FieldValue::ScalarOther(nested)) => nested.find_field(rest),
+            (Some(_), _) => None,
+        }
+    }
+
+    pub(crate) fn find_field_mut(&mut self, path: &str) -> Option<&mut inst::Field> {
+        let (name, rest) = match path.split_once('/') {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (path, None),
+        };
+
+        let field = self.fields.iter_mut().find(|field| field.name == name)?;
+
+        match rest {
+            None => Some(field),
+            Some(rest) => match &mut field.value {
+                inst::FieldValue::ScalarOther(nested) => nested.find_field_mut(rest),
+                _ => None,
+            },
+        }
+    }
+
+    /// Like `flatten_fields()`, but also expands the elements of arrays of nested formats into
+    /// their fields in `ArrayMode::Nested`, e.g. `esc.00/rpm`, so that every value is a scalar or
+    /// an array of primitives.  This is the layout of the tabular exporters.
+    pub(crate) fn flatten_columns(&self, mode: inst::ArrayMode) -> Vec<(String, inst::FieldValue)> {
+        let mut columns = Vec::new();
+        self.flatten_columns_sub(None, mode, &mut columns);
+        columns
+    }
+
+    fn flatten_columns_sub(
+        &self,
+        path: Option<&str>,
+        mode: inst::ArrayMode,
+        columns: &mut Vec<(String, inst::FieldValue)>,
+    ) {
+        for (name, value) in self.flatten_fields(mode) {
+            let name = match path {
+                None => name,
+                Some(path) => format!("{path}/{name}"),
+            };
+
+            match value {
+                inst::FieldValue::ArrayOther(elements) => {
+                    for (index, element) in elements.iter().enumerate() {
+                        element.flatten_columns_sub(
+                            Some(&format!("{name}.{index:02}")),
+                            mode,
+                            columns,
+                        );
+                    }
+                }
+                value => columns.push((name, value)),
+            }
+        }
+    }
+
+    /// Returns the column names produced by `flatten_fields()`, in the same order.
+    pub fn column_names(&self, mode: inst::ArrayMode) -> Vec<String> {
+        self.flatten_fields(mode)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn flatten_fields_sub(
+        &self,
+        path: Option<&str>,
+        mode: inst::ArrayMode,
+        flattened: &mut Vec<(String, inst::FieldValue)>,
+    ) {
+        for field in &self.fields {
+            let field_path = match path {
+                None => field.name.clone(),
+                Some(path) => format!("{path}/{}", field.name),
+            };
+
+            match (field.value.to_scalars(), mode) {
+                (Some(elements), inst::ArrayMode::Expand) => {
+                    for (index, element) in elements.into_iter().enumerate() {
+                        push_flattened(
+                            format!("{field_path}.{index:02}"),
+                            element,
+                            mode,
+                            flattened,
+                        );
+                    }
+                }
+                _ => push_flattened(field_path, field.value.clone(), mode, flattened),
+            }
+        }
+    }
+
     #[deprecated]
     pub fn flatten(&self) -> Vec<(String, inst::FieldValue)> {
         let prefix: String = self.to_string();
@@ -366,6 +743,20 @@ impl inst::Format {
 }
 
 impl def::TypeExpr {
+    /// Creates a scalar type, or a one dimensional array of `array_size` elements.
+    pub fn new(base_type: def::BaseType, array_size: Option<usize>) -> Self {
+        Self {
+            base_type,
+            array_size,
+            matrix_shape: None,
+        }
+    }
+
+    /// Returns the `(rows, columns)` of a two dimensional array declared as `float[3][4]`.
+    pub fn matrix_shape(&self) -> Option<(usize, usize)> {
+        self.matrix_shape
+    }
+
     pub fn is_scalar(&self) -> bool {
         self.array_size.is_none()
     }
@@ -373,4 +764,23 @@ impl def::TypeExpr {
     pub fn is_array(&self) -> bool {
         self.array_size.is_some()
     }
+
+    pub fn is_matrix(&self) -> bool {
+        self.matrix_shape.is_some()
+    }
+}
+
+// Pushes a single column, or the columns of a nested format.
+fn push_flattened(
+    path: String,
+    value: inst::FieldValue,
+    mode: inst::ArrayMode,
+    flattened: &mut Vec<(String, inst::FieldValue)>,
+) {
+    match value {
+        inst::FieldValue::ScalarOther(nested) => {
+            nested.flatten_fields_sub(Some(&path), mode, flattened);
+        }
+        value => flattened.push((path, value)),
+    }
 }
@@ -0,0 +1,229 @@
+//! Decoding of a topic into a contiguous array of `#[repr(C)]` structs, for handing to C code.
+//!
+//! The PX4 logger writes each sample as the bytes of its C struct, so a Rust struct with the same
+//! layout can be filled by copying the payloads directly.  The layout of the struct is declared by
+//! implementing [`PackedRecord`], and is checked against the format definition in the log before
+//! any data is copied.
+
+use std::io::Read;
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use crate::builder::ULogParserBuilder;
+use crate::errors::ULogError;
+use crate::layout::{field_range, format_size};
+use crate::model::{def, msg};
+use crate::parser::ULogParser;
+
+/// A field of a [`PackedRecord`]: its name in the format definition, and its location in the struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedField {
+    pub name: &'static str,
+    /// The offset of the field within the struct, as given by `std::mem::offset_of!`.
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl PackedField {
+    pub const fn new(name: &'static str, offset: usize, size: usize) -> Self {
+        PackedField { name, offset, size }
+    }
+}
+
+/// A struct which can be filled from the raw payload of a `LoggedData` message.
+///
+/// ```
+/// use std::mem::{offset_of, size_of};
+/// use yule_log::packed::{PackedField, PackedRecord};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct Gyro {
+///     timestamp: u64,
+///     x: f32,
+///     id: u8,
+///     _padding0: [u8; 3],
+/// }
+///
+/// unsafe impl PackedRecord for Gyro {
+///     const FIELDS: &'static [PackedField] = &[
+///         PackedField::new("timestamp", offset_of!(Gyro, timestamp), size_of::<u64>()),
+///         PackedField::new("x", offset_of!(Gyro, x), size_of::<f32>()),
+///         PackedField::new("id", offset_of!(Gyro, id), size_of::<u8>()),
+///         PackedField::new("_padding0", offset_of!(Gyro, _padding0), 3),
+///     ];
+/// }
+/// ```
+///
+/// # Safety
+///
+/// The struct must be `#[repr(C)]`, `FIELDS` must list every one of its fields with its true
+/// offset and size, and every bit pattern must be a valid value of each field.  Fields should
+/// therefore be integers, floats, arrays of these or other `PackedRecord` structs.  In particular
+/// `bool` and `char` fields of the format must be declared as `u8`.
+pub unsafe trait PackedRecord: Copy {
+    const FIELDS: &'static [PackedField];
+}
+
+/// The samples of a topic, decoded by [`PackedArrayBuilder`] into a contiguous array.
+#[derive(Debug, Clone)]
+pub struct PackedArray<T: PackedRecord> {
+    records: Vec<T>,
+}
+
+impl<T: PackedRecord> PackedArray<T> {
+    /// Returns a pointer to the first record, which stays valid as long as the array is alive.
+    pub fn as_ptr(&self) -> *const T {
+        self.records.as_ptr()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.records
+    }
+
+    /// Returns the records as bytes, which are the concatenated payloads of the samples.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the layout check guarantees that the fields of `T` cover all of its bytes, so
+        // there is no uninitialised padding.
+        unsafe {
+            std::slice::from_raw_parts(
+                self.records.as_ptr().cast::<u8>(),
+                self.records.len() * size_of::<T>(),
+            )
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.records
+    }
+}
+
+/// Decodes the samples of a single topic into a [`PackedArray`].
+pub struct PackedArrayBuilder<R: Read, T: PackedRecord> {
+    reader: R,
+    message_name: String,
+    multi_id: u8,
+    record: PhantomData<T>,
+}
+
+impl<R: Read, T: PackedRecord> PackedArrayBuilder<R, T> {
+    #[must_use]
+    pub fn new(reader: R, message_name: &str) -> Self {
+        PackedArrayBuilder {
+            reader,
+            message_name: message_name.to_string(),
+            multi_id: 0,
+            record: PhantomData,
+        }
+    }
+
+    /// Selects the instance of the topic to decode.  The default is 0.
+    #[must_use]
+    pub fn multi_id(mut self, multi_id: u8) -> Self {
+        self.multi_id = multi_id;
+        self
+    }
+
+    /// Reads the log, returning the samples of the topic in file order.
+    ///
+    /// The layout of `T` is checked against the format definition of the topic before the first
+    /// sample is copied, and `ULogError::LayoutMismatch` is returned if they differ.
+    pub fn build(self) -> Result<PackedArray<T>, ULogError> {
+        let mut parser = ULogParserBuilder::new(self.reader)
+            .set_subscription_allow_list([self.message_name.as_str()])
+            .with_raw_payload(true)
+            .build()?;
+
+        let mut records: Vec<T> = Vec::new();
+        let mut layout_checked = false;
+
+        while let Some(msg_res) = parser.next() {
+            let msg::UlogMessage::LoggedData(data) = msg_res? else {
+                continue;
+            };
+
+            let sub = parser.get_subscription_ref(data.msg_id)?;
+            if sub.message_name != self.message_name || sub.multi_id != self.multi_id {
+                continue;
+            }
+
+            if !layout_checked {
+                parser.check_packed_layout::<T>(&data.data.def_format)?;
+                layout_checked = true;
+            }
+
+            // Unwrap is safe: the parser was built with `with_raw_payload(true)`.
+            let payload = data.raw_payload.unwrap();
+            if payload.len() != size_of::<T>() {
+                return Err(ULogError::ParseError(format!(
+                    "{} payload of {} bytes, expected {}",
+                    self.message_name,
+                    payload.len(),
+                    size_of::<T>()
+                )));
+            }
+
+            records.reserve(1);
+            // SAFETY: the payload is exactly as long as `T`, its layout has been checked, and
+            // `PackedRecord` guarantees that every bit pattern is valid.
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    payload.as_ptr(),
+                    records.as_mut_ptr().add(records.len()).cast::<u8>(),
+                    payload.len(),
+                );
+                records.set_len(records.len() + 1);
+            }
+        }
+
+        Ok(PackedArray { records })
+    }
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Checks that the fields of `T` match the fields of `format`, by name, offset and size, and
+    /// that together they account for every byte of both.
+    fn check_packed_layout<T: PackedRecord>(&self, format: &def::Format) -> Result<(), ULogError> {
+        let mismatch = |msg: String| ULogError::LayoutMismatch(format!("{}: {msg}", format.name));
+
+        let encoded_size = format_size(format, &self.formats, &self.opaque_type_sizes)?;
+        if size_of::<T>() != encoded_size {
+            return Err(mismatch(format!(
+                "the struct is {} bytes, but the format is {encoded_size} bytes",
+                size_of::<T>()
+            )));
+        }
+
+        if T::FIELDS.len() != format.fields.len() {
+            return Err(mismatch(format!(
+                "the struct has {} fields, but the format has {}",
+                T::FIELDS.len(),
+                format.fields.len()
+            )));
+        }
+
+        for field in T::FIELDS {
+            let range = field_range(format, field.name, &self.formats, &self.opaque_type_sizes)?
+                .ok_or_else(|| mismatch(format!("no field named {}", field.name)))?;
+
+            if range != (field.offset..field.offset + field.size) {
+                return Err(mismatch(format!(
+                    "field {} is at bytes {range:?}, but at {}..{} in the struct",
+                    field.name,
+                    field.offset,
+                    field.offset + field.size
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
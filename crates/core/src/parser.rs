@@ -1,15 +1,24 @@
 #![allow(non_camel_case_types)]
 
-use std::collections::{HashMap, HashSet};
-use std::io::Read;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Read, Seek};
+use std::mem::size_of;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Instant;
 
 use byteorder::{ByteOrder, LittleEndian};
+use regex::Regex;
 
 use crate::datastream::DataStream;
 use crate::errors::ULogError;
 use crate::errors::ULogError::{UndefinedFormat, UndefinedSubscription};
 use crate::field_helpers::{parse_array, parse_data_field, parse_primitive_array};
 use crate::formats::{parse_field, parse_format};
+use crate::header_codec::HeaderCodec;
+use crate::index::{IndexEntry, LogIndex};
+use crate::layout::{decode_timestamp, field_range, formats_structurally_equal, type_size};
+use crate::memory::{self, MemoryReport};
 use crate::message_buf::MessageBuf;
 use crate::model::def::BaseType;
 use crate::model::msg::{
@@ -17,26 +26,194 @@ use crate::model::msg::{
 };
 use crate::model::MAGIC;
 use crate::model::{def, inst, msg};
+use crate::pipeline::{Output, Pipeline};
 use crate::tokenizer::TokenList;
+use crate::warnings::{ParseWarning, WarningKind};
 
 pub struct ULogParser<R: Read> {
     state: State,
     file_header: Option<FileHeader>,
     pub formats: HashMap<String, def::Format>,
-    subscriptions: HashMap<u16, msg::Subscription>,
+    pub(crate) subscriptions: HashMap<u16, msg::Subscription>,
+    // The last subscription of each msg_id removed by a REMOVE_SUBSCRIPTION message, so the
+    // records logged before the removal can still be attributed to it.
+    removed_subscriptions: HashMap<u16, msg::Subscription>,
     message_name_with_multi_id: HashSet<String>,
-    subscription_filter: SubscriptionFilter,
-    datastream: DataStream<R>,
+    pub(crate) subscription_filter: SubscriptionFilter,
+    pub(crate) drop_ignored: bool,
+    // Records whose timestamp fails this predicate are skipped before being decoded.
+    pub(crate) time_filter: Option<TimeFilter>,
+    pub(crate) datastream: DataStream<R>,
+    // Reads the message headers, or `None` for the standard header.
+    pub(crate) header_codec: Option<Arc<dyn HeaderCodec>>,
     max_bytes_to_read: Option<usize>,
+    pub(crate) read_appended_data: bool,
+    pub(crate) resync_on_error: bool,
+    pub(crate) recover_on_error: bool,
+    pub(crate) error_on_dropout: bool,
+    pub(crate) require_sorted: bool,
+    pub(crate) collect_warnings: bool,
+    // Recoverable issues met so far, if `collect_warnings` is set.
+    warnings: Vec<ParseWarning>,
+    pub(crate) fold_resubscriptions: bool,
+    // The msg_id of the first subscription to each topic instance, by name and `multi_id`, if
+    // `fold_resubscriptions` is set.
+    folded_msg_ids: HashMap<(String, u8), u16>,
+    pub(crate) growing: bool,
+    // Where to resume a growing log and the state to resume in, with the offset at which the
+    // stream ended, once its end has been reached.
+    resume: Option<(usize, State, usize)>,
+    // The timestamp of the last record yielded, reported by `error_on_dropout`.
+    last_data_timestamp: u64,
+    // The timestamp of the last record yielded, checked by `require_sorted`.
+    last_sorted_timestamp: Option<u64>,
+    // The file offset of the last record yielded by the pipeline, which reads ahead of it.
+    record_offset: usize,
+    // A copy of the contents of the message being parsed, kept only for `resync_on_error` and
+    // `recover_on_error`.
+    pub(crate) last_message: Vec<u8>,
+    // Offsets of the appended sections not yet reached, in increasing order.
+    appended_offsets: VecDeque<usize>,
     pub(crate) include_header: bool,
     pub(crate) include_timestamp: bool,
     pub(crate) include_padding: bool,
+    pub(crate) bad_timestamp_policy: BadTimestampPolicy,
+    pub(crate) add_index_field: bool,
+    pub(crate) max_subscriptions: Option<usize>,
+    pub(crate) max_records: Option<u64>,
+    // The number of `LoggedData` records yielded, counted against `max_records`.
+    records_yielded: u64,
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) max_array_size: Option<usize>,
+    pub(crate) opaque_type_sizes: HashMap<String, usize>,
+    // Field transforms, keyed by subscription name.
+    pub(crate) transforms: HashMap<String, Vec<(String, FieldTransform)>>,
+    pub(crate) name_instance_pattern: Option<Regex>,
+    pub(crate) raw_payload: bool,
+    pub(crate) config: ParserConfig,
+    pub(crate) pipelined: bool,
+    // The decoding workers, started by the first call to `next()` if `pipelined` is set.
+    pub(crate) pipeline: Option<Pipeline>,
+    messages_read: u64,
+    last_timestamps: HashMap<u16, u64>,
+    sample_counts: HashMap<u16, u64>,
+    // Byte offset in the stream of the message currently being parsed.
+    pub(crate) message_offset: usize,
+    // Byte offset of the first message of the data section, once it has been reached.
+    pub(crate) data_section_offset: Option<usize>,
+    multi_info: HashMap<String, Vec<msg::MultiInfo>>,
+    info: HashMap<String, msg::Info>,
+    info_values: HashMap<String, msg::InfoValue>,
+    parameters: HashMap<String, inst::ParameterValue>,
+    // Parameters logged in the definitions section, before any change in flight.
+    initial_parameters: HashMap<String, inst::ParameterValue>,
+    // Messages already parsed, to be yielded before reading any further.
+    pub(crate) pending: VecDeque<UlogMessage>,
+    // Offset at which to stop parsing, set by `parse_range()`.
+    range_end: Option<usize>,
+    // Span covering the parsing of this file, entered while reading each message.
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "arena")]
+    pub(crate) arena_batch_size: usize,
+}
+
+/// The largest backwards jump (in microseconds) between consecutive timestamps of the same
+/// subscription that is still considered plausible.
+const MAX_TIMESTAMP_REGRESSION_US: u64 = 60_000_000;
+
+/// The number of messages read between checks of the deadline, to amortise the cost of reading
+/// the clock.
+const DEADLINE_CHECK_INTERVAL: u64 = 256;
+
+/// Determines what the parser does with a `LoggedData` message whose timestamp is implausible,
+/// i.e. zero after non-zero timestamps, or far earlier than the previous sample of the same
+/// subscription.  Such timestamps usually indicate misaligned or corrupt data.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum BadTimestampPolicy {
+    /// Drop the message; it is not yielded by the parser.
+    Skip,
+    /// Yield the message unchanged.  This is the default.
+    #[default]
+    Include,
+    /// Return `ULogError::ImplausibleTimestamp`.
+    Error,
+}
+
+/// Name of the synthetic per-subscription sample counter added by `ULogParserBuilder::add_index_field()`.
+pub const INDEX_FIELD_NAME: &str = "_index";
+
+/// The default pattern of `ULogParserBuilder::name_instance_pattern()`, matching names such as
+/// `sensor_gyro_1`.
+pub const DEFAULT_NAME_INSTANCE_PATTERN: &str = r"^(.+)_(\d+)$";
+
+/// A conversion applied to the values of a field, registered with
+/// `ULogParserBuilder::transform()`.
+pub type FieldTransform = fn(f64) -> f64;
+
+/// A predicate on the timestamps of records, set by `ULogParserBuilder::filter_time()`.
+pub type TimeFilter = Arc<dyn Fn(u64) -> bool + Send + Sync>;
+
+/// The options a parser was built with, as returned by [`ULogParser::config()`].
+///
+/// Each field holds the value given to the `ULogParserBuilder` method of the same name, or its
+/// default.  This is a read-only snapshot for tools which display or record how a log was parsed.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParserConfig {
+    pub include_header: bool,
+    pub include_timestamp: bool,
+    pub include_padding: bool,
+    /// The subscription names passed to `set_subscription_allow_list()`, or `None` if no allow
+    /// list was set.
+    pub allowed_subscription_names: Option<HashSet<String>>,
+    /// The patterns passed to `set_subscription_allow_list_glob()`.
+    pub allowed_subscription_globs: Option<Vec<String>>,
+    pub drop_ignored: bool,
+    /// Set if `filter_time()` was given a predicate.
+    pub time_filter: bool,
+    pub bad_timestamp_policy: BadTimestampPolicy,
+    pub add_index_field: bool,
+    pub read_appended_data: bool,
+    pub resync_on_error: bool,
+    pub recover_on_error: bool,
+    pub error_on_dropout: bool,
+    pub require_sorted: bool,
+    pub collect_warnings: bool,
+    pub fold_resubscriptions: bool,
+    pub growing: bool,
+    pub max_subscriptions: Option<usize>,
+    pub max_records: Option<u64>,
+    pub deadline: Option<Instant>,
+    /// Set by `skip_arrays_larger_than()`.
+    pub max_array_size: Option<usize>,
+    pub opaque_type_sizes: HashMap<String, usize>,
+    /// The `(subscription, field)` pairs with a transform, sorted by subscription.
+    pub transformed_fields: Vec<(String, String)>,
+    /// The pattern used to split names and instances, or `None` if `split_name_instance()` is
+    /// off.
+    pub name_instance_pattern: Option<String>,
+    /// Set by `with_raw_payload()`.
+    pub raw_payload: bool,
+    pub pipelined: bool,
+    pub read_ahead: usize,
+    /// Set if `header_codec()` was given a codec.
+    pub custom_header_codec: bool,
+}
+
+/// The result of reading a single message from the stream.
+#[allow(clippy::large_enum_variant)] // Short-lived, moved out immediately.
+pub(crate) enum Step {
+    Yield(UlogMessage),
+    Skip,
 }
 
 #[derive(Default)]
 pub struct SubscriptionFilter {
     allowed_subscription_names: Option<HashSet<String>>,
-    allowed_subscription_ids: Option<HashSet<u16>>,
+    // Names matching any of these are allowed as well, see `with_patterns()`.
+    allowed_subscription_patterns: Vec<Regex>,
+    // Indexed by msg_id, so the filter can be consulted for every data message without hashing.
+    allowed_subscription_ids: Option<Vec<bool>>,
 }
 
 impl SubscriptionFilter {
@@ -44,30 +221,305 @@ impl SubscriptionFilter {
         let names: HashSet<String> = subscr_names.into_iter().collect::<HashSet<_>>();
         Self {
             allowed_subscription_names: Some(names),
-            allowed_subscription_ids: Some(HashSet::new()),
+            allowed_subscription_patterns: Vec::new(),
+            allowed_subscription_ids: Some(Vec::new()),
         }
     }
 
-    fn update_ids(&mut self, sub: &Subscription) {
+    /// Also allows the subscriptions whose name matches any of `patterns`.
+    pub(crate) fn with_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.allowed_subscription_patterns = patterns;
+        self
+    }
+
+    pub(crate) fn update_ids(&mut self, sub: &Subscription) {
         // Because msg_ids are not known ahead of time the API specifies allowed subscriptions by name.
         // Once the AddSubscription messages come in, then we can convert the strings names to msg_ids
         // to more efficiently filter the subscriptions.
         if let Some(allowed_subscription_names) = &self.allowed_subscription_names {
-            if allowed_subscription_names.contains(&sub.message_name) {
+            if allowed_subscription_names.contains(&sub.message_name)
+                || self
+                    .allowed_subscription_patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(&sub.message_name))
+            {
                 // Unwrap is safe here because of the initialisation code in set_allowed_subscription_names().
-                self.allowed_subscription_ids
-                    .as_mut()
-                    .unwrap()
-                    .insert(sub.msg_id);
+                let allowed_ids = self.allowed_subscription_ids.as_mut().unwrap();
+                let index = usize::from(sub.msg_id);
+
+                if allowed_ids.len() <= index {
+                    allowed_ids.resize(index + 1, false);
+                }
+                allowed_ids[index] = true;
             }
         }
     }
 
-    fn is_allowed(&self, msg_id: u16) -> bool {
+    /// Returns the approximate number of bytes of the names, patterns and msg_ids retained.
+    pub(crate) fn memory_bytes(&self) -> usize {
+        self.allowed_subscription_names
+            .iter()
+            .flatten()
+            .map(String::len)
+            .sum::<usize>()
+            + self
+                .allowed_subscription_patterns
+                .iter()
+                .map(|pattern| size_of::<Regex>() + pattern.as_str().len())
+                .sum::<usize>()
+            + self.allowed_subscription_ids.as_ref().map_or(0, Vec::len)
+    }
+
+    pub(crate) fn is_allowed(&self, msg_id: u16) -> bool {
         match &self.allowed_subscription_ids {
             None => true,
-            Some(set) => set.contains(&msg_id),
+            Some(table) => table.get(usize::from(msg_id)).copied().unwrap_or(false),
+        }
+    }
+}
+
+impl<R: Read + Seek> ULogParser<R> {
+    /// Restricts the parser to the messages whose header starts within the byte range `[start, end)`.
+    ///
+    /// This allows several workers to decode disjoint parts of the same file in parallel.  The
+    /// header and definitions are always read, since they are needed to decode the data, but only
+    /// yielded if they fall within the range.  The data section before `start` is then scanned by
+    /// seeking from header to header, decoding only the format and subscription messages.
+    ///
+    /// `start` and `end` need not be aligned to message boundaries: every message belongs to
+    /// exactly one of a set of adjacent ranges, so the union of the ranges is the whole log.
+    /// Options which depend on earlier messages, such as `add_index_field()` and the bad timestamp
+    /// policy, are applied to each range independently.
+    pub fn parse_range(mut self, start: u64, end: u64) -> Result<Self, ULogError> {
+        let range = start as usize..end as usize;
+
+        while matches!(self.state, State::HEADER | State::DEFINITIONS) {
+            let Some(msg) = self.next_from_stream()? else {
+                break;
+            };
+
+            if range.contains(&self.message_offset) {
+                self.pending.push_back(msg);
+            }
+        }
+
+        self.skip_data_until(range.start)?;
+
+        self.range_end = Some(range.end);
+
+        Ok(self)
+    }
+
+    /// Reads the rest of the log, recording the timestamp and byte offset of every `LoggedData`
+    /// record, for random access with [`ULogParser::seek_to_offset`] and
+    /// [`ULogParser::messages_in_range`].
+    ///
+    /// Only the `msg_id` and the `timestamp` field of each record are read, so this is a cheap
+    /// scan.  Any messages not yet yielded are consumed, so the parser is at the end of the log
+    /// afterwards, but it keeps the definitions it has read.
+    pub fn build_index(&mut self) -> Result<LogIndex, ULogError> {
+        self.read_definitions()?;
+
+        let mut index = LogIndex::default();
+        let mut timestamp_ranges: HashMap<u16, Range<usize>> = HashMap::new();
+
+        while self.state == State::DATA {
+            if self.reaches_max_bytes_to_read(ULOG_MESSAGE_HEADER_SIZE) {
+                self.state = State::EOF;
+                break;
+            }
+
+            self.message_offset = self.datastream.num_bytes_read;
+
+            let Some(header) = self.read_message_header()? else {
+                self.state = State::EOF;
+                break;
+            };
+
+            if !matches!(header.msg_type, ULogMessageType::DATA) {
+                if !self.read_data_definition(&header)? {
+                    self.datastream.seek_forward(header.msg_size as usize)?;
+                }
+                continue;
+            }
+
+            let mut message_buf = self.read_message(header.msg_size as usize)?;
+            let msg_id = message_buf.take_u16()?;
+
+            let range = match timestamp_ranges.get(&msg_id) {
+                Some(range) => range.clone(),
+                None => {
+                    let format =
+                        self.get_format(&self.get_subscription_ref(msg_id)?.message_name)?;
+                    let range =
+                        field_range(&format, "timestamp", &self.formats, &self.opaque_type_sizes)?
+                            .ok_or(ULogError::MissingTimestamp)?;
+                    timestamp_ranges.insert(msg_id, range.clone());
+                    range
+                }
+            };
+
+            let timestamp = message_buf
+                .remaining_bytes()
+                .get(range)
+                .and_then(decode_timestamp)
+                .ok_or(ULogError::MissingTimestamp)?;
+
+            index.push(
+                msg_id,
+                IndexEntry {
+                    timestamp,
+                    offset: self.message_offset as u64,
+                },
+            );
+        }
+
+        // Names are only final once every subscription is known, see `subscription_display_name()`.
+        for sub in self.subscriptions.values() {
+            index.add_name(self.subscription_display_name(sub), sub.msg_id);
+        }
+        index.finish();
+
+        Ok(index)
+    }
+
+    /// Continues parsing a log opened with `growing(true)`, after the iterator has reached the end
+    /// of what has been written so far.
+    ///
+    /// Parsing resumes from the start of the message which was cut off by the end of the file, so
+    /// no message is lost or decoded twice.  Returns true if the file has grown since, i.e. if
+    /// there may be new messages to read.  If the iterator has not reached the end of the file,
+    /// this does nothing and returns false.
+    pub fn refresh(&mut self) -> Result<bool, ULogError> {
+        let Some((offset, state, end)) = self.resume else {
+            return Ok(false);
+        };
+
+        let len = self.datastream.stream_len()?;
+        self.datastream.seek_to(offset)?;
+        self.state = state;
+        self.resume = None;
+        // The pipeline has ended with the stream, and is restarted by the next call to `next()`.
+        self.pipeline = None;
+
+        Ok(len > end)
+    }
+
+    /// Moves the parser to the message starting at byte `offset`, e.g. the offset of an
+    /// [`IndexEntry`], so that parsing resumes from there.
+    ///
+    /// `offset` must be the start of a message in the data section.  When moving forwards, the
+    /// messages in between are skipped cheaply, only reading the formats and subscriptions
+    /// among them.  Options which depend on earlier messages, such as `add_index_field()` and the
+    /// bad timestamp policy, start afresh from the new position.
+    pub fn seek_to_offset(&mut self, offset: u64) -> Result<(), ULogError> {
+        self.read_definitions()?;
+        self.skip_data_until(offset as usize)?;
+
+        self.datastream.seek_to(offset as usize)?;
+        self.state = State::DATA;
+        self.pending.clear();
+        self.pipeline = None;
+        self.range_end = None;
+        self.resume = None;
+        self.last_timestamps.clear();
+        self.last_data_timestamp = 0;
+        self.last_sorted_timestamp = None;
+        self.sample_counts.clear();
+
+        Ok(())
+    }
+
+    /// Parses the records of the subscription `msg_id` with a timestamp in `[start, end)`, in
+    /// order of timestamp, seeking directly to each record in `index`.
+    ///
+    /// The records are parsed with the options of the parser, so those of a subscription outside
+    /// the allow list are yielded as `UlogMessage::Ignored`.  Afterwards the parser is positioned
+    /// just after the last record.
+    pub fn messages_in_range<'a>(
+        &'a mut self,
+        index: &'a LogIndex,
+        msg_id: u16,
+        start: u64,
+        end: u64,
+    ) -> impl Iterator<Item = Result<UlogMessage, ULogError>> + 'a {
+        index
+            .entries_in_range(msg_id, start, end)
+            .iter()
+            .filter_map(move |entry| {
+                if let Err(err) = self.seek_to_offset(entry.offset) {
+                    return Some(Err(err));
+                }
+
+                // A record may be dropped by the bad timestamp policy, rather than yielded.
+                match self.next_step() {
+                    Ok(Some(Step::Yield(msg))) => Some(Ok(msg)),
+                    Ok(Some(Step::Skip) | None) => None,
+                    Err(err) => Some(Err(err)),
+                }
+            })
+    }
+
+    /// Skips the messages of the data section starting before byte `offset`, while keeping track
+    /// of the definitions among them.
+    fn skip_data_until(&mut self, offset: usize) -> Result<(), ULogError> {
+        while self.state == State::DATA && self.datastream.num_bytes_read < offset {
+            if self.reaches_max_bytes_to_read(ULOG_MESSAGE_HEADER_SIZE) {
+                self.state = State::EOF;
+                break;
+            }
+
+            self.message_offset = self.datastream.num_bytes_read;
+
+            let Some(header) = self.read_message_header()? else {
+                self.state = State::EOF;
+                break;
+            };
+
+            if !self.read_data_definition(&header)? {
+                self.datastream.seek_forward(header.msg_size as usize)?;
+            }
         }
+
+        Ok(())
+    }
+
+    /// Reads the header and the definitions section, if they have not been read yet.
+    fn read_definitions(&mut self) -> Result<(), ULogError> {
+        while matches!(self.state, State::HEADER | State::DEFINITIONS) {
+            if self.next_from_stream()?.is_none() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a message of the data section which changes the definitions, recording the new
+    /// definitions.  Returns false, without reading anything, for any other message.
+    fn read_data_definition(&mut self, header: &ULogMessageHeader) -> Result<bool, ULogError> {
+        if !matches!(
+            header.msg_type,
+            ULogMessageType::FORMAT
+                | ULogMessageType::ADD_SUBSCRIPTION
+                | ULogMessageType::REMOVE_SUBSCRIPTION
+        ) {
+            return Ok(false);
+        }
+
+        let message_buf = self.read_message(header.msg_size as usize)?;
+
+        match self.parse_data(header.msg_type, message_buf)? {
+            UlogMessage::FormatDefinition(format) => {
+                self.formats.insert(format.name.clone(), format);
+            }
+            UlogMessage::AddSubscription(mut sub) => {
+                self.register_subscription(&mut sub)?;
+            }
+            _ => {}
+        }
+
+        Ok(true)
     }
 }
 
@@ -100,13 +552,67 @@ impl<R: Read> ULogParser<R> {
             file_header: None,
             formats: HashMap::new(),
             subscriptions: HashMap::new(),
+            removed_subscriptions: HashMap::new(),
             message_name_with_multi_id: HashSet::new(),
             subscription_filter: SubscriptionFilter::default(),
+            drop_ignored: false,
             datastream: DataStream::new(reader),
+            time_filter: None,
+            header_codec: None,
             max_bytes_to_read: None,
+            read_appended_data: false,
+            resync_on_error: false,
+            recover_on_error: false,
+            error_on_dropout: false,
+            require_sorted: false,
+            collect_warnings: false,
+            warnings: Vec::new(),
+            fold_resubscriptions: false,
+            folded_msg_ids: HashMap::new(),
+            growing: false,
+            resume: None,
+            last_data_timestamp: 0,
+            last_sorted_timestamp: None,
+            record_offset: 0,
+            last_message: Vec::new(),
+            appended_offsets: VecDeque::new(),
             include_header: false,
             include_timestamp: false,
             include_padding: false,
+            bad_timestamp_policy: BadTimestampPolicy::default(),
+            add_index_field: false,
+            max_subscriptions: None,
+            max_records: None,
+            records_yielded: 0,
+            deadline: None,
+            max_array_size: None,
+            opaque_type_sizes: HashMap::new(),
+            transforms: HashMap::new(),
+            name_instance_pattern: None,
+            raw_payload: false,
+            config: ParserConfig::default(),
+            pipelined: false,
+            pipeline: None,
+            messages_read: 0,
+            last_timestamps: HashMap::new(),
+            sample_counts: HashMap::new(),
+            message_offset: 0,
+            data_section_offset: None,
+            multi_info: HashMap::new(),
+            info: HashMap::new(),
+            info_values: HashMap::new(),
+            parameters: HashMap::new(),
+            initial_parameters: HashMap::new(),
+            pending: VecDeque::new(),
+            range_end: None,
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "ulog_file",
+                version = tracing::field::Empty,
+                bytes_read = tracing::field::Empty,
+            ),
+            #[cfg(feature = "arena")]
+            arena_batch_size: crate::arena::DEFAULT_ARENA_BATCH_SIZE,
         })
     }
 
@@ -132,29 +638,614 @@ impl<R: Read> ULogParser<R> {
     }
 
     pub fn get_subscription(&self, msg_id: u16) -> Result<msg::Subscription, ULogError> {
-        match self.subscriptions.get(&msg_id) {
-            None => Err(UndefinedSubscription(msg_id)),
-            Some(sub) => Ok(sub.clone()),
+        self.get_subscription_ref(msg_id).cloned()
+    }
+
+    pub(crate) fn get_subscription_ref(
+        &self,
+        msg_id: u16,
+    ) -> Result<&msg::Subscription, ULogError> {
+        self.subscriptions
+            .get(&msg_id)
+            .ok_or(UndefinedSubscription(msg_id))
+    }
+
+    /// Returns the subscription of `msg_id`, or the last one removed for it.
+    pub(crate) fn last_known_subscription(
+        &self,
+        msg_id: u16,
+    ) -> Result<&msg::Subscription, ULogError> {
+        self.subscriptions
+            .get(&msg_id)
+            .or_else(|| self.removed_subscriptions.get(&msg_id))
+            .ok_or(UndefinedSubscription(msg_id))
+    }
+
+    /// Returns the name used to identify a subscription in per-topic reports.
+    ///
+    /// This matches the `Display` impl of `inst::Format`: topics logged with several instances
+    /// are suffixed with their `multi_id`, e.g. `actuator_outputs.01`.
+    pub(crate) fn subscription_display_name(&self, sub: &msg::Subscription) -> String {
+        if self.message_name_with_multi_id.contains(&sub.message_name) {
+            format!("{}.{:02}", sub.message_name, sub.multi_id)
+        } else {
+            sub.message_name.clone()
+        }
+    }
+
+    /// Returns the options the parser was built with.
+    ///
+    /// A parser created with `ULogParser::new()` has the default options.
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
+    }
+
+    /// Returns the value of every `Info` message seen so far, by key.
+    ///
+    /// The info messages, e.g. `sys_name` and `ver_sw`, are in the definitions section, so the
+    /// map is complete once the first message of the data section has been read.  A key logged
+    /// more than once has its latest value.
+    pub fn info(&self) -> &HashMap<String, msg::InfoValue> {
+        &self.info_values
+    }
+
+    /// Returns the current value of every parameter seen so far, by name, with its original
+    /// type.
+    ///
+    /// The initial values are in the definitions section.  Parameters changed in flight are
+    /// logged again in the data section, and update the map as the stream is consumed, so it
+    /// always holds the latest value read.
+    pub fn parameters(&self) -> &HashMap<String, inst::ParameterValue> {
+        &self.parameters
+    }
+
+    /// Returns the parameters logged in the definitions section, i.e. the configuration the
+    /// vehicle booted with, by name.
+    ///
+    /// Changes in flight are not applied, so a parameter keeps its initial value here however
+    /// often it is changed.  Parameters first logged in the data section are left out.
+    pub fn initial_parameters(&self) -> &HashMap<String, inst::ParameterValue> {
+        &self.initial_parameters
+    }
+
+    /// Returns the initial parameters overlaid with every change read so far, i.e. the
+    /// configuration the vehicle ended with once the stream has been fully consumed.
+    ///
+    /// This is the same map as [`ULogParser::parameters()`].
+    pub fn final_parameters(&self) -> &HashMap<String, inst::ParameterValue> {
+        &self.parameters
+    }
+
+    /// Returns the recoverable issues met so far, in the order they were met.
+    ///
+    /// Only collected if the parser was built with `collect_warnings(true)`, otherwise empty.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Returns an estimate of the memory retained by the parser; see [`MemoryReport`].
+    ///
+    /// Parsing a log of any length retains memory in proportion to its formats, metadata keys and
+    /// subscriptions, and not to its number of records, unless warnings are collected or
+    /// `MultiInfo` messages are logged throughout the data section.  A long-running service can
+    /// watch the report, and `flush()` the parser to release that state.
+    pub fn memory_report(&self) -> MemoryReport {
+        let options = self.subscription_filter.memory_bytes()
+            + self
+                .transforms
+                .iter()
+                .map(|(subscription, fields)| {
+                    subscription.len()
+                        + fields
+                            .iter()
+                            .map(|(field, _)| field.len() + size_of::<(String, FieldTransform)>())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+            + self
+                .opaque_type_sizes
+                .keys()
+                .map(|name| name.len() + size_of::<usize>())
+                .sum::<usize>();
+
+        let definitions = self
+            .formats
+            .values()
+            .map(memory::def_format_bytes)
+            .sum::<usize>()
+            + self
+                .subscriptions
+                .values()
+                .chain(self.removed_subscriptions.values())
+                .map(|sub| size_of::<u16>() + memory::subscription_bytes(sub))
+                .sum::<usize>()
+            + self
+                .message_name_with_multi_id
+                .iter()
+                .map(String::len)
+                .sum::<usize>();
+
+        let parameter_bytes = |parameters: &HashMap<String, inst::ParameterValue>| {
+            parameters
+                .keys()
+                .map(|name| name.len() + size_of::<inst::ParameterValue>())
+                .sum::<usize>()
+        };
+        let metadata = self
+            .info
+            .values()
+            .map(|info| memory::info_bytes(&info.key, &info.r#type, &info.value))
+            .sum::<usize>()
+            + self
+                .info_values
+                .keys()
+                .map(|key| key.len() + size_of::<msg::InfoValue>())
+                .sum::<usize>()
+            + self
+                .multi_info
+                .values()
+                .flatten()
+                .map(|entry| memory::info_bytes(&entry.key, &entry.r#type, &entry.value))
+                .sum::<usize>()
+            + parameter_bytes(&self.parameters)
+            + parameter_bytes(&self.initial_parameters)
+            + self.appended_offsets.len() * size_of::<usize>();
+
+        let per_subscription = (self.last_timestamps.len() + self.sample_counts.len())
+            * (size_of::<u16>() + size_of::<u64>())
+            + self
+                .folded_msg_ids
+                .keys()
+                .map(|(name, _)| name.len() + size_of::<(String, u8)>() + size_of::<u16>())
+                .sum::<usize>();
+
+        let warnings = self
+            .warnings
+            .iter()
+            .map(|warning| size_of::<ParseWarning>() + warning.description.len())
+            .sum();
+
+        let buffers = self.last_message.capacity()
+            + self.datastream.buffered_capacity()
+            + self
+                .pending
+                .iter()
+                .map(memory::message_bytes)
+                .sum::<usize>();
+
+        MemoryReport {
+            options,
+            definitions,
+            metadata,
+            per_subscription,
+            warnings,
+            buffers,
         }
     }
 
+    /// Releases the state which grows with the length of the log: the warnings collected so far,
+    /// and all but the latest `MultiInfo` message of each key.
+    ///
+    /// This is an explicit flush point for a long-running stream, e.g. after the warnings have
+    /// been reported.  Parsing carries on unchanged, and the latest message of each key is kept
+    /// so that a continuation of it is still accepted, but `multi_info()` and the queries built on
+    /// it, such as `perf_counters()`, only see the messages read since.
+    pub fn flush(&mut self) {
+        self.warnings = Vec::new();
+
+        for entries in self.multi_info.values_mut() {
+            let latest = entries.pop();
+            *entries = latest.into_iter().collect();
+        }
+    }
+
+    /// Records a recoverable issue for `warnings()`, if enabled.
+    pub(crate) fn warn(&mut self, offset: usize, kind: WarningKind, description: String) {
+        if self.collect_warnings {
+            self.warnings.push(ParseWarning {
+                offset: offset as u64,
+                kind,
+                description,
+            });
+        }
+    }
+
+    /// Returns all `MultiInfo` messages seen so far, grouped by key in the order they appeared.
+    ///
+    /// `MultiInfo` messages can appear both in the definitions section and in the data section
+    /// (e.g. the postflight performance counters), so the map is only complete once the stream
+    /// has been fully consumed.
+    pub fn multi_info(&self) -> &HashMap<String, Vec<msg::MultiInfo>> {
+        &self.multi_info
+    }
+
+    /// Returns the complete values of the `MultiInfo` messages with the given key, in the order
+    /// they appeared.
+    ///
+    /// A long value may be split over several messages, each after the first having
+    /// `is_continued` set.  The arrays of such a sequence are concatenated into a single value,
+    /// e.g. a `char` array holding a whole multi-line string.  Returns `ULogError::TypeMismatch`
+    /// if a continuation has a different type from the message it continues.
+    pub fn multi_info_values(&self, key: &str) -> Result<Vec<inst::FieldValue>, ULogError> {
+        let mut values: Vec<inst::FieldValue> = Vec::new();
+
+        for entry in self.multi_info.get(key).into_iter().flatten() {
+            match values.last_mut() {
+                Some(value) if entry.is_continued => value.append(&entry.value)?,
+                _ => values.push(entry.value.clone()),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Returns the keys of all `Info` and `MultiInfo` messages seen so far, sorted and without
+    /// duplicates.
+    ///
+    /// This is a cheap listing of the available metadata, which does not clone any of the values.
+    pub fn info_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .info
+            .keys()
+            .chain(self.multi_info.keys())
+            .map(String::as_str)
+            .collect();
+
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Parses the rest of the stream without stopping at the first error.
+    ///
+    /// This is the lenient counterpart to iterating the parser: every message that decodes
+    /// successfully is collected, and every error is recorded together with the byte offset of
+    /// the message that caused it.  Parsing only stops early when the stream itself can no
+    /// longer be read (an IO error or an invalid file header).
+    ///
+    /// This gives a complete picture of a partially corrupt file in a single call.
+    pub fn parse_all(mut self) -> (Vec<UlogMessage>, Vec<(u64, ULogError)>) {
+        let mut messages = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            match self.next_sub() {
+                Ok(Some(msg)) => messages.push(msg),
+                Ok(None) => break,
+                Err(err) => {
+                    let is_fatal = matches!(err, ULogError::Io(_)) || self.state == State::ERROR;
+                    errors.push((self.message_offset as u64, err));
+
+                    if is_fatal {
+                        break;
+                    }
+
+                    if self.resync_on_error && self.state == State::DATA {
+                        match self.resync() {
+                            Ok(skipped_bytes) => errors.push((
+                                self.message_offset as u64,
+                                ULogError::Resynchronized { skipped_bytes },
+                            )),
+                            Err(err) => {
+                                errors.push((self.message_offset as u64, err));
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        (messages, errors)
+    }
+
+    /// Decodes up to `max_records` `LoggedData` messages, and returns them as a batch.
+    ///
+    /// This lets batch-oriented consumers choose the size of each batch, e.g. for fixed-latency
+    /// processing.  Other messages are read but not returned, though their info and parameters
+    /// are recorded as usual.  The last batch holds the records left, however few, and once the
+    /// log has been read an empty batch is returned.  On an error, the records already read into
+    /// the batch are dropped.  Returns `ULogError::InvalidConfiguration` if `max_records` is 0.
+    pub fn next_chunk(&mut self, max_records: usize) -> Result<Vec<LoggedData>, ULogError> {
+        if max_records == 0 {
+            return Err(ULogError::InvalidConfiguration(
+                "a chunk must hold at least one record".to_string(),
+            ));
+        }
+
+        let mut chunk = Vec::new();
+
+        while chunk.len() < max_records {
+            match self.next_sub()? {
+                Some(UlogMessage::LoggedData(data)) => chunk.push(data),
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        Ok(chunk)
+    }
+
     pub(crate) fn read_message(&mut self, msg_size: usize) -> Result<MessageBuf, ULogError> {
         let mut message: Vec<u8> = vec![0; msg_size];
+        let start = self.datastream.num_bytes_read;
         self.datastream.read_exact(&mut message)?;
+
+        if self.resync_on_error || self.recover_on_error {
+            self.last_message.clone_from(&message);
+            // Only the bytes actually read of a truncated message.
+            self.last_message
+                .truncate(self.datastream.num_bytes_read - start);
+        }
+
         Ok(MessageBuf::from_vec(message))
     }
 
-    #[allow(clippy::single_match_else)]
+    /// Skips ahead to just after the next SYNC message, after a message failed to parse.
+    ///
+    /// The search starts within the contents of the failed message, since a corrupt message
+    /// size may have swallowed the following messages.  Returns the number of bytes skipped since
+    /// the start of the failed message.
+    fn resync(&mut self) -> Result<u64, ULogError> {
+        let failed_message = std::mem::take(&mut self.last_message);
+        self.datastream.unread(&failed_message);
+
+        let mut window = [0u8; SYNC_MAGIC.len()];
+        while !self.datastream.eof {
+            window.rotate_left(1);
+            window[SYNC_MAGIC.len() - 1] = self.datastream.read_u8()?;
+
+            if window == SYNC_MAGIC {
+                break;
+            }
+        }
+
+        let skipped_bytes = self.datastream.num_bytes_read - self.message_offset;
+        log::warn!(
+            "Skipped {skipped_bytes} bytes from offset {} to resynchronize.",
+            self.message_offset
+        );
+        self.warn(
+            self.message_offset,
+            WarningKind::SkippedBytes,
+            format!("Skipped {skipped_bytes} bytes to resynchronize"),
+        );
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            offset = self.message_offset,
+            skipped_bytes,
+            "resynchronized after corrupt data"
+        );
+
+        Ok(skipped_bytes as u64)
+    }
+
+    /// Reads the header and the definitions section, if they have not been read yet, keeping the
+    /// messages to be yielded.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn buffer_definitions(&mut self) -> Result<(), ULogError> {
+        while matches!(self.state, State::HEADER | State::DEFINITIONS) {
+            let Some(msg) = self.next_from_stream()? else {
+                break;
+            };
+            self.pending.push_back(msg);
+        }
+
+        Ok(())
+    }
+
     fn next_sub(&mut self) -> Result<Option<msg::UlogMessage>, ULogError> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+
+        if self.reached_max_records() {
+            return Ok(None);
+        }
+
+        let result = match self.pending.pop_front() {
+            Some(msg) => Ok(Some(msg)),
+            None if self.pipelined => self.next_pipelined(),
+            None => self.next_from_stream(),
+        };
+        let result = self.finish_message(result);
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Err(err) => tracing::warn!(
+                offset = self.message_offset,
+                error = %err,
+                "failed to parse message"
+            ),
+            Ok(None) => {
+                self.span
+                    .record("bytes_read", self.datastream.num_bytes_read);
+            }
+            Ok(Some(_)) => {}
+        }
+
+        result
+    }
+
+    /// Ends iteration once `max_records` records have been yielded, returning whether it ended.
+    pub(crate) fn reached_max_records(&mut self) -> bool {
+        if self
+            .max_records
+            .is_some_and(|max_records| self.records_yielded >= max_records)
+        {
+            self.state = State::EOF;
+            self.pending.clear();
+            self.pipeline = None;
+            return true;
+        }
+
+        false
+    }
+
+    /// Does the bookkeeping and the checks of the parser options for a decoded message, before it
+    /// is yielded.
+    pub(crate) fn finish_message(
+        &mut self,
+        mut result: Result<Option<UlogMessage>, ULogError>,
+    ) -> Result<Option<UlogMessage>, ULogError> {
+        if let Ok(Some(UlogMessage::LoggedData(_))) = &result {
+            self.records_yielded += 1;
+        }
+
+        if self.error_on_dropout {
+            if let Ok(Some(msg)) = &result {
+                if let Err(err) = self.check_dropout(msg) {
+                    result = Err(err);
+                }
+            }
+        }
+
+        if self.require_sorted {
+            if let Ok(Some(UlogMessage::LoggedData(data))) = &result {
+                let offset = if self.pipelined {
+                    self.record_offset
+                } else {
+                    self.message_offset
+                };
+                if let Err(err) = self.check_sorted(data.timestamp, offset) {
+                    result = Err(err);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns `ULogError::DataDropout` for a dropout, and otherwise records the timestamp of the
+    /// message if it is a record.
+    fn check_dropout(&mut self, msg: &UlogMessage) -> Result<(), ULogError> {
+        match msg {
+            UlogMessage::LoggedData(data) => self.last_data_timestamp = data.timestamp,
+            UlogMessage::DropoutMark(dropout) => {
+                // Treat this as fatal: the file is not parsed any further.
+                self.state = State::EOF;
+                self.pending.clear();
+                self.pipeline = None;
+
+                return Err(ULogError::DataDropout {
+                    timestamp: self.last_data_timestamp,
+                    duration_ms: dropout.duration(),
+                });
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Returns `ULogError::OutOfOrder` if the timestamp of the record at `offset` is before that of
+    /// the record yielded before it, whatever their topics.
+    fn check_sorted(&mut self, timestamp: u64, offset: usize) -> Result<(), ULogError> {
+        match self.last_sorted_timestamp {
+            Some(prev) if timestamp < prev => {
+                // Treat this as fatal: the file is not parsed any further.
+                self.state = State::EOF;
+                self.pending.clear();
+                self.pipeline = None;
+
+                Err(ULogError::OutOfOrder {
+                    prev,
+                    cur: timestamp,
+                    offset: offset as u64,
+                })
+            }
+            _ => {
+                self.last_sorted_timestamp = Some(timestamp);
+                Ok(())
+            }
+        }
+    }
+
+    fn next_from_stream(&mut self) -> Result<Option<msg::UlogMessage>, ULogError> {
+        // Some parser options drop messages rather than yielding them, so keep reading until
+        // there is something to return (or the stream ends).
+        loop {
+            match self.next_step()? {
+                None => return Ok(None),
+                Some(Step::Yield(msg)) => return Ok(Some(msg)),
+                Some(Step::Skip) => {}
+            }
+        }
+    }
+
+    /// Yields the next message like `next_from_stream()`, while reading ahead and decoding `DATA`
+    /// messages on the pipeline's workers.
+    fn next_pipelined(&mut self) -> Result<Option<msg::UlogMessage>, ULogError> {
+        if self.pipeline.is_none() {
+            self.pipeline = Some(Pipeline::new(self));
+        }
+
+        loop {
+            // Unwrap is safe: the pipeline was started above.
+            let pipeline = self.pipeline.as_mut().unwrap();
+            let read_ahead = pipeline.wants_more();
+
+            match pipeline.pop(!read_ahead) {
+                Some(Output::Message(msg_res)) => return msg_res.map(Some),
+                Some(Output::Decoded(result, offset)) => {
+                    self.record_offset = offset;
+                    let mut logged_data = result?;
+                    if self.finish_logged_data(&mut logged_data)? {
+                        return Ok(Some(UlogMessage::LoggedData(logged_data)));
+                    }
+                    continue;
+                }
+                None if !read_ahead => return Ok(None),
+                None => {}
+            }
+
+            // Allowed DATA messages are queued by `next_step()` itself.
+            let step = self.next_step();
+            let pipeline = self.pipeline.as_mut().unwrap();
+            match step {
+                Ok(None) => pipeline.stream_ended = true,
+                Ok(Some(Step::Yield(msg))) => {
+                    // A subscription may add a copy of its format under a new name, see
+                    // `split_name_instance()`.
+                    if let UlogMessage::AddSubscription(sub) = &msg {
+                        if let Some(format) = self.formats.get(&sub.message_name) {
+                            pipeline.add_format(format);
+                        }
+                    }
+                    pipeline.push(Ok(msg));
+                }
+                Ok(Some(Step::Skip)) => {}
+                Err(err) => pipeline.push(Err(err)),
+            }
+        }
+    }
+
+    /// Ends the iterator at the end of the stream, remembering where to resume if the log is
+    /// still being written.
+    fn wait_for_growth(&mut self) {
+        if self.growing {
+            self.resume = Some((
+                self.message_offset,
+                self.state,
+                self.datastream.num_bytes_read,
+            ));
+        }
+        self.state = State::EOF;
+    }
+
+    #[allow(clippy::single_match_else)]
+    pub(crate) fn next_step(&mut self) -> Result<Option<Step>, ULogError> {
         if self.state == State::HEADER {
             match self.read_file_header() {
                 Ok(header) => {
+                    #[cfg(feature = "tracing")]
+                    self.span.record("version", header.version);
+
                     self.file_header = Some(header);
                     self.state = State::DEFINITIONS;
 
                     #[allow(clippy::redundant_else)]
                     if self.include_header {
-                        return Ok(Some(UlogMessage::Header(header)));
+                        return Ok(Some(Step::Yield(UlogMessage::Header(header))));
                     } else {
                         //Fallthrough.
                     }
@@ -170,32 +1261,80 @@ impl<R: Read> ULogParser<R> {
             return Ok(None);
         }
 
+        self.count_message()?;
+
         // ⚠️ ULOG files can contain binary crash dumps at offsets determined by the FLAG_BITS message.
         // In such cases self.max_bytes_to_read will contain the offset in the stream where the crash dump begins.
         // We must return EOF when we reach this limit to avoid attempting to parse invalid ULOG data.
-        let max_bytes_to_read = self.max_bytes_to_read;
+        // The data before the limit may also end with a partially written message, which is dropped.
+        if self.reaches_max_bytes_to_read(ULOG_MESSAGE_HEADER_SIZE) {
+            self.state = State::EOF;
+            return Ok(None);
+        }
 
-        if let Some(max_bytes_to_read) = max_bytes_to_read {
-            if self.datastream.num_bytes_read >= max_bytes_to_read {
-                self.state = State::EOF;
-                return Ok(None);
-            }
+        if self
+            .range_end
+            .is_some_and(|range_end| self.datastream.num_bytes_read >= range_end)
+        {
+            self.state = State::EOF;
+            return Ok(None);
         }
 
-        let (message_type, message_buf) = match self.read_message_header()? {
+        self.message_offset = self.datastream.num_bytes_read;
+
+        if let Some(marker) = self.enter_appended_section(ULOG_MESSAGE_HEADER_SIZE)? {
+            return Ok(Some(Step::Yield(marker)));
+        }
+
+        let (header, message_buf) = match self.read_message_header()? {
             None => {
-                self.state = State::EOF;
+                self.wait_for_growth();
                 return Ok(None);
             }
-            Some(header) => (
-                header.msg_type,
-                self.read_message(header.msg_size as usize)?,
-            ),
+            Some(header) => {
+                if self.reaches_max_bytes_to_read(header.msg_size as usize) {
+                    self.warn(
+                        self.message_offset,
+                        WarningKind::TruncatedMessage,
+                        format!(
+                            "Dropped a {:?} message cut off by the appended data",
+                            header.msg_type
+                        ),
+                    );
+                    self.state = State::EOF;
+                    return Ok(None);
+                }
+
+                if let Some(marker) = self.enter_appended_section(header.msg_size as usize)? {
+                    return Ok(Some(Step::Yield(marker)));
+                }
+
+                let message_buf = self.read_message(header.msg_size as usize)?;
+
+                // The rest of the message has not been written yet.
+                if self.growing && self.datastream.eof {
+                    self.wait_for_growth();
+                    return Ok(None);
+                }
+
+                if self.recover_on_error
+                    && self.state == State::DATA
+                    && (self.datastream.eof
+                        || !self.is_plausible_message(&header, &self.last_message))
+                {
+                    return self
+                        .recover(&header)
+                        .map(|marker| Some(Step::Yield(marker)));
+                }
+
+                (header, message_buf)
+            }
         };
+        let message_type = header.msg_type;
 
         match self.state {
             State::DEFINITIONS => {
-                let msg = self.parse_definition(message_type, message_buf)?;
+                let mut msg = self.parse_definition(message_type, message_buf)?;
 
                 match msg {
                     UlogMessage::FormatDefinition(ref format) => {
@@ -205,43 +1344,71 @@ impl<R: Read> ULogParser<R> {
 
                         self.formats.insert(format.name.clone(), format.clone());
                     }
-                    UlogMessage::AddSubscription(ref sub) => {
-                        self.subscriptions.insert(sub.msg_id, sub.clone());
-                        self.subscription_filter.update_ids(sub);
-
-                        if sub.multi_id > 0 {
-                            self.message_name_with_multi_id
-                                .insert(sub.message_name.clone());
-                        }
+                    UlogMessage::AddSubscription(ref mut sub) => {
+                        self.register_subscription(sub)?;
 
                         // Now that we've seen the first subscription message we can advance to state 'DATA.'
                         self.state = State::DATA;
+                        self.data_section_offset = Some(self.message_offset);
                     }
+                    UlogMessage::MultiInfo(ref multi_info) => self.record_multi_info(multi_info)?,
+                    UlogMessage::Info(ref info) => self.record_info(info),
+                    UlogMessage::Parameter(ref param) => self.record_parameter(param),
                     _ => (),
                 }
 
-                return Ok(Some(msg));
+                return Ok(Some(Step::Yield(msg)));
             }
             State::DATA => {
-                let mut msg = self.parse_data(message_type, message_buf)?;
+                if self.is_filtered_by_time(message_type, &message_buf) {
+                    return Ok(Some(Step::Skip));
+                }
 
-                match msg {
-                    UlogMessage::AddSubscription(ref sub) => {
-                        self.subscriptions.insert(sub.msg_id, sub.clone());
-                        self.subscription_filter.update_ids(sub);
+                if let Some(sub) = self.deferrable_subscription(message_type, &message_buf) {
+                    let sub = sub.clone();
+                    let mut message_buf = message_buf;
+                    message_buf.take_u16()?;
+
+                    // Unwrap is safe: checked by `deferrable_subscription()`.
+                    self.pipeline
+                        .as_mut()
+                        .unwrap()
+                        .decode(sub, message_buf, self.message_offset);
+                    return Ok(Some(Step::Skip));
+                }
 
-                        if sub.multi_id > 0 {
-                            self.message_name_with_multi_id
-                                .insert(sub.message_name.clone());
-                        }
+                if self.drop_ignored && self.is_ignored_data(message_type, &message_buf) {
+                    return Ok(Some(Step::Skip));
+                }
+
+                let mut msg = match self.parse_data(message_type, message_buf) {
+                    Err(err) if self.recover_on_error => {
+                        log::warn!("Recovering from a corrupt message: {err}");
+                        return self
+                            .recover(&header)
+                            .map(|marker| Some(Step::Yield(marker)));
+                    }
+                    result => result?,
+                };
+
+                match msg {
+                    UlogMessage::FormatDefinition(ref format) => {
+                        self.formats.insert(format.name.clone(), format.clone());
                     }
+                    UlogMessage::AddSubscription(ref mut sub) => self.register_subscription(sub)?,
                     UlogMessage::LoggedData(ref mut logged_data) => {
-                        logged_data.filter_fields(self.include_timestamp, self.include_padding);
+                        let keep = self.finish_logged_data(logged_data)?;
+                        if !keep {
+                            return Ok(Some(Step::Skip));
+                        }
                     }
+                    UlogMessage::MultiInfo(ref multi_info) => self.record_multi_info(multi_info)?,
+                    UlogMessage::Info(ref info) => self.record_info(info),
+                    UlogMessage::Parameter(ref param) => self.record_parameter(param),
                     _ => {}
                 }
 
-                return Ok(Some(msg));
+                return Ok(Some(Step::Yield(msg)));
             }
             _ => {
                 return Err(ULogError::ParseError(format!(
@@ -252,6 +1419,502 @@ impl<R: Read> ULogParser<R> {
         }
     }
 
+    /// Returns true if the file header has not been read yet.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn expects_file_header(&self) -> bool {
+        self.state == State::HEADER
+    }
+
+    /// Returns the subscription of a `DATA` message which would be decoded, if it can be handed
+    /// to the pipeline instead.
+    fn deferrable_subscription(
+        &self,
+        message_type: ULogMessageType,
+        message_buf: &MessageBuf,
+    ) -> Option<&msg::Subscription> {
+        // Recovery from a corrupt record needs its header, so those records are decoded serially.
+        if self.pipeline.is_none()
+            || self.recover_on_error
+            || !matches!(message_type, ULogMessageType::DATA)
+        {
+            return None;
+        }
+
+        let contents = message_buf.remaining_bytes();
+        let msg_id = u16::from_le_bytes([*contents.first()?, *contents.get(1)?]);
+
+        self.subscription_filter
+            .is_allowed(msg_id)
+            .then(|| self.subscriptions.get(&msg_id))
+            .flatten()
+    }
+
+    /// Returns true if the message is a `DATA` message of a known subscription outside the allow
+    /// list, which would be yielded as `Ignored`.
+    fn is_ignored_data(&self, message_type: ULogMessageType, message_buf: &MessageBuf) -> bool {
+        if !matches!(message_type, ULogMessageType::DATA) {
+            return false;
+        }
+
+        let contents = message_buf.remaining_bytes();
+        let (Some(&low), Some(&high)) = (contents.first(), contents.get(1)) else {
+            return false;
+        };
+        let msg_id = u16::from_le_bytes([low, high]);
+
+        self.subscriptions.contains_key(&msg_id) && !self.subscription_filter.is_allowed(msg_id)
+    }
+
+    /// Returns true if the message is a `DATA` message whose timestamp is rejected by the
+    /// predicate set by `filter_time()`.
+    fn is_filtered_by_time(&self, message_type: ULogMessageType, message_buf: &MessageBuf) -> bool {
+        let Some(time_filter) = &self.time_filter else {
+            return false;
+        };
+        if !matches!(message_type, ULogMessageType::DATA) {
+            return false;
+        }
+
+        let contents = message_buf.remaining_bytes();
+        let (Some(&low), Some(&high)) = (contents.first(), contents.get(1)) else {
+            return false;
+        };
+        let msg_id = u16::from_le_bytes([low, high]);
+
+        // Records which cannot be peeked are left for decoding to report.
+        let Some(format) = self
+            .subscriptions
+            .get(&msg_id)
+            .and_then(|sub| self.formats.get(&sub.message_name))
+        else {
+            return false;
+        };
+        let Ok(Some(range)) =
+            field_range(format, "timestamp", &self.formats, &self.opaque_type_sizes)
+        else {
+            return false;
+        };
+        let Some(timestamp) = contents
+            .get(range.start + 2..range.end + 2)
+            .and_then(decode_timestamp)
+        else {
+            return false;
+        };
+
+        !time_filter(timestamp)
+    }
+
+    /// Returns a parser with the definitions and decoding options of this one, for decoding
+    /// `DATA` messages on another thread.
+    pub(crate) fn decoder(&self) -> ULogParser<io::Empty> {
+        // Unwrap is safe: creating a parser does not read from the stream.
+        let mut decoder = ULogParser::new(io::empty()).unwrap();
+        decoder.formats.clone_from(&self.formats);
+        decoder
+            .message_name_with_multi_id
+            .clone_from(&self.message_name_with_multi_id);
+        decoder.include_padding = self.include_padding;
+        decoder.max_array_size = self.max_array_size;
+        decoder
+            .opaque_type_sizes
+            .clone_from(&self.opaque_type_sizes);
+        decoder.raw_payload = self.raw_payload;
+        decoder
+    }
+
+    /// Marks a topic as logged with several instances, as if a subscription with a non-zero
+    /// `multi_id` had been registered.
+    pub(crate) fn add_multi_instance(&mut self, message_name: String) {
+        self.message_name_with_multi_id.insert(message_name);
+    }
+
+    /// Counts a message read, failing with `ULogError::Timeout` once the deadline has passed.
+    fn count_message(&mut self) -> Result<(), ULogError> {
+        if let Some(deadline) = self.deadline {
+            if self.messages_read.is_multiple_of(DEADLINE_CHECK_INTERVAL)
+                && Instant::now() >= deadline
+            {
+                self.state = State::EOF;
+                return Err(ULogError::Timeout);
+            }
+        }
+        self.messages_read += 1;
+
+        Ok(())
+    }
+
+    /// Returns true if the next `len` bytes hold a message which can be consumed without
+    /// `next_step()`: the data section has been reached, nothing is waiting to be yielded, and no
+    /// record limit, range end, appended section or crash dump falls within them.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn can_bypass_parsing(&self, len: usize) -> bool {
+        let position = self.datastream.num_bytes_read;
+
+        self.state == State::DATA
+            && self.header_codec.is_none()
+            && self.time_filter.is_none()
+            && self.pending.is_empty()
+            && self.pipeline.is_none()
+            && !self.reaches_max_bytes_to_read(len)
+            && self.range_end.is_none_or(|range_end| position < range_end)
+            && self
+                .appended_offsets
+                .front()
+                .is_none_or(|&offset| position + len <= offset)
+            && self
+                .max_records
+                .is_none_or(|max_records| self.records_yielded < max_records)
+    }
+
+    /// Does the bookkeeping of `next_step()` for a message consumed without it, before the stream
+    /// is moved past the message.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn bypass_message(&mut self, is_record: bool) -> Result<(), ULogError> {
+        self.count_message()?;
+        self.message_offset = self.datastream.num_bytes_read;
+        if is_record {
+            self.records_yielded += 1;
+        }
+
+        Ok(())
+    }
+
+    fn reaches_max_bytes_to_read(&self, len: usize) -> bool {
+        self.max_bytes_to_read.is_some_and(|max_bytes_to_read| {
+            self.datastream.num_bytes_read + len > max_bytes_to_read
+        })
+    }
+
+    /// Moves to the next appended section if the next `len` bytes would reach into it.
+    ///
+    /// The section before an appended offset may end with a partially written message, whose
+    /// remaining bytes are skipped.  Returns the marker to yield on entering a section.
+    fn enter_appended_section(&mut self, len: usize) -> Result<Option<UlogMessage>, ULogError> {
+        let Some(&offset) = self.appended_offsets.front() else {
+            return Ok(None);
+        };
+
+        let position = self.datastream.num_bytes_read;
+        if position + len <= offset {
+            return Ok(None);
+        }
+
+        if offset > position {
+            log::warn!(
+                "Skipping {} bytes of a truncated message before the appended section at offset {offset}.",
+                offset - position
+            );
+            self.warn(
+                position,
+                WarningKind::TruncatedMessage,
+                format!(
+                    "Skipped {} bytes of a message cut off by the appended section",
+                    offset - position
+                ),
+            );
+            self.datastream.skip(offset - position)?;
+        }
+
+        self.appended_offsets.pop_front();
+
+        Ok(Some(UlogMessage::AppendedSectionStart {
+            offset: offset as u64,
+        }))
+    }
+
+    /// Applies the parser options to a freshly decoded `LoggedData` message.
+    ///
+    /// Returns `Ok(false)` if the message should be skipped.
+    fn finish_logged_data(&mut self, logged_data: &mut LoggedData) -> Result<bool, ULogError> {
+        if self.fold_resubscriptions {
+            self.fold_msg_id(logged_data);
+        }
+
+        if !self.check_timestamp(logged_data)? {
+            return Ok(false);
+        }
+
+        logged_data.filter_fields(self.include_timestamp, self.include_padding);
+
+        if !self.transforms.is_empty() {
+            // The format is that of the subscription, so has the same name.
+            let transforms = self.transforms.get(&logged_data.data.name);
+
+            for (path, transform) in transforms.into_iter().flatten() {
+                if let Some(field) = logged_data.data.find_field_mut(path) {
+                    if let Some(value) = field.value.map_numeric(*transform) {
+                        field.value = value;
+                    }
+                }
+            }
+        }
+
+        if self.add_index_field {
+            self.append_index_field(logged_data);
+        }
+
+        Ok(true)
+    }
+
+    /// Decodes the contents of a `DATA` message that was previously returned undecoded,
+    /// exactly as if it had been read from the stream.
+    ///
+    /// Returns `Ok(None)` if the message is skipped by the parser options.
+    pub(crate) fn decode_data(
+        &mut self,
+        message_contents: Vec<u8>,
+    ) -> Result<Option<UlogMessage>, ULogError> {
+        let mut msg = self.parse_data(
+            ULogMessageType::DATA,
+            MessageBuf::from_vec(message_contents),
+        )?;
+
+        if let UlogMessage::LoggedData(ref mut logged_data) = msg {
+            if !self.finish_logged_data(logged_data)? {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(msg))
+    }
+
+    /// Stops decoding `LoggedData`, so all `DATA` messages are returned as `Ignored` raw bytes,
+    /// even if `drop_ignored` is set.
+    ///
+    /// Returns the previous subscription filter, to be passed to `resume_data_decoding()`.
+    pub(crate) fn suspend_data_decoding(&mut self) -> SubscriptionFilter {
+        self.drop_ignored = false;
+        std::mem::replace(
+            &mut self.subscription_filter,
+            SubscriptionFilter::new(std::iter::empty()),
+        )
+    }
+
+    pub(crate) fn resume_data_decoding(&mut self, mut filter: SubscriptionFilter) {
+        // Subscriptions added while decoding was suspended are not yet known to the filter.
+        for sub in self.subscriptions.values() {
+            filter.update_ids(sub);
+        }
+
+        self.subscription_filter = filter;
+    }
+
+    /// Replaces the msg_id of a record with that of the first subscription to its topic instance.
+    fn fold_msg_id(&self, logged_data: &mut LoggedData) {
+        // The format is that of the subscription, so has the same name.
+        let key = (
+            logged_data.data.name.clone(),
+            logged_data.data.multi_id_index.unwrap_or(0),
+        );
+
+        if let Some(msg_id) = self.folded_msg_ids.get(&key) {
+            logged_data.msg_id = *msg_id;
+        }
+    }
+
+    /// Appends the synthetic `_index` field, counting the samples of each subscription from zero.
+    fn append_index_field(&mut self, logged_data: &mut LoggedData) {
+        let count = self.sample_counts.entry(logged_data.msg_id).or_insert(0);
+
+        logged_data.data.fields.push(inst::Field {
+            name: INDEX_FIELD_NAME.to_string(),
+            r#type: def::TypeExpr {
+                base_type: BaseType::UINT64,
+                array_size: None,
+                matrix_shape: None,
+            },
+            value: inst::FieldValue::ScalarU64(*count),
+        });
+
+        *count += 1;
+    }
+
+    fn record_multi_info(&mut self, multi_info: &msg::MultiInfo) -> Result<(), ULogError> {
+        // A continuation extends the previous message with the same key, so one must exist.
+        if multi_info.is_continued && !self.multi_info.contains_key(&multi_info.key) {
+            return Err(ULogError::DanglingContinuation(multi_info.key.clone()));
+        }
+
+        self.multi_info
+            .entry(multi_info.key.clone())
+            .or_default()
+            .push(multi_info.clone());
+
+        Ok(())
+    }
+
+    fn register_subscription(&mut self, sub: &mut msg::Subscription) -> Result<(), ULogError> {
+        self.split_name_instance(sub);
+
+        if let Some(max_subscriptions) = self.max_subscriptions {
+            if self.subscriptions.len() >= max_subscriptions
+                && !self.subscriptions.contains_key(&sub.msg_id)
+            {
+                // Treat this as fatal: the file is not parsed any further.
+                self.state = State::EOF;
+                return Err(ULogError::TooManySubscriptions(max_subscriptions));
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            msg_id = sub.msg_id,
+            multi_id = sub.multi_id,
+            message_name = %sub.message_name,
+            "subscription registered"
+        );
+
+        if let Some(previous) = self.subscriptions.get(&sub.msg_id) {
+            let description = format!(
+                "msg_id {} of {} was subscribed again, by {}",
+                sub.msg_id, previous.message_name, sub.message_name
+            );
+            self.warn(
+                self.message_offset,
+                WarningKind::DuplicateSubscription,
+                description,
+            );
+        }
+
+        self.subscriptions.insert(sub.msg_id, sub.clone());
+        self.subscription_filter.update_ids(sub);
+
+        if self.fold_resubscriptions {
+            self.folded_msg_ids
+                .entry((sub.message_name.clone(), sub.multi_id))
+                .or_insert(sub.msg_id);
+        }
+
+        if sub.multi_id > 0 {
+            self.message_name_with_multi_id
+                .insert(sub.message_name.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Renames a subscription such as `sensor_gyro_1` to its base name and instance, if enabled
+    /// by `ULogParserBuilder::split_name_instance()`.
+    fn split_name_instance(&mut self, sub: &mut msg::Subscription) {
+        let Some(pattern) = &self.name_instance_pattern else {
+            return;
+        };
+
+        if sub.multi_id != 0 {
+            return;
+        }
+
+        let Some(captures) = pattern.captures(&sub.message_name) else {
+            return;
+        };
+
+        // Unwraps are safe: the builder checks that the pattern has two capture groups.
+        let base_name = captures.get(1).unwrap().as_str().to_string();
+        let Ok(instance) = captures.get(2).unwrap().as_str().parse::<u8>() else {
+            return;
+        };
+
+        match (
+            self.formats.get(&base_name),
+            self.formats.get(&sub.message_name),
+        ) {
+            // Records of a different layout must not be merged with those of the base name.
+            (Some(base_format), Some(format))
+                if !formats_structurally_equal(base_format, format, &self.formats) =>
+            {
+                log::debug!(
+                    "Not splitting subscription {}: its format differs from {base_name}.",
+                    sub.message_name
+                );
+                return;
+            }
+            (None, Some(format)) => {
+                let mut format = format.clone();
+                format.name.clone_from(&base_name);
+                self.formats.insert(base_name.clone(), format);
+            }
+            _ => {}
+        }
+
+        log::debug!(
+            "Splitting subscription {} into {base_name} instance {instance}.",
+            sub.message_name
+        );
+
+        sub.message_name = base_name;
+        sub.multi_id = instance;
+    }
+
+    fn record_info(&mut self, info: &msg::Info) {
+        // Later values of the same key replace earlier ones.
+        self.info.insert(info.key.clone(), info.clone());
+        self.info_values.insert(info.key.clone(), info.info_value());
+    }
+
+    fn record_parameter(&mut self, param: &msg::Parameter) {
+        if self.state == State::DEFINITIONS {
+            self.initial_parameters
+                .insert(param.key.clone(), param.value.clone());
+        }
+
+        // Parameters changed in flight replace their initial value.
+        self.parameters
+            .insert(param.key.clone(), param.value.clone());
+    }
+
+    /// Returns the most recent `Info` message seen so far with the given key.
+    pub(crate) fn get_info(&self, key: &str) -> Option<&msg::Info> {
+        self.info.get(key)
+    }
+
+    /// Applies the `BadTimestampPolicy` to a decoded `LoggedData` message.
+    ///
+    /// Returns `Ok(false)` if the message should be skipped.
+    fn check_timestamp(&mut self, logged_data: &LoggedData) -> Result<bool, ULogError> {
+        let timestamp = logged_data.timestamp;
+        let previous = self.last_timestamps.get(&logged_data.msg_id).copied();
+
+        let is_plausible = match previous {
+            None => true,
+            // A zero timestamp after non-zero ones, or a massive jump backwards, usually means
+            // the message bytes are misaligned garbage rather than a real sample.
+            Some(previous) => {
+                !(timestamp == 0 && previous > 0
+                    || timestamp.saturating_add(MAX_TIMESTAMP_REGRESSION_US) < previous)
+            }
+        };
+
+        if is_plausible {
+            self.last_timestamps.insert(logged_data.msg_id, timestamp);
+            return Ok(true);
+        }
+
+        match self.bad_timestamp_policy {
+            BadTimestampPolicy::Include => Ok(true),
+            BadTimestampPolicy::Skip => {
+                log::warn!(
+                    "Skipping LoggedData for msg_id {} with implausible timestamp {timestamp}.",
+                    logged_data.msg_id
+                );
+                self.warn(
+                    self.message_offset,
+                    WarningKind::ImplausibleTimestamp,
+                    format!(
+                        "Dropped a record of msg_id {} with implausible timestamp {timestamp}",
+                        logged_data.msg_id
+                    ),
+                );
+                Ok(false)
+            }
+            BadTimestampPolicy::Error => Err(ULogError::ImplausibleTimestamp {
+                msg_id: logged_data.msg_id,
+                timestamp,
+                // Unwrap is safe: a timestamp can only be implausible relative to a previous one.
+                previous: previous.unwrap(),
+            }),
+        }
+    }
+
     pub fn parse_data(
         &mut self,
         message_type: ULogMessageType,
@@ -263,9 +1926,16 @@ impl<R: Read> ULogParser<R> {
 
                 Ok(msg::UlogMessage::AddSubscription(sub))
             }
+            // Formats may still be defined after the first subscription.
+            ULogMessageType::FORMAT => {
+                let format = parse_format(message_buf)?;
+                Ok(msg::UlogMessage::FormatDefinition(format))
+            }
             ULogMessageType::REMOVE_SUBSCRIPTION => {
                 let msg_id = message_buf.take_u16()?;
-                self.subscriptions.remove(&msg_id);
+                if let Some(sub) = self.subscriptions.remove(&msg_id) {
+                    self.removed_subscriptions.insert(msg_id, sub);
+                }
                 Ok(UlogMessage::Unhandled {
                     msg_type: message_type.into(),
                     message_contents: message_buf.into_remaining_bytes(),
@@ -279,9 +1949,14 @@ impl<R: Read> ULogParser<R> {
 
                         return Ok(msg::UlogMessage::LoggedData(logged_data.clone()));
                     } else {
+                        // Keep the msg_id in the raw contents so the message can be attributed to
+                        // its subscription, and re-encoded without loss.
+                        let mut message_contents = msg_id.to_le_bytes().to_vec();
+                        message_contents.extend(message_buf.into_remaining_bytes());
+
                         return Ok(UlogMessage::Ignored {
                             msg_type: message_type.into(),
-                            message_contents: message_buf.into_remaining_bytes(),
+                            message_contents,
                         });
                     }
                 } else {
@@ -304,9 +1979,18 @@ impl<R: Read> ULogParser<R> {
                     msg: String::from_utf8(message_buf.into_remaining_bytes())?,
                 }))
             }
-            ULogMessageType::DROPOUT => Ok(msg::UlogMessage::DropoutMark(Dropout {
-                duration: message_buf.take_u16()?,
-            })),
+            ULogMessageType::DROPOUT => {
+                let duration = message_buf.take_u16()?;
+
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    offset = self.message_offset,
+                    duration_ms = duration,
+                    "dropout"
+                );
+
+                Ok(msg::UlogMessage::DropoutMark(Dropout { duration }))
+            }
             // FIXME: Implement SYNC
             //ULogMessageType::SYNC => {}
             ULogMessageType::PARAMETER => {
@@ -325,8 +2009,19 @@ impl<R: Read> ULogParser<R> {
                 let multi_info = self.parse_multi_info(message_buf)?;
                 Ok(msg::UlogMessage::MultiInfo(multi_info))
             }
+            // When resynchronizing, unknown message types are the usual sign of corrupt data.
+            ULogMessageType::UNKNOWN(byte) if self.resync_on_error => Err(ULogError::ParseError(
+                format!("Unknown message type 0x{byte:02X} in data section."),
+            )),
             _ => {
                 log::debug!("Received unhandled message type {message_type:?}. Ignoring.");
+                if let ULogMessageType::UNKNOWN(byte) = message_type {
+                    self.warn(
+                        self.message_offset,
+                        WarningKind::UnknownMessageType,
+                        format!("Unknown message type 0x{byte:02X}"),
+                    );
+                }
                 Ok(UlogMessage::Unhandled {
                     msg_type: message_type.into(),
                     message_contents: message_buf.into_remaining_bytes(),
@@ -344,8 +2039,15 @@ impl<R: Read> ULogParser<R> {
 
         let message_name = String::from_utf8(message_buf.into_remaining_bytes())?;
 
-        // Force a lookup of the format and return an error if not found.
-        let _format = self.get_format(&message_name)?;
+        // ⚠️ The format is deliberately not looked up here.  Some loggers write a subscription
+        // before the definition of its format, so binding is deferred until the first data message
+        // for this subscription is decoded.  Only data which arrives before the format is defined
+        // results in an `UndefinedFormat` error.
+        if !self.formats.contains_key(&message_name) {
+            log::debug!(
+                "Subscription {msg_id} references format {message_name}, which is not yet defined."
+            );
+        }
 
         Ok(msg::Subscription {
             multi_id,
@@ -355,6 +2057,19 @@ impl<R: Read> ULogParser<R> {
     }
 
     fn read_message_header(&mut self) -> Result<Option<ULogMessageHeader>, ULogError> {
+        if let Some(codec) = &self.header_codec {
+            let datastream = &mut self.datastream;
+            let header = codec.read_header(&mut || {
+                let byte = datastream.read_u8()?;
+                Ok((!datastream.eof).then_some(byte))
+            })?;
+
+            if let Some(header) = &header {
+                log::trace!("MSG HEADER: {} {:?}", header.msg_size, header.msg_type);
+            }
+            return Ok(header);
+        }
+
         let msg_size = self.datastream.read_u16()?;
 
         // ⚠️This is the only place where we check for EOF when calling a datastream read method.
@@ -369,13 +2084,16 @@ impl<R: Read> ULogParser<R> {
         Ok(Some(ULogMessageHeader { msg_size, msg_type }))
     }
 
-    fn parse_data_message(
+    pub(crate) fn parse_data_message(
         &self,
         sub: &msg::Subscription,
         mut message_buf: MessageBuf,
     ) -> Result<msg::LoggedData, ULogError> {
         let format = self.get_format(&sub.message_name)?;
         let _message_len = message_buf.len();
+        let raw_payload = self
+            .raw_payload
+            .then(|| message_buf.remaining_bytes().to_vec());
 
         if !format.fields.iter().any(|f| f.name == "timestamp") {
             return Err(ULogError::MissingTimestamp);
@@ -400,6 +2118,7 @@ impl<R: Read> ULogParser<R> {
             timestamp,
             msg_id: sub.msg_id,
             data: data_format,
+            raw_payload,
         })
     }
 
@@ -420,7 +2139,22 @@ impl<R: Read> ULogParser<R> {
                 continue;
             }
 
-            let value = self.parse_field_value(field, message_buf)?;
+            let value = match field.r#type.array_size {
+                _ if self.opaque_size(&field.r#type).is_some() => {
+                    // Unwrap is safe: checked by the match guard.
+                    let size = self.opaque_size(&field.r#type).unwrap();
+                    inst::FieldValue::Opaque(message_buf.advance(size)?.to_vec())
+                }
+                Some(array_size) if self.max_array_size.is_some_and(|max| array_size > max) => {
+                    message_buf.skip(type_size(
+                        &field.r#type,
+                        &self.formats,
+                        &self.opaque_type_sizes,
+                    )?)?;
+                    inst::FieldValue::Skipped(array_size)
+                }
+                _ => self.parse_field_value(field, message_buf)?,
+            };
 
             // ⚠️ Extract the timestamp field if present.
             // According to the ULOG spec, the timestamp for a LOGGED_DATA message is the value of
@@ -428,9 +2162,12 @@ impl<R: Read> ULogParser<R> {
             // This function will extract all such fields, regardless of location in the Format hierarchy.
             // When this function returns, the top-level timestamp will then be extracted and assigned
             // to msg::LoggedData.timestamp. See: `parse_data_message()`
-            if let inst::FieldValue::ScalarU64(value) = value {
-                if field.name == "timestamp" {
-                    timestamp = Some(value);
+            // A few legacy topics declare a `uint32_t` timestamp, which is widened.
+            if field.name == "timestamp" {
+                match value {
+                    inst::FieldValue::ScalarU64(value) => timestamp = Some(value),
+                    inst::FieldValue::ScalarU32(value) => timestamp = Some(u64::from(value)),
+                    _ => {}
                 }
             }
 
@@ -487,6 +2224,18 @@ impl<R: Read> ULogParser<R> {
         Ok(None)
     }
 
+    /// Returns the encoded size of a field whose base type is neither a primitive nor a defined
+    /// format, if a size was registered for it with `ULogParserBuilder::opaque_type_sizes()`.
+    fn opaque_size(&self, type_expr: &def::TypeExpr) -> Option<usize> {
+        match &type_expr.base_type {
+            def::BaseType::OTHER(name) if !self.formats.contains_key(name) => self
+                .opaque_type_sizes
+                .get(name)
+                .map(|size| size * type_expr.array_size.unwrap_or(1)),
+            _ => None,
+        }
+    }
+
     fn parse_field_value(
         &self,
         field: &def::Field,
@@ -541,7 +2290,7 @@ impl<R: Read> ULogParser<R> {
             INT64 => ArrayI64(parse_primitive_array(array_size, message_buf)?),
             FLOAT => ArrayF32(parse_primitive_array(array_size, message_buf)?),
             DOUBLE => ArrayF64(parse_primitive_array(array_size, message_buf)?),
-            BOOL => ArrayBool(parse_primitive_array(array_size, message_buf)?),
+            BOOL => ArrayBool(message_buf.take_bool_array(array_size)?),
             CHAR => ArrayChar(parse_primitive_array(array_size, message_buf)?),
             OTHER(type_name) => {
                 let child_format = &self.get_format(type_name)?;
@@ -578,7 +2327,16 @@ impl<R: Read> ULogParser<R> {
             ULogMessageType::FLAG_BITS => {
                 let flag_bits = self.parse_flag_bits(message_buf)?;
 
-                if flag_bits.has_data_appended() {
+                if flag_bits.has_data_appended() && self.read_appended_data {
+                    let mut offsets: Vec<usize> = flag_bits
+                        .appended_data_offsets
+                        .iter()
+                        .filter(|&&offset| offset > 0)
+                        .map(|&offset| offset as usize)
+                        .collect();
+                    offsets.sort_unstable();
+                    self.appended_offsets = offsets.into();
+                } else if flag_bits.has_data_appended() {
                     // Stop reading from this stream at the first non-zero appended data offset in the list.
                     self.max_bytes_to_read = flag_bits
                         .appended_data_offsets
@@ -620,6 +2378,11 @@ impl<R: Read> ULogParser<R> {
              */
             ULogMessageType::UNKNOWN(byte) => {
                 log::warn!("Unknown message type: 0x{byte:02X}");
+                self.warn(
+                    self.message_offset,
+                    WarningKind::UnknownMessageType,
+                    format!("Unknown message type 0x{byte:02X}"),
+                );
                 Ok(UlogMessage::Unhandled {
                     msg_type: message_type.into(),
                     message_contents: message_buf.into_remaining_bytes(),
@@ -636,7 +2399,10 @@ impl<R: Read> ULogParser<R> {
     }
 
     #[allow(clippy::unused_self)]
-    fn parse_flag_bits(&self, mut message_buf: MessageBuf) -> Result<FlagBits, ULogError> {
+    pub(crate) fn parse_flag_bits(
+        &self,
+        mut message_buf: MessageBuf,
+    ) -> Result<FlagBits, ULogError> {
         if message_buf.len() != 40 {
             log::warn!(
                 "Length of flag bits >40bytes (Contained {len} extra bytes).  Ignoring.",
@@ -782,6 +2548,12 @@ impl<R: Read> ULogParser<R> {
     }
 }
 
+/// The contents of a SYNC message, which can be searched for to find the next message boundary.
+pub(crate) const SYNC_MAGIC: [u8; 8] = [0x2F, 0x73, 0x13, 0x20, 0x25, 0x0C, 0xBB, 0x12];
+
+/// The encoded size of a `ULogMessageHeader`: a u16 size followed by a u8 type.
+pub(crate) const ULOG_MESSAGE_HEADER_SIZE: usize = 3;
+
 #[derive(Debug)]
 pub struct ULogMessageHeader {
     pub msg_size: u16,
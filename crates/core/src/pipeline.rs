@@ -0,0 +1,201 @@
+//! Pipelined decoding, enabled by `ULogParserBuilder::pipelined()`.
+//!
+//! The parser keeps reading and segmenting messages on the calling thread, and hands the `DATA`
+//! messages to a pool of worker threads to decode.  The results are put back into file order
+//! before they are yielded, so the output is exactly that of the serial parser.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::errors::ULogError;
+use crate::message_buf::MessageBuf;
+use crate::model::def;
+use crate::model::msg::{LoggedData, Subscription, UlogMessage};
+use crate::parser::ULogParser;
+
+/// The most messages read ahead of the message being yielded.
+pub(crate) const PIPELINE_QUEUE_SIZE: usize = 1024;
+
+/// The work sent to a worker.  Definitions are sent to every worker, ahead of the messages which
+/// depend on them.
+enum Job {
+    Format(def::Format),
+    MultiInstance(String),
+    Decode(u64, Subscription, MessageBuf),
+}
+
+/// A message in the queue, in file order.
+#[allow(clippy::large_enum_variant)] // Boxing would cost an allocation per message.
+enum Slot {
+    Ready(Result<UlogMessage, ULogError>),
+    /// A `DATA` message being decoded by a worker, identified by its sequence number, along with
+    /// its file offset.
+    Decoding(u64, usize),
+}
+
+/// The message at the front of the queue, removed by [`Pipeline::pop`].
+#[allow(clippy::large_enum_variant)] // Short-lived, moved out immediately.
+pub(crate) enum Output {
+    Message(Result<UlogMessage, ULogError>),
+    /// A decoded `DATA` message, to which the parser options have yet to be applied, along with
+    /// its file offset.
+    Decoded(Result<LoggedData, ULogError>, usize),
+}
+
+pub(crate) struct Pipeline {
+    workers: Vec<(Sender<Job>, JoinHandle<()>)>,
+    results: Receiver<(u64, Result<LoggedData, ULogError>)>,
+    queue: VecDeque<Slot>,
+    // Results which arrived before the messages ahead of them were yielded.
+    decoded: HashMap<u64, Result<LoggedData, ULogError>>,
+    next_seq: u64,
+    pub(crate) stream_ended: bool,
+}
+
+impl Pipeline {
+    /// Starts one worker per available core, each decoding with its own copy of the definitions
+    /// and options of `parser`.
+    pub(crate) fn new<R: Read>(parser: &ULogParser<R>) -> Self {
+        let num_workers = thread::available_parallelism().map_or(1, |n| n.get());
+        let (result_tx, results) = mpsc::channel();
+
+        let workers = (0..num_workers)
+            .map(|_| {
+                let (job_tx, jobs) = mpsc::channel();
+                let result_tx = result_tx.clone();
+                let decoder = parser.decoder();
+
+                (
+                    job_tx,
+                    thread::spawn(move || run_worker(decoder, &jobs, &result_tx)),
+                )
+            })
+            .collect();
+
+        Pipeline {
+            workers,
+            results,
+            queue: VecDeque::new(),
+            decoded: HashMap::new(),
+            next_seq: 0,
+            stream_ended: false,
+        }
+    }
+
+    /// Returns true if more messages should be read ahead.
+    pub(crate) fn wants_more(&self) -> bool {
+        !self.stream_ended && self.queue.len() < PIPELINE_QUEUE_SIZE
+    }
+
+    /// Queues a message read by the parser, passing on any definitions to the workers.
+    pub(crate) fn push(&mut self, msg_res: Result<UlogMessage, ULogError>) {
+        match &msg_res {
+            Ok(UlogMessage::FormatDefinition(format)) => self.add_format(format),
+            Ok(UlogMessage::AddSubscription(sub)) if sub.multi_id > 0 => {
+                self.broadcast(|| Job::MultiInstance(sub.message_name.clone()));
+            }
+            _ => {}
+        }
+
+        self.queue.push_back(Slot::Ready(msg_res));
+    }
+
+    /// Passes a format definition on to every worker.
+    pub(crate) fn add_format(&self, format: &def::Format) {
+        self.broadcast(|| Job::Format(format.clone()));
+    }
+
+    /// Queues a `DATA` message read at `offset`, whose contents follow the `msg_id`, to be decoded
+    /// by the next worker in turn.
+    pub(crate) fn decode(&mut self, sub: Subscription, message_buf: MessageBuf, offset: usize) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        let (jobs, _) = &self.workers[seq as usize % self.workers.len()];
+        // A worker only stops once the pipeline is dropped.
+        let _ = jobs.send(Job::Decode(seq, sub, message_buf));
+
+        self.queue.push_back(Slot::Decoding(seq, offset));
+    }
+
+    /// Removes the message at the front of the queue, if it is ready.  With `wait`, blocks until
+    /// it has been decoded.  Returns `None` if the queue is empty.
+    pub(crate) fn pop(&mut self, wait: bool) -> Option<Output> {
+        let (seq, offset) = match self.queue.front()? {
+            Slot::Ready(_) => {
+                let Some(Slot::Ready(msg_res)) = self.queue.pop_front() else {
+                    unreachable!();
+                };
+                return Some(Output::Message(msg_res));
+            }
+            Slot::Decoding(seq, offset) => (*seq, *offset),
+        };
+
+        while let Ok((done_seq, result)) = self.results.try_recv() {
+            self.decoded.insert(done_seq, result);
+        }
+
+        while wait && !self.decoded.contains_key(&seq) {
+            let Ok((done_seq, result)) = self.results.recv() else {
+                // Every worker holds a sender until the pipeline is dropped.
+                unreachable!();
+            };
+            self.decoded.insert(done_seq, result);
+        }
+
+        let result = self.decoded.remove(&seq)?;
+        self.queue.pop_front();
+
+        Some(Output::Decoded(result, offset))
+    }
+
+    fn broadcast(&self, job: impl Fn() -> Job) {
+        for (jobs, _) in &self.workers {
+            let _ = jobs.send(job());
+        }
+    }
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        for (jobs, handle) in self.workers.drain(..) {
+            // Closing the job channel stops the worker.
+            drop(jobs);
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run_worker(
+    mut decoder: ULogParser<io::Empty>,
+    jobs: &Receiver<Job>,
+    results: &Sender<(u64, Result<LoggedData, ULogError>)>,
+) {
+    for job in jobs {
+        match job {
+            Job::Format(format) => {
+                decoder.formats.insert(format.name.clone(), format);
+            }
+            Job::MultiInstance(message_name) => decoder.add_multi_instance(message_name),
+            Job::Decode(seq, sub, message_buf) => {
+                // Report a panic as an error, rather than leaving the parser waiting for a result.
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    decoder.parse_data_message(&sub, message_buf)
+                }))
+                .unwrap_or_else(|_| {
+                    Err(ULogError::ParseError(format!(
+                        "Panicked while decoding a message of {}.",
+                        sub.message_name
+                    )))
+                });
+
+                if results.send((seq, result)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
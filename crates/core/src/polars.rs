@@ -0,0 +1,192 @@
+//! Decoding of a subscription directly into a Polars `DataFrame`.
+
+use std::io::Read;
+
+use ::polars::prelude::{Column, DataFrame, IntoColumn, NamedFrom, PolarsError, Series};
+
+use crate::errors::ULogError;
+use crate::model::inst::{ArrayMode, FieldValue};
+use crate::parser::ULogParser;
+
+impl From<PolarsError> for ULogError {
+    fn from(err: PolarsError) -> Self {
+        ULogError::InternalError(err.to_string())
+    }
+}
+
+/// Reads the records of a single subscription into a Polars `DataFrame`, with one row per record.
+///
+/// The subscription is named as for [`ULogParser::bytes_per_subscription`], e.g.
+/// `actuator_outputs.01`.  Columns have the native Polars type of their field, e.g. `float`
+/// becomes `Float32`, and are named as by `inst::Format::flatten_fields()` with `mode`:
+///
+/// - Nested formats become one column per field, e.g. `accel/x`.
+/// - Arrays become `List` columns with `ArrayMode::Nested`, and one column per element, e.g.
+///   `gyro.00`, with `ArrayMode::Expand`.  Arrays of nested formats become the columns of each
+///   element in both modes, e.g. `esc.00/rpm`.
+/// - `char` fields and arrays become `String` columns, with trailing NULs removed.
+/// - Fields of opaque types become `Binary` columns.
+///
+/// The `timestamp` and padding fields are included as configured by the parser.  Only the named
+/// subscription is decoded, overriding any allow list the parser was built with.  If it has no
+/// records, the `DataFrame` is empty.
+pub fn to_polars<R: Read>(
+    mut parser: ULogParser<R>,
+    subscription: &str,
+    mode: ArrayMode,
+) -> Result<DataFrame, ULogError> {
+    let mut columns: Vec<(String, ColumnValues)> = Vec::new();
+    let mut height = 0;
+
+    parser.for_each_record(subscription, |data| {
+        let values = data.data.flatten_columns(mode);
+
+        if columns.is_empty() {
+            columns = values
+                .iter()
+                .map(|(name, value)| (name.clone(), ColumnValues::new(value)))
+                .collect();
+        }
+
+        if values.len() != columns.len() {
+            return Err(ULogError::TypeMismatch(format!(
+                "record of {subscription} has {} columns, expected {}",
+                values.len(),
+                columns.len()
+            )));
+        }
+
+        for ((_, column), (_, value)) in columns.iter_mut().zip(values) {
+            column.push(value)?;
+        }
+        height += 1;
+
+        Ok(())
+    })?;
+
+    let columns = columns
+        .into_iter()
+        .map(|(name, values)| values.into_column(&name))
+        .collect();
+
+    Ok(DataFrame::new(height, columns)?)
+}
+
+/// The values of a column, of the type of the first record.
+enum ColumnValues {
+    UInt8(Vec<u8>),
+    UInt16(Vec<u16>),
+    UInt32(Vec<u32>),
+    UInt64(Vec<u64>),
+    Int8(Vec<i8>),
+    Int16(Vec<i16>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+    Boolean(Vec<bool>),
+    String(Vec<String>),
+    Binary(Vec<Vec<u8>>),
+    List(Vec<Series>),
+}
+
+impl ColumnValues {
+    fn new(value: &FieldValue) -> Self {
+        match value {
+            FieldValue::ScalarU8(_) => ColumnValues::UInt8(Vec::new()),
+            FieldValue::ScalarU16(_) => ColumnValues::UInt16(Vec::new()),
+            FieldValue::ScalarU32(_) => ColumnValues::UInt32(Vec::new()),
+            FieldValue::ScalarU64(_) => ColumnValues::UInt64(Vec::new()),
+            FieldValue::ScalarI8(_) => ColumnValues::Int8(Vec::new()),
+            FieldValue::ScalarI16(_) => ColumnValues::Int16(Vec::new()),
+            FieldValue::ScalarI32(_) => ColumnValues::Int32(Vec::new()),
+            FieldValue::ScalarI64(_) => ColumnValues::Int64(Vec::new()),
+            FieldValue::ScalarF32(_) => ColumnValues::Float32(Vec::new()),
+            FieldValue::ScalarF64(_) => ColumnValues::Float64(Vec::new()),
+            FieldValue::ScalarBool(_) => ColumnValues::Boolean(Vec::new()),
+            FieldValue::ScalarChar(_) | FieldValue::ArrayChar(_) => {
+                ColumnValues::String(Vec::new())
+            }
+            FieldValue::Opaque(_) => ColumnValues::Binary(Vec::new()),
+            _ => ColumnValues::List(Vec::new()),
+        }
+    }
+
+    fn push(&mut self, value: FieldValue) -> Result<(), ULogError> {
+        match (self, value) {
+            (ColumnValues::UInt8(values), FieldValue::ScalarU8(v)) => values.push(v),
+            (ColumnValues::UInt16(values), FieldValue::ScalarU16(v)) => values.push(v),
+            (ColumnValues::UInt32(values), FieldValue::ScalarU32(v)) => values.push(v),
+            (ColumnValues::UInt64(values), FieldValue::ScalarU64(v)) => values.push(v),
+            (ColumnValues::Int8(values), FieldValue::ScalarI8(v)) => values.push(v),
+            (ColumnValues::Int16(values), FieldValue::ScalarI16(v)) => values.push(v),
+            (ColumnValues::Int32(values), FieldValue::ScalarI32(v)) => values.push(v),
+            (ColumnValues::Int64(values), FieldValue::ScalarI64(v)) => values.push(v),
+            (ColumnValues::Float32(values), FieldValue::ScalarF32(v)) => values.push(v),
+            (ColumnValues::Float64(values), FieldValue::ScalarF64(v)) => values.push(v),
+            (ColumnValues::Boolean(values), FieldValue::ScalarBool(v)) => values.push(v),
+            (ColumnValues::String(values), FieldValue::ScalarChar(v)) => values.push(v.to_string()),
+            (ColumnValues::String(values), FieldValue::ArrayChar(chars)) => {
+                let text: String = chars.into_iter().collect();
+                values.push(text.trim_end_matches('\0').to_string());
+            }
+            (ColumnValues::Binary(values), FieldValue::Opaque(bytes)) => values.push(bytes),
+            (ColumnValues::List(values), value) => values.push(array_series(value)?),
+            (_, value) => {
+                return Err(ULogError::TypeMismatch(format!(
+                    "cannot append {value:?} to a Polars column"
+                )))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_column(self, name: &str) -> Column {
+        let name = name.into();
+        let series = match self {
+            ColumnValues::UInt8(values) => Series::new(name, values),
+            ColumnValues::UInt16(values) => Series::new(name, values),
+            ColumnValues::UInt32(values) => Series::new(name, values),
+            ColumnValues::UInt64(values) => Series::new(name, values),
+            ColumnValues::Int8(values) => Series::new(name, values),
+            ColumnValues::Int16(values) => Series::new(name, values),
+            ColumnValues::Int32(values) => Series::new(name, values),
+            ColumnValues::Int64(values) => Series::new(name, values),
+            ColumnValues::Float32(values) => Series::new(name, values),
+            ColumnValues::Float64(values) => Series::new(name, values),
+            ColumnValues::Boolean(values) => Series::new(name, values),
+            ColumnValues::String(values) => Series::new(name, values),
+            ColumnValues::Binary(values) => Series::new(name, values),
+            ColumnValues::List(values) => Series::new(name, values),
+        };
+
+        series.into_column()
+    }
+}
+
+/// Converts the value of an array field into the `Series` of its elements.
+fn array_series(value: FieldValue) -> Result<Series, ULogError> {
+    let name = "".into();
+
+    Ok(match value {
+        FieldValue::ArrayU8(v) => Series::new(name, v),
+        FieldValue::ArrayU16(v) => Series::new(name, v),
+        FieldValue::ArrayU32(v) => Series::new(name, v),
+        FieldValue::ArrayU64(v) => Series::new(name, v),
+        FieldValue::ArrayI8(v) => Series::new(name, v),
+        FieldValue::ArrayI16(v) => Series::new(name, v),
+        FieldValue::ArrayI32(v) => Series::new(name, v),
+        FieldValue::ArrayI64(v) => Series::new(name, v),
+        FieldValue::ArrayF32(v) => Series::new(name, v),
+        FieldValue::ArrayF64(v) => Series::new(name, v),
+        FieldValue::ArrayBool(v) => Series::new(name, v),
+        // Arrays skipped with `skip_arrays_larger_than()`, and nested values, which are
+        // flattened before this point.
+        value => {
+            return Err(ULogError::TypeMismatch(format!(
+                "cannot convert {value:?} to a Polars list"
+            )))
+        }
+    })
+}
@@ -0,0 +1,257 @@
+//! Export of records as dynamic Protobuf messages, e.g. for streaming them over gRPC.
+//!
+//! [`ULogParser::protobuf_descriptor`] generates a Protobuf file with a message type for each
+//! format of the log, and [`to_dynamic_message`] converts a record into a message of its type,
+//! which can then be encoded with `prost::Message::encode_to_vec()`.
+
+use std::io::Read;
+
+use prost_reflect::bytes::Bytes;
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+use prost_reflect::prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorProto};
+use prost_reflect::{
+    DescriptorError, DescriptorPool, DynamicMessage, FieldDescriptor, FileDescriptor, Kind,
+    MessageDescriptor, ReflectMessage, Value,
+};
+
+use crate::errors::ULogError;
+use crate::model::def::{self, BaseType};
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::LoggedData;
+use crate::parser::{ULogParser, INDEX_FIELD_NAME};
+
+/// The Protobuf package of the generated message types.
+pub const PROTOBUF_PACKAGE: &str = "ulog";
+
+/// The name of the generated Protobuf file.
+const PROTOBUF_FILE_NAME: &str = "ulog.proto";
+
+impl From<DescriptorError> for ULogError {
+    fn from(err: DescriptorError) -> Self {
+        ULogError::InternalError(err.to_string())
+    }
+}
+
+impl<R: Read> ULogParser<R> {
+    /// Returns a Protobuf file descriptor with a message type for each format of the log, so
+    /// that each subscription maps to the message type of its format.
+    ///
+    /// Message types are named after their format in the `ulog` package, e.g.
+    /// `ulog.vehicle_gps_position`, and their fields are numbered by their position in the
+    /// format, from 1.  Padding fields are left out.  Types are mapped as follows:
+    ///
+    /// - Unsigned integers become `uint32`, or `uint64` for `uint64_t`, and signed integers
+    ///   likewise become `int32` or `int64`.
+    /// - `float`, `double` and `bool` keep their type.
+    /// - `char` fields and arrays become `string`.
+    /// - Other arrays become repeated fields, and matrices are flattened in row major order.
+    /// - Nested formats become fields of their own message type.
+    /// - Fields of opaque types become `bytes`.
+    ///
+    /// With `add_index_field(true)` every message type also has a `uint64` index field, after
+    /// the fields of its format.  The formats must have been seen already, so this is typically
+    /// called once the definitions section has been read.
+    pub fn protobuf_descriptor(&self) -> Result<FileDescriptor, ULogError> {
+        let mut formats: Vec<&def::Format> = self.formats.values().collect();
+        formats.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let file = FileDescriptorProto {
+            name: Some(PROTOBUF_FILE_NAME.to_string()),
+            package: Some(PROTOBUF_PACKAGE.to_string()),
+            message_type: formats
+                .into_iter()
+                .map(|format| self.message_proto(format))
+                .collect::<Result<_, _>>()?,
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        };
+
+        let mut pool = DescriptorPool::new();
+        pool.add_file_descriptor_proto(file)?;
+
+        // Unwrap is safe: the file was just added.
+        Ok(pool.get_file_by_name(PROTOBUF_FILE_NAME).unwrap())
+    }
+
+    fn message_proto(&self, format: &def::Format) -> Result<DescriptorProto, ULogError> {
+        let mut fields = Vec::new();
+        for (number, field) in (1..).zip(&format.fields) {
+            if field.name.starts_with("_padding") {
+                continue;
+            }
+
+            let (r#type, type_name) = self.protobuf_type(&field.r#type.base_type)?;
+            // A `char` array is a single string.
+            let repeated = field.r#type.is_array() && field.r#type.base_type != BaseType::CHAR;
+
+            fields.push(FieldDescriptorProto {
+                name: Some(field.name.clone()),
+                number: Some(number),
+                label: Some(if repeated {
+                    Label::Repeated
+                } else {
+                    Label::Optional
+                } as i32),
+                r#type: Some(r#type as i32),
+                type_name,
+                ..Default::default()
+            });
+        }
+
+        if self.add_index_field {
+            fields.push(FieldDescriptorProto {
+                name: Some(INDEX_FIELD_NAME.to_string()),
+                number: Some(format.fields.len() as i32 + 1),
+                label: Some(Label::Optional as i32),
+                r#type: Some(Type::Uint64 as i32),
+                ..Default::default()
+            });
+        }
+
+        Ok(DescriptorProto {
+            name: Some(format.name.clone()),
+            field: fields,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the Protobuf type of a ULOG type, and the full name of its message type if it is
+    /// a nested format.
+    fn protobuf_type(&self, base_type: &BaseType) -> Result<(Type, Option<String>), ULogError> {
+        use BaseType::*;
+
+        Ok(match base_type {
+            UINT8 | UINT16 | UINT32 => (Type::Uint32, None),
+            UINT64 => (Type::Uint64, None),
+            INT8 | INT16 | INT32 => (Type::Int32, None),
+            INT64 => (Type::Int64, None),
+            FLOAT => (Type::Float, None),
+            DOUBLE => (Type::Double, None),
+            BOOL => (Type::Bool, None),
+            CHAR => (Type::String, None),
+            OTHER(name) if self.formats.contains_key(name) => {
+                (Type::Message, Some(format!(".{PROTOBUF_PACKAGE}.{name}")))
+            }
+            OTHER(name) if self.opaque_type_sizes.contains_key(name) => (Type::Bytes, None),
+            OTHER(name) => return Err(ULogError::UndefinedFormat(name.clone())),
+        })
+    }
+}
+
+/// Converts a record into a dynamic message of its type in `descriptor`, as returned by
+/// [`ULogParser::protobuf_descriptor`].
+///
+/// The `timestamp` field is set even if the parser leaves it out of the decoded fields, while
+/// arrays which were not decoded, see `ULogParserBuilder::skip_arrays_larger_than()`, are left
+/// unset.  Trailing NULs are removed from `char` arrays.  Returns `ULogError::UndefinedFormat` if
+/// the descriptor has no message type for the format of the record.
+pub fn to_dynamic_message(
+    data: &LoggedData,
+    descriptor: &FileDescriptor,
+) -> Result<DynamicMessage, ULogError> {
+    let message_type = message_type(descriptor, &data.data.def_format.name)?;
+    let mut message = format_message(&data.data, message_type)?;
+
+    if let Some(field) = message.descriptor().get_field_by_name("timestamp") {
+        if !message.has_field(&field) {
+            let timestamp = match field.kind() {
+                Kind::Uint64 => Value::U64(data.timestamp),
+                // A legacy `uint32_t` timestamp, which was widened while decoding.
+                _ => Value::U32(data.timestamp as u32),
+            };
+            set_field(&mut message, &field, timestamp)?;
+        }
+    }
+
+    Ok(message)
+}
+
+fn message_type(descriptor: &FileDescriptor, name: &str) -> Result<MessageDescriptor, ULogError> {
+    descriptor
+        .parent_pool()
+        .get_message_by_name(&format!("{PROTOBUF_PACKAGE}.{name}"))
+        .ok_or_else(|| ULogError::UndefinedFormat(name.to_string()))
+}
+
+fn format_message(
+    format: &inst::Format,
+    message_type: MessageDescriptor,
+) -> Result<DynamicMessage, ULogError> {
+    let mut message = DynamicMessage::new(message_type);
+
+    for field in &format.fields {
+        let Some(descriptor) = message.descriptor().get_field_by_name(&field.name) else {
+            // Padding fields, if included, have no counterpart.
+            if field.name.starts_with("_padding") {
+                continue;
+            }
+            return Err(ULogError::InvalidFieldName(format!(
+                "{}.{}",
+                format.name, field.name
+            )));
+        };
+
+        if let Some(value) = field_value(&field.value, &descriptor.kind())? {
+            set_field(&mut message, &descriptor, value)?;
+        }
+    }
+
+    Ok(message)
+}
+
+fn set_field(
+    message: &mut DynamicMessage,
+    field: &FieldDescriptor,
+    value: Value,
+) -> Result<(), ULogError> {
+    message
+        .try_set_field(field, value)
+        .map_err(|err| ULogError::TypeMismatch(format!("{}: {err}", field.full_name())))
+}
+
+/// Converts a decoded value into a Protobuf value of the given kind, or `None` if it was not
+/// decoded.
+fn field_value(value: &FieldValue, kind: &Kind) -> Result<Option<Value>, ULogError> {
+    use FieldValue::*;
+
+    let nested = |format: &inst::Format| match kind {
+        Kind::Message(message_type) => Ok(Value::Message(format_message(
+            format,
+            message_type.clone(),
+        )?)),
+        _ => Err(ULogError::TypeMismatch(format!(
+            "{} is not a message type",
+            format.name
+        ))),
+    };
+
+    Ok(Some(match value {
+        ScalarU8(v) => Value::U32(u32::from(*v)),
+        ScalarU16(v) => Value::U32(u32::from(*v)),
+        ScalarU32(v) => Value::U32(*v),
+        ScalarU64(v) => Value::U64(*v),
+        ScalarI8(v) => Value::I32(i32::from(*v)),
+        ScalarI16(v) => Value::I32(i32::from(*v)),
+        ScalarI32(v) => Value::I32(*v),
+        ScalarI64(v) => Value::I64(*v),
+        ScalarF32(v) => Value::F32(*v),
+        ScalarF64(v) => Value::F64(*v),
+        ScalarBool(v) => Value::Bool(*v),
+        ScalarChar(v) => Value::String(v.to_string()),
+        ScalarOther(format) => nested(format)?,
+        ArrayChar(v) => Value::String(v.iter().collect::<String>().trim_end_matches('\0').into()),
+        ArrayOther(v) => Value::List(v.iter().map(nested).collect::<Result<_, _>>()?),
+        Opaque(v) => Value::Bytes(Bytes::copy_from_slice(v)),
+        Skipped(_) => return Ok(None),
+        array => {
+            // Unwrap is safe: the remaining values are arrays of primitives.
+            let elements = array.to_scalars().unwrap();
+            Value::List(
+                elements
+                    .iter()
+                    .map(|element| Ok(field_value(element, kind)?.unwrap()))
+                    .collect::<Result<_, ULogError>>()?,
+            )
+        }
+    }))
+}
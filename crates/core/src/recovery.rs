@@ -0,0 +1,184 @@
+//! Recovery from corrupt messages, enabled by `ULogParserBuilder::recover_on_error()`.
+//!
+//! A message is only trusted if its header and contents are plausible for its type, and a
+//! plausible header follows it.  After a corrupt message the parser scans forward a byte at a time
+//! for the next such message, and reports the bytes in between as a `UlogMessage::Corrupt`.
+
+use std::collections::VecDeque;
+use std::io::Read;
+
+use crate::errors::ULogError;
+use crate::layout::{format_size, type_size};
+use crate::model::msg::UlogMessage;
+use crate::parser::{
+    ULogMessageHeader, ULogMessageType, ULogParser, SYNC_MAGIC, ULOG_MESSAGE_HEADER_SIZE,
+};
+use crate::warnings::WarningKind;
+
+impl<R: Read> ULogParser<R> {
+    /// Returns true if the message, with the given header and contents, could be a valid message
+    /// of the data section.
+    pub(crate) fn is_plausible_message(&self, header: &ULogMessageHeader, contents: &[u8]) -> bool {
+        is_plausible_header(header.msg_type, usize::from(header.msg_size))
+            && contents.len() == usize::from(header.msg_size)
+            && self.is_plausible_contents(header.msg_type, contents)
+    }
+
+    /// Skips the corrupt message starting at `message_offset`, whose header was `header` and
+    /// whose contents are in `last_message`, up to the next plausible message.
+    ///
+    /// Returns a marker of the bytes skipped.  If no plausible message is found, the rest of the
+    /// stream is skipped.
+    pub(crate) fn recover(&mut self, header: &ULogMessageHeader) -> Result<UlogMessage, ULogError> {
+        let offset = self.message_offset;
+
+        // Start the search within the failed message, since a corrupt size may have swallowed
+        // the following messages.
+        let mut failed_message = header.msg_size.to_le_bytes().to_vec();
+        failed_message.push(u8::from(header.msg_type));
+        failed_message.append(&mut self.last_message);
+        self.datastream.unread(&failed_message[1..]);
+
+        let mut window = VecDeque::new();
+        let mut skipped = 1;
+
+        loop {
+            if !self.fill_window(&mut window, ULOG_MESSAGE_HEADER_SIZE)? {
+                skipped += window.len();
+                window.clear();
+                break;
+            }
+
+            if self.is_plausible_candidate(&mut window)? {
+                break;
+            }
+
+            window.pop_front();
+            skipped += 1;
+        }
+
+        self.datastream.unread(window.make_contiguous());
+
+        log::warn!("Skipped {skipped} corrupt bytes from offset {offset}.");
+        self.warn(
+            offset,
+            WarningKind::SkippedBytes,
+            format!("Skipped {skipped} corrupt bytes"),
+        );
+        #[cfg(feature = "tracing")]
+        tracing::warn!(offset, skipped, "skipped corrupt data");
+
+        Ok(UlogMessage::Corrupt {
+            offset: offset as u64,
+            len: skipped as u64,
+        })
+    }
+
+    /// Returns true if the window starts with a plausible message, which is either followed by a
+    /// plausible header or ends the stream.
+    fn is_plausible_candidate(&mut self, window: &mut VecDeque<u8>) -> Result<bool, ULogError> {
+        let msg_size = usize::from(u16::from_le_bytes([window[0], window[1]]));
+        let msg_type = ULogMessageType::from(window[2]);
+
+        if !is_plausible_header(msg_type, msg_size) {
+            return Ok(false);
+        }
+
+        let end = ULOG_MESSAGE_HEADER_SIZE + msg_size;
+        let complete = self.fill_window(window, end + ULOG_MESSAGE_HEADER_SIZE)?;
+        if window.len() < end {
+            return Ok(false);
+        }
+
+        let bytes = window.make_contiguous();
+        if !self.is_plausible_contents(msg_type, &bytes[ULOG_MESSAGE_HEADER_SIZE..end]) {
+            return Ok(false);
+        }
+
+        Ok(!complete
+            || is_plausible_header(
+                ULogMessageType::from(bytes[end + 2]),
+                usize::from(u16::from_le_bytes([bytes[end], bytes[end + 1]])),
+            ))
+    }
+
+    /// Reads from the stream until the window holds `len` bytes.  Returns false if the stream
+    /// ends first.
+    fn fill_window(&mut self, window: &mut VecDeque<u8>, len: usize) -> Result<bool, ULogError> {
+        if window.len() >= len {
+            return Ok(true);
+        }
+
+        let mut chunk = vec![0; len - window.len()];
+        let start = self.datastream.num_bytes_read;
+        self.datastream.read_exact(&mut chunk)?;
+
+        let num_read = self.datastream.num_bytes_read - start;
+        window.extend(&chunk[..num_read]);
+
+        Ok(num_read == chunk.len())
+    }
+
+    fn is_plausible_contents(&self, msg_type: ULogMessageType, contents: &[u8]) -> bool {
+        match msg_type {
+            ULogMessageType::DATA => {
+                let msg_id = u16::from_le_bytes([contents[0], contents[1]]);
+                let Ok(sub) = self.get_subscription_ref(msg_id) else {
+                    return false;
+                };
+
+                match self.data_size_range(&sub.message_name) {
+                    Some((min, max)) => (min..=max).contains(&(contents.len() - 2)),
+                    // The size of types neither defined nor registered as opaque is not known.
+                    None => true,
+                }
+            }
+            ULogMessageType::SYNC => contents == SYNC_MAGIC,
+            // The key length must fit within the message.
+            ULogMessageType::INFO | ULogMessageType::PARAMETER => {
+                usize::from(contents[0]) < contents.len()
+            }
+            ULogMessageType::INFO_MULTIPLE | ULogMessageType::PARAMETER_DEFAULT => {
+                usize::from(contents[1]) + 2 <= contents.len()
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns the smallest and largest sizes of a record of the format, which may omit its
+    /// trailing padding.
+    fn data_size_range(&self, message_name: &str) -> Option<(usize, usize)> {
+        let format = self.formats.get(message_name)?;
+        let max = format_size(format, &self.formats, &self.opaque_type_sizes).ok()?;
+
+        let mut min = 0;
+        let mut offset = 0;
+        for field in &format.fields {
+            offset += type_size(&field.r#type, &self.formats, &self.opaque_type_sizes).ok()?;
+            if !field.name.starts_with("_padding") {
+                min = offset;
+            }
+        }
+
+        Some((min, max))
+    }
+}
+
+/// Returns true if a message of the data section could have the given type and size.
+fn is_plausible_header(msg_type: ULogMessageType, msg_size: usize) -> bool {
+    match msg_type {
+        ULogMessageType::DATA => msg_size >= 2,
+        ULogMessageType::SYNC => msg_size == SYNC_MAGIC.len(),
+        ULogMessageType::DROPOUT => msg_size == 2,
+        // The log level and a timestamp, and for tagged strings a tag.
+        ULogMessageType::LOGGING => msg_size >= 9,
+        ULogMessageType::LOGGING_TAGGED => msg_size >= 11,
+        ULogMessageType::INFO
+        | ULogMessageType::PARAMETER
+        | ULogMessageType::REMOVE_SUBSCRIPTION => msg_size >= 2,
+        ULogMessageType::INFO_MULTIPLE | ULogMessageType::PARAMETER_DEFAULT => msg_size >= 3,
+        ULogMessageType::ADD_SUBSCRIPTION => msg_size >= 4,
+        ULogMessageType::FORMAT => msg_size >= 1,
+        ULogMessageType::FLAG_BITS | ULogMessageType::HEADER | ULogMessageType::UNKNOWN(_) => false,
+    }
+}
@@ -0,0 +1,129 @@
+//! A flat buffer of the scalar fields of a record, for bulk processing in hot loops.
+//!
+//! `inst::FieldValue` is a large enum, and a record holds a `Vec` of boxed fields.  A
+//! [`ScalarBuffer`] instead stores each scalar as its type and the 64 bits of its value in two
+//! parallel vectors, which can be reused from one record to the next.  Values are only decoded
+//! back into a `FieldValue` or an `f64` when asked for.
+
+use crate::model::def::BaseType;
+use crate::model::inst::{self, FieldValue};
+
+/// The scalar fields of a record, stored as parallel vectors of types and value bits.
+///
+/// Fields are in the order of [`inst::Format::flatten_fields()`], including the fields of nested
+/// formats.  Array fields and opaque fields are left out.  Integers are stored sign or zero
+/// extended to 64 bits, floats as their IEEE 754 bits, bools as 0 or 1 and chars as their code
+/// point.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScalarBuffer {
+    pub types: Vec<BaseType>,
+    pub values: Vec<u64>,
+}
+
+impl ScalarBuffer {
+    pub fn new() -> Self {
+        ScalarBuffer::default()
+    }
+
+    /// Returns a buffer holding the scalar fields of `format`.
+    pub fn from_format(format: &inst::Format) -> Self {
+        let mut buffer = ScalarBuffer::new();
+        buffer.fill(format);
+        buffer
+    }
+
+    /// Replaces the contents of the buffer with the scalar fields of `format`, reusing its
+    /// allocations.
+    pub fn fill(&mut self, format: &inst::Format) {
+        self.types.clear();
+        self.values.clear();
+        self.push_format(format);
+    }
+
+    fn push_format(&mut self, format: &inst::Format) {
+        for field in &format.fields {
+            if let FieldValue::ScalarOther(nested) = &field.value {
+                self.push_format(nested);
+            } else if let Some((base_type, bits)) = scalar_bits(&field.value) {
+                self.types.push(base_type);
+                self.values.push(bits);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Decodes the scalar at `index` back into a `FieldValue`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn get(&self, index: usize) -> Option<FieldValue> {
+        let bits = *self.values.get(index)?;
+
+        Some(match self.types[index] {
+            BaseType::UINT8 => FieldValue::ScalarU8(bits as u8),
+            BaseType::UINT16 => FieldValue::ScalarU16(bits as u16),
+            BaseType::UINT32 => FieldValue::ScalarU32(bits as u32),
+            BaseType::UINT64 => FieldValue::ScalarU64(bits),
+            BaseType::INT8 => FieldValue::ScalarI8(bits as i8),
+            BaseType::INT16 => FieldValue::ScalarI16(bits as i16),
+            BaseType::INT32 => FieldValue::ScalarI32(bits as i32),
+            BaseType::INT64 => FieldValue::ScalarI64(bits as i64),
+            BaseType::FLOAT => FieldValue::ScalarF32(f32::from_bits(bits as u32)),
+            BaseType::DOUBLE => FieldValue::ScalarF64(f64::from_bits(bits)),
+            BaseType::BOOL => FieldValue::ScalarBool(bits != 0),
+            BaseType::CHAR => FieldValue::ScalarChar(char::from_u32(bits as u32)?),
+            BaseType::OTHER(_) => return None,
+        })
+    }
+
+    /// Decodes the numeric scalar at `index` as an `f64`, without building a `FieldValue`.
+    ///
+    /// As for `FieldValue::as_f64()`, 64 bit integers above 2^53 lose precision.  Returns `None`
+    /// for bools and chars.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn get_f64(&self, index: usize) -> Option<f64> {
+        let bits = *self.values.get(index)?;
+
+        match self.types[index] {
+            BaseType::UINT8 | BaseType::UINT16 | BaseType::UINT32 | BaseType::UINT64 => {
+                Some(bits as f64)
+            }
+            BaseType::INT8 | BaseType::INT16 | BaseType::INT32 | BaseType::INT64 => {
+                Some(bits as i64 as f64)
+            }
+            BaseType::FLOAT => Some(f64::from(f32::from_bits(bits as u32))),
+            BaseType::DOUBLE => Some(f64::from_bits(bits)),
+            BaseType::BOOL | BaseType::CHAR | BaseType::OTHER(_) => None,
+        }
+    }
+
+    /// Decodes every scalar back into a `FieldValue`, in order.
+    pub fn iter(&self) -> impl Iterator<Item = FieldValue> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+/// Returns the type and value bits of a scalar, or `None` for any other value.
+#[allow(clippy::cast_sign_loss)]
+fn scalar_bits(value: &FieldValue) -> Option<(BaseType, u64)> {
+    Some(match *value {
+        FieldValue::ScalarU8(v) => (BaseType::UINT8, u64::from(v)),
+        FieldValue::ScalarU16(v) => (BaseType::UINT16, u64::from(v)),
+        FieldValue::ScalarU32(v) => (BaseType::UINT32, u64::from(v)),
+        FieldValue::ScalarU64(v) => (BaseType::UINT64, v),
+        FieldValue::ScalarI8(v) => (BaseType::INT8, i64::from(v) as u64),
+        FieldValue::ScalarI16(v) => (BaseType::INT16, i64::from(v) as u64),
+        FieldValue::ScalarI32(v) => (BaseType::INT32, i64::from(v) as u64),
+        FieldValue::ScalarI64(v) => (BaseType::INT64, v as u64),
+        FieldValue::ScalarF32(v) => (BaseType::FLOAT, u64::from(v.to_bits())),
+        FieldValue::ScalarF64(v) => (BaseType::DOUBLE, v.to_bits()),
+        FieldValue::ScalarBool(v) => (BaseType::BOOL, u64::from(v)),
+        FieldValue::ScalarChar(v) => (BaseType::CHAR, u64::from(u32::from(v))),
+        _ => return None,
+    })
+}
@@ -0,0 +1,169 @@
+//! Export of the records of a log into a SQLite database, for ad-hoc SQL queries.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, Transaction};
+
+use crate::errors::ULogError;
+use crate::model::inst::{self, FieldValue};
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// The number of rows inserted per transaction.
+const BATCH_SIZE: usize = 10_000;
+
+impl From<rusqlite::Error> for ULogError {
+    fn from(err: rusqlite::Error) -> Self {
+        ULogError::DatabaseError(err.to_string())
+    }
+}
+
+/// Reads a log into a SQLite database at `path`, with one table per subscription.
+///
+/// Tables are named as in [`ULogParser::bytes_per_subscription`], e.g. `actuator_outputs.01`.
+/// Each has a `timestamp` column followed by one column per field, named with `/` separated paths
+/// for nested formats and suffixed with the element index for arrays, e.g. `accel/x` or
+/// `output.03`.  `char` arrays are stored as a single `TEXT` column.  Integers and bools have
+/// `INTEGER` affinity, floats `REAL`, and `uint64_t` values above `i64::MAX` are stored as reals.
+///
+/// The database is created if needed, and must not already contain tables with the same names.
+/// Rows are inserted in batches, each within a transaction.
+pub fn export_sqlite<R: Read>(
+    mut parser: ULogParser<R>,
+    path: impl AsRef<Path>,
+) -> Result<(), ULogError> {
+    let mut conn = Connection::open(path)?;
+
+    // The insert statement of each subscription, keyed by msg_id.
+    let mut inserts: HashMap<u16, String> = HashMap::new();
+    let mut tx = conn.transaction()?;
+    let mut rows_in_batch = 0;
+
+    while let Some(msg_res) = parser.next() {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+
+        let mut columns = vec![("timestamp".to_string(), timestamp_value(data.timestamp))];
+        for field in &data.data.fields {
+            // The timestamp is already the first column, if it was kept in the fields.
+            if field.name != "timestamp" {
+                push_columns(field.name.clone(), &field.value, &mut columns);
+            }
+        }
+
+        let insert = match inserts.get(&data.msg_id) {
+            Some(insert) => insert,
+            None => {
+                let sub = parser.get_subscription(data.msg_id)?;
+                let table = parser.subscription_display_name(&sub);
+                let insert = create_table(&tx, &table, &columns)?;
+                inserts.entry(data.msg_id).or_insert(insert)
+            }
+        };
+
+        tx.prepare_cached(insert)?.execute(params_from_iter(
+            columns.into_iter().map(|(_, value)| value),
+        ))?;
+
+        rows_in_batch += 1;
+        if rows_in_batch == BATCH_SIZE {
+            tx.commit()?;
+            tx = conn.transaction()?;
+            rows_in_batch = 0;
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Creates the table of a subscription, returning the statement which inserts a row into it.
+fn create_table(
+    tx: &Transaction,
+    table: &str,
+    columns: &[(String, Value)],
+) -> Result<String, ULogError> {
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|(name, value)| format!("{} {}", quote(name), affinity(value)))
+        .collect();
+
+    tx.execute(
+        &format!("CREATE TABLE {} ({})", quote(table), definitions.join(", ")),
+        [],
+    )?;
+
+    let names: Vec<String> = columns.iter().map(|(name, _)| quote(name)).collect();
+    let placeholders = vec!["?"; columns.len()].join(", ");
+
+    Ok(format!(
+        "INSERT INTO {} ({}) VALUES ({placeholders})",
+        quote(table),
+        names.join(", ")
+    ))
+}
+
+/// Flattens a field into columns, recursing into nested formats and arrays.
+fn push_columns(path: String, value: &FieldValue, columns: &mut Vec<(String, Value)>) {
+    match value {
+        FieldValue::ArrayChar(chars) => {
+            let text: String = chars.iter().collect();
+            columns.push((path, Value::Text(text.trim_end_matches('\0').to_string())));
+        }
+        FieldValue::ScalarOther(nested) => push_format_columns(&path, nested, columns),
+        FieldValue::ArrayOther(elements) => {
+            for (index, nested) in elements.iter().enumerate() {
+                push_format_columns(&format!("{path}.{index:02}"), nested, columns);
+            }
+        }
+        _ => match value.to_scalars() {
+            Some(elements) => {
+                for (index, element) in elements.iter().enumerate() {
+                    push_columns(format!("{path}.{index:02}"), element, columns);
+                }
+            }
+            None => columns.push((path, sql_value(value))),
+        },
+    }
+}
+
+fn push_format_columns(path: &str, format: &inst::Format, columns: &mut Vec<(String, Value)>) {
+    for field in &format.fields {
+        push_columns(format!("{path}/{}", field.name), &field.value, columns);
+    }
+}
+
+fn sql_value(value: &FieldValue) -> Value {
+    match value {
+        FieldValue::ScalarBool(v) => Value::Integer(i64::from(*v)),
+        FieldValue::ScalarChar(v) => Value::Text(v.to_string()),
+        FieldValue::Opaque(bytes) => Value::Blob(bytes.clone()),
+        _ => value
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| value.as_f64().map(Value::Real))
+            .unwrap_or(Value::Null),
+    }
+}
+
+fn timestamp_value(timestamp: u64) -> Value {
+    sql_value(&FieldValue::ScalarU64(timestamp))
+}
+
+fn affinity(value: &Value) -> &'static str {
+    match value {
+        Value::Integer(_) => "INTEGER",
+        Value::Real(_) => "REAL",
+        Value::Text(_) => "TEXT",
+        Value::Blob(_) | Value::Null => "BLOB",
+    }
+}
+
+/// Quotes an SQL identifier, which may contain characters such as `/` and `.`.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
@@ -0,0 +1,73 @@
+//! A `futures::Stream` of the messages of a log, for use with `StreamExt` combinators.
+//!
+//! Parsing is blocking, so [`ULogParser::into_stream`] moves the parser to a background thread,
+//! which parses ahead of the consumer and hands the messages over through a bounded channel.  The
+//! stream yields exactly the messages of synchronous iteration, in the same order.
+//!
+//! With the `tokio` feature, a stream created within a tokio runtime is parsed on the runtime's
+//! blocking thread pool, so many streams share its threads.  Otherwise each stream spawns a
+//! dedicated thread, which ends with the stream.
+
+use std::io::Read;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{SinkExt, Stream};
+
+use crate::errors::ULogError;
+use crate::model::msg::UlogMessage;
+use crate::parser::ULogParser;
+
+/// The most messages parsed ahead of the consumer of the stream.
+const STREAM_BUFFER_SIZE: usize = 1024;
+
+/// The messages of a parser moved to a background thread, returned by
+/// [`ULogParser::into_stream`].
+///
+/// Dropping the stream stops the parsing thread after the message it is parsing.
+pub struct ULogStream {
+    receiver: mpsc::Receiver<Result<UlogMessage, ULogError>>,
+}
+
+impl Stream for ULogStream {
+    type Item = Result<UlogMessage, ULogError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl<R: Read + Send + 'static> ULogParser<R> {
+    /// Turns the parser into a `Stream` of its messages, parsed on a background thread.
+    ///
+    /// The thread is taken from the blocking pool of the current tokio runtime, if the `tokio`
+    /// feature is enabled and there is one, and is otherwise spawned for the stream.  It parses
+    /// at most a bounded number of messages ahead of the consumer, so memory stays bounded for a
+    /// slow consumer.  The stream ends after the last message, or after the first error which
+    /// ends iteration.
+    pub fn into_stream(self) -> ULogStream {
+        let (mut sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+
+        let parse = move || {
+            for msg_res in self {
+                // The stream was dropped, so there is no one left to parse for.
+                if block_on(sender.send(msg_res)).is_err() {
+                    break;
+                }
+            }
+        };
+
+        #[cfg(feature = "tokio")]
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn_blocking(parse);
+            return ULogStream { receiver };
+        }
+
+        thread::spawn(parse);
+
+        ULogStream { receiver }
+    }
+}
@@ -5,7 +5,7 @@ use crate::errors::ULogError;
 
 static TOKEN_REGEXP: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
-        r"(?P<identifier>[a-zA-Z_][a-zA-Z0-9_]*)|(?P<number>[0-9]+)|(?P<colon>:)|(?P<semicolon>;)|(?P<lbrace>\[)|(?P<rbrace>\])|(?P<whitespace>\s+)|(?P<unknown>.)"
+        r"(?P<identifier>[a-zA-Z_][a-zA-Z0-9_]*)|(?P<number>[0-9]+)|(?P<colon>:)|(?P<semicolon>;)|(?P<lbrace>\[)|(?P<rbrace>\])|(?P<whitespace>[\s\x00]+)|(?P<unknown>.)"
     )
     .unwrap()
 });
@@ -65,7 +65,8 @@ pub enum Token<'a> {
     LBrace,
     RBrace,
     Unknown(char),
-    // Whitespace is skipped by the tokenizer, not represented as a token.
+    // Whitespace, including the NUL padding of C strings, is skipped by the tokenizer, not
+    // represented as a token.
 }
 
 pub fn tokenize(input: &str) -> VecDeque<Token<'_>> {
@@ -135,4 +136,20 @@ mod tests {
         let tokens = tokenize(input);
         assert_eq!(expected_tokens, tokens);
     }
+
+    #[test]
+    fn test_tokenize_nul_padding() {
+        let input = "message1:\tint field0;\0\0";
+        let expected_tokens: VecDeque<Token> = [
+            Token::Identifier("message1"),
+            Token::Colon,
+            Token::Identifier("int"),
+            Token::Identifier("field0"),
+            Token::Semicolon,
+        ]
+        .into();
+
+        let tokens = tokenize(input);
+        assert_eq!(expected_tokens, tokens);
+    }
 }
@@ -0,0 +1,37 @@
+//! Recoverable issues met while parsing, collected when `ULogParserBuilder::collect_warnings()`
+//! is enabled.
+//!
+//! The parser tolerates many defects of a log, such as corrupt bytes or a message cut off by an
+//! appended section, and otherwise only reports them through the `log` crate.  Collected
+//! [`ParseWarning`]s let a tool show them to its users alongside the parsed data.
+
+use std::fmt;
+
+/// The kind of issue a [`ParseWarning`] reports.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WarningKind {
+    /// Corrupt bytes skipped by `resync_on_error()` or `recover_on_error()`.
+    SkippedBytes,
+    /// A subscription reusing the msg_id of an earlier one, which it replaces.
+    DuplicateSubscription,
+    /// A partially written message before an appended section, which is dropped.
+    TruncatedMessage,
+    /// A message of an unknown type, yielded as `UlogMessage::Unhandled`.
+    UnknownMessageType,
+    /// A record with an implausible timestamp, dropped by `BadTimestampPolicy::Skip`.
+    ImplausibleTimestamp,
+}
+
+/// A recoverable issue, with the byte offset in the file of the message concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    pub offset: u64,
+    pub kind: WarningKind,
+    pub description: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at offset {})", self.description, self.offset)
+    }
+}
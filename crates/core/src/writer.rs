@@ -0,0 +1,474 @@
+//! Serialization of a message stream back into a ULOG file, e.g. after dropping or rewriting
+//! some of its subscriptions.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+
+use crate::builder::ULogParserBuilder;
+use crate::encode::Encode;
+use crate::errors::ULogError;
+use crate::layout::{
+    decode_timestamp, field_range, format_size, payload_size, trailing_padding_count,
+};
+use crate::model::msg::{FileHeader, FlagBits, LoggedData, UlogMessage};
+use crate::model::{def, inst};
+use crate::parser::{BadTimestampPolicy, SubscriptionFilter, ULogMessageType, ULogParser};
+
+/// The largest message contents that fit the `u16` size of the message header.
+const MAX_MESSAGE_SIZE: usize = u16::MAX as usize;
+
+/// Writes messages to a ULOG file, checking that the result can be read back.
+///
+/// Messages are encoded exactly as by [`crate::encode::Encode`], so a stream parsed with
+/// `include_header(true)`, `include_timestamp(true)` and `include_padding(true)` is written back
+/// byte for byte.  Records parsed without their `timestamp` or padding fields have them put back,
+/// from `LoggedData::timestamp` and as zeros respectively, and every record is checked against
+/// the size of its format.  Messages which the parser left undecoded, such as `Ignored` records
+/// of subscriptions outside the allow list, are written as their raw contents.
+///
+/// The writer checks the structure of the file as it goes: the header comes first, and every
+/// subscription refers to a defined format and every record to a subscription.  This catches
+/// messages dropped out of order, e.g. a subscription removed while its records are kept.
+///
+/// ⚠️ Appended data offsets in the `FlagBits` message are written unchanged.  If messages are
+/// dropped or rewritten, they no longer point at the appended data, so such logs should be parsed
+/// without `read_appended_data()`.
+pub struct ULogWriter<W: Write> {
+    writer: W,
+    header: Option<FileHeader>,
+    backfill_header_timestamp: bool,
+    // The messages written while the header waits for its timestamp to be backfilled.
+    held: Option<Vec<u8>>,
+    messages_written: u64,
+    formats: HashMap<String, def::Format>,
+    // The message name of each subscription, by msg_id.
+    subscriptions: HashMap<u16, String>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> ULogWriter<W> {
+    pub fn new(writer: W) -> Self {
+        ULogWriter {
+            writer,
+            header: None,
+            backfill_header_timestamp: false,
+            held: None,
+            messages_written: 0,
+            formats: HashMap::new(),
+            subscriptions: HashMap::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Replaces a zero header timestamp with the timestamp of the first data record, so that
+    /// tools which only read the header show when the log started.
+    ///
+    /// The header and the definitions are held in memory until the first record is written.  If
+    /// the log has no records, the header is written unchanged by [`ULogWriter::finish`].
+    #[must_use]
+    pub fn backfill_header_timestamp(mut self, backfill: bool) -> Self {
+        self.backfill_header_timestamp = backfill;
+        self
+    }
+
+    /// Writes the file header, which must be the first thing written.
+    pub fn write_header(&mut self, header: &FileHeader) -> Result<(), ULogError> {
+        if self.header.is_some() {
+            return Err(ULogError::InvalidMessageOrder(
+                "the header has already been written".to_string(),
+            ));
+        }
+
+        if self.backfill_header_timestamp && header.timestamp == 0 {
+            self.held = Some(Vec::new());
+        } else {
+            self.writer.write_all(&header.to_bytes())?;
+        }
+        self.header = Some(*header);
+
+        Ok(())
+    }
+
+    /// Writes the raw bytes of a file header and definitions section, such as those returned by
+    /// `ULogParser::raw_header()`, in place of [`ULogWriter::write_header`].
+    ///
+    /// The bytes are written unchanged, so the header of the output is identical to that of the
+    /// original file.  They are parsed first, to check them and to learn the formats they define,
+    /// so `bytes` must hold complete messages.  Header timestamps are not backfilled.
+    pub fn write_raw_header(&mut self, bytes: &[u8]) -> Result<(), ULogError> {
+        if self.header.is_some() {
+            return Err(ULogError::InvalidMessageOrder(
+                "the header has already been written".to_string(),
+            ));
+        }
+
+        let mut header = None;
+        let mut messages = 0;
+        for msg_res in ULogParserBuilder::new(bytes).include_header(true).build()? {
+            match msg_res? {
+                UlogMessage::Header(file_header) => header = Some(file_header),
+                msg => {
+                    self.check_definitions(&msg)?;
+                    messages += 1;
+                }
+            }
+        }
+
+        let Some(header) = header else {
+            return Err(ULogError::InvalidMessageOrder(
+                "the raw header has no file header".to_string(),
+            ));
+        };
+
+        self.writer.write_all(bytes)?;
+        self.header = Some(header);
+        self.messages_written += messages;
+
+        Ok(())
+    }
+
+    /// Writes a single message.
+    ///
+    /// A `Header` message is passed to [`ULogWriter::write_header`].  For files of version 1 or
+    /// later, an empty `FlagBits` message is written first if the stream does not start with one,
+    /// since readers expect it directly after the header.
+    pub fn write_message(&mut self, message: &UlogMessage) -> Result<(), ULogError> {
+        let version = match (&self.header, message) {
+            (_, UlogMessage::Header(header)) => return self.write_header(header),
+            // Markers only, which have no representation in the file.
+            (_, UlogMessage::AppendedSectionStart { .. } | UlogMessage::Corrupt { .. }) => {
+                return Ok(())
+            }
+            (None, _) => {
+                return Err(ULogError::InvalidMessageOrder(
+                    "a message was written before the header".to_string(),
+                ))
+            }
+            (Some(header), _) => header.version,
+        };
+
+        if self.messages_written == 0
+            && version >= 1
+            && !matches!(message, UlogMessage::FlagBits(_))
+        {
+            self.write_checked(&UlogMessage::FlagBits(FlagBits {
+                compat_flags: [0; 8],
+                incompat_flags: [0; 8],
+                appended_data_offsets: [0; 3],
+            }))?;
+        }
+
+        self.check_definitions(message)?;
+        if self.held.is_some() {
+            let timestamp = data_timestamp(message, &self.formats, &HashMap::new(), |msg_id| {
+                self.subscriptions.get(&msg_id).map(String::as_str)
+            });
+            if let Some(timestamp) = timestamp {
+                self.release_header(timestamp)?;
+            }
+        }
+        self.write_checked(message)
+    }
+
+    /// Flushes the writer and returns it.
+    pub fn finish(mut self) -> Result<W, ULogError> {
+        let Some(header) = self.header else {
+            return Err(ULogError::InvalidMessageOrder(
+                "no header was written".to_string(),
+            ));
+        };
+
+        if self.held.is_some() {
+            self.release_header(header.timestamp)?;
+        }
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Writes the held header with the given timestamp, followed by the held messages.
+    fn release_header(&mut self, timestamp: u64) -> Result<(), ULogError> {
+        let (Some(header), Some(held)) = (&mut self.header, self.held.take()) else {
+            return Ok(());
+        };
+
+        header.timestamp = timestamp;
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.write_all(&held)?;
+
+        Ok(())
+    }
+
+    /// Records the formats and subscriptions defined by `message`, and checks those it refers to.
+    fn check_definitions(&mut self, message: &UlogMessage) -> Result<(), ULogError> {
+        match message {
+            UlogMessage::FormatDefinition(format) => {
+                self.formats.insert(format.name.clone(), format.clone());
+            }
+            UlogMessage::AddSubscription(sub) => {
+                if !self.formats.contains_key(&sub.message_name) {
+                    return Err(ULogError::UndefinedFormat(sub.message_name.clone()));
+                }
+                self.subscriptions
+                    .insert(sub.msg_id, sub.message_name.clone());
+            }
+            UlogMessage::LoggedData(data) => self.check_subscribed(data.msg_id)?,
+            UlogMessage::Ignored {
+                msg_type,
+                message_contents,
+            } if matches!(ULogMessageType::from(*msg_type), ULogMessageType::DATA) => {
+                let [low, high, ..] = message_contents[..] else {
+                    return Err(ULogError::InvalidMessageOrder(
+                        "a DATA message without a msg_id".to_string(),
+                    ));
+                };
+                self.check_subscribed(u16::from_le_bytes([low, high]))?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn check_subscribed(&self, msg_id: u16) -> Result<(), ULogError> {
+        if self.subscriptions.contains_key(&msg_id) {
+            Ok(())
+        } else {
+            Err(ULogError::UndefinedSubscription(msg_id))
+        }
+    }
+
+    /// Writes a message with its size and type prefix, checking that its size fits the prefix.
+    fn write_checked(&mut self, message: &UlogMessage) -> Result<(), ULogError> {
+        self.buf.clear();
+        match message {
+            UlogMessage::LoggedData(data) => self.encode_record(data)?,
+            _ => message.encode_content(&mut self.buf)?,
+        }
+
+        if self.buf.len() > MAX_MESSAGE_SIZE {
+            return Err(ULogError::MessageTooLarge(self.buf.len()));
+        }
+
+        let out: &mut dyn Write = match &mut self.held {
+            Some(held) => held,
+            None => &mut self.writer,
+        };
+        out.write_all(&(self.buf.len() as u16).to_le_bytes())?;
+        out.write_all(&[u8::from(message.message_type())])?;
+        out.write_all(&self.buf)?;
+        self.messages_written += 1;
+
+        Ok(())
+    }
+
+    /// Encodes a record into `buf`, putting back the `timestamp` and padding fields left out by
+    /// the parser, and checks its size against that of its format.
+    fn encode_record(&mut self, data: &LoggedData) -> Result<(), ULogError> {
+        self.buf.extend_from_slice(&data.msg_id.to_le_bytes());
+        encode_fields(&data.data, Some(data.timestamp), &mut self.buf)?;
+
+        // Formats with types unknown to the writer, such as opaque types, cannot be sized.
+        let Some(format) = self
+            .subscriptions
+            .get(&data.msg_id)
+            .and_then(|name| self.formats.get(name))
+        else {
+            return Ok(());
+        };
+        let (Ok(size), Ok(payload_size)) = (
+            format_size(format, &self.formats, &HashMap::new()),
+            payload_size(format, &self.formats, &HashMap::new()),
+        ) else {
+            return Ok(());
+        };
+
+        let encoded_size = self.buf.len() - 2;
+        if encoded_size != size && encoded_size != payload_size {
+            return Err(ULogError::LayoutMismatch(format!(
+                "{}: record of {encoded_size} bytes, expected {payload_size}",
+                format.name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Encodes the fields of a decoded format in the order of its definition, writing zeros for
+/// padding fields which are not present and `timestamp`, if given, for a missing `timestamp`
+/// field.  Trailing padding of a top-level format is not written, as in the log.
+fn encode_fields(
+    format: &inst::Format,
+    timestamp: Option<u64>,
+    out: &mut Vec<u8>,
+) -> Result<(), ULogError> {
+    let definitions = &format.def_format.fields;
+    let trailing_padding = match timestamp {
+        Some(_) => trailing_padding_count(&format.def_format),
+        None => 0,
+    };
+    let mut fields = format.fields.iter().peekable();
+
+    for (index, definition) in definitions.iter().enumerate() {
+        match fields.next_if(|field| field.name == definition.name) {
+            Some(field) => match &field.value {
+                inst::FieldValue::ScalarOther(nested) => encode_fields(nested, None, out)?,
+                inst::FieldValue::ArrayOther(nested) => {
+                    for element in nested {
+                        encode_fields(element, None, out)?;
+                    }
+                }
+                value => value.encode(out)?,
+            },
+            None if definition.name == "timestamp" && timestamp.is_some() => {
+                // Unwrap is safe: checked by the guard.
+                let timestamp = timestamp.unwrap();
+                match definition.r#type.base_type {
+                    def::BaseType::UINT32 => {
+                        out.extend_from_slice(&(timestamp as u32).to_le_bytes())
+                    }
+                    _ => out.extend_from_slice(&timestamp.to_le_bytes()),
+                }
+            }
+            None if definition.name.starts_with("_padding") => {
+                // Scalar padding is not read by the parser, so is not written either.
+                if index < definitions.len() - trailing_padding {
+                    let size = definition.r#type.base_type.primitive_size().unwrap_or(1);
+                    let len = size * definition.r#type.array_size.unwrap_or(0);
+                    out.resize(out.len() + len, 0);
+                }
+            }
+            None => {
+                return Err(ULogError::LayoutMismatch(format!(
+                    "{}: the record has no field {}",
+                    format.name, definition.name
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the timestamp of a record, decoded or not, or `None` for other messages.
+///
+/// The timestamp of an `Ignored` record is read at the offset of the `timestamp` field of its
+/// format, which `message_name` looks up by msg_id, as a `uint64_t` or a legacy `uint32_t`.
+pub(crate) fn data_timestamp<'a>(
+    message: &UlogMessage,
+    formats: &HashMap<String, def::Format>,
+    opaque_sizes: &HashMap<String, usize>,
+    message_name: impl FnOnce(u16) -> Option<&'a str>,
+) -> Option<u64> {
+    match message {
+        UlogMessage::LoggedData(data) => Some(data.timestamp),
+        UlogMessage::Ignored {
+            msg_type,
+            message_contents,
+        } if matches!(ULogMessageType::from(*msg_type), ULogMessageType::DATA) => {
+            let msg_id =
+                u16::from_le_bytes([*message_contents.first()?, *message_contents.get(1)?]);
+            let format = formats.get(message_name(msg_id)?)?;
+            // The fields follow the msg_id.
+            let range = field_range(format, "timestamp", formats, opaque_sizes).ok()??;
+            decode_timestamp(message_contents.get(range.start + 2..range.end + 2)?)
+        }
+        _ => None,
+    }
+}
+
+/// Copies the log read by `parser` to `writer`, without the fields in `fields_to_remove`, and
+/// returns the inner writer.
+///
+/// Fields are named `format.field`, e.g. `vehicle_status.nav_state`, and may belong to a nested
+/// format.  The formats are rewritten without the fields, and every record is re-encoded without
+/// them, so the output is a smaller valid ULOG file.  Unknown fields are ignored.  The
+/// `timestamp` field cannot be removed, since records are ordered by it.
+///
+/// The parser is configured to decode every subscription, with its header, timestamps and
+/// padding, overriding the options it was built with.  Options which would alter or drop the
+/// copied records are reset to their defaults: `filter_time()`, `transform()`,
+/// `skip_arrays_larger_than()`, `split_name_instance()`, `add_index_field()`,
+/// `fold_resubscriptions()`, `on_bad_timestamp()`, `max_records()`, `recover_on_error()`,
+/// `error_on_dropout()`, `require_sorted()` and `read_appended_data()`.  Appended data is thus
+/// not copied, while as for [`ULogWriter`], appended data offsets are written unchanged.
+pub fn strip_fields<R: Read, W: Write>(
+    mut parser: ULogParser<R>,
+    mut writer: ULogWriter<W>,
+    fields_to_remove: &[&str],
+) -> Result<W, ULogError> {
+    let mut removed: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for field in fields_to_remove {
+        let Some((format, name)) = field.split_once('.') else {
+            return Err(ULogError::InvalidConfiguration(format!(
+                "field '{field}' is not of the form 'format.field'"
+            )));
+        };
+        if name == "timestamp" {
+            return Err(ULogError::InvalidConfiguration(format!(
+                "the timestamp field '{field}' cannot be removed"
+            )));
+        }
+        removed.entry(format).or_default().insert(name);
+    }
+
+    parser.include_header = true;
+    parser.include_timestamp = true;
+    parser.include_padding = true;
+    parser.subscription_filter = SubscriptionFilter::default();
+    // Options which change the decoded records or drop some of them.
+    parser.time_filter = None;
+    parser.transforms.clear();
+    parser.max_array_size = None;
+    parser.name_instance_pattern = None;
+    parser.add_index_field = false;
+    parser.fold_resubscriptions = false;
+    parser.bad_timestamp_policy = BadTimestampPolicy::default();
+    parser.max_records = None;
+    parser.recover_on_error = false;
+    parser.error_on_dropout = false;
+    parser.require_sorted = false;
+    parser.read_appended_data = false;
+
+    for msg_res in parser {
+        let mut msg = msg_res?;
+        match &mut msg {
+            UlogMessage::FormatDefinition(format) => strip_def_format(format, &removed),
+            UlogMessage::LoggedData(data) => strip_inst_format(&mut data.data, &removed),
+            _ => {}
+        }
+        writer.write_message(&msg)?;
+    }
+
+    writer.finish()
+}
+
+fn strip_def_format(format: &mut def::Format, removed: &HashMap<&str, HashSet<&str>>) {
+    if let Some(names) = removed.get(format.name.as_str()) {
+        format
+            .fields
+            .retain(|field| !names.contains(field.name.as_str()));
+    }
+}
+
+fn strip_inst_format(format: &mut inst::Format, removed: &HashMap<&str, HashSet<&str>>) {
+    if let Some(names) = removed.get(format.name.as_str()) {
+        format
+            .fields
+            .retain(|field| !names.contains(field.name.as_str()));
+    }
+    strip_def_format(&mut format.def_format, removed);
+
+    for field in &mut format.fields {
+        match &mut field.value {
+            inst::FieldValue::ScalarOther(nested) => strip_inst_format(nested, removed),
+            inst::FieldValue::ArrayOther(nested) => {
+                for element in nested {
+                    strip_inst_format(element, removed);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "ndarray")]
+
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use ndarray::{array, s};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_actuator_outputs_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read("../../tests/fixtures/test_data/input/sample_log_small.ulg")?;
+
+    let (timestamps, outputs) = ULogParserBuilder::new(input.as_slice())
+        .build()?
+        .actuator_outputs()?;
+
+    let records = ULogParserBuilder::new(input.as_slice())
+        .build()?
+        .coverage()?["actuator_outputs.00"]
+        .count;
+    // The first instance drives 8 of its 16 channels.
+    assert_eq!(outputs.dim(), (records as usize, 8));
+    assert_eq!(timestamps.len(), outputs.nrows());
+    assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    assert_eq!(timestamps[0], 20_305_329);
+    assert_eq!(
+        outputs.row(0),
+        array![1596.0, 1491.0, 950.0, 1508.0, 1031.0, 1002.0, 1000.0, 1023.0]
+    );
+    assert_eq!(outputs[[1, 7]], 1025.0);
+
+    Ok(())
+}
+
+#[test]
+fn test_actuator_outputs_without_count() -> Result<(), ULogError> {
+    let record = |timestamp: u64, output: [f32; 3]| {
+        let bytes: Vec<u8> = output.iter().flat_map(|v| v.to_le_bytes()).collect();
+        with_timestamp(timestamp, &bytes)
+    };
+
+    let (timestamps, outputs) = SyntheticLog::new(0)
+        .format("actuator_outputs:uint64_t timestamp;float[3] output;")
+        .subscription(0, 0, "actuator_outputs")
+        .data(0, &record(100, [1.0, 2.0, 3.0]))
+        .data(0, &record(200, [4.0, 5.0, 6.0]))
+        .builder()
+        .build()?
+        .actuator_outputs()?;
+
+    assert_eq!(timestamps, [100, 200]);
+    assert_eq!(outputs.dim(), (2, 3));
+    assert_eq!(outputs.slice(s![.., 2]), array![3.0, 6.0]);
+
+    Ok(())
+}
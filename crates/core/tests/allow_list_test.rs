@@ -0,0 +1,162 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_allow_list_filters_by_msg_id() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .format("baro:uint64_t timestamp;float pressure;")
+        .subscription(0, 3, "gyro")
+        .subscription(1, u16::MAX, "gyro")
+        .subscription(0, 0, "baro")
+        .data(3, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .data(0, &with_timestamp(110, &2.0f32.to_le_bytes()))
+        .data(u16::MAX, &with_timestamp(120, &3.0f32.to_le_bytes()))
+        // A subscription added after the data section has started, with a msg_id beyond any
+        // allowed one.
+        .subscription(0, 1000, "baro")
+        .data(1000, &with_timestamp(140, &5.0f32.to_le_bytes()))
+        .builder()
+        .set_subscription_allow_list(["gyro"])
+        .build()?;
+
+    let mut decoded = Vec::new();
+    let mut ignored = 0;
+    for msg_res in parser {
+        match msg_res? {
+            UlogMessage::LoggedData(data) => decoded.push((data.msg_id, data.timestamp)),
+            UlogMessage::Ignored { .. } => ignored += 1,
+            _ => {}
+        }
+    }
+
+    assert_eq!(decoded, vec![(3, 100), (u16::MAX, 120)]);
+    assert_eq!(ignored, 2);
+
+    Ok(())
+}
+
+/// Returns the names of the decoded records, and the number of ignored records.
+fn decoded_names(
+    builder: ULogParserBuilder<Cursor<Vec<u8>>>,
+) -> Result<(Vec<String>, usize), ULogError> {
+    let mut decoded = Vec::new();
+    let mut ignored = 0;
+    for msg_res in builder.build()? {
+        match msg_res? {
+            UlogMessage::LoggedData(data) => decoded.push(data.data.name),
+            UlogMessage::Ignored { .. } => ignored += 1,
+            _ => {}
+        }
+    }
+
+    Ok((decoded, ignored))
+}
+
+/// A log with one record of each of several topics, some with instance suffixes.
+fn instance_log() -> SyntheticLog {
+    let names = [
+        "sensor_gyro",
+        "sensor_gyro_00",
+        "sensor_gyro_01",
+        "sensor_accel_00",
+        "vehicle_gps",
+        "vehicle_magnetometer",
+        "vehicle_status",
+    ];
+
+    let mut log = SyntheticLog::new(0);
+    for name in names {
+        log = log.format(&format!("{name}:uint64_t timestamp;"));
+    }
+    for (msg_id, name) in (0u16..).zip(names) {
+        log = log
+            .subscription(0, msg_id, name)
+            .data(msg_id, &with_timestamp(100, &[]));
+    }
+
+    log
+}
+
+#[test]
+fn test_allow_list_glob() -> Result<(), ULogError> {
+    let (decoded, ignored) = decoded_names(
+        instance_log()
+            .builder()
+            .set_subscription_allow_list_glob(["sensor_gyro*", "vehicle_{gps,magnetometer}"]),
+    )?;
+
+    assert_eq!(
+        decoded,
+        [
+            "sensor_gyro",
+            "sensor_gyro_00",
+            "sensor_gyro_01",
+            "vehicle_gps",
+            "vehicle_magnetometer"
+        ]
+    );
+    assert_eq!(ignored, 2);
+
+    let (decoded, _) = decoded_names(
+        instance_log()
+            .builder()
+            .set_subscription_allow_list_glob(["sensor_*_0?"]),
+    )?;
+    assert_eq!(
+        decoded,
+        ["sensor_gyro_00", "sensor_gyro_01", "sensor_accel_00"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_allow_list_glob_with_exact_list() -> Result<(), ULogError> {
+    let (decoded, ignored) = decoded_names(
+        instance_log()
+            .builder()
+            .set_subscription_allow_list(["vehicle_status"])
+            .set_subscription_allow_list_glob(["sensor_accel*"]),
+    )?;
+
+    assert_eq!(decoded, ["sensor_accel_00", "vehicle_status"]);
+    assert_eq!(ignored, 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_allow_list_glob_matching_nothing() -> Result<(), ULogError> {
+    let (decoded, ignored) = decoded_names(
+        instance_log()
+            .builder()
+            .set_subscription_allow_list_glob(["sensor_baro*", "vehicle"]),
+    )?;
+
+    assert!(decoded.is_empty());
+    assert_eq!(ignored, 7);
+
+    Ok(())
+}
+
+#[test]
+fn test_allow_list_glob_unbalanced_braces() {
+    for pattern in ["vehicle_{gps", "vehicle_gps}"] {
+        let result = instance_log()
+            .builder()
+            .set_subscription_allow_list_glob([pattern])
+            .build();
+
+        assert!(
+            matches!(result, Err(ULogError::InvalidConfiguration(_))),
+            "{pattern}"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const DATA_APPENDED: [u8; 8] = [1, 0, 0, 0, 0, 0, 0, 0];
+
+fn data_message(msg_id: u16, timestamp: u64) -> Vec<u8> {
+    let mut payload = msg_id.to_le_bytes().to_vec();
+    payload.extend(with_timestamp(timestamp, &[]));
+
+    let mut bytes = (payload.len() as u16).to_le_bytes().to_vec();
+    bytes.push(b'D');
+    bytes.extend(payload);
+    bytes
+}
+
+/// A log whose data section ends with a partially written message, followed by an appended section.
+fn log_with_appended_section() -> (Vec<u8>, u64) {
+    let build = |offset: u64| {
+        let mut bytes = SyntheticLog::with_flag_bits(0, [0; 8], DATA_APPENDED, [offset, 0, 0])
+            .format("topic:uint64_t timestamp;")
+            .subscription(0, 0, "topic")
+            .data(0, &with_timestamp(100, &[]))
+            .into_bytes();
+        bytes.extend_from_slice(&data_message(0, 200)[..5]);
+        bytes
+    };
+
+    // The flag bits have a fixed size, so the offset can be found with a placeholder.
+    let offset = build(0).len() as u64;
+    let mut bytes = build(offset);
+    bytes.extend(data_message(0, 300));
+    bytes.extend(data_message(0, 400));
+
+    (bytes, offset)
+}
+
+fn parse_events(bytes: Vec<u8>, read_appended_data: bool) -> Result<Vec<String>, ULogError> {
+    let parser = ULogParserBuilder::new(Cursor::new(bytes))
+        .read_appended_data(read_appended_data)
+        .build()?;
+
+    let mut events = Vec::new();
+    for msg_res in parser {
+        match msg_res? {
+            UlogMessage::LoggedData(data) => events.push(format!("data {}", data.timestamp)),
+            UlogMessage::AppendedSectionStart { offset } => {
+                events.push(format!("section {offset}"))
+            }
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+#[test]
+fn test_read_appended_data() -> Result<(), ULogError> {
+    let (bytes, offset) = log_with_appended_section();
+
+    assert_eq!(
+        parse_events(bytes, true)?,
+        vec![
+            "data 100".to_string(),
+            format!("section {offset}"),
+            "data 300".to_string(),
+            "data 400".to_string(),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_appended_data_ignored_by_default() -> Result<(), ULogError> {
+    let (bytes, _) = log_with_appended_section();
+
+    assert_eq!(parse_events(bytes, false)?, vec!["data 100".to_string()]);
+
+    Ok(())
+}
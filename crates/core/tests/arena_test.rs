@@ -0,0 +1,212 @@
+#![cfg(feature = "arena")]
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::BufReader;
+use std::ptr::NonNull;
+
+use allocator_api2::alloc::{AllocError, Allocator};
+use bumpalo::Bump;
+use yule_log::arena::{ArenaField, ArenaValue, RecordArena};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::{Field, FieldValue};
+use yule_log::model::msg::UlogMessage;
+
+const SAMPLE_LOG: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn builder() -> Result<ULogParserBuilder<BufReader<File>>, ULogError> {
+    Ok(ULogParserBuilder::new(BufReader::new(File::open(
+        SAMPLE_LOG,
+    )?)))
+}
+
+/// Compares fields by their rendered values, recursing into nested formats.
+fn assert_same_fields(fields: &[ArenaField<'_>], heap_fields: &[Field]) {
+    assert_eq!(fields.len(), heap_fields.len());
+
+    for (field, heap_field) in fields.iter().zip(heap_fields) {
+        assert_eq!(field.name, heap_field.name);
+
+        match (&field.value, &heap_field.value) {
+            (ArenaValue::ScalarOther(nested), FieldValue::ScalarOther(heap_nested)) => {
+                assert_same_fields(nested, &heap_nested.fields);
+            }
+            (ArenaValue::ArrayOther(elements), FieldValue::ArrayOther(heap_elements)) => {
+                assert_eq!(elements.len(), heap_elements.len());
+                for (nested, heap_nested) in elements.iter().zip(heap_elements) {
+                    assert_same_fields(nested, &heap_nested.fields);
+                }
+            }
+            (value, heap_value) => assert_eq!(format!("{value:?}"), format!("{heap_value:?}")),
+        }
+    }
+}
+
+#[test]
+fn test_arena_records_match_heap_records() -> Result<(), ULogError> {
+    let heap_records: Vec<_> = builder()?
+        .include_padding(true)
+        .build()?
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(Ok(data)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut matched = 0;
+    let mut batches = 0;
+
+    builder()?
+        .include_padding(true)
+        .arena_batch_size(100)
+        .build()?
+        .for_each_arena_batch(|records| {
+            assert!(records.len() <= 100);
+            for record in records {
+                let heap_record = &heap_records[matched];
+
+                // NaN never compares equal, so fall back to comparing the rendered values.
+                if format!("{heap_record:?}").contains("NaN") {
+                    assert_eq!(record.timestamp, heap_record.timestamp);
+                    assert_same_fields(record.fields, &heap_record.data.fields);
+                } else {
+                    assert_eq!(record, heap_record);
+                }
+                matched += 1;
+            }
+            batches += 1;
+            Ok(())
+        })?;
+
+    assert_eq!(matched, heap_records.len());
+    assert_eq!(batches, heap_records.len().div_ceil(100));
+
+    Ok(())
+}
+
+#[test]
+fn test_arena_honours_allow_list() -> Result<(), ULogError> {
+    let mut names = Vec::new();
+
+    builder()?
+        .set_subscription_allow_list(["vehicle_status"])
+        .include_timestamp(true)
+        .build()?
+        .for_each_arena_batch(|records| {
+            for record in records {
+                names.push(record.name.to_owned());
+                assert_eq!(record.fields[0].name, "timestamp");
+            }
+            Ok(())
+        })?;
+
+    assert!(!names.is_empty());
+    assert!(names.iter().all(|name| name == "vehicle_status"));
+
+    Ok(())
+}
+
+#[test]
+fn test_arena_decodes_into_supplied_arena() -> Result<(), ULogError> {
+    // A preallocated region large enough for a batch is reused, without growing.
+    let mut bump = Bump::with_capacity(1 << 20);
+    let capacity = bump.allocated_bytes();
+    let mut count = 0;
+
+    builder()?
+        .arena_batch_size(64)
+        .build()?
+        .for_each_arena_batch_in(&mut bump, |records| {
+            count += records.len();
+            Ok(())
+        })?;
+
+    assert!(count > 0);
+    assert_eq!(bump.allocated_bytes(), capacity);
+
+    // The records are allocated from the supplied arena, so a small one has to grow.
+    let mut small_bump = Bump::with_capacity(64);
+    let small_capacity = small_bump.allocated_bytes();
+
+    builder()?
+        .arena_batch_size(64)
+        .build()?
+        .for_each_arena_batch_in(&mut small_bump, |_| Ok(()))?;
+
+    assert!(small_bump.allocated_bytes() > small_capacity);
+
+    Ok(())
+}
+
+/// A bump arena which counts the allocations made through it.
+#[derive(Default)]
+struct TrackingArena {
+    bump: Bump,
+    allocations: Cell<usize>,
+    allocated_bytes: Cell<usize>,
+    resets: usize,
+}
+
+#[derive(Clone, Copy)]
+struct TrackingAlloc<'a>(&'a TrackingArena);
+
+unsafe impl Allocator for TrackingAlloc<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let arena = self.0;
+        arena.allocations.set(arena.allocations.get() + 1);
+        arena
+            .allocated_bytes
+            .set(arena.allocated_bytes.get() + layout.size());
+        (&arena.bump).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (&self.0.bump).deallocate(ptr, layout) }
+    }
+}
+
+impl RecordArena for TrackingArena {
+    type Alloc<'a> = TrackingAlloc<'a>;
+
+    fn allocator(&self) -> TrackingAlloc<'_> {
+        TrackingAlloc(self)
+    }
+
+    fn reset(&mut self) {
+        self.bump.reset();
+        self.resets += 1;
+    }
+}
+
+#[test]
+fn test_arena_decodes_through_custom_allocator() -> Result<(), ULogError> {
+    let mut arena = TrackingArena::default();
+    let mut records = 0;
+    let mut fields = 0;
+    let mut batches = 0;
+
+    builder()?
+        .arena_batch_size(64)
+        .build()?
+        .for_each_arena_batch_in(&mut arena, |batch| {
+            records += batch.len();
+            fields += batch
+                .iter()
+                .map(|record| record.fields.len())
+                .sum::<usize>();
+            batches += 1;
+            Ok(())
+        })?;
+
+    assert!(records > 0);
+    // At least the batch, and the field list and name of each record, are allocated through the
+    // arena, which is reset before the first batch and after each batch.
+    assert!(arena.allocations.get() >= batches + 2 * records);
+    assert!(arena.allocated_bytes.get() >= fields * size_of::<ArenaField<'_>>());
+    assert_eq!(arena.resets, batches + 1);
+
+    Ok(())
+}
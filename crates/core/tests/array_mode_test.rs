@@ -0,0 +1,72 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::{self, ArrayMode, FieldValue};
+use yule_log::model::msg::UlogMessage;
+
+fn parse_sample() -> Result<inst::Format, ULogError> {
+    let mut payload = Vec::new();
+    for v in [1.0f32, 2.0, 3.0] {
+        payload.extend_from_slice(&v.to_le_bytes());
+    }
+    payload.extend_from_slice(&[7, 8]);
+    payload.push(9);
+
+    let parser = SyntheticLog::new(0)
+        .format("esc:uint8_t rpm;")
+        .format("sensor:uint64_t timestamp;float[3] gyro;esc[2] esc;uint8_t status;")
+        .subscription(0, 0, "sensor")
+        .data(0, &with_timestamp(100, &payload))
+        .builder()
+        .build()?;
+
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            return Ok(data.data);
+        }
+    }
+    panic!("No LoggedData in log");
+}
+
+#[test]
+fn test_array_mode_expand() -> Result<(), ULogError> {
+    let sample = parse_sample()?;
+
+    assert_eq!(
+        sample.column_names(ArrayMode::Expand),
+        vec![
+            "gyro.00",
+            "gyro.01",
+            "gyro.02",
+            "esc.00/rpm",
+            "esc.01/rpm",
+            "status"
+        ]
+    );
+    assert_eq!(
+        sample.flatten_fields(ArrayMode::Expand)[1],
+        ("gyro.01".to_string(), FieldValue::ScalarF32(2.0))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_array_mode_nested() -> Result<(), ULogError> {
+    let sample = parse_sample()?;
+
+    assert_eq!(
+        sample.column_names(ArrayMode::Nested),
+        vec!["gyro", "esc", "status"]
+    );
+    assert_eq!(
+        sample.flatten_fields(ArrayMode::Nested)[0],
+        (
+            "gyro".to_string(),
+            FieldValue::ArrayF32(vec![1.0, 2.0, 3.0])
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+
+use yule_log::errors::ULogError;
+use yule_log::message_buf::MessageBuf;
+
+#[test]
+fn test_take_array_ref() -> Result<(), ULogError> {
+    let values = [1.5f32, -2.0, 3.25];
+
+    // Read the same array at two offsets, so that at least one of them is misaligned.
+    for offset in 0..2 {
+        let mut bytes = vec![0xAA; offset];
+        for value in values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.push(0x7F);
+
+        let mut buf = MessageBuf::from_vec(bytes);
+        buf.advance(offset)?;
+
+        let aligned = buf.remaining_bytes().as_ptr().align_offset(4) == 0;
+        let array = buf.take_array_ref::<f32>(values.len())?;
+
+        assert_eq!(*array, values);
+        assert_eq!(
+            matches!(array, Cow::Borrowed(_)),
+            aligned && cfg!(target_endian = "little")
+        );
+        assert_eq!(buf.take_u8()?, 0x7F);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_take_array_ref_bytes_are_borrowed() -> Result<(), ULogError> {
+    let mut buf = MessageBuf::from_vec(vec![1, 2, 0xFF, 4]);
+    buf.advance(1)?;
+
+    let array = buf.take_array_ref::<i8>(3)?;
+    assert!(matches!(array, Cow::Borrowed(&[2, -1, 4])));
+
+    Ok(())
+}
+
+#[test]
+fn test_take_array_ref_out_of_bounds() {
+    let mut buf = MessageBuf::from_vec(vec![0; 7]);
+
+    assert!(matches!(
+        buf.take_array_ref::<u64>(1),
+        Err(ULogError::ParseError(_))
+    ));
+    assert!(matches!(
+        buf.take_array_ref::<u64>(usize::MAX),
+        Err(ULogError::ParseError(_))
+    ));
+}
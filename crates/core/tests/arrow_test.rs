@@ -0,0 +1,104 @@
+#![cfg(feature = "arrow")]
+
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{DataType, Float32Type, Int16Type, UInt64Type};
+use common::{with_timestamp, SyntheticLog};
+use yule_log::arrow::to_record_batches;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+#[test]
+fn test_to_record_batches_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let batches = to_record_batches(
+        ULogParserBuilder::new(open_sample_log()?)
+            .include_timestamp(true)
+            .build()?,
+    )?;
+
+    let coverage = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .coverage()?;
+    assert_eq!(batches.len(), coverage.len());
+    for (name, topic) in &coverage {
+        assert_eq!(batches[name].num_rows() as u64, topic.count, "{name}");
+    }
+
+    let batch = &batches["actuator_outputs.01"];
+    let timestamps = batch
+        .column_by_name("timestamp")
+        .unwrap()
+        .as_primitive::<UInt64Type>();
+    assert!(timestamps
+        .values()
+        .windows(2)
+        .all(|pair| pair[0] <= pair[1]));
+
+    let output = batch.column_by_name("output").unwrap();
+    assert!(matches!(
+        output.data_type(),
+        DataType::FixedSizeList(item, 16) if item.data_type() == &DataType::Float32
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_to_record_batches_schema() -> Result<(), ULogError> {
+    let mut record = with_timestamp(1_000, &(-3i16).to_le_bytes());
+    record.extend([1.5f32, 2.5].iter().flat_map(|v| v.to_le_bytes()));
+    record.extend(b"abc\0");
+    record.extend(7i16.to_le_bytes());
+
+    let parser = SyntheticLog::new(0)
+        .format("inner:int16_t value;")
+        .format("outer:uint64_t timestamp;int16_t level;float[2] gains;char[4] label;inner nested;")
+        .subscription(0, 0, "outer")
+        .data(0, &record)
+        .builder()
+        .build()?;
+
+    let batches = to_record_batches(parser)?;
+    let batch = &batches["outer"];
+    assert_eq!(batch.num_rows(), 1);
+
+    // The timestamp is not included by default.
+    let names: Vec<&str> = batch
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect();
+    assert_eq!(names, ["level", "gains", "label", "nested"]);
+
+    let level = batch.column(0).as_primitive::<Int16Type>();
+    assert_eq!(level.value(0), -3);
+
+    let gains = batch.column(1).as_fixed_size_list();
+    assert_eq!(
+        gains.value(0).as_primitive::<Float32Type>().values(),
+        &[1.5, 2.5]
+    );
+
+    assert_eq!(batch.column(2).as_string::<i32>().value(0), "abc");
+
+    let nested = batch.column(3).as_struct();
+    let value = nested
+        .column_by_name("value")
+        .unwrap()
+        .as_primitive::<Int16Type>();
+    assert_eq!(value.value(0), 7);
+    assert_eq!(nested.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,212 @@
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use common::{with_timestamp, SyntheticLog};
+use tokio::io::{AsyncRead, ReadBuf};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const SAMPLE_LOG: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+/// Hands out a few bytes per read, so messages arrive split across many reads.
+struct Trickle {
+    bytes: Vec<u8>,
+    position: usize,
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let n = buf.remaining().min(5).min(self.bytes.len() - self.position);
+        let start = self.position;
+        buf.put_slice(&self.bytes[start..start + n]);
+        self.position += n;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_async_matches_sync() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(SAMPLE_LOG)?;
+
+    let expected = ULogParserBuilder::new(BufReader::new(File::open(SAMPLE_LOG)?))
+        .include_header(true)
+        .include_timestamp(true)
+        .set_subscription_allow_list(["vehicle_status", "actuator_outputs"])
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<Vec<_>, ULogError>>()?;
+
+    let mut parser = ULogParserBuilder::new(Trickle { bytes, position: 0 })
+        .include_header(true)
+        .include_timestamp(true)
+        .set_subscription_allow_list(["vehicle_status", "actuator_outputs"])
+        .build_async()?;
+
+    let mut messages = Vec::new();
+    while let Some(msg_res) = parser.next_message().await {
+        messages.push(format!("{:?}", msg_res?));
+    }
+
+    assert!(messages.len() > 100);
+    assert_eq!(messages, expected);
+    assert_eq!(
+        parser.parser().sys_uuid().as_deref(),
+        Some("000600000000383638393239510d0035002d")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_truncated_log() -> Result<(), Box<dyn std::error::Error>> {
+    let mut bytes = std::fs::read(SAMPLE_LOG)?;
+    bytes.truncate(bytes.len() - 7);
+
+    let expected = ULogParserBuilder::new(bytes.as_slice())
+        .build()?
+        .map(|msg_res| {
+            msg_res
+                .map(|msg| format!("{msg:?}"))
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    let mut parser = ULogParserBuilder::new(bytes.as_slice()).build_async()?;
+
+    let mut messages = Vec::new();
+    while let Some(msg_res) = parser.next_message().await {
+        messages.push(
+            msg_res
+                .map(|msg| format!("{msg:?}"))
+                .map_err(|e| e.to_string()),
+        );
+    }
+
+    // The final message is partially written, and its missing bytes are left unspecified.
+    assert_eq!(messages.len(), expected.len());
+    assert_eq!(
+        messages[..messages.len() - 1],
+        expected[..expected.len() - 1]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_max_records() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(SAMPLE_LOG)?;
+
+    let expected = ULogParserBuilder::new(bytes.as_slice())
+        .max_records(5)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<Vec<_>, ULogError>>()?;
+
+    let mut parser = ULogParserBuilder::new(Trickle { bytes, position: 0 })
+        .max_records(5)
+        .build_async()?;
+
+    let mut messages = Vec::new();
+    let mut records = 0;
+    while let Some(msg_res) = parser.next_message().await {
+        let msg = msg_res?;
+        if matches!(msg, UlogMessage::LoggedData(_)) {
+            records += 1;
+        }
+        messages.push(format!("{msg:?}"));
+    }
+
+    assert_eq!(records, 5);
+    assert_eq!(messages, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_error_on_dropout() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(SAMPLE_LOG)?;
+
+    let mut parser = ULogParserBuilder::new(Trickle { bytes, position: 0 })
+        .error_on_dropout(true)
+        .build_async()?;
+
+    let err = loop {
+        match parser.next_message().await {
+            Some(Ok(msg)) => assert!(!matches!(msg, UlogMessage::DropoutMark(_))),
+            Some(Err(err)) => break err,
+            None => panic!("expected a dropout error"),
+        }
+    };
+
+    assert!(matches!(
+        err,
+        ULogError::DataDropout {
+            timestamp: 20_309_013,
+            duration_ms: 30,
+        }
+    ));
+
+    // Parsing stops at the dropout.
+    assert!(parser.next_message().await.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_require_sorted() -> Result<(), Box<dyn std::error::Error>> {
+    let log = || {
+        SyntheticLog::new(0)
+            .format("first:uint64_t timestamp;")
+            .format("second:uint64_t timestamp;")
+            .subscription(0, 0, "first")
+            .subscription(0, 1, "second")
+            .data(0, &with_timestamp(150, &[]))
+            .data(1, &with_timestamp(120, &[]))
+    };
+
+    let expected = log()
+        .builder()
+        .require_sorted(true)
+        .build()?
+        .map(|msg_res| {
+            msg_res
+                .map(|msg| format!("{msg:?}"))
+                .map_err(|e| format!("{e:?}"))
+        })
+        .collect::<Vec<_>>();
+
+    let mut parser = ULogParserBuilder::new(Trickle {
+        bytes: log().into_bytes(),
+        position: 0,
+    })
+    .require_sorted(true)
+    .build_async()?;
+
+    let mut messages = Vec::new();
+    while let Some(msg_res) = parser.next_message().await {
+        messages.push(
+            msg_res
+                .map(|msg| format!("{msg:?}"))
+                .map_err(|e| format!("{e:?}")),
+        );
+    }
+
+    let Some(Err(err)) = messages.last() else {
+        panic!("expected an out of order error");
+    };
+    assert!(err.starts_with("OutOfOrder { prev: 150, cur: 120"), "{err}");
+    assert_eq!(messages, expected);
+
+    Ok(())
+}
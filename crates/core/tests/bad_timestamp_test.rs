@@ -0,0 +1,94 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::BadTimestampPolicy;
+
+/// A topic with four samples, the third of which has a zeroed (implausible) timestamp.
+fn log_with_bad_timestamp() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("my_topic:uint64_t timestamp;float x;")
+        .subscription(0, 0, "my_topic")
+        .data(0, &with_timestamp(1_000, &1.0f32.to_le_bytes()))
+        .data(0, &with_timestamp(2_000, &2.0f32.to_le_bytes()))
+        .data(0, &with_timestamp(0, &3.0f32.to_le_bytes()))
+        .data(0, &with_timestamp(4_000, &4.0f32.to_le_bytes()))
+}
+
+fn logged_timestamps(policy: BadTimestampPolicy) -> Result<Vec<Result<u64, ULogError>>, ULogError> {
+    let parser = log_with_bad_timestamp()
+        .builder()
+        .on_bad_timestamp(policy)
+        .build()?;
+
+    Ok(parser
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(Ok(data.timestamp)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect())
+}
+
+#[test]
+fn test_bad_timestamp_include() -> Result<(), ULogError> {
+    let timestamps: Vec<u64> = logged_timestamps(BadTimestampPolicy::Include)?
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(timestamps, vec![1_000, 2_000, 0, 4_000]);
+    Ok(())
+}
+
+#[test]
+fn test_bad_timestamp_skip() -> Result<(), ULogError> {
+    let timestamps: Vec<u64> = logged_timestamps(BadTimestampPolicy::Skip)?
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(timestamps, vec![1_000, 2_000, 4_000]);
+    Ok(())
+}
+
+#[test]
+fn test_bad_timestamp_error() -> Result<(), ULogError> {
+    let results = logged_timestamps(BadTimestampPolicy::Error)?;
+
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[0], Ok(1_000)));
+    assert!(matches!(results[1], Ok(2_000)));
+    assert!(matches!(
+        results[2],
+        Err(ULogError::ImplausibleTimestamp {
+            msg_id: 0,
+            timestamp: 0,
+            previous: 2_000
+        })
+    ));
+    assert!(matches!(results[3], Ok(4_000)));
+    Ok(())
+}
+
+#[test]
+fn test_large_backwards_jump_is_implausible() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .format("my_topic:uint64_t timestamp;")
+        .subscription(0, 0, "my_topic")
+        .data(0, &with_timestamp(500_000_000, &[]))
+        .data(0, &with_timestamp(1_000, &[]))
+        .data(0, &with_timestamp(500_001_000, &[]))
+        .builder()
+        .on_bad_timestamp(BadTimestampPolicy::Skip)
+        .build()?;
+
+    let mut timestamps = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            timestamps.push(data.timestamp);
+        }
+    }
+
+    assert_eq!(timestamps, vec![500_000_000, 500_001_000]);
+    Ok(())
+}
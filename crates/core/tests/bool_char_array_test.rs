@@ -0,0 +1,76 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_bool_and_char_arrays_accept_any_byte() -> Result<(), ULogError> {
+    // Bytes other than 0 and 1 are not valid bools, and bytes above 0x7F are not ASCII, so each
+    // element must be converted rather than reinterpreted.
+    let mut fields = vec![0, 1, 2, 3];
+    fields.extend_from_slice(&[0x41, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x11, 0x00]);
+
+    let parser = SyntheticLog::new(0)
+        .format("flags:uint64_t timestamp;bool[4] set;char[8] label;")
+        .subscription(0, 0, "flags")
+        .data(0, &with_timestamp(100, &fields))
+        .builder()
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let values: Vec<&FieldValue> = records[0].data.fields.iter().map(|f| &f.value).collect();
+    assert_eq!(
+        values,
+        vec![
+            &FieldValue::ArrayBool(vec![false, true, true, true]),
+            &FieldValue::ArrayChar(vec!['A', '\0', '\0', '\0', 'ÿ', 'ÿ', '\u{11}', '\0']),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_bool_array_matches_scalar_rule() -> Result<(), ULogError> {
+    let bytes = [0, 1, 2, 0x80, 0xFF, 0, 0x7F, 1];
+
+    let mut fields = bytes.to_vec();
+    fields.extend_from_slice(&bytes);
+
+    let parser = SyntheticLog::new(0)
+        .format("flags:uint64_t timestamp;bool[8] set;bool a;bool b;bool c;bool d;bool e;bool f;bool g;bool h;")
+        .subscription(0, 0, "flags")
+        .data(0, &with_timestamp(100, &fields))
+        .builder()
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    // Each element of the array is decoded as the scalar with the same byte.
+    let fields = &records[0].data.fields;
+    let scalars: Vec<bool> = fields[1..]
+        .iter()
+        .map(|field| match field.value {
+            FieldValue::ScalarBool(value) => value,
+            ref other => panic!("expected a bool, got {other:?}"),
+        })
+        .collect();
+
+    assert_eq!(scalars, [false, true, true, true, true, false, true, true]);
+    assert_eq!(fields[0].value, FieldValue::ArrayBool(scalars));
+
+    Ok(())
+}
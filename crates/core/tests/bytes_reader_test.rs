@@ -0,0 +1,146 @@
+#![cfg(feature = "bytes")]
+
+use bytes::Bytes;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_field_slices_share_source() -> Result<(), Box<dyn std::error::Error>> {
+    let source = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let source_range = source.as_ptr_range();
+
+    let mut parser = ULogParserBuilder::from_bytes_crate(source.clone())
+        .set_subscription_allow_list(["actuator_outputs"])
+        .build()?;
+
+    let mut slices = Vec::new();
+    while let Some(msg_res) = parser.next() {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+
+        let slice = parser.field_slice(&data, "output").unwrap();
+        let FieldValue::ArrayF32(output) = &data.data.find_field("output").unwrap().value else {
+            panic!("Expected output to be a float array");
+        };
+
+        let decoded: Vec<u8> = output.iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(slice, decoded);
+        // The slice points into the source buffer, rather than a copy of it.
+        assert!(source_range.contains(&slice.as_ptr()));
+
+        assert_eq!(parser.field_slice(&data, "no_such_field"), None);
+        slices.push(slice);
+    }
+
+    // The slices remain valid after the parser is dropped.
+    drop(parser);
+    assert!(!slices.is_empty());
+    assert!(slices.iter().all(|slice| slice.len() == 16 * 4));
+
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_records_match_decoded() -> Result<(), Box<dyn std::error::Error>> {
+    let source = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let source_range = source.as_ptr_range();
+
+    let mut decoded = Vec::new();
+    for msg_res in ULogParserBuilder::from_bytes_crate(source.clone())
+        .set_subscription_allow_list(["actuator_outputs"])
+        .build()?
+    {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            decoded.push(data);
+        }
+    }
+
+    let mut parser = ULogParserBuilder::from_bytes_crate(source.clone())
+        .set_subscription_allow_list(["actuator_outputs"])
+        .build()?;
+
+    let mut count = 0;
+    while let Some(record_res) = parser.next_borrowed() {
+        let record = record_res?;
+        let data = &decoded[count];
+
+        assert_eq!(record.msg_id, data.msg_id);
+        assert_eq!(record.message_name, "actuator_outputs");
+
+        assert_eq!(record.timestamp(), Some(data.timestamp));
+
+        let FieldValue::ScalarU32(noutputs) = data.data.find_field("noutputs").unwrap().value
+        else {
+            panic!("Expected noutputs to be a uint32_t");
+        };
+        assert_eq!(record.get::<u32>("noutputs"), Some(noutputs));
+
+        let FieldValue::ArrayF32(output) = &data.data.find_field("output").unwrap().value else {
+            panic!("Expected output to be a float array");
+        };
+        // Compared as strings, since the outputs include NaN.
+        let borrowed = record.get_array::<f32>("output").unwrap();
+        assert_eq!(format!("{borrowed:?}"), format!("{output:?}"));
+
+        // Fields are read from the source buffer, rather than a copy of it.
+        assert!(source_range.contains(&record.field_bytes("output").unwrap().as_ptr()));
+
+        // Accessors check the type of the field.
+        assert_eq!(record.get::<f32>("noutputs"), None);
+        assert_eq!(record.get_array::<f32>("noutputs"), None);
+        assert_eq!(record.get::<u32>("no_such_field"), None);
+
+        count += 1;
+    }
+
+    assert!(count > 0);
+    assert_eq!(count, decoded.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_records_then_pipelined() -> Result<(), Box<dyn std::error::Error>> {
+    let source = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+
+    let remaining = |pipelined: bool| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut parser = ULogParserBuilder::from_bytes_crate(source.clone())
+            .set_subscription_allow_list(["actuator_outputs"])
+            .pipelined(pipelined)
+            .build()?;
+
+        for _ in 0..3 {
+            parser.next_borrowed().unwrap()?;
+        }
+
+        let mut messages = Vec::new();
+        for msg_res in parser.by_ref() {
+            messages.push(format!("{:?}", msg_res?));
+        }
+
+        // Records queued in the pipeline would be skipped.
+        if pipelined {
+            assert!(matches!(
+                parser.next_borrowed(),
+                Some(Err(ULogError::InvalidConfiguration(_)))
+            ));
+        }
+
+        Ok(messages)
+    };
+
+    let serial = remaining(false)?;
+    assert!(serial.len() > 10);
+    assert_eq!(remaining(true)?, serial);
+
+    Ok(())
+}
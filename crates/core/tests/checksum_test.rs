@@ -0,0 +1,52 @@
+#![cfg(feature = "crc32")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn log() -> Vec<u8> {
+    SyntheticLog::new(0)
+        .format("first:uint64_t timestamp;uint32_t value;")
+        .format("second:uint64_t timestamp;uint32_t value;")
+        .subscription(0, 0, "first")
+        .subscription(0, 1, "second")
+        .data(0, &with_timestamp(100, &0x1111_1111u32.to_le_bytes()))
+        .data(1, &with_timestamp(150, &0x2222_2222u32.to_le_bytes()))
+        .data(0, &with_timestamp(200, &0x3333_3333u32.to_le_bytes()))
+        .into_bytes()
+}
+
+fn checksums(bytes: Vec<u8>) -> Result<HashMap<String, u32>, ULogError> {
+    ULogParserBuilder::new(Cursor::new(bytes))
+        .build()?
+        .subscription_crc()
+}
+
+#[test]
+fn test_subscription_crc() -> Result<(), ULogError> {
+    let original = checksums(log())?;
+    assert_eq!(original.len(), 2);
+    assert_ne!(original["first"], original["second"]);
+
+    // The checksums are stable.
+    assert_eq!(checksums(log())?, original);
+
+    // Flipping a byte of the second record of `first` only changes its checksum.
+    let mut altered = log();
+    let offset = altered
+        .windows(4)
+        .position(|window| window == [0x33; 4])
+        .unwrap();
+    altered[offset] ^= 0x01;
+
+    let altered = checksums(altered)?;
+    assert_ne!(altered["first"], original["first"]);
+    assert_eq!(altered["second"], original["second"]);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+fn log(records: u64) -> SyntheticLog {
+    let mut log = SyntheticLog::new(0)
+        .format("sample:uint64_t timestamp;")
+        .subscription(0, 0, "sample");
+
+    for timestamp in 0..records {
+        log = log.data(0, &with_timestamp(timestamp, &[]));
+        if timestamp % 3 == 0 {
+            log = log.logging(b'6', timestamp, "between records");
+        }
+    }
+
+    log
+}
+
+#[test]
+fn test_next_chunk() -> Result<(), ULogError> {
+    let mut parser = log(10).builder().build()?;
+
+    let mut sizes = Vec::new();
+    let mut timestamps = Vec::new();
+    loop {
+        let chunk = parser.next_chunk(4)?;
+        if chunk.is_empty() {
+            break;
+        }
+        sizes.push(chunk.len());
+        timestamps.extend(chunk.iter().map(|data| data.timestamp));
+    }
+
+    // The final partial batch is returned too.
+    assert_eq!(sizes, [4, 4, 2]);
+    assert_eq!(timestamps, (0..10).collect::<Vec<_>>());
+
+    // Once the log has been read, every batch is empty.
+    assert!(parser.next_chunk(4)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_next_chunk_requires_records() -> Result<(), ULogError> {
+    let mut parser = log(1).builder().build()?;
+
+    assert!(matches!(
+        parser.next_chunk(0),
+        Err(ULogError::InvalidConfiguration(_))
+    ));
+    assert_eq!(parser.next_chunk(5)?.len(), 1);
+
+    Ok(())
+}
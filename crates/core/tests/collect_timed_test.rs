@@ -0,0 +1,80 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::ULogParser;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn parser() -> Result<ULogParser<BufReader<File>>, ULogError> {
+    ULogParserBuilder::new(BufReader::new(File::open(PATH)?)).build()
+}
+
+#[test]
+fn test_collect_timed_fixture() -> Result<(), ULogError> {
+    let records = parser()?.collect_timed("vehicle_status")?;
+    assert!(records.len() > 2);
+
+    let timestamps: Vec<u64> = records.keys().copied().collect();
+    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(records
+        .iter()
+        .all(|(timestamp, data)| *timestamp == data.timestamp));
+
+    // Spot-check a record against a serial parse.
+    let mut parser = parser()?;
+    let msg_id = records.values().next().unwrap().msg_id;
+    let expected = parser
+        .by_ref()
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) if data.msg_id == msg_id => Some(data),
+            _ => None,
+        })
+        .nth(1)
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", records[&expected.timestamp]),
+        format!("{expected:?}")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_collect_timed_multi_instance() -> Result<(), ULogError> {
+    let first = parser()?.collect_timed("actuator_outputs.00")?;
+    let second = parser()?.collect_timed("actuator_outputs.01")?;
+    assert!(!first.is_empty() && !second.is_empty());
+
+    // Each instance only holds its own records.
+    let msg_id = first.values().next().unwrap().msg_id;
+    assert!(first.values().all(|data| data.msg_id == msg_id));
+    assert!(second.values().all(|data| data.msg_id != msg_id));
+    assert!(parser()?.collect_timed("no_such_topic")?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_collect_timed_keeps_last_duplicate() -> Result<(), ULogError> {
+    let records = SyntheticLog::new(0)
+        .format("sensor:uint64_t timestamp;uint8_t value;")
+        .subscription(0, 1, "sensor")
+        .data(1, &with_timestamp(2_000, &[1]))
+        .data(1, &with_timestamp(1_000, &[2]))
+        .data(1, &with_timestamp(2_000, &[3]))
+        .builder()
+        .build()?
+        .collect_timed("sensor")?;
+
+    assert_eq!(records.keys().copied().collect::<Vec<_>>(), [1_000, 2_000]);
+    assert!(format!("{:?}", records[&2_000]).contains('3'));
+    assert!(!format!("{:?}", records[&2_000]).contains("U8(1)"));
+
+    Ok(())
+}
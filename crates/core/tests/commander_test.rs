@@ -0,0 +1,49 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::commander::StateChange;
+
+#[test]
+fn test_state_transitions() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+
+    let transitions = ULogParserBuilder::new(reader)
+        .build()?
+        .state_transitions()?;
+
+    // The vehicle starts armed in manual mode, and is disarmed once.
+    assert_eq!(
+        transitions,
+        vec![
+            (20_220_673, StateChange::Arming { from: None, to: 2 }),
+            (20_220_673, StateChange::Nav { from: None, to: 0 }),
+            (20_220_678, StateChange::Main { from: None, to: 0 }),
+            (
+                25_829_736,
+                StateChange::Arming {
+                    from: Some(2),
+                    to: 1
+                }
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_state_transitions_absent() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/trig_stats.ulg",
+    )?);
+
+    assert!(ULogParserBuilder::new(reader)
+        .build()?
+        .state_transitions()?
+        .is_empty());
+
+    Ok(())
+}
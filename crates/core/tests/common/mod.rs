@@ -0,0 +1,123 @@
+// Helpers shared by the integration tests for building synthetic ULOG files in memory.
+#![allow(dead_code)]
+
+use std::io::Cursor;
+
+use yule_log::builder::ULogParserBuilder;
+
+const MAGIC: [u8; 7] = [b'U', b'L', b'o', b'g', 0x01, 0x12, 0x35];
+
+/// Builds the raw bytes of a ULOG file one message at a time.
+///
+/// Every method appends a single message, framed with the standard 3 byte message header.
+pub struct SyntheticLog {
+    bytes: Vec<u8>,
+}
+
+impl SyntheticLog {
+    /// Starts a new log with the file header and an empty FLAG_BITS message.
+    pub fn new(timestamp: u64) -> Self {
+        Self::with_flag_bits(timestamp, [0; 8], [0; 8], [0; 3])
+    }
+
+    pub fn with_flag_bits(
+        timestamp: u64,
+        compat_flags: [u8; 8],
+        incompat_flags: [u8; 8],
+        appended_offsets: [u64; 3],
+    ) -> Self {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(1);
+        bytes.extend_from_slice(&timestamp.to_le_bytes());
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&compat_flags);
+        payload.extend_from_slice(&incompat_flags);
+        for offset in appended_offsets {
+            payload.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        Self { bytes }.message(b'B', &payload)
+    }
+
+    pub fn message(mut self, msg_type: u8, payload: &[u8]) -> Self {
+        self.bytes
+            .extend_from_slice(&(payload.len() as u16).to_le_bytes());
+        self.bytes.push(msg_type);
+        self.bytes.extend_from_slice(payload);
+        self
+    }
+
+    /// Appends a FORMAT message, e.g. `"my_topic:uint64_t timestamp;float x;"`.
+    pub fn format(self, format: &str) -> Self {
+        self.message(b'F', format.as_bytes())
+    }
+
+    /// Appends an INFO message with a key such as `"char[3] sys_name"`.
+    pub fn info(self, key: &str, value: &[u8]) -> Self {
+        let mut payload = vec![key.len() as u8];
+        payload.extend_from_slice(key.as_bytes());
+        payload.extend_from_slice(value);
+        self.message(b'I', &payload)
+    }
+
+    pub fn multi_info(self, is_continued: bool, key: &str, value: &[u8]) -> Self {
+        let mut payload = vec![is_continued as u8, key.len() as u8];
+        payload.extend_from_slice(key.as_bytes());
+        payload.extend_from_slice(value);
+        self.message(b'M', &payload)
+    }
+
+    /// Appends a PARAMETER message with a key such as `"int32_t SYS_AUTOSTART"`.
+    pub fn parameter(self, key: &str, value: &[u8]) -> Self {
+        let mut payload = vec![key.len() as u8];
+        payload.extend_from_slice(key.as_bytes());
+        payload.extend_from_slice(value);
+        self.message(b'P', &payload)
+    }
+
+    pub fn subscription(self, multi_id: u8, msg_id: u16, name: &str) -> Self {
+        let mut payload = vec![multi_id];
+        payload.extend_from_slice(&msg_id.to_le_bytes());
+        payload.extend_from_slice(name.as_bytes());
+        self.message(b'A', &payload)
+    }
+
+    /// Appends a DATA message.  `fields` is the encoded message body following the msg_id.
+    pub fn data(self, msg_id: u16, fields: &[u8]) -> Self {
+        let mut payload = msg_id.to_le_bytes().to_vec();
+        payload.extend_from_slice(fields);
+        self.message(b'D', &payload)
+    }
+
+    pub fn dropout(self, duration_ms: u16) -> Self {
+        self.message(b'O', &duration_ms.to_le_bytes())
+    }
+
+    pub fn logging(self, level: u8, timestamp: u64, msg: &str) -> Self {
+        let mut payload = vec![level];
+        payload.extend_from_slice(&timestamp.to_le_bytes());
+        payload.extend_from_slice(msg.as_bytes());
+        self.message(b'L', &payload)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn builder(self) -> ULogParserBuilder<Cursor<Vec<u8>>> {
+        ULogParserBuilder::new(Cursor::new(self.bytes))
+    }
+}
+
+/// Concatenates the little endian bytes of a `timestamp` followed by the given field bytes.
+pub fn with_timestamp(timestamp: u64, rest: &[u8]) -> Vec<u8> {
+    let mut bytes = timestamp.to_le_bytes().to_vec();
+    bytes.extend_from_slice(rest);
+    bytes
+}
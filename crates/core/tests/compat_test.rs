@@ -0,0 +1,56 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::compat::{compatibility_check, CompatProblem, RequiredFeature};
+
+#[test]
+fn test_compatibility_check_plain_log() {
+    let bytes = SyntheticLog::new(0)
+        .format("my_topic:uint64_t timestamp;")
+        .into_bytes();
+
+    let report = compatibility_check(&bytes);
+
+    assert!(report.can_parse());
+    assert_eq!(report.version, Some(1));
+    assert!(report.required_features.is_empty());
+}
+
+#[test]
+fn test_compatibility_check_reports_required_features() {
+    let mut incompat_flags = [0; 8];
+    // DATA_APPENDED, plus an unknown bit standing in for e.g. a compression flag.
+    incompat_flags[0] = 0b0000_0001;
+    incompat_flags[1] = 0b0000_0100;
+
+    let bytes = SyntheticLog::with_flag_bits(0, [0; 8], incompat_flags, [1000, 0, 0]).into_bytes();
+
+    let report = compatibility_check(&bytes);
+
+    assert_eq!(
+        report.required_features,
+        vec![
+            RequiredFeature::AppendedData,
+            RequiredFeature::Unknown { byte: 1, bit: 2 }
+        ]
+    );
+    assert!(!report.can_parse());
+    assert_eq!(
+        report.problems,
+        vec![CompatProblem::UnsupportedFeature(
+            RequiredFeature::Unknown { byte: 1, bit: 2 }
+        )]
+    );
+}
+
+#[test]
+fn test_compatibility_check_invalid_header() {
+    assert_eq!(
+        compatibility_check(b"not a ulog file, at all").problems,
+        vec![CompatProblem::InvalidMagicBits]
+    );
+    assert_eq!(
+        compatibility_check(b"ULog").problems,
+        vec![CompatProblem::Truncated]
+    );
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "lz4")]
+
+use std::fs::File;
+use std::io::BufReader;
+
+use yule_log::builder::ULogParserBuilder;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+#[test]
+fn test_compressibility_estimate() -> Result<(), Box<dyn std::error::Error>> {
+    let ratios = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .compressibility_estimate()?;
+
+    let bytes = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .bytes_per_subscription()?;
+
+    // Every subscription with data has an estimate.
+    let with_data = bytes.values().filter(|&&bytes| bytes > 0).count();
+    assert_eq!(ratios.len(), with_data);
+
+    // LZ4 adds a few bytes of overhead, which outweighs any gain for topics with a single small
+    // message, and telemetry is rarely more than 100x compressible.
+    for (name, ratio) in &ratios {
+        assert!((0.5..100.0).contains(ratio), "{name}: {ratio}");
+    }
+
+    // Slowly changing topics compress well.
+    assert!(ratios.values().any(|&ratio| ratio > 2.0));
+
+    Ok(())
+}
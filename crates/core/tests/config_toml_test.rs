@@ -0,0 +1,47 @@
+#![cfg(feature = "toml")]
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::config::config_toml;
+use yule_log::model::inst::ParameterValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_config_toml_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+    let document: toml::Table = config_toml(parser)?.parse()?;
+
+    let info = document["info"].as_table().unwrap();
+    let params = document["params"].as_table().unwrap();
+
+    assert_eq!(info["sys_name"].as_str(), Some("PX4"));
+    assert!(info["ver_sw_release"].is_integer());
+
+    // Compare every parameter against its last value in the log.
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+    let mut expected = HashMap::new();
+    for msg_res in parser {
+        if let UlogMessage::Parameter(param) = msg_res? {
+            expected.insert(param.key, param.value);
+        }
+    }
+
+    assert_eq!(params.len(), expected.len());
+    for (key, value) in expected {
+        match value {
+            ParameterValue::INT32(v) => {
+                assert_eq!(params[&key].as_integer(), Some(i64::from(v)), "{key}");
+            }
+            ParameterValue::FLOAT(v) => {
+                assert_eq!(params[&key].as_float().map(|f| f as f32), Some(v), "{key}");
+            }
+        }
+    }
+
+    Ok(())
+}
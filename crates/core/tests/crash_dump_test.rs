@@ -0,0 +1,95 @@
+#![cfg(feature = "bytes")]
+
+mod common;
+
+use bytes::Bytes;
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const DATA_APPENDED: [u8; 8] = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Two `hardfault_plain` messages, as appended by PX4 after a crash.
+fn hardfault_dump() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (is_continued, text) in [(0u8, "[hardfault_log] -- 2024-05-01 "), (1, "Hard Fault")] {
+        let key = format!("char[{}] hardfault_plain", text.len());
+        let mut payload = vec![is_continued, key.len() as u8];
+        payload.extend_from_slice(key.as_bytes());
+        payload.extend_from_slice(text.as_bytes());
+
+        bytes.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+        bytes.push(b'M');
+        bytes.extend(payload);
+    }
+    bytes
+}
+
+fn log(incompat_flags: [u8; 8], offset: u64) -> Vec<u8> {
+    SyntheticLog::with_flag_bits(0, [0; 8], incompat_flags, [offset, 0, 0])
+        .format("topic:uint64_t timestamp;")
+        .subscription(0, 1, "topic")
+        .data(1, &with_timestamp(100, &[]))
+        .into_bytes()
+}
+
+/// A crashed log whose dump starts at the recorded offset, or at an unrecorded one.
+fn crashed_log(record_offset: bool) -> (Bytes, usize) {
+    // The flag bits have a fixed size, so the offset can be found with a placeholder.
+    let offset = log(DATA_APPENDED, 0).len();
+    let mut bytes = log(DATA_APPENDED, if record_offset { offset as u64 } else { 0 });
+    bytes.extend(hardfault_dump());
+
+    (Bytes::from(bytes), offset)
+}
+
+#[test]
+fn test_crash_dump_at_offset() -> Result<(), ULogError> {
+    let (bytes, offset) = crashed_log(true);
+    let parser = ULogParserBuilder::from_bytes_crate(bytes.clone()).build()?;
+
+    assert_eq!(parser.crash_dump()?, Some(bytes.slice(offset..)));
+    assert_eq!(parser.crash_dump()?.unwrap(), hardfault_dump());
+
+    // The dump holds complete messages, which `read_appended_data` decodes.
+    let keys: Vec<_> = ULogParserBuilder::from_bytes_crate(bytes)
+        .read_appended_data(true)
+        .build()?
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::MultiInfo(multi_info)) => Some(multi_info.key),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(keys, ["hardfault_plain", "hardfault_plain"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_crash_dump_by_marker() -> Result<(), ULogError> {
+    let (bytes, offset) = crashed_log(false);
+    let parser = ULogParserBuilder::from_bytes_crate(bytes.clone()).build()?;
+
+    assert_eq!(parser.crash_dump()?, Some(bytes.slice(offset..)));
+
+    Ok(())
+}
+
+#[test]
+fn test_no_crash_dump() -> Result<(), ULogError> {
+    let bytes = Bytes::from(log([0; 8], 0));
+    let parser = ULogParserBuilder::from_bytes_crate(bytes).build()?;
+    assert_eq!(parser.crash_dump()?, None);
+
+    let bytes = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let mut parser = ULogParserBuilder::from_bytes_crate(bytes).build()?;
+    assert_eq!(parser.crash_dump()?, None);
+
+    // Parsing is unaffected.
+    assert!(parser.next().is_some());
+
+    Ok(())
+}
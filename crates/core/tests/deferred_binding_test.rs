@@ -0,0 +1,44 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_subscription_before_format() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .subscription(0, 0, "late_topic")
+        .format("late_topic:uint64_t timestamp;uint8_t value;")
+        .data(0, &with_timestamp(100, &[42]))
+        .builder()
+        .build()?;
+
+    let mut values = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            values.push((data.timestamp, data.data.fields[0].value.clone()));
+        }
+    }
+
+    assert_eq!(values, vec![(100, FieldValue::ScalarU8(42))]);
+
+    Ok(())
+}
+
+#[test]
+fn test_data_before_format_fails() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .subscription(0, 0, "late_topic")
+        .data(0, &with_timestamp(100, &[42]))
+        .format("late_topic:uint64_t timestamp;uint8_t value;")
+        .builder()
+        .build()?;
+
+    let errors: Vec<ULogError> = parser.filter_map(Result::err).collect();
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(&errors[0], ULogError::UndefinedFormat(name) if name == "late_topic"));
+
+    Ok(())
+}
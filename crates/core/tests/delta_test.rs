@@ -0,0 +1,95 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::delta::FieldDelta;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+
+fn gyro(x: f32, y: f32, id: u8) -> Vec<u8> {
+    let mut fields = x.to_le_bytes().to_vec();
+    fields.extend_from_slice(&y.to_le_bytes());
+    fields.extend_from_slice(&[id, 0, 0, 0]);
+    fields
+}
+
+#[test]
+fn test_changed_fields() -> Result<(), ULogError> {
+    let deltas = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;float y;uint8_t id;uint8_t[3] _padding0;")
+        .subscription(0, 0, "gyro")
+        .subscription(1, 1, "gyro")
+        .data(0, &with_timestamp(100, &gyro(1.0, 2.0, 7)))
+        .data(1, &with_timestamp(105, &gyro(5.0, 6.0, 9)))
+        .data(0, &with_timestamp(200, &gyro(1.0, 2.5, 7)))
+        // A change below the epsilon is ignored.
+        .data(0, &with_timestamp(300, &gyro(1.0000001, 2.5, 7)))
+        .data(0, &with_timestamp(400, &gyro(1.0, 2.5, 8)))
+        .data(1, &with_timestamp(405, &gyro(5.0, 6.0, 9)))
+        .builder()
+        .build()?
+        .changed_fields(1e-3)
+        .collect::<Result<Vec<_>, ULogError>>()?;
+
+    let delta = |msg_id, timestamp, changed: &[(&str, FieldValue)]| FieldDelta {
+        msg_id,
+        timestamp,
+        changed: changed
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect(),
+    };
+
+    assert_eq!(
+        deltas,
+        vec![
+            delta(
+                0,
+                100,
+                &[
+                    ("x", FieldValue::ScalarF32(1.0)),
+                    ("y", FieldValue::ScalarF32(2.0)),
+                    ("id", FieldValue::ScalarU8(7)),
+                ]
+            ),
+            delta(
+                1,
+                105,
+                &[
+                    ("x", FieldValue::ScalarF32(5.0)),
+                    ("y", FieldValue::ScalarF32(6.0)),
+                    ("id", FieldValue::ScalarU8(9)),
+                ]
+            ),
+            delta(0, 200, &[("y", FieldValue::ScalarF32(2.5))]),
+            delta(0, 300, &[]),
+            delta(0, 400, &[("id", FieldValue::ScalarU8(8))]),
+            delta(1, 405, &[]),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_constant_fields() -> Result<(), ULogError> {
+    let constant = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;float y;uint8_t id;uint8_t[3] _padding0;")
+        .format("status:uint64_t timestamp;uint8_t[2] mode;")
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "status")
+        .data(0, &with_timestamp(100, &gyro(1.0, 2.0, 7)))
+        .data(1, &with_timestamp(105, &[3, 4]))
+        // A change below the epsilon is ignored.
+        .data(0, &with_timestamp(200, &gyro(1.0000001, 2.5, 7)))
+        .data(1, &with_timestamp(205, &[3, 5]))
+        .data(0, &with_timestamp(300, &gyro(1.0, 2.0, 7)))
+        .builder()
+        .build()?
+        .constant_fields(1e-3)?;
+
+    assert_eq!(constant.len(), 2);
+    assert_eq!(constant["gyro"], ["x", "id"]);
+    assert_eq!(constant["status"], ["mode.00"]);
+
+    Ok(())
+}
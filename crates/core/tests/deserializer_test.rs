@@ -0,0 +1,147 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use serde::Deserialize;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::deserializer::{from_record, RecordDeserializer};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::{LoggedData, UlogMessage};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Accel {
+    #[serde(rename = "timestamp")]
+    time_us: u64,
+    #[serde(rename = "accel")]
+    acceleration: Vec3,
+    #[serde(rename = "temperature")]
+    temperature_c: f64,
+    flags: [u8; 2],
+    device: String,
+    // Widened from `uint16_t`.
+    error_count: u32,
+    missing: Option<u8>,
+}
+
+fn records(bytes: Vec<u8>, include_timestamp: bool) -> Result<Vec<LoggedData>, ULogError> {
+    ULogParserBuilder::new(bytes.as_slice())
+        .include_timestamp(include_timestamp)
+        .build()?
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(Ok(data)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+#[test]
+fn test_deserialize_record() -> Result<(), ULogError> {
+    let mut payload = Vec::new();
+    for v in [1.5f32, -2.0, 9.81] {
+        payload.extend_from_slice(&v.to_le_bytes());
+    }
+    payload.extend_from_slice(&21.5f32.to_le_bytes());
+    payload.extend_from_slice(&[3, 4]);
+    payload.extend_from_slice(b"imu\0\0\0");
+    payload.extend_from_slice(&7u16.to_le_bytes());
+
+    let bytes = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format(
+            "sensor_accel:uint64_t timestamp;vec3 accel;float temperature;uint8_t[2] flags;\
+             char[6] device;uint16_t error_count;",
+        )
+        .subscription(0, 1, "sensor_accel")
+        .data(1, &with_timestamp(1_234, &payload))
+        .into_bytes();
+
+    let expected = Accel {
+        time_us: 1_234,
+        acceleration: Vec3 {
+            x: 1.5,
+            y: -2.0,
+            z: 9.81,
+        },
+        temperature_c: 21.5,
+        flags: [3, 4],
+        device: "imu".to_string(),
+        error_count: 7,
+        missing: None,
+    };
+
+    // The timestamp is available whether or not the parser keeps the `timestamp` field.
+    for include_timestamp in [false, true] {
+        let records = records(bytes.clone(), include_timestamp)?;
+        assert_eq!(records.len(), 1);
+
+        let accel = Accel::deserialize(RecordDeserializer::new(&records[0]))?;
+        assert_eq!(accel, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_deserialize_type_mismatch() -> Result<(), ULogError> {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Wrong {
+        value: bool,
+    }
+
+    let bytes = SyntheticLog::new(0)
+        .format("topic:uint64_t timestamp;float value;")
+        .subscription(0, 1, "topic")
+        .data(1, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .into_bytes();
+    let records = records(bytes, false)?;
+
+    assert!(matches!(
+        from_record::<Wrong>(&records[0]),
+        Err(ULogError::Deserialize(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_deserialize_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(Debug, Deserialize)]
+    struct VehicleStatus {
+        timestamp: u64,
+        #[serde(rename = "nav_state")]
+        navigation_state: u8,
+        arming_state: u8,
+    }
+
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?))
+        .set_subscription_allow_list(["vehicle_status"])
+        .build()?;
+
+    let mut count = 0;
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            let status: VehicleStatus = from_record(&data)?;
+            assert_eq!(status.timestamp, data.timestamp);
+            assert!(status.navigation_state < 32);
+            assert!(status.arming_state < 8);
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::model::msg::UlogMessage;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn open() -> Result<ULogParserBuilder<BufReader<File>>, std::io::Error> {
+    Ok(ULogParserBuilder::new(BufReader::new(File::open(PATH)?))
+        .set_subscription_allow_list(["vehicle_status"]))
+}
+
+#[test]
+fn test_drop_ignored() -> Result<(), Box<dyn std::error::Error>> {
+    // The messages parsed without the option, less the ignored records.
+    let mut expected = Vec::new();
+    let mut ignored = 0;
+    for msg_res in open()?.build()? {
+        match msg_res? {
+            UlogMessage::Ignored { .. } => ignored += 1,
+            msg => expected.push(format!("{msg:?}")),
+        }
+    }
+    assert!(ignored > 0);
+
+    for pipelined in [false, true] {
+        let mut messages = Vec::new();
+        let mut records = 0;
+        for msg_res in open()?.drop_ignored(true).pipelined(pipelined).build()? {
+            let msg = msg_res?;
+            assert!(!matches!(msg, UlogMessage::Ignored { .. }));
+            if let UlogMessage::LoggedData(data) = &msg {
+                assert_eq!(data.data.name, "vehicle_status");
+                records += 1;
+            }
+            messages.push(format!("{msg:?}"));
+        }
+
+        assert!(records > 0);
+        assert_eq!(messages, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_drop_ignored_keeps_raw_scans() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        open()?
+            .drop_ignored(true)
+            .build()?
+            .bytes_per_subscription()?,
+        open()?.build()?.bytes_per_subscription()?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+#[test]
+fn test_error_on_dropout_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    // The messages before the dropout, parsed without the option.
+    let mut expected = Vec::new();
+    for msg_res in ULogParserBuilder::new(BufReader::new(File::open(PATH)?)).build()? {
+        let msg = msg_res?;
+        if matches!(msg, UlogMessage::DropoutMark(_)) {
+            break;
+        }
+        expected.push(format!("{msg:?}"));
+    }
+
+    for pipelined in [false, true] {
+        let mut parser = ULogParserBuilder::new(BufReader::new(File::open(PATH)?))
+            .error_on_dropout(true)
+            .pipelined(pipelined)
+            .build()?;
+
+        let mut messages = Vec::new();
+        let err = loop {
+            match parser.next() {
+                Some(Ok(msg)) => messages.push(format!("{msg:?}")),
+                Some(Err(err)) => break err,
+                None => panic!("expected a dropout error"),
+            }
+        };
+
+        assert!(matches!(
+            err,
+            ULogError::DataDropout {
+                timestamp: 20_309_013,
+                duration_ms: 30,
+            }
+        ));
+        assert!(messages == expected);
+
+        // Parsing stops at the dropout.
+        assert!(parser.next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_error_on_dropout_before_any_record() -> Result<(), ULogError> {
+    let results: Vec<_> = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;")
+        .subscription(0, 0, "gyro")
+        .dropout(5)
+        .data(0, &with_timestamp(100, &[]))
+        .builder()
+        .error_on_dropout(true)
+        .build()?
+        .collect();
+
+    assert!(matches!(
+        results[..],
+        [
+            Ok(UlogMessage::FlagBits(_)),
+            Ok(UlogMessage::FormatDefinition(_)),
+            Ok(UlogMessage::AddSubscription(_)),
+            Err(ULogError::DataDropout {
+                timestamp: 0,
+                duration_ms: 5,
+            }),
+        ]
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::encode::{Encode, EncodedReader};
+use yule_log::model::msg::UlogMessage;
+
+fn parse_messages(path: &str) -> Vec<UlogMessage> {
+    let reader = BufReader::new(File::open(path).expect("Failed to open input file"));
+
+    ULogParserBuilder::new(reader)
+        .include_header(true)
+        .include_timestamp(true)
+        .include_padding(true)
+        .build()
+        .expect("Failed to build parser")
+        .map(|msg| msg.expect("Failed to parse message"))
+        .collect()
+}
+
+#[test]
+fn test_encoded_reader_matches_encode() {
+    let messages = parse_messages("../../tests/fixtures/test_data/input/sample_log_small.ulg");
+
+    let mut expected = Vec::new();
+    for message in &messages {
+        message.encode(&mut expected).expect("Encoding failed");
+    }
+
+    // Read in small, odd sized chunks so messages straddle read boundaries.
+    let mut reader = EncodedReader::new(&messages);
+    let mut actual = Vec::new();
+    let mut chunk = [0u8; 7];
+    loop {
+        let n = reader.read(&mut chunk).expect("Read failed");
+        if n == 0 {
+            break;
+        }
+        actual.extend_from_slice(&chunk[..n]);
+    }
+
+    assert_eq!(actual.len(), expected.len());
+    assert!(actual == expected);
+}
+
+#[test]
+fn test_encoded_reader_read_to_end() {
+    let path = "../../tests/fixtures/test_data/input/trig_stats.ulg";
+    let messages = parse_messages(path);
+
+    let mut actual = Vec::new();
+    EncodedReader::new(messages)
+        .read_to_end(&mut actual)
+        .expect("Read failed");
+
+    let original = std::fs::read(path).expect("Failed to read input file");
+    assert!(actual == original);
+}
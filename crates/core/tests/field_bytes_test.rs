@@ -0,0 +1,53 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_field_bytes() -> Result<(), ULogError> {
+    let mut fields = Vec::new();
+    fields.push(5u8);
+    for v in [1.0f32, -2.0, 3.5] {
+        fields.extend_from_slice(&v.to_le_bytes());
+    }
+    fields.extend_from_slice(&0x1234u16.to_le_bytes());
+    let payload = with_timestamp(100, &fields);
+
+    let mut parser = SyntheticLog::new(0)
+        .format("vec:uint16_t x;")
+        .format("imu:uint64_t timestamp;uint8_t id;float[3] gyro;vec accel;")
+        .subscription(0, 0, "imu")
+        .data(0, &payload)
+        .builder()
+        .build()?;
+
+    let record = loop {
+        match parser.next() {
+            Some(Ok(UlogMessage::LoggedData(data))) => break data,
+            Some(msg_res) => {
+                msg_res?;
+            }
+            None => panic!("No LoggedData in log"),
+        }
+    };
+
+    assert_eq!(
+        parser.field_bytes(&record, &payload, "timestamp"),
+        Some(&payload[0..8])
+    );
+    assert_eq!(
+        parser.field_bytes(&record, &payload, "gyro"),
+        Some(&payload[9..21])
+    );
+    assert_eq!(
+        parser.field_bytes(&record, &payload, "accel/x"),
+        Some(&0x1234u16.to_le_bytes()[..])
+    );
+
+    assert_eq!(parser.field_bytes(&record, &payload, "missing"), None);
+    assert_eq!(parser.field_bytes(&record, &payload, "gyro/x"), None);
+    assert_eq!(parser.field_bytes(&record, &payload[..10], "gyro"), None);
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+
+#[test]
+fn test_try_from_matching_variant() -> Result<(), ULogError> {
+    assert_eq!(f32::try_from(FieldValue::ScalarF32(1.5))?, 1.5);
+    assert_eq!(u64::try_from(&FieldValue::ScalarU64(42))?, 42);
+    assert_eq!(i32::try_from(FieldValue::ScalarI32(-7))?, -7);
+    assert!(bool::try_from(FieldValue::ScalarBool(true))?);
+    assert_eq!(char::try_from(FieldValue::ScalarChar('x'))?, 'x');
+
+    Ok(())
+}
+
+#[test]
+fn test_try_from_mismatched_variant_fails() {
+    // Conversions are strict, even when the value would fit.
+    assert!(matches!(
+        f32::try_from(FieldValue::ScalarF64(1.5)),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        u64::try_from(FieldValue::ScalarU32(1)),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        u8::try_from(FieldValue::ArrayU8(vec![1])),
+        Err(ULogError::TypeMismatch(_))
+    ));
+}
+
+#[test]
+fn test_as_f64() {
+    assert_eq!(FieldValue::ScalarU8(3).as_f64(), Some(3.0));
+    assert_eq!(FieldValue::ScalarI64(-3).as_f64(), Some(-3.0));
+    assert_eq!(FieldValue::ScalarF32(0.5).as_f64(), Some(0.5));
+    assert_eq!(FieldValue::ScalarF64(0.25).as_f64(), Some(0.25));
+
+    assert_eq!(FieldValue::ScalarBool(true).as_f64(), None);
+    assert_eq!(FieldValue::ScalarChar('a').as_f64(), None);
+    assert_eq!(FieldValue::ArrayF32(vec![1.0]).as_f64(), None);
+}
+
+#[test]
+fn test_as_i64() {
+    assert_eq!(
+        FieldValue::ScalarU32(u32::MAX).as_i64(),
+        Some(i64::from(u32::MAX))
+    );
+    assert_eq!(FieldValue::ScalarI8(-1).as_i64(), Some(-1));
+    assert_eq!(FieldValue::ScalarU64(7).as_i64(), Some(7));
+
+    assert_eq!(FieldValue::ScalarU64(u64::MAX).as_i64(), None);
+    assert_eq!(FieldValue::ScalarF32(1.0).as_i64(), None);
+    assert_eq!(FieldValue::ScalarBool(false).as_i64(), None);
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "geo")]
+
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+fn position(lat: f64, lon: f64) -> Vec<u8> {
+    let mut fields = lat.to_le_bytes().to_vec();
+    fields.extend_from_slice(&lon.to_le_bytes());
+    fields.extend_from_slice(&488.5f32.to_le_bytes());
+    fields.extend_from_slice(&[0; 4]);
+    fields
+}
+
+#[test]
+fn test_flight_path() -> Result<(), ULogError> {
+    let path = SyntheticLog::new(0)
+        .format("vehicle_global_position:uint64_t timestamp;double lat;double lon;float alt;uint8_t[4] _padding0;")
+        .format("vehicle_status:uint64_t timestamp;uint8_t arming_state;uint8_t[7] _padding0;")
+        .subscription(0, 0, "vehicle_global_position")
+        .subscription(0, 1, "vehicle_status")
+        .data(0, &with_timestamp(100, &position(47.39, 8.54)))
+        .data(1, &with_timestamp(150, &[2, 0, 0, 0, 0, 0, 0, 0]))
+        .data(0, &with_timestamp(200, &position(47.40, 8.55)))
+        .data(0, &with_timestamp(300, &position(f64::NAN, 8.56)))
+        .data(0, &with_timestamp(400, &position(47.41, 8.57)))
+        .builder()
+        .build()?
+        .flight_path()?;
+
+    assert_eq!(path.0.len(), 3);
+
+    let points: Vec<(f64, f64)> = path.points().map(|point| point.x_y()).collect();
+    assert_eq!(points, vec![(8.54, 47.39), (8.55, 47.40), (8.57, 47.41)]);
+
+    Ok(())
+}
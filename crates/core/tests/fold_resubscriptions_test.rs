@@ -0,0 +1,76 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+/// Two segments, with the logger removing its subscriptions between them and adding them again
+/// with swapped msg_ids.
+fn two_segment_log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("a:uint64_t timestamp;")
+        .format("b:uint64_t timestamp;")
+        .subscription(0, 0, "a")
+        .subscription(0, 1, "b")
+        .data(0, &with_timestamp(100, &[]))
+        .data(1, &with_timestamp(110, &[]))
+        .message(b'R', &0u16.to_le_bytes())
+        .message(b'R', &1u16.to_le_bytes())
+        .subscription(0, 0, "b")
+        .subscription(0, 1, "a")
+        .data(1, &with_timestamp(200, &[]))
+        .data(0, &with_timestamp(210, &[]))
+}
+
+/// The `(topic, msg_id, timestamp)` of each record, grouped by msg_id.
+fn grouped_records(fold: bool) -> Result<Vec<(String, u16, u64)>, ULogError> {
+    let grouped = two_segment_log()
+        .builder()
+        .fold_resubscriptions(fold)
+        .build()?
+        .group_by_subscription()?;
+
+    let mut records = Vec::new();
+    for msg_res in grouped {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push((data.data.name.clone(), data.msg_id, data.timestamp));
+        }
+    }
+
+    Ok(records)
+}
+
+#[test]
+fn test_fold_resubscriptions_merges_segments() -> Result<(), ULogError> {
+    let records = grouped_records(true)?;
+
+    assert_eq!(
+        records,
+        [
+            ("a".to_string(), 0, 100),
+            ("a".to_string(), 0, 200),
+            ("b".to_string(), 1, 110),
+            ("b".to_string(), 1, 210),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resubscriptions_not_folded_by_default() -> Result<(), ULogError> {
+    let records = grouped_records(false)?;
+
+    // Each msg_id mixes the records of both topics.
+    assert_eq!(
+        records,
+        [
+            ("a".to_string(), 0, 100),
+            ("b".to_string(), 0, 210),
+            ("b".to_string(), 1, 110),
+            ("a".to_string(), 1, 200),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_format_byte_size_matches_payloads() -> Result<(), Box<dyn std::error::Error>> {
+    for name in [
+        "sample_log_small.ulg",
+        "powers.ulg",
+        "short_list.ulg",
+        "trig_stats.ulg",
+    ] {
+        let path = format!("../../tests/fixtures/test_data/input/{name}");
+        let mut parser = ULogParserBuilder::new(BufReader::new(File::open(path)?))
+            .with_raw_payload(true)
+            .build()?;
+
+        let mut records = Vec::new();
+        for msg_res in parser.by_ref() {
+            if let UlogMessage::LoggedData(data) = msg_res? {
+                records.push(data);
+            }
+        }
+
+        for data in &records {
+            let payload = data.raw_payload().unwrap();
+            assert_eq!(
+                parser.format_byte_size(&data.data.def_format)?,
+                payload.len(),
+                "{name}: {}",
+                data.data.name
+            );
+        }
+        assert!(!records.is_empty(), "{name} has no records");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_format_byte_size_padding() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("inner:uint16_t id;uint8_t[2] _padding0;")
+        .format("outer:uint64_t timestamp;uint8_t flag;uint8_t[3] _padding0;inner[2] items;uint8_t[4] _padding1;")
+        .format("missing:uint64_t timestamp;undefined value;")
+        .subscription(0, 1, "outer")
+        .data(1, &with_timestamp(100, &[0; 12]))
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    // Padding between fields and within nested formats is written, trailing padding is not.
+    assert_eq!(parser.format_byte_size(&parser.get_format("inner")?)?, 2);
+    assert_eq!(parser.format_byte_size(&parser.get_format("outer")?)?, 20);
+
+    assert!(matches!(
+        parser.format_byte_size(&parser.get_format("missing")?),
+        Err(ULogError::UndefinedFormat(name)) if name == "undefined"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_format_byte_size_overflow() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("huge:uint64_t[2305843009213693952] values;")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    // 2^61 elements of 8 bytes each overflow a 64-bit size.
+    assert!(matches!(
+        parser.format_byte_size(&parser.get_format("huge")?),
+        Err(ULogError::ParseError(msg)) if msg.contains("overflows")
+    ));
+
+    Ok(())
+}
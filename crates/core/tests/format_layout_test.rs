@@ -0,0 +1,63 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_validate_format_layout() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("good:uint64_t timestamp;vec3 accel;uint16_t id;uint8_t flag;uint8_t[1] _padding0;")
+        .format("bad:uint64_t timestamp;uint8_t flag;float x;")
+        .format("bad_nested:uint64_t timestamp;uint8_t flag;vec3 accel;")
+        .format("unpadded:uint64_t timestamp;float x;uint8_t flag;")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    parser.validate_format_layout(&parser.get_format("good")?)?;
+
+    match parser.validate_format_layout(&parser.get_format("bad")?) {
+        Err(ULogError::MisalignedField {
+            format,
+            field,
+            offset,
+            alignment,
+        }) => {
+            assert_eq!(format, "bad");
+            assert_eq!(field, "x");
+            assert_eq!(offset, 9);
+            assert_eq!(alignment, 4);
+        }
+        other => panic!("Expected a misaligned field, got {other:?}"),
+    }
+
+    // A nested format is aligned to its most strictly aligned field.
+    assert!(matches!(
+        parser.validate_format_layout(&parser.get_format("bad_nested")?),
+        Err(ULogError::MisalignedField {
+            offset: 9,
+            alignment: 4,
+            ..
+        })
+    ));
+
+    // Without its trailing padding, the next element of an array would be misaligned.
+    match parser.validate_format_layout(&parser.get_format("unpadded")?) {
+        Err(ULogError::MisalignedFormatSize {
+            format,
+            size,
+            alignment,
+        }) => {
+            assert_eq!(format, "unpadded");
+            assert_eq!(size, 13);
+            assert_eq!(alignment, 8);
+        }
+        other => panic!("Expected a misaligned format size, got {other:?}"),
+    }
+
+    Ok(())
+}
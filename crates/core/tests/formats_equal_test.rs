@@ -0,0 +1,36 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_formats_equal() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("xyz:float x;float y;float z;")
+        .format("sensor_accel:uint64_t timestamp;vec3 accel;uint8_t[4] _padding0;")
+        .format("sensor_accel_copy:uint64_t timestamp;xyz accel;uint8_t[4] _padding0;")
+        .format("sensor_gyro:uint64_t timestamp;vec3 gyro;uint8_t[4] _padding0;")
+        .subscription(0, 0, "sensor_accel")
+        .subscription(1, 1, "sensor_accel")
+        .subscription(0, 2, "sensor_gyro")
+        .subscription(0, 3, "sensor_accel_copy")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let accel_0 = parser.get_subscription(0)?;
+    let accel_1 = parser.get_subscription(1)?;
+    let gyro = parser.get_subscription(2)?;
+    let accel_copy = parser.get_subscription(3)?;
+
+    assert!(parser.formats_equal(&accel_0, &accel_1));
+    assert!(!parser.formats_equal(&accel_0, &gyro));
+    // Nested formats are compared by their fields, not their names.
+    assert!(parser.formats_equal(&accel_0, &accel_copy));
+
+    Ok(())
+}
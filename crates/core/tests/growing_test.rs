@@ -0,0 +1,70 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Write};
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::parser::ULogParser;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn collect_debug<R: std::io::Read>(
+    parser: &mut ULogParser<R>,
+    messages: &mut Vec<String>,
+) -> Result<(), ULogError> {
+    for msg_res in parser.by_ref() {
+        messages.push(format!("{:?}", msg_res?));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_refresh_continues_growing_file() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read(PATH)?;
+
+    let mut expected = Vec::new();
+    collect_debug(
+        &mut ULogParserBuilder::new(input.as_slice()).build()?,
+        &mut expected,
+    )?;
+
+    // Cut points within the data section, which fall inside message headers and contents.
+    let middle = input.len() / 2;
+    for (cut, pipelined) in [
+        (middle, false),
+        (middle + 1, false),
+        (middle + 2, true),
+        (middle + 3, true),
+    ] {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("growing.ulg");
+        std::fs::write(&path, &input[..cut])?;
+
+        let mut parser = ULogParserBuilder::new(BufReader::new(File::open(&path)?))
+            .growing(true)
+            .pipelined(pipelined)
+            .build()?;
+
+        let mut messages = Vec::new();
+        collect_debug(&mut parser, &mut messages)?;
+        assert!(messages.len() < expected.len());
+        assert_eq!(messages[..], expected[..messages.len()]);
+
+        // Nothing has been written since.
+        assert!(!parser.refresh()?);
+        collect_debug(&mut parser, &mut messages)?;
+
+        OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(&input[cut..])?;
+
+        assert!(parser.refresh()?);
+        collect_debug(&mut parser, &mut messages)?;
+        assert_eq!(messages, expected, "cut at {cut}");
+
+        // The iterator has ended again, at the end of the complete file.
+        assert!(!parser.refresh()?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::header_codec::HeaderCodec;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::{ULogMessageHeader, ULogMessageType};
+
+/// Reads headers whose size is an unsigned LEB128 varint, followed by the type byte.
+struct VarintHeaderCodec;
+
+impl HeaderCodec for VarintHeaderCodec {
+    fn read_header(
+        &self,
+        next_byte: &mut dyn FnMut() -> Result<Option<u8>, ULogError>,
+    ) -> Result<Option<ULogMessageHeader>, ULogError> {
+        let mut msg_size: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let Some(byte) = next_byte()? else {
+                return Ok(None);
+            };
+            msg_size |= u64::from(byte & 0x7F) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let Some(msg_type) = next_byte()? else {
+            return Ok(None);
+        };
+        let msg_size = u16::try_from(msg_size)
+            .map_err(|_| ULogError::ParseError(format!("message size {msg_size} is too large")))?;
+
+        Ok(Some(ULogMessageHeader {
+            msg_size,
+            msg_type: ULogMessageType::from(msg_type),
+        }))
+    }
+}
+
+/// Rewrites a standard log with varint message sizes.
+fn to_varint_headers(standard: &[u8]) -> Vec<u8> {
+    // The file header is unchanged.
+    let mut bytes = standard[..16].to_vec();
+
+    let mut rest = &standard[16..];
+    while !rest.is_empty() {
+        let mut msg_size = u16::from_le_bytes([rest[0], rest[1]]);
+        let contents = &rest[3..3 + usize::from(msg_size)];
+
+        while msg_size >= 0x80 {
+            bytes.push((msg_size & 0x7F) as u8 | 0x80);
+            msg_size >>= 7;
+        }
+        bytes.push(msg_size as u8);
+        bytes.push(rest[2]);
+        bytes.extend_from_slice(contents);
+
+        rest = &rest[3 + contents.len()..];
+    }
+
+    bytes
+}
+
+fn log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        // Long enough for a two byte size.
+        .info("char[200] description", &[b'x'; 200])
+        .format("sample:uint64_t timestamp;int32_t value;")
+        .subscription(0, 0, "sample")
+        .data(0, &with_timestamp(100, &7i32.to_le_bytes()))
+        .logging(b'6', 150, "hello")
+        .data(0, &with_timestamp(200, &(-3i32).to_le_bytes()))
+}
+
+#[test]
+fn test_varint_headers() -> Result<(), ULogError> {
+    let expected: Vec<String> = log()
+        .builder()
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    let varint = to_varint_headers(&log().into_bytes());
+    assert!(varint.len() < log().len());
+
+    let parsed: Vec<String> = ULogParserBuilder::new(Cursor::new(varint))
+        .header_codec(VarintHeaderCodec)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(parsed, expected);
+    assert!(parsed.iter().any(|msg| msg.contains("LoggedData")));
+
+    Ok(())
+}
+
+#[test]
+fn test_varint_headers_need_codec() -> Result<(), ULogError> {
+    let varint = to_varint_headers(&log().into_bytes());
+
+    let parser = ULogParserBuilder::new(Cursor::new(varint.clone()))
+        .header_codec(VarintHeaderCodec)
+        .build()?;
+    assert!(parser.config().custom_header_codec);
+
+    // Read with the standard header, the varint sizes frame the wrong bytes.
+    let parser = ULogParserBuilder::new(Cursor::new(varint)).build()?;
+    assert!(!parser.config().custom_header_codec);
+    let records = parser
+        .filter(|msg_res| matches!(msg_res, Ok(UlogMessage::LoggedData(_))))
+        .count();
+    assert_eq!(records, 0);
+
+    Ok(())
+}
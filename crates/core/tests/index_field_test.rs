@@ -0,0 +1,64 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::INDEX_FIELD_NAME;
+
+fn two_topic_log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("topic_a:uint64_t timestamp;float x;")
+        .format("topic_b:uint64_t timestamp;")
+        .subscription(0, 0, "topic_a")
+        .subscription(0, 1, "topic_b")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .data(1, &with_timestamp(150, &[]))
+        .data(1, &with_timestamp(160, &[]))
+        .data(0, &with_timestamp(200, &2.0f32.to_le_bytes()))
+        .data(1, &with_timestamp(170, &[]))
+}
+
+#[test]
+fn test_index_field_counts_per_topic() -> Result<(), ULogError> {
+    let parser = two_topic_log().builder().add_index_field(true).build()?;
+
+    let mut indices = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            let index_field = data.data.fields.last().unwrap();
+            assert_eq!(index_field.name, INDEX_FIELD_NAME);
+
+            let FieldValue::ScalarU64(index) = index_field.value else {
+                panic!("Unexpected index value {:?}", index_field.value);
+            };
+            indices.push((data.data.name.clone(), index));
+        }
+    }
+
+    assert_eq!(
+        indices,
+        vec![
+            ("topic_a".to_string(), 0),
+            ("topic_b".to_string(), 0),
+            ("topic_b".to_string(), 1),
+            ("topic_a".to_string(), 1),
+            ("topic_b".to_string(), 2),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_index_field_disabled_by_default() -> Result<(), ULogError> {
+    let parser = two_topic_log().builder().build()?;
+
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            assert!(data.data.fields.iter().all(|f| f.name != INDEX_FIELD_NAME));
+        }
+    }
+
+    Ok(())
+}
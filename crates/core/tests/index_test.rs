@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::ULogParser;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn parser() -> Result<ULogParser<BufReader<File>>, ULogError> {
+    ULogParserBuilder::new(BufReader::new(File::open(PATH)?))
+        .include_timestamp(true)
+        .build()
+}
+
+/// The records of every subscription, in file order, formatted for comparison.
+fn records_by_msg_id() -> Result<HashMap<u16, Vec<(u64, String)>>, ULogError> {
+    let mut records: HashMap<u16, Vec<(u64, String)>> = HashMap::new();
+    for msg_res in parser()? {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            let debug = format!("{:?}", UlogMessage::LoggedData(data.clone()));
+            records
+                .entry(data.msg_id)
+                .or_default()
+                .push((data.timestamp, debug));
+        }
+    }
+
+    Ok(records)
+}
+
+#[test]
+fn test_build_index() -> Result<(), ULogError> {
+    let index = parser()?.build_index()?;
+    let records = records_by_msg_id()?;
+
+    assert!(!records.is_empty());
+    for (msg_id, records) in &records {
+        let mut expected: Vec<u64> = records.iter().map(|(timestamp, _)| *timestamp).collect();
+        expected.sort_unstable();
+
+        let timestamps: Vec<u64> = index
+            .entries(*msg_id)
+            .iter()
+            .map(|entry| entry.timestamp)
+            .collect();
+        assert_eq!(timestamps, expected, "msg_id {msg_id}");
+    }
+
+    assert!(index.msg_id("vehicle_status").is_some());
+    assert!(index.msg_id("actuator_outputs.01").is_some());
+    assert_eq!(index.msg_id("no_such_topic"), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_messages_in_range() -> Result<(), ULogError> {
+    let mut parser = parser()?;
+    let index = parser.build_index()?;
+    let msg_id = index.msg_id("vehicle_status").unwrap();
+
+    let records = &records_by_msg_id()?[&msg_id];
+    let (start, end) = (records[2].0, records[records.len() - 2].0);
+    assert!(start < end);
+
+    let expected: Vec<&String> = records
+        .iter()
+        .filter(|(timestamp, _)| (start..end).contains(timestamp))
+        .map(|(_, debug)| debug)
+        .collect();
+
+    let messages: Vec<String> = parser
+        .messages_in_range(&index, msg_id, start, end)
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(messages.iter().collect::<Vec<_>>(), expected);
+
+    // An empty range, and one before the log.
+    assert_eq!(
+        parser.messages_in_range(&index, msg_id, end, end).count(),
+        0
+    );
+    assert_eq!(parser.messages_in_range(&index, msg_id, 0, 1).count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_seek_to_offset_resumes_parsing() -> Result<(), ULogError> {
+    let index = parser()?.build_index()?;
+    let msg_id = index.msg_id("vehicle_status").unwrap();
+    let entry = index.entries(msg_id)[1];
+
+    // Parsing resumes at the record, and continues with the messages which follow it.
+    let mut resumed_parser = parser()?;
+    resumed_parser.seek_to_offset(entry.offset)?;
+    let resumed: Vec<String> = resumed_parser
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    let serial: Vec<String> = parser()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    assert!(resumed.len() > 1);
+    assert!(resumed[0].contains(&entry.timestamp.to_string()));
+    assert!(serial.ends_with(&resumed));
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+use yule_log::model::def::BaseType;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::{InfoValue, UlogMessage};
+
+fn parse_info_values() -> Result<HashMap<String, InfoValue>, ULogError> {
+    let parser = SyntheticLog::new(0)
+        .info("uint32_t ver_sw_release", &0x010d_00ffu32.to_le_bytes())
+        .info("int16_t[3] offsets", &[1, 0, 0xfe, 0xff, 3, 0])
+        .info("char[8] sys_name", b"PX4\0\0\0\0\0")
+        .multi_info(false, "char[5] boot_console", b"hello")
+        .builder()
+        .build()?;
+
+    let mut values = HashMap::new();
+    for msg_res in parser {
+        match msg_res? {
+            UlogMessage::Info(info) => {
+                values.insert(info.key.clone(), info.info_value());
+            }
+            UlogMessage::MultiInfo(info) => {
+                values.insert(info.key.clone(), info.info_value());
+            }
+            _ => {}
+        }
+    }
+    Ok(values)
+}
+
+#[test]
+fn test_info_value_variants() -> Result<(), ULogError> {
+    let values = parse_info_values()?;
+
+    assert_eq!(
+        values["ver_sw_release"],
+        InfoValue::Scalar(FieldValue::ScalarU32(0x010d_00ff))
+    );
+    assert_eq!(
+        values["offsets"],
+        InfoValue::Array(
+            BaseType::INT16,
+            vec![
+                FieldValue::ScalarI16(1),
+                FieldValue::ScalarI16(-2),
+                FieldValue::ScalarI16(3)
+            ]
+        )
+    );
+    assert_eq!(values["sys_name"], InfoValue::Str("PX4".to_string()));
+    assert_eq!(values["boot_console"], InfoValue::Str("hello".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_info_value_display() -> Result<(), ULogError> {
+    let values = parse_info_values()?;
+
+    assert_eq!(values["ver_sw_release"].to_string(), "17629439");
+    assert_eq!(values["offsets"].to_string(), "[1, -2, 3]");
+    assert_eq!(values["sys_name"].to_string(), "PX4");
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_until_info() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .info("char[8] sys_name", b"PX4\0\0\0\0\0")
+        .info("uint32_t ver_sw_release", &0x010d_00ffu32.to_le_bytes())
+        .format("sample:uint64_t timestamp;")
+        .builder()
+        .build()?;
+
+    assert_eq!(
+        parser.parse_until_info("sys_name")?,
+        Some(InfoValue::Str("PX4".to_string()))
+    );
+
+    // Parsing stopped at the key, so the following messages are still to be read.
+    let UlogMessage::Info(info) = parser.next().unwrap()? else {
+        panic!("Expected the next info message");
+    };
+    assert_eq!(info.key, "ver_sw_release");
+
+    // Keys already seen are returned without reading further.
+    assert_eq!(
+        parser.parse_until_info("sys_name")?,
+        Some(InfoValue::Str("PX4".to_string()))
+    );
+    assert!(matches!(
+        parser.next().unwrap()?,
+        UlogMessage::FormatDefinition(_)
+    ));
+
+    assert_eq!(parser.parse_until_info("no_such_key")?, None);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_jitter() -> Result<(), ULogError> {
+    let mut log = SyntheticLog::new(0)
+        .format("periodic:uint64_t timestamp;")
+        .format("jittered:uint64_t timestamp;")
+        .format("single:uint64_t timestamp;")
+        .subscription(0, 1, "periodic")
+        .subscription(0, 2, "jittered")
+        .subscription(0, 3, "single")
+        .data(3, &with_timestamp(5, &[]));
+
+    for i in 0..=100u64 {
+        log = log.data(1, &with_timestamp(1_000 + i * 10_000, &[])).data(
+            2,
+            &with_timestamp(1_000 + i * 10_000 + (i % 2) * 2_000, &[]),
+        );
+    }
+
+    let jitter = log.builder().build()?.jitter()?;
+
+    assert_eq!(jitter.len(), 2);
+    assert!(jitter["periodic"] < 1e-9);
+    // The intervals alternate between 12ms and 8ms.
+    assert!((jitter["jittered"] - 2_000.0).abs() < 1e-6);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "json_schema")]
+
+mod common;
+
+use common::SyntheticLog;
+use serde_json::json;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::ArrayMode;
+
+#[test]
+fn test_topic_json_schema() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("imu:uint64_t timestamp;int16_t temp;vec3 accel;uint8_t[4] ids;char[8] name;uint8_t[3] _padding0;")
+        .subscription(0, 0, "imu")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let schema = parser.topic_json_schema("imu", ArrayMode::Nested)?;
+
+    assert_eq!(schema["title"], "imu");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], json!(["temp", "accel", "ids", "name"]));
+
+    let properties = &schema["properties"];
+    assert_eq!(
+        properties["temp"],
+        json!({ "type": "integer", "minimum": -32768, "maximum": 32767 })
+    );
+    assert_eq!(properties["accel"]["type"], "object");
+    assert_eq!(properties["accel"]["required"], json!(["x", "y", "z"]));
+    assert_eq!(
+        properties["accel"]["properties"]["x"],
+        json!({ "type": "number" })
+    );
+    assert_eq!(
+        properties["ids"],
+        json!({
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "minItems": 4,
+            "maxItems": 4,
+        })
+    );
+    assert_eq!(
+        properties["name"],
+        json!({ "type": "string", "maxLength": 8 })
+    );
+
+    assert!(matches!(
+        parser.topic_json_schema("missing", ArrayMode::Nested),
+        Err(ULogError::UndefinedFormat(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_topic_json_schema_expanded_arrays() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("esc:uint8_t rpm;")
+        .format("motors:uint64_t timestamp;uint16_t[2] ids;esc[2] esc;")
+        .subscription(0, 0, "motors")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let schema = parser.topic_json_schema("motors", ArrayMode::Expand)?;
+
+    assert_eq!(
+        schema["required"],
+        json!(["ids.00", "ids.01", "esc.00", "esc.01"])
+    );
+    let properties = &schema["properties"];
+    assert_eq!(
+        properties["ids.01"],
+        json!({ "type": "integer", "minimum": 0, "maximum": 65535 })
+    );
+    assert_eq!(properties["esc.00"]["required"], json!(["rpm"]));
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn legacy(timestamp: u32, x: f32) -> Vec<u8> {
+    let mut fields = timestamp.to_le_bytes().to_vec();
+    fields.extend_from_slice(&x.to_le_bytes());
+    fields
+}
+
+#[test]
+fn test_u32_timestamp_filters_by_time() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .format("legacy_status:uint32_t timestamp;float x;")
+        .format("modern_status:uint64_t timestamp;float y;")
+        .subscription(0, 0, "legacy_status")
+        .subscription(0, 1, "modern_status")
+        .data(0, &legacy(100, 1.0))
+        .data(1, &with_timestamp(150, &2.0f32.to_le_bytes()))
+        .data(0, &legacy(200, 3.0))
+        .data(0, &legacy(300, 4.0))
+        .data(1, &with_timestamp(350, &5.0f32.to_le_bytes()))
+        .data(0, &legacy(u32::MAX, 6.0))
+        .builder()
+        .include_timestamp(true)
+        .build()?;
+
+    let mut in_window = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            assert_eq!(data.timestamp_field("timestamp"), Some(data.timestamp));
+
+            if (150..=300).contains(&data.timestamp) {
+                in_window.push((data.msg_id, data.timestamp));
+            }
+        }
+    }
+
+    assert_eq!(in_window, vec![(1, 150), (0, 200), (0, 300)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_u32_timestamp_is_widened() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .format("legacy_status:uint32_t timestamp;float x;")
+        .subscription(0, 0, "legacy_status")
+        .data(0, &legacy(u32::MAX, 6.0))
+        .builder()
+        .build()?;
+
+    let timestamps: Vec<u64> = parser
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(data.timestamp),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(timestamps, vec![u64::from(u32::MAX)]);
+
+    Ok(())
+}
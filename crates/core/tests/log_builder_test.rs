@@ -0,0 +1,194 @@
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::log_builder::ULogBuilder;
+use yule_log::model::def::{BaseType, TypeExpr};
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::{Info, UlogMessage};
+
+#[test]
+fn test_log_builder_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = ULogBuilder::new(Vec::new())?;
+    builder.message(&UlogMessage::Info(Info {
+        key: "sys_name".to_string(),
+        r#type: TypeExpr::new(BaseType::CHAR, Some(9)),
+        value: FieldValue::ArrayChar("simulator".chars().collect()),
+    }))?;
+    builder.format("vec3:float x;float y;float z;")?;
+    builder.format(
+        "sensor_accel:uint64_t timestamp;vec3 accel;uint8_t[2] flags;uint8_t[5] _padding0;",
+    )?;
+    builder.format("battery_status:uint64_t timestamp;float voltage;bool connected;")?;
+
+    let accel = builder.subscription("sensor_accel", 0)?;
+    let battery_0 = builder.subscription("battery_status", 0)?;
+    let battery_1 = builder.subscription("battery_status", 1)?;
+
+    for i in 0..3u64 {
+        let t = 1_000 + i * 100;
+        builder.record(
+            accel,
+            t,
+            [
+                builder.nested(
+                    "vec3",
+                    [
+                        FieldValue::ScalarF32(i as f32),
+                        FieldValue::ScalarF32(0.0),
+                        FieldValue::ScalarF32(-9.81),
+                    ],
+                )?,
+                FieldValue::ArrayU8(vec![1, i as u8]),
+            ],
+        )?;
+        builder.record(
+            battery_0,
+            t + 10,
+            [FieldValue::ScalarF32(16.0), FieldValue::ScalarBool(true)],
+        )?;
+        builder.record(
+            battery_1,
+            t + 20,
+            [FieldValue::ScalarF32(15.5), FieldValue::ScalarBool(false)],
+        )?;
+    }
+
+    let bytes = builder.finish()?;
+
+    let messages = ULogParserBuilder::new(bytes.as_slice())
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let UlogMessage::Header(header) = &messages[0] else {
+        panic!("expected a header, got {:?}", messages[0]);
+    };
+    assert_eq!(header.version, 1);
+    assert_eq!(header.timestamp, 1_000);
+    assert!(matches!(messages[1], UlogMessage::FlagBits(_)));
+    assert!(messages.iter().any(|msg| matches!(
+        msg,
+        UlogMessage::Info(info) if info.key == "sys_name"
+            && info.value == FieldValue::ArrayChar("simulator".chars().collect())
+    )));
+
+    let topics: Vec<_> = messages
+        .iter()
+        .filter_map(|msg| match msg {
+            UlogMessage::AddSubscription(sub) => Some((sub.message_name.as_str(), sub.multi_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        topics,
+        [
+            ("sensor_accel", 0),
+            ("battery_status", 0),
+            ("battery_status", 1)
+        ]
+    );
+
+    let records: Vec<_> = messages
+        .iter()
+        .filter_map(|msg| match msg {
+            UlogMessage::LoggedData(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(records.len(), 9);
+
+    let accel_records: Vec<_> = records.iter().filter(|data| data.msg_id == accel).collect();
+    assert_eq!(
+        accel_records
+            .iter()
+            .map(|data| data.timestamp)
+            .collect::<Vec<_>>(),
+        [1_000, 1_100, 1_200]
+    );
+    let last = accel_records[2];
+    assert_eq!(
+        last.data.find_field("accel/x").map(|f| &f.value),
+        Some(&FieldValue::ScalarF32(2.0))
+    );
+    assert_eq!(
+        last.data.find_field("accel/z").map(|f| &f.value),
+        Some(&FieldValue::ScalarF32(-9.81))
+    );
+    assert_eq!(
+        last.data.find_field("flags").map(|f| &f.value),
+        Some(&FieldValue::ArrayU8(vec![1, 2]))
+    );
+
+    let battery_1_record = records
+        .iter()
+        .find(|data| data.msg_id == battery_1)
+        .unwrap();
+    assert_eq!(battery_1_record.timestamp, 1_020);
+    assert_eq!(
+        battery_1_record
+            .data
+            .find_field("voltage")
+            .map(|f| &f.value),
+        Some(&FieldValue::ScalarF32(15.5))
+    );
+    assert_eq!(
+        battery_1_record
+            .data
+            .find_field("connected")
+            .map(|f| &f.value),
+        Some(&FieldValue::ScalarBool(false))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_log_builder_rejects_invalid_records() -> Result<(), ULogError> {
+    let mut builder = ULogBuilder::new(Vec::new())?;
+    builder.format("battery_status:uint64_t timestamp;float voltage;")?;
+    builder.format("no_timestamp:float voltage;")?;
+
+    assert!(matches!(
+        builder.subscription("unknown", 0),
+        Err(ULogError::UndefinedFormat(_))
+    ));
+    assert!(matches!(
+        builder.subscription("no_timestamp", 0),
+        Err(ULogError::MissingTimestamp)
+    ));
+
+    let msg_id = builder.subscription("battery_status", 0)?;
+
+    assert!(matches!(
+        builder.record(msg_id, 1_000, [FieldValue::ScalarF64(16.0)]),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        builder.record(msg_id, 1_000, []),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        builder.record(
+            msg_id,
+            1_000,
+            [FieldValue::ScalarF32(16.0), FieldValue::ScalarF32(1.0)]
+        ),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        builder.record(msg_id + 1, 1_000, [FieldValue::ScalarF32(16.0)]),
+        Err(ULogError::UndefinedSubscription(_))
+    ));
+    assert!(matches!(
+        builder.nested("battery_status", [FieldValue::ScalarU64(1)]),
+        Err(ULogError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        builder.format("late:uint64_t timestamp;"),
+        Err(ULogError::InvalidMessageOrder(_))
+    ));
+
+    builder.record(msg_id, 1_000, [FieldValue::ScalarF32(16.0)])?;
+    builder.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::SyntheticLog;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::metadata::LogMetadata;
+
+fn metadata(log: SyntheticLog) -> Result<LogMetadata, ULogError> {
+    let mut parser = log.builder().build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    Ok(parser.metadata())
+}
+
+#[test]
+fn test_metadata_fixture() -> Result<(), ULogError> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let mut parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let metadata = parser.metadata();
+    assert_eq!(metadata.sys_name.as_deref(), Some("PX4"));
+    assert_eq!(metadata.sys_os_name.as_deref(), Some("NuttX"));
+    assert_eq!(metadata.ver_hw.as_deref(), Some("CUBEPILOT_CUBEORANGE"));
+    assert_eq!(
+        metadata.ver_sw.as_deref(),
+        Some("8583f1da30b63154d6ba0bc187d86135dfe33cf9")
+    );
+    assert_eq!(
+        metadata.ver_sw_branch.as_deref(),
+        Some("v1.11.2_w_rc_sysid")
+    );
+    assert_eq!(metadata.ver_sw_release, Some(0x010b_0200));
+    assert_eq!(
+        metadata.sys_uuid.as_deref(),
+        Some("000600000000383638393239510d0035002d")
+    );
+    assert_eq!(metadata.time_ref_utc, Some(0));
+
+    // Not logged by this firmware.
+    assert_eq!(metadata.ver_hw_subtype, None);
+    assert_eq!(metadata.replay, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_missing_and_mistyped_keys() -> Result<(), ULogError> {
+    assert_eq!(metadata(SyntheticLog::new(0))?, LogMetadata::default());
+
+    let log = SyntheticLog::new(0)
+        .info("char[12] replay", b"log_001.ulg\0")
+        .info("int32_t ver_sw_release", &(-1i32).to_le_bytes())
+        .info("uint32_t sys_name", &7u32.to_le_bytes());
+    let metadata = metadata(log)?;
+
+    assert_eq!(metadata.replay.as_deref(), Some("log_001.ulg"));
+    assert_eq!(metadata.ver_sw_release, None);
+    assert_eq!(metadata.sys_name, None);
+
+    Ok(())
+}
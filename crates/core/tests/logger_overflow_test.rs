@@ -0,0 +1,62 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::analysis::LoggerOverflow;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_logger_overflows_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+
+    assert_eq!(
+        parser.logger_overflows()?,
+        [LoggerOverflow {
+            timestamp: 20_309_013,
+            duration_ms: 30,
+        }]
+    );
+
+    let path = "../../tests/fixtures/test_data/input/trig_stats.ulg";
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+    assert!(parser.logger_overflows()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_logger_overflows_use_last_record() -> Result<(), ULogError> {
+    let overflows = SyntheticLog::new(0)
+        .format("fast:uint64_t timestamp;")
+        .format("slow:uint64_t timestamp;")
+        .subscription(0, 1, "fast")
+        .subscription(0, 2, "slow")
+        .dropout(5)
+        .data(1, &with_timestamp(2_000, &[]))
+        .data(2, &with_timestamp(1_500, &[]))
+        .dropout(120)
+        .data(1, &with_timestamp(200_000, &[]))
+        .builder()
+        .build()?
+        .logger_overflows()?;
+
+    assert_eq!(
+        overflows,
+        [
+            LoggerOverflow {
+                timestamp: 0,
+                duration_ms: 5,
+            },
+            LoggerOverflow {
+                timestamp: 1_500,
+                duration_ms: 120,
+            },
+        ]
+    );
+
+    Ok(())
+}
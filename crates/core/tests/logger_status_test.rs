@@ -0,0 +1,71 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_logger_status_buffer_usage() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let statuses = ULogParserBuilder::new(reader)
+        .set_subscription_allow_list(["vehicle_status"])
+        .build()?
+        .logger_status()?;
+
+    let buffer_used: Vec<(u64, u32)> = statuses
+        .iter()
+        .map(|(timestamp, status)| (*timestamp, status.buffer_used_bytes))
+        .collect();
+    assert_eq!(
+        buffer_used,
+        [
+            (20329662, 65531),
+            (21329807, 8751),
+            (22330540, 6976),
+            (23331542, 6258),
+            (24332575, 5848),
+            (25333485, 5465),
+            (26334748, 5015),
+        ]
+    );
+
+    let (_, first) = statuses[0];
+    assert_eq!(first.buffer_size_bytes, 65536);
+    assert_eq!(first.dropouts, 1);
+    assert_eq!(first.log_type, 0);
+    assert!(first.buffer_usage().unwrap() > 0.99);
+
+    Ok(())
+}
+
+#[test]
+fn test_logger_status_missing_fields() -> Result<(), ULogError> {
+    // An older layout, without message_gaps and num_messages.
+    let mut record = 1000u32.to_le_bytes().to_vec();
+    record.extend(4096u32.to_le_bytes());
+    record.push(1);
+
+    let statuses = SyntheticLog::new(0)
+        .format("logger_status:uint64_t timestamp;uint32_t buffer_used_bytes;uint32_t buffer_size_bytes;uint8_t type;")
+        .subscription(0, 0, "logger_status")
+        .data(0, &with_timestamp(100, &record))
+        .builder()
+        .build()?
+        .logger_status()?;
+
+    assert_eq!(statuses.len(), 1);
+    let (timestamp, status) = statuses[0];
+    assert_eq!(timestamp, 100);
+    assert_eq!(status.buffer_used_bytes, 1000);
+    assert_eq!(status.log_type, 1);
+    assert_eq!(status.message_gaps, 0);
+    assert_eq!(status.num_messages, 0);
+    assert_eq!(status.buffer_usage(), Some(1000.0 / 4096.0));
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::csv::export_long_csv;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::ArrayMode;
+
+#[test]
+fn test_export_long_csv() -> Result<(), Box<dyn std::error::Error>> {
+    let imu = |timestamp: u64, x: f32, y: f32, samples: [i16; 2]| {
+        let mut payload = x.to_le_bytes().to_vec();
+        payload.extend(y.to_le_bytes());
+        payload.extend(samples.iter().flat_map(|v| v.to_le_bytes()));
+        // The bool and char fields, which are not numeric.
+        payload.extend([1, b'o', b'k']);
+        with_timestamp(timestamp, &payload)
+    };
+
+    let parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;")
+        .format("imu:uint64_t timestamp;vec3 accel;int16_t[2] samples;bool ok;char[2] tag;")
+        .format("baro:uint64_t timestamp;uint32_t pressure;")
+        .subscription(0, 0, "imu")
+        .subscription(0, 1, "baro")
+        .data(0, &imu(100, 1.5, -2.0, [3, -4]))
+        .data(1, &with_timestamp(150, &101_325u32.to_le_bytes()))
+        .data(0, &imu(200, 0.25, 8.0, [5, 6]))
+        .builder()
+        .include_timestamp(true)
+        .build()?;
+
+    let csv = String::from_utf8(export_long_csv(parser, Vec::new(), ArrayMode::Expand)?)?;
+
+    assert_eq!(
+        csv,
+        "timestamp,subscription,field,value\n\
+         100,imu,accel/x,1.5\n\
+         100,imu,accel/y,-2\n\
+         100,imu,samples.00,3\n\
+         100,imu,samples.01,-4\n\
+         150,baro,pressure,101325\n\
+         200,imu,accel/x,0.25\n\
+         200,imu,accel/y,8\n\
+         200,imu,samples.00,5\n\
+         200,imu,samples.01,6\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_export_long_csv_fixture() -> Result<(), ULogError> {
+    let input = std::fs::read("../../tests/fixtures/test_data/input/sample_log_small.ulg")
+        .map_err(ULogError::Io)?;
+
+    let csv = export_long_csv(
+        ULogParserBuilder::new(input.as_slice()).build()?,
+        Vec::new(),
+        ArrayMode::default(),
+    )?;
+    let csv = String::from_utf8(csv).unwrap();
+
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("timestamp,subscription,field,value"));
+
+    let mut outputs = 0;
+    for line in lines {
+        let columns: Vec<&str> = line.split(',').collect();
+        assert_eq!(columns.len(), 4, "{line}");
+        assert!(columns[0].parse::<u64>().is_ok(), "{line}");
+        assert!(columns[3].parse::<f64>().is_ok(), "{line}");
+
+        if columns[1] == "actuator_outputs.01" && columns[2].starts_with("output.") {
+            outputs += 1;
+        }
+    }
+
+    let records = ULogParserBuilder::new(input.as_slice())
+        .build()?
+        .coverage()?["actuator_outputs.01"]
+        .count;
+    assert_eq!(outputs, records * 16);
+
+    Ok(())
+}
+
+#[test]
+fn test_export_long_csv_nested_arrays() -> Result<(), Box<dyn std::error::Error>> {
+    let mut payload = Vec::new();
+    payload.extend([3i16, -4].iter().flat_map(|v| v.to_le_bytes()));
+    payload.extend([10u8, 20]);
+    payload.extend(1.5f32.to_le_bytes());
+
+    let parser = SyntheticLog::new(0)
+        .format("esc:uint8_t rpm;")
+        .format("motors:uint64_t timestamp;int16_t[2] samples;esc[2] esc;float gain;")
+        .subscription(0, 0, "motors")
+        .data(0, &with_timestamp(100, &payload))
+        .builder()
+        .build()?;
+
+    let csv = String::from_utf8(export_long_csv(parser, Vec::new(), ArrayMode::Nested)?)?;
+
+    assert_eq!(
+        csv,
+        "timestamp,subscription,field,value\n\
+         100,motors,samples,\"[3,-4]\"\n\
+         100,motors,esc.00/rpm,10\n\
+         100,motors,esc.01/rpm,20\n\
+         100,motors,gain,1.5\n"
+    );
+
+    Ok(())
+}
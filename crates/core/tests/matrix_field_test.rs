@@ -0,0 +1,38 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_matrix_field_decodes_into_rows() -> Result<(), ULogError> {
+    let values: Vec<f32> = (0..6u8).map(f32::from).collect();
+    let payload: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+    let parser = SyntheticLog::new(0)
+        .format("pose:uint64_t timestamp;float[2][3] rotation;")
+        .subscription(0, 0, "pose")
+        .data(0, &with_timestamp(100, &payload))
+        .builder()
+        .build()?;
+
+    let mut matrices = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            let field = data.data.fields.last().unwrap();
+
+            // The flattened form holds all elements in row major order.
+            assert_eq!(field.value, FieldValue::ArrayF32(values.clone()));
+            matrices.push(field.to_matrix().unwrap());
+        }
+    }
+
+    let row = |r: [f32; 3]| r.map(FieldValue::ScalarF32).to_vec();
+    assert_eq!(
+        matrices,
+        vec![vec![row([0.0, 1.0, 2.0]), row([3.0, 4.0, 5.0])]]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+#[test]
+fn test_max_records_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    // The messages up to and including the tenth record, parsed without the option.
+    let mut expected = Vec::new();
+    let mut records = 0;
+    for msg_res in ULogParserBuilder::new(BufReader::new(File::open(PATH)?)).build()? {
+        let msg = msg_res?;
+        expected.push(format!("{msg:?}"));
+        if matches!(msg, UlogMessage::LoggedData(_)) {
+            records += 1;
+            if records == 10 {
+                break;
+            }
+        }
+    }
+
+    for pipelined in [false, true] {
+        let mut parser = ULogParserBuilder::new(BufReader::new(File::open(PATH)?))
+            .max_records(10)
+            .pipelined(pipelined)
+            .build()?;
+
+        let messages = parser
+            .by_ref()
+            .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(messages, expected);
+        assert!(parser.next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_max_records_counts_decoded_records() -> Result<(), ULogError> {
+    let log = || {
+        SyntheticLog::new(0)
+            .format("gyro:uint64_t timestamp;float x;")
+            .format("baro:uint64_t timestamp;float p;")
+            .subscription(0, 0, "gyro")
+            .subscription(0, 1, "baro")
+            .data(1, &with_timestamp(100, &1.0f32.to_le_bytes()))
+            .data(0, &with_timestamp(200, &2.0f32.to_le_bytes()))
+            .data(1, &with_timestamp(300, &3.0f32.to_le_bytes()))
+            .data(0, &with_timestamp(400, &4.0f32.to_le_bytes()))
+            .data(0, &with_timestamp(500, &5.0f32.to_le_bytes()))
+    };
+
+    let timestamps = |max_records| -> Result<Vec<u64>, ULogError> {
+        let mut timestamps = Vec::new();
+        for msg_res in log()
+            .builder()
+            .set_subscription_allow_list(["gyro"])
+            .max_records(max_records)
+            .build()?
+        {
+            if let UlogMessage::LoggedData(data) = msg_res? {
+                timestamps.push(data.timestamp);
+            }
+        }
+        Ok(timestamps)
+    };
+
+    // Ignored `baro` records do not count.
+    assert_eq!(timestamps(2)?, [200, 400]);
+    assert!(timestamps(0)?.is_empty());
+    assert_eq!(timestamps(10)?, [200, 400, 500]);
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn log_with_subscriptions(count: u16) -> SyntheticLog {
+    let mut log = SyntheticLog::new(0).format("topic:uint64_t timestamp;");
+    for msg_id in 0..count {
+        log = log.subscription(msg_id as u8, msg_id, "topic");
+    }
+    log
+}
+
+#[test]
+fn test_too_many_subscriptions() -> Result<(), ULogError> {
+    let parser = log_with_subscriptions(5)
+        .builder()
+        .max_subscriptions(3)
+        .build()?;
+
+    let results: Vec<_> = parser.collect();
+    let subscriptions = results
+        .iter()
+        .filter(|r| matches!(r, Ok(UlogMessage::AddSubscription(_))))
+        .count();
+
+    assert_eq!(subscriptions, 3);
+    assert!(matches!(
+        results.last(),
+        Some(Err(ULogError::TooManySubscriptions(3)))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_subscriptions_within_limit() -> Result<(), ULogError> {
+    let parser = log_with_subscriptions(3)
+        .builder()
+        .max_subscriptions(3)
+        .build()?;
+
+    for msg_res in parser {
+        msg_res?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const NUM_RECORDS: u64 = 100_000;
+
+/// A log with `NUM_RECORDS` records of two topics, and a `MultiInfo` message every
+/// `multi_info_interval` records.
+fn large_log(multi_info_interval: u64) -> Vec<u8> {
+    let mut log = SyntheticLog::new(0)
+        .info("char[3] sys_name", b"PX4")
+        .parameter("int32_t SYS_AUTOSTART", &4001i32.to_le_bytes())
+        .format("gyro:uint64_t timestamp;float[3] xyz;uint8_t id;uint8_t[3] _padding0;")
+        .format("status:uint64_t timestamp;uint8_t nav_state;")
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "status");
+
+    for i in 0..NUM_RECORDS {
+        log = if i % 10 == 0 {
+            log.data(1, &with_timestamp(i, &[3]))
+        } else {
+            log.data(0, &with_timestamp(i, &[0; 13]))
+        };
+        if i % multi_info_interval == multi_info_interval - 1 {
+            log = log.multi_info(false, "char[4] note", b"tick");
+        }
+    }
+
+    log.into_bytes()
+}
+
+#[test]
+fn test_memory_bounded_while_parsing() -> Result<(), ULogError> {
+    let mut parser = ULogParserBuilder::new(Cursor::new(large_log(u64::MAX)))
+        .read_ahead(4096)
+        .build()?;
+
+    let mut records = 0;
+    let mut early = None;
+    while let Some(msg_res) = parser.next() {
+        if let UlogMessage::LoggedData(_) = msg_res? {
+            records += 1;
+            if records == 1_000 {
+                early = Some(parser.memory_report());
+            }
+        }
+    }
+    assert_eq!(records, NUM_RECORDS);
+
+    // Nothing grows with the number of records.
+    let early = early.unwrap();
+    let report = parser.memory_report();
+    assert_eq!(report, early);
+    assert!(report.definitions > 0);
+    assert!(report.metadata > 0);
+    assert!(report.buffers >= 4096);
+    assert_eq!(report.warnings, 0);
+    assert!(report.total() < 64 * 1024, "{report:?}");
+
+    Ok(())
+}
+
+#[test]
+fn test_flush_releases_accumulated_state() -> Result<(), ULogError> {
+    let mut parser = ULogParserBuilder::new(Cursor::new(large_log(100))).build()?;
+
+    let mut records = 0;
+    let mut peak = 0;
+    let mut flushed = Vec::new();
+    while let Some(msg_res) = parser.next() {
+        if let UlogMessage::LoggedData(_) = msg_res? {
+            records += 1;
+            if records % 10_000 == 0 {
+                peak = peak.max(parser.memory_report().metadata);
+                parser.flush();
+                flushed.push(parser.memory_report().metadata);
+            }
+        }
+    }
+
+    // The `MultiInfo` messages accumulate between flush points, and are released by each.
+    assert!(peak > flushed[0]);
+    assert!(flushed.iter().all(|metadata| *metadata == flushed[0]));
+
+    // The latest message of each key is kept.
+    parser.flush();
+    assert_eq!(parser.multi_info()["note"].len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_report_counts_queued_messages() -> Result<(), ULogError> {
+    // Messages of the definitions section are queued by `parse_range()` until they are yielded.
+    let queued = |field_name: &str| -> Result<usize, ULogError> {
+        let log = SyntheticLog::new(0)
+            .format(&format!("gyro:uint64_t timestamp;float {field_name};"))
+            .subscription(0, 0, "gyro")
+            .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()));
+        let len = log.len() as u64;
+
+        Ok(log
+            .builder()
+            .build()?
+            .parse_range(0, len)?
+            .memory_report()
+            .buffers)
+    };
+
+    let long_name = "x".repeat(1000);
+    assert!(queued(&long_name)? >= queued("x")? + 999);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_report_counts_options() -> Result<(), ULogError> {
+    let log = || SyntheticLog::new(0).format("gyro:uint64_t timestamp;float x;");
+
+    let plain = log().builder().build()?.memory_report();
+    assert_eq!(plain.options, 0);
+
+    let configured = log()
+        .builder()
+        .set_subscription_allow_list(["gyro", "baro"])
+        .transform("gyro", "x", f64::to_degrees)
+        .opaque_type_sizes([("float16", 2)])
+        .build()?
+        .memory_report();
+    assert!(configured.options >= "gyrobarogyroxfloat16".len());
+    assert_eq!(configured.total(), plain.total() + configured.options);
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::merge::merge_by_timestamp;
+use yule_log::model::msg::UlogMessage;
+
+fn vehicle_log(timestamps: &[u64]) -> SyntheticLog {
+    let mut log = SyntheticLog::new(0)
+        .format("vehicle_status:uint64_t timestamp;")
+        .subscription(0, 0, "vehicle_status");
+
+    for &timestamp in timestamps {
+        log = log.data(0, &with_timestamp(timestamp, &[]));
+    }
+
+    log
+}
+
+#[test]
+fn test_merge_by_timestamp() -> Result<(), ULogError> {
+    let parsers = vec![
+        vehicle_log(&[10, 20, 30, 60]).builder().build()?,
+        vehicle_log(&[5, 25, 30, 50]).builder().build()?,
+    ];
+
+    let mut data = Vec::new();
+    let mut subscriptions = Vec::new();
+    for (source_id, msg_res) in merge_by_timestamp(parsers) {
+        match msg_res? {
+            UlogMessage::LoggedData(record) => data.push((source_id, record.timestamp)),
+            UlogMessage::AddSubscription(sub) => subscriptions.push((source_id, sub.message_name)),
+            _ => {}
+        }
+    }
+
+    // The same topic in both logs is told apart by the source id.
+    assert_eq!(
+        subscriptions,
+        vec![
+            (0, "vehicle_status".to_string()),
+            (1, "vehicle_status".to_string())
+        ]
+    );
+    assert_eq!(
+        data,
+        vec![
+            (1, 5),
+            (0, 10),
+            (0, 20),
+            (1, 25),
+            (0, 30),
+            (1, 30),
+            (1, 50),
+            (0, 60)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_by_timestamp_stops_source_at_error() -> Result<(), ULogError> {
+    let mut broken = vehicle_log(&[15]).into_bytes();
+    // A data message for an undefined subscription.
+    broken.extend_from_slice(&[2, 0, b'D', 9, 0]);
+
+    let parsers = vec![
+        vehicle_log(&[10, 20]).builder().build()?,
+        yule_log::builder::ULogParserBuilder::new(std::io::Cursor::new(broken)).build()?,
+    ];
+
+    let results: Vec<(usize, bool)> = merge_by_timestamp(parsers)
+        .map(|(source_id, msg_res)| (source_id, msg_res.is_ok()))
+        .collect();
+
+    assert_eq!(results.iter().filter(|(_, ok)| !ok).count(), 1);
+    assert_eq!(results.last(), Some(&(0, true)));
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::SyntheticLog;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_missing_parameters() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+
+    let missing = parser.missing_parameters(&[
+        "ASPD_BETA_GATE",
+        "NOT_A_PARAM",
+        "ASPD_FS_INNOV",
+        "ALSO_NOT_A_PARAM",
+    ])?;
+
+    assert_eq!(missing, ["NOT_A_PARAM", "ALSO_NOT_A_PARAM"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_parameters_in_data_section() -> Result<(), ULogError> {
+    let missing = SyntheticLog::new(0)
+        .parameter("int32_t INITIAL", &1i32.to_le_bytes())
+        .format("topic:uint64_t timestamp;")
+        .subscription(0, 1, "topic")
+        .data(1, &5u64.to_le_bytes())
+        .parameter("float CHANGED", &2.0f32.to_le_bytes())
+        .builder()
+        .build()?
+        .missing_parameters(&["CHANGED", "INITIAL", "ABSENT"])?;
+
+    assert_eq!(missing, ["ABSENT"]);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_multi_info_continuation_is_reassembled() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .multi_info(false, "char[6] boot_log", b"line 1")
+        .multi_info(true, "char[7] boot_log", b"\nline 2")
+        .multi_info(false, "char[5] boot_log", b"again")
+        .multi_info(false, "uint8_t[2] calibration", &[1, 2])
+        .multi_info(true, "uint8_t[3] calibration", &[3, 4, 5])
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(
+        parser.multi_info_values("boot_log")?,
+        vec![
+            FieldValue::ArrayChar("line 1\nline 2".chars().collect()),
+            FieldValue::ArrayChar("again".chars().collect()),
+        ]
+    );
+    assert_eq!(
+        parser.multi_info_values("calibration")?,
+        vec![FieldValue::ArrayU8(vec![1, 2, 3, 4, 5])]
+    );
+    assert_eq!(parser.multi_info_values("no_such_key")?, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_dangling_continuation() -> Result<(), ULogError> {
+    let parser = SyntheticLog::new(0)
+        .multi_info(false, "char[6] boot_log", b"line 1")
+        .multi_info(true, "char[7] calibration", b"orphan!")
+        .builder()
+        .build()?;
+
+    let result: Result<Vec<UlogMessage>, ULogError> = parser.collect();
+    assert!(matches!(result, Err(ULogError::DanglingContinuation(key)) if key == "calibration"));
+
+    Ok(())
+}
+
+#[test]
+fn test_continuation_with_different_type() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .multi_info(false, "char[6] boot_log", b"line 1")
+        .multi_info(true, "uint8_t[2] boot_log", &[1, 2])
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert!(matches!(
+        parser.multi_info_values("boot_log"),
+        Err(ULogError::TypeMismatch(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+fn build_log() -> SyntheticLog {
+    let mut fields = vec![0x00, 0x3C];
+    fields.extend_from_slice(&7u16.to_le_bytes());
+    fields.extend_from_slice(&[0x00, 0x40, 0x00, 0x42]);
+    fields.extend_from_slice(&2.5f32.to_le_bytes());
+
+    SyntheticLog::new(0)
+        .format("vendor:uint64_t timestamp;float16 half;uint16_t id;float16[2] pair;float gain;")
+        .subscription(0, 0, "vendor")
+        .data(0, &with_timestamp(100, &fields))
+}
+
+#[test]
+fn test_opaque_type_sizes() -> Result<(), ULogError> {
+    let parser = build_log()
+        .builder()
+        .opaque_type_sizes([("float16", 2)])
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let values: Vec<&FieldValue> = records[0].data.fields.iter().map(|f| &f.value).collect();
+    assert_eq!(
+        values,
+        vec![
+            &FieldValue::Opaque(vec![0x00, 0x3C]),
+            &FieldValue::ScalarU16(7),
+            &FieldValue::Opaque(vec![0x00, 0x40, 0x00, 0x42]),
+            &FieldValue::ScalarF32(2.5),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_type_fails_without_size() -> Result<(), ULogError> {
+    let result: Result<Vec<UlogMessage>, ULogError> = build_log().builder().build()?.collect();
+
+    assert!(matches!(result, Err(ULogError::UndefinedFormat(name)) if name == "float16"));
+
+    Ok(())
+}
+
+#[test]
+fn test_opaque_type_layout() -> Result<(), ULogError> {
+    let topics = build_log()
+        .builder()
+        .opaque_type_sizes([("float16", 2)])
+        .build()?
+        .topics_with_field("gain")?;
+    assert_eq!(topics, vec!["vendor".to_string()]);
+
+    // Skipping the `float16[2]` array needs its size.
+    let plan = build_log()
+        .builder()
+        .opaque_type_sizes([("float16", 2)])
+        .skip_arrays_larger_than(1)
+        .build()?
+        .plan()?;
+    assert_eq!(
+        plan.subscriptions[0].ignored_fields,
+        vec!["timestamp".to_string(), "pair".to_string()]
+    );
+
+    let mut parser = build_log()
+        .builder()
+        .opaque_type_sizes([("float16", 2)])
+        .build()?;
+    let mut records = Vec::new();
+    for msg_res in parser.by_ref() {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+    assert_eq!(parser.format_byte_size(&records[0].data.def_format)?, 20);
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use std::mem::{offset_of, size_of};
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::packed::{PackedArrayBuilder, PackedField, PackedRecord};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Gyro {
+    timestamp: u64,
+    xyz: [f32; 3],
+    id: u8,
+    _padding0: [u8; 3],
+}
+
+unsafe impl PackedRecord for Gyro {
+    const FIELDS: &'static [PackedField] = &[
+        PackedField::new("timestamp", offset_of!(Gyro, timestamp), size_of::<u64>()),
+        PackedField::new("xyz", offset_of!(Gyro, xyz), size_of::<[f32; 3]>()),
+        PackedField::new("id", offset_of!(Gyro, id), size_of::<u8>()),
+        PackedField::new("_padding0", offset_of!(Gyro, _padding0), 3),
+    ];
+}
+
+// The same fields as `Gyro`, but with `id` first, which forces padding before `xyz`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Misordered {
+    id: u8,
+    timestamp: u64,
+    xyz: [f32; 3],
+    _padding0: [u8; 3],
+}
+
+unsafe impl PackedRecord for Misordered {
+    const FIELDS: &'static [PackedField] = &[
+        PackedField::new("id", offset_of!(Misordered, id), size_of::<u8>()),
+        PackedField::new(
+            "timestamp",
+            offset_of!(Misordered, timestamp),
+            size_of::<u64>(),
+        ),
+        PackedField::new("xyz", offset_of!(Misordered, xyz), size_of::<[f32; 3]>()),
+        PackedField::new("_padding0", offset_of!(Misordered, _padding0), 3),
+    ];
+}
+
+fn gyro_payload(timestamp: u64, x: f32, id: u8) -> Vec<u8> {
+    let mut fields = Vec::new();
+    for value in [x, -x, 2.0 * x] {
+        fields.extend_from_slice(&value.to_le_bytes());
+    }
+    fields.extend_from_slice(&[id, 0, 0, 0]);
+    with_timestamp(timestamp, &fields)
+}
+
+fn build_log() -> Vec<u8> {
+    SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float[3] xyz;uint8_t id;uint8_t[3] _padding0;")
+        .format("baro:uint64_t timestamp;float pressure;")
+        .subscription(0, 0, "gyro")
+        .subscription(1, 1, "gyro")
+        .subscription(0, 2, "baro")
+        .data(0, &gyro_payload(100, 1.5, 7))
+        .data(1, &gyro_payload(110, 9.0, 9))
+        .data(2, &with_timestamp(120, &1013.0f32.to_le_bytes()))
+        .data(0, &gyro_payload(200, -0.25, 8))
+        .into_bytes()
+}
+
+#[test]
+fn test_packed_array_matches_payloads() -> Result<(), ULogError> {
+    let bytes = build_log();
+
+    let array = PackedArrayBuilder::<_, Gyro>::new(bytes.as_slice(), "gyro").build()?;
+
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.as_slice()[1].timestamp, 200);
+    assert_eq!(array.as_slice()[1].xyz, [-0.25, 0.25, -0.5]);
+    assert_eq!(array.as_slice()[1].id, 8);
+    assert_eq!(
+        array.as_bytes(),
+        [gyro_payload(100, 1.5, 7), gyro_payload(200, -0.25, 8)].concat()
+    );
+
+    let instance = PackedArrayBuilder::<_, Gyro>::new(bytes.as_slice(), "gyro")
+        .multi_id(1)
+        .build()?;
+    assert_eq!(instance.as_bytes(), gyro_payload(110, 9.0, 9));
+
+    Ok(())
+}
+
+#[test]
+fn test_packed_array_rejects_mismatched_layout() {
+    let bytes = build_log();
+
+    let result = PackedArrayBuilder::<_, Misordered>::new(bytes.as_slice(), "gyro").build();
+    assert!(matches!(result, Err(ULogError::LayoutMismatch(_))));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::ParameterValue;
+use yule_log::model::msg::{InfoValue, UlogMessage};
+
+#[test]
+fn test_parameters_and_info() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .info("char[3] sys_name", b"PX4")
+        .info("uint32_t ver_sw_release", &0x010d_00ffu32.to_le_bytes())
+        .parameter("int32_t SYS_AUTOSTART", &4001i32.to_le_bytes())
+        .parameter("float MPC_XY_VEL_MAX", &12.0f32.to_le_bytes())
+        .format("topic:uint64_t timestamp;")
+        .subscription(0, 1, "topic")
+        .data(1, &with_timestamp(100, &[]))
+        .parameter("float MPC_XY_VEL_MAX", &8.5f32.to_le_bytes())
+        .data(1, &with_timestamp(200, &[]))
+        .builder()
+        .build()?;
+
+    // Read up to the first record, which ends the definitions section.
+    for msg_res in parser.by_ref() {
+        if let UlogMessage::LoggedData(_) = msg_res? {
+            break;
+        }
+    }
+
+    assert_eq!(parser.info().len(), 2);
+    assert_eq!(parser.info()["sys_name"], InfoValue::Str("PX4".to_string()));
+    assert!(matches!(
+        parser.info()["ver_sw_release"],
+        InfoValue::Scalar(_)
+    ));
+
+    assert_eq!(parser.parameters().len(), 2);
+    assert_eq!(
+        parser.parameters()["SYS_AUTOSTART"],
+        ParameterValue::INT32(4001)
+    );
+    assert_eq!(
+        parser.parameters()["MPC_XY_VEL_MAX"],
+        ParameterValue::FLOAT(12.0)
+    );
+
+    // The change in flight replaces the initial value.
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(parser.parameters().len(), 2);
+    assert_eq!(
+        parser.parameters()["MPC_XY_VEL_MAX"],
+        ParameterValue::FLOAT(8.5)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_initial_and_final_parameters() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .parameter("int32_t SYS_AUTOSTART", &4001i32.to_le_bytes())
+        .parameter("float MPC_XY_VEL_MAX", &12.0f32.to_le_bytes())
+        .format("topic:uint64_t timestamp;")
+        .subscription(0, 1, "topic")
+        .data(1, &with_timestamp(100, &[]))
+        .parameter("float MPC_XY_VEL_MAX", &8.5f32.to_le_bytes())
+        .parameter("int32_t COM_ARM_CHK", &1i32.to_le_bytes())
+        .data(1, &with_timestamp(200, &[]))
+        .parameter("float MPC_XY_VEL_MAX", &10.0f32.to_le_bytes())
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let initial = parser.initial_parameters();
+    assert_eq!(initial.len(), 2);
+    assert_eq!(initial["SYS_AUTOSTART"], ParameterValue::INT32(4001));
+    assert_eq!(initial["MPC_XY_VEL_MAX"], ParameterValue::FLOAT(12.0));
+
+    let fin = parser.final_parameters();
+    assert_eq!(fin.len(), 3);
+    assert_eq!(fin["SYS_AUTOSTART"], ParameterValue::INT32(4001));
+    assert_eq!(fin["MPC_XY_VEL_MAX"], ParameterValue::FLOAT(10.0));
+    assert_eq!(fin["COM_ARM_CHK"], ParameterValue::INT32(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_parameters_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let mut parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+
+    let mut count = 0;
+    while let Some(msg_res) = parser.next() {
+        if let UlogMessage::Parameter(param) = msg_res? {
+            assert!(parser.parameters().contains_key(&param.key));
+            count += 1;
+        }
+    }
+
+    assert!(count > 0);
+    assert!(parser.parameters().len() <= count);
+    assert_eq!(parser.info()["sys_name"], InfoValue::Str("PX4".to_string()));
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::SyntheticLog;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::csv::export_params_csv;
+
+#[test]
+fn test_export_params_csv_matches_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let parser = ULogParserBuilder::new(reader).build()?;
+
+    let csv = String::from_utf8(export_params_csv(parser, Vec::new())?)?;
+    let expected = std::fs::read_to_string(
+        "../../tests/fixtures/test_data/output/sample_log_small_params.csv",
+    )?;
+
+    assert_eq!(csv, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_export_params_csv_float_format() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = SyntheticLog::new(0)
+        .parameter("float P_SMALL", &1.5e-5f32.to_le_bytes())
+        .parameter("float P_LARGE", &1e16f32.to_le_bytes())
+        .parameter("float P_WHOLE", &(-3.0f32).to_le_bytes())
+        .parameter("int32_t P_INT", &(-7i32).to_le_bytes())
+        .format("sample:uint64_t timestamp;")
+        .subscription(0, 0, "sample")
+        // Changed in flight, so not an initial parameter.
+        .parameter("float P_WHOLE", &4.0f32.to_le_bytes())
+        .builder()
+        .build()?;
+
+    let csv = String::from_utf8(export_params_csv(parser, Vec::new())?)?;
+    assert_eq!(
+        csv,
+        "P_INT,-7\n\
+         P_LARGE,1.0000000272564224e+16\n\
+         P_SMALL,1.4999999621068127e-05\n\
+         P_WHOLE,-3.0\n"
+    );
+
+    Ok(())
+}
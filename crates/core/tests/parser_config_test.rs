@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::parser::{BadTimestampPolicy, ParserConfig, ULogParser};
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+#[test]
+fn test_config_reflects_builder() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read(PATH)?;
+
+    let parser = ULogParserBuilder::new(input.as_slice())
+        .include_timestamp(true)
+        .set_subscription_allow_list(["vehicle_status", "actuator_outputs"])
+        .on_bad_timestamp(BadTimestampPolicy::Skip)
+        .max_records(100)
+        .skip_arrays_larger_than(8)
+        .transform("vehicle_status", "nav_state", |v| v * 2.0)
+        .transform("actuator_outputs", "output", |v| v + 1.0)
+        .split_name_instance(true)
+        .build()?;
+
+    let config = parser.config();
+    assert!(config.include_timestamp);
+    assert!(!config.include_header && !config.include_padding);
+    assert_eq!(
+        config.allowed_subscription_names,
+        Some(HashSet::from([
+            "vehicle_status".to_string(),
+            "actuator_outputs".to_string()
+        ]))
+    );
+    assert_eq!(config.allowed_subscription_globs, None);
+    assert_eq!(config.bad_timestamp_policy, BadTimestampPolicy::Skip);
+    assert_eq!(config.max_records, Some(100));
+    assert_eq!(config.max_array_size, Some(8));
+    assert_eq!(
+        config.transformed_fields,
+        [
+            ("actuator_outputs".to_string(), "output".to_string()),
+            ("vehicle_status".to_string(), "nav_state".to_string()),
+        ]
+    );
+    assert_eq!(
+        config.name_instance_pattern.as_deref(),
+        Some(yule_log::parser::DEFAULT_NAME_INSTANCE_PATTERN)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_config_defaults() -> Result<(), ULogError> {
+    let input = std::fs::read(PATH).map_err(ULogError::Io)?;
+
+    let built = ULogParserBuilder::new(input.as_slice()).build()?;
+    assert_eq!(built.config(), &ParserConfig::default());
+
+    let parser = ULogParser::new(input.as_slice())?;
+    assert_eq!(parser.config(), &ParserConfig::default());
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn debug_messages<R: std::io::Read>(
+    builder: ULogParserBuilder<R>,
+) -> Result<Vec<String>, ULogError> {
+    builder
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect()
+}
+
+#[test]
+fn test_pipelined_matches_serial() -> Result<(), Box<dyn std::error::Error>> {
+    for path in [
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+        "../../tests/fixtures/test_data/input/trig_stats.ulg",
+    ] {
+        let builder = || -> Result<_, std::io::Error> {
+            Ok(ULogParserBuilder::new(BufReader::new(File::open(path)?))
+                .include_timestamp(true)
+                .add_index_field(true))
+        };
+
+        let serial = debug_messages(builder()?)?;
+        let pipelined = debug_messages(builder()?.pipelined(true))?;
+
+        assert!(serial.len() > 100);
+        assert_eq!(pipelined, serial, "{path}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_pipelined_late_definitions() -> Result<(), ULogError> {
+    // A format defined after the first records, a multi-instance subscription, and a subscription
+    // which is removed while its records may still be queued.
+    let log = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .format("baro:uint64_t timestamp;float pressure;")
+        .subscription(1, 1, "gyro")
+        .subscription(0, 2, "baro")
+        .data(1, &with_timestamp(110, &2.0f32.to_le_bytes()))
+        .data(2, &with_timestamp(120, &3.0f32.to_le_bytes()))
+        .message(b'R', &2u16.to_le_bytes())
+        .data(0, &with_timestamp(130, &4.0f32.to_le_bytes()))
+        .into_bytes();
+
+    let serial = debug_messages(ULogParserBuilder::new(log.as_slice()).transform(
+        "baro",
+        "pressure",
+        |p| p * 100.0,
+    ))?;
+    let pipelined = debug_messages(
+        ULogParserBuilder::new(log.as_slice())
+            .transform("baro", "pressure", |p| p * 100.0)
+            .pipelined(true),
+    )?;
+
+    assert_eq!(pipelined, serial);
+
+    Ok(())
+}
+
+#[test]
+fn test_pipelined_reports_errors_in_order() -> Result<(), ULogError> {
+    // The second record is too short to decode.
+    let log = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .data(0, &100u64.to_le_bytes())
+        .data(0, &with_timestamp(300, &3.0f32.to_le_bytes()))
+        .into_bytes();
+
+    let collect = |pipelined| -> Result<Vec<Result<String, String>>, ULogError> {
+        Ok(ULogParserBuilder::new(log.as_slice())
+            .pipelined(pipelined)
+            .build()?
+            .map(|msg_res| {
+                msg_res
+                    .map(|msg| format!("{msg:?}"))
+                    .map_err(|err| err.to_string())
+            })
+            .collect())
+    };
+
+    let serial = collect(false)?;
+    assert!(serial.iter().any(Result::is_err));
+    assert_eq!(collect(true)?, serial);
+
+    Ok(())
+}
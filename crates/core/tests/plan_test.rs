@@ -0,0 +1,44 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_plan_reflects_allow_list() -> Result<(), ULogError> {
+    let mut gyro = 1.5f32.to_le_bytes().to_vec();
+    gyro.extend_from_slice(&[7, 0, 0, 0]);
+
+    let plan = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;uint8_t id;uint8_t[3] _padding0;")
+        .format("baro:uint64_t timestamp;float pressure;")
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "baro")
+        .data(0, &with_timestamp(100, &gyro))
+        .data(1, &with_timestamp(110, &1013.0f32.to_le_bytes()))
+        .data(0, &with_timestamp(200, &gyro))
+        .builder()
+        .set_subscription_allow_list(["gyro"])
+        .build()?
+        .plan()?;
+
+    let names: Vec<&str> = plan.subscriptions.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["baro", "gyro"]);
+
+    let baro = &plan.subscriptions[0];
+    assert!(!baro.decoded);
+    assert_eq!(baro.message_count, 1);
+    assert_eq!(baro.payload_bytes, 12);
+    assert_eq!(baro.estimated_output_bytes, 0);
+
+    let gyro = &plan.subscriptions[1];
+    assert!(gyro.decoded);
+    assert_eq!(gyro.fields, vec!["x", "id"]);
+    assert_eq!(gyro.ignored_fields, vec!["timestamp", "_padding0"]);
+    assert_eq!(gyro.message_count, 2);
+    assert_eq!(gyro.payload_bytes, 32);
+    assert_eq!(gyro.estimated_output_bytes, 10);
+
+    assert_eq!(plan.estimated_output_bytes, 10);
+
+    Ok(())
+}
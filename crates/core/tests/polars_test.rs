@@ -0,0 +1,143 @@
+#![cfg(feature = "polars")]
+
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use polars::prelude::{DataFrame, DataType};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::inst::ArrayMode;
+use yule_log::polars::to_polars;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+fn column_names(df: &DataFrame) -> Vec<&str> {
+    df.get_column_names()
+        .into_iter()
+        .map(|name| name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_to_polars_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let df = to_polars(
+        ULogParserBuilder::new(open_sample_log()?)
+            .include_timestamp(true)
+            .build()?,
+        "actuator_outputs.01",
+        ArrayMode::Nested,
+    )?;
+
+    let coverage = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .coverage()?;
+    assert_eq!(df.height() as u64, coverage["actuator_outputs.01"].count);
+    assert_eq!(column_names(&df), ["timestamp", "noutputs", "output"]);
+
+    let timestamps: Vec<u64> = df.column("timestamp")?.u64()?.into_no_null_iter().collect();
+    assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    assert_eq!(
+        df.column("output")?.dtype(),
+        &DataType::List(Box::new(DataType::Float32))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_polars_values() -> Result<(), ULogError> {
+    let record = |timestamp: u64, x: f32, samples: [i16; 2], label: &[u8; 4]| {
+        let mut payload = x.to_le_bytes().to_vec();
+        payload.extend(samples.iter().flat_map(|v| v.to_le_bytes()));
+        payload.extend_from_slice(label);
+        with_timestamp(timestamp, &payload)
+    };
+
+    let parser = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;int16_t[2] samples;char[4] label;")
+        .subscription(0, 0, "gyro")
+        .data(0, &record(100, 1.5, [1, -1], b"ab\0\0"))
+        .data(0, &record(200, 2.5, [2, -2], b"abcd"))
+        .builder()
+        .build()?;
+
+    let df = to_polars(parser, "gyro", ArrayMode::Nested)?;
+
+    assert_eq!(df.shape(), (2, 3));
+    assert_eq!(column_names(&df), ["x", "samples", "label"]);
+
+    let column = |name| df.column(name).map_err(ULogError::from);
+    let x: Vec<f32> = column("x")?.f32().unwrap().into_no_null_iter().collect();
+    assert_eq!(x, [1.5, 2.5]);
+
+    let label: Vec<&str> = column("label")?.str().unwrap().iter().flatten().collect();
+    assert_eq!(label, ["ab", "abcd"]);
+
+    let samples = column("samples")?;
+    assert_eq!(samples.dtype(), &DataType::List(Box::new(DataType::Int16)));
+    let second: Vec<i16> = samples
+        .list()
+        .unwrap()
+        .get_as_series(1)
+        .unwrap()
+        .i16()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(second, [2, -2]);
+
+    Ok(())
+}
+
+#[test]
+fn test_to_polars_unknown_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let df = to_polars(
+        ULogParserBuilder::new(open_sample_log()?).build()?,
+        "no_such_topic",
+        ArrayMode::Nested,
+    )?;
+
+    assert_eq!(df.shape(), (0, 0));
+
+    Ok(())
+}
+
+#[test]
+fn test_to_polars_expanded_arrays() -> Result<(), ULogError> {
+    let mut payload = Vec::new();
+    payload.extend([1i16, -1].iter().flat_map(|v| v.to_le_bytes()));
+    payload.extend([10u8, 20]);
+
+    let parser = SyntheticLog::new(0)
+        .format("esc:uint8_t rpm;")
+        .format("gyro:uint64_t timestamp;int16_t[2] samples;esc[2] esc;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &payload))
+        .builder()
+        .build()?;
+
+    let df = to_polars(parser, "gyro", ArrayMode::Expand)?;
+
+    assert_eq!(
+        column_names(&df),
+        ["samples.00", "samples.01", "esc.00/rpm", "esc.01/rpm"]
+    );
+    let column = |name| df.column(name).map_err(ULogError::from);
+    assert_eq!(column("samples.01")?.dtype(), &DataType::Int16);
+    let rpm: Vec<u8> = column("esc.01/rpm")?
+        .u8()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(rpm, [20]);
+
+    Ok(())
+}
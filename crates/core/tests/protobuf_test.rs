@@ -0,0 +1,131 @@
+#![cfg(feature = "prost-reflect")]
+
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use prost_reflect::prost::Message;
+use prost_reflect::{DynamicMessage, Value};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::protobuf::to_dynamic_message;
+
+#[test]
+fn test_protobuf_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let mut fields = Vec::new();
+    for v in [1.0f32, -2.5, 3.0] {
+        fields.extend_from_slice(&v.to_le_bytes());
+    }
+    fields.extend_from_slice(&7u16.to_le_bytes());
+    fields.extend_from_slice(&9u16.to_le_bytes());
+    fields.extend_from_slice(b"imu\0\0\0\0\0");
+
+    let mut parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("sensor:uint64_t timestamp;vec3 accel;uint16_t[2] ids;char[8] label;")
+        .subscription(0, 0, "sensor")
+        .data(0, &with_timestamp(1_500, &fields))
+        .builder()
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser.by_ref() {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let descriptor = parser.protobuf_descriptor()?;
+    let message_type = descriptor
+        .parent_pool()
+        .get_message_by_name("ulog.sensor")
+        .unwrap();
+    let field_numbers: Vec<u32> = message_type.fields().map(|field| field.number()).collect();
+    assert_eq!(field_numbers, vec![1, 2, 3, 4]);
+
+    // Serialize the record, and read it back through reflection.
+    let bytes = to_dynamic_message(&records[0], &descriptor)?.encode_to_vec();
+    let decoded = DynamicMessage::decode(message_type, bytes.as_slice())?;
+
+    assert_eq!(
+        decoded.get_field_by_name("timestamp").unwrap().as_u64(),
+        Some(1_500)
+    );
+    let accel = decoded.get_field_by_name("accel").unwrap();
+    let accel = accel.as_message().unwrap();
+    assert_eq!(accel.get_field_by_name("y").unwrap().as_f32(), Some(-2.5));
+    assert_eq!(
+        decoded.get_field_by_name("ids").unwrap().as_list(),
+        Some([Value::U32(7), Value::U32(9)].as_slice())
+    );
+    assert_eq!(
+        decoded.get_field_by_name("label").unwrap().as_str(),
+        Some("imu")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_protobuf_sample_log() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let mut parser = ULogParserBuilder::new(reader)
+        .include_timestamp(false)
+        .add_index_field(true)
+        .build()?;
+
+    let mut descriptor = None;
+    let mut count = 0;
+    while let Some(msg_res) = parser.next() {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+        // Every format has been defined by the first record.
+        if descriptor.is_none() {
+            descriptor = Some(parser.protobuf_descriptor()?);
+        }
+
+        let message = to_dynamic_message(&data, descriptor.as_ref().unwrap())?;
+        assert_eq!(
+            message.get_field_by_name("timestamp").unwrap().as_u64(),
+            Some(data.timestamp)
+        );
+        count += 1;
+    }
+    assert!(count > 100);
+
+    Ok(())
+}
+
+#[test]
+fn test_protobuf_unknown_format() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .builder()
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser.by_ref() {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    // A descriptor generated before the format was seen has no message type for it.
+    let empty = ULogParserBuilder::new(&[][..])
+        .build()?
+        .protobuf_descriptor()?;
+    assert!(matches!(
+        to_dynamic_message(&records[0], &empty),
+        Err(ULogError::UndefinedFormat(name)) if name == "gyro"
+    ));
+
+    Ok(())
+}
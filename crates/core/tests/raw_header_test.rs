@@ -0,0 +1,74 @@
+#![cfg(feature = "bytes")]
+
+use bytes::Bytes;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::writer::ULogWriter;
+
+#[test]
+fn test_raw_header_transcode() -> Result<(), Box<dyn std::error::Error>> {
+    let source = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+
+    let mut parser = ULogParserBuilder::from_bytes_crate(source.clone())
+        .include_timestamp(true)
+        .include_padding(true)
+        .build()?;
+    let raw_header = parser.raw_header()?;
+    assert!(!raw_header.is_empty() && raw_header.len() < source.len());
+
+    // Transcode only the data section, dropping the records of one topic.
+    let mut writer = ULogWriter::new(Vec::new());
+    writer.write_raw_header(&raw_header)?;
+
+    let mut in_data_section = false;
+    let mut dropped_ids = Vec::new();
+    for msg_res in parser {
+        let msg = msg_res?;
+        if let UlogMessage::AddSubscription(sub) = &msg {
+            in_data_section = true;
+            if sub.message_name == "vehicle_status" {
+                dropped_ids.push(sub.msg_id);
+            }
+        }
+
+        match &msg {
+            _ if !in_data_section => {}
+            UlogMessage::LoggedData(data) if dropped_ids.contains(&data.msg_id) => {}
+            _ => writer.write_message(&msg)?,
+        }
+    }
+    let output = writer.finish()?;
+
+    assert!(output.len() < source.len());
+    assert_eq!(output[..raw_header.len()], raw_header[..]);
+
+    let coverage = ULogParserBuilder::new(output.as_slice())
+        .build()?
+        .coverage()?;
+    assert!(!coverage.contains_key("vehicle_status"));
+    assert!(coverage.contains_key("actuator_outputs.01"));
+
+    Ok(())
+}
+
+#[test]
+fn test_write_raw_header_twice() -> Result<(), Box<dyn std::error::Error>> {
+    let source = Bytes::from(std::fs::read(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?);
+    let raw_header = ULogParserBuilder::from_bytes_crate(source)
+        .build()?
+        .raw_header()?;
+
+    let mut writer = ULogWriter::new(Vec::new());
+    writer.write_raw_header(&raw_header)?;
+    assert!(matches!(
+        writer.write_raw_header(&raw_header),
+        Err(ULogError::InvalidMessageOrder(_))
+    ));
+
+    Ok(())
+}
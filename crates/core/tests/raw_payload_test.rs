@@ -0,0 +1,58 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::encode::Encode;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn build_log() -> SyntheticLog {
+    let mut fields = 1.5f32.to_le_bytes().to_vec();
+    fields.extend_from_slice(&[7, 0, 0, 0]);
+
+    SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;uint8_t id;uint8_t[3] _padding0;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &fields))
+        .data(0, &with_timestamp(200, &fields))
+}
+
+#[test]
+fn test_raw_payload_matches_decoded_record() -> Result<(), ULogError> {
+    let expected = with_timestamp(100, &[0, 0, 0xC0, 0x3F, 7, 0, 0, 0]);
+
+    let parser = build_log()
+        .builder()
+        .include_timestamp(true)
+        .include_padding(true)
+        .with_raw_payload(true)
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].raw_payload(), Some(expected.as_slice()));
+
+    for record in &records {
+        let mut decoded = Vec::new();
+        record.data.encode(&mut decoded)?;
+        assert_eq!(record.raw_payload(), Some(decoded.as_slice()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_payload_is_off_by_default() -> Result<(), ULogError> {
+    for msg_res in build_log().builder().build()? {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            assert!(data.raw_payload().is_none());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::cell::Cell;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::rc::Rc;
+
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+/// Counts the reads made of the underlying reader.
+struct CountingReader {
+    inner: Cursor<Vec<u8>>,
+    reads: Rc<Cell<usize>>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for CountingReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+fn counting_builder(
+    path: &str,
+    reads: &Rc<Cell<usize>>,
+) -> Result<ULogParserBuilder<CountingReader>, Box<dyn std::error::Error>> {
+    Ok(ULogParserBuilder::new(CountingReader {
+        inner: Cursor::new(std::fs::read(path)?),
+        reads: Rc::clone(reads),
+    })
+    .include_timestamp(true)
+    .include_padding(true))
+}
+
+/// Returns the messages of the log, formatted for comparison, and the number of reads made.
+fn parse_counting(
+    path: &str,
+    read_ahead: usize,
+) -> Result<(Vec<String>, usize), Box<dyn std::error::Error>> {
+    let reads = Rc::new(Cell::new(0));
+    let messages = counting_builder(path, &reads)?
+        .read_ahead(read_ahead)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<_, _>>()?;
+
+    Ok((messages, reads.get()))
+}
+
+#[test]
+fn test_read_ahead_reduces_reads() -> Result<(), Box<dyn std::error::Error>> {
+    for path in [
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+        "../../tests/fixtures/test_data/input/trig_stats.ulg",
+    ] {
+        let (unbuffered, unbuffered_reads) = parse_counting(path, 0)?;
+        let (small, small_reads) = parse_counting(path, 256)?;
+        let (large, large_reads) = parse_counting(path, 64 * 1024)?;
+
+        assert_eq!(small, unbuffered, "{path}");
+        assert_eq!(large, unbuffered, "{path}");
+
+        assert!(small_reads < unbuffered_reads, "{path}");
+        assert!(large_reads < small_reads, "{path}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_read_ahead_parse_range() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let len = std::fs::metadata(path)?.len();
+
+    let range = |builder: ULogParserBuilder<CountingReader>| -> Result<Vec<String>, ULogError> {
+        builder
+            .build()?
+            .parse_range(len / 2, len)?
+            .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+            .collect()
+    };
+
+    let reads = Rc::new(Cell::new(0));
+    let unbuffered = range(counting_builder(path, &reads)?)?;
+    let buffered = range(counting_builder(path, &reads)?.read_ahead(4096))?;
+
+    assert!(!unbuffered.is_empty());
+    assert_eq!(buffered, unbuffered);
+
+    Ok(())
+}
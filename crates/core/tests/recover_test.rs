@@ -0,0 +1,154 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const PATH: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn parse(bytes: &[u8], recover: bool) -> Result<Vec<String>, ULogError> {
+    ULogParserBuilder::new(Cursor::new(bytes.to_vec()))
+        .include_timestamp(true)
+        .recover_on_error(recover)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect()
+}
+
+/// Deterministic garbage, from a linear congruential generator.
+fn garbage(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 24) as u8
+        })
+        .collect()
+}
+
+fn is_corrupt(debug: &str) -> bool {
+    debug.starts_with("Corrupt")
+}
+
+/// The number of messages which end before byte `end`.
+fn messages_before(bytes: &[u8], end: usize) -> Result<usize, ULogError> {
+    let messages = parse(&bytes[..end], true)?;
+    Ok(messages.iter().filter(|debug| !is_corrupt(debug)).count())
+}
+
+#[test]
+fn test_recover_valid_log_unchanged() -> Result<(), Box<dyn std::error::Error>> {
+    for path in [PATH, "../../tests/fixtures/test_data/input/trig_stats.ulg"] {
+        let bytes = std::fs::read(path)?;
+        assert_eq!(parse(&bytes, true)?, parse(&bytes, false)?, "{path}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_recover_corrupt_middle() -> Result<(), Box<dyn std::error::Error>> {
+    let original = std::fs::read(PATH)?;
+    let expected = parse(&original, false)?;
+
+    let mut bytes = original.clone();
+    let start = bytes.len() / 2;
+    bytes[start..start + 300].copy_from_slice(&garbage(300));
+
+    assert!(parse(&bytes, false).is_err());
+
+    let messages = parse(&bytes, true)?;
+    let last_marker = messages.iter().rposition(|debug| is_corrupt(debug));
+
+    // Everything before the damage and after it is unchanged.
+    let before = messages_before(&original, start)?;
+    assert!(before > 100);
+    assert!(messages[..before] == expected[..before]);
+
+    let tail = &messages[last_marker.unwrap() + 1..];
+    assert!(tail.len() > 100);
+    assert!(expected.ends_with(tail));
+
+    Ok(())
+}
+
+#[test]
+fn test_recover_undecodable_record_pipelined() -> Result<(), ULogError> {
+    // The size of the undefined `mystery` type is not known, so only decoding the record fails.
+    let log = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .format("odd:uint64_t timestamp;mystery m;")
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "odd")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .data(1, &with_timestamp(110, &[0, 0]))
+        .data(0, &with_timestamp(120, &3.0f32.to_le_bytes()))
+        .into_bytes();
+
+    let pipelined = ULogParserBuilder::new(log.as_slice())
+        .include_timestamp(true)
+        .recover_on_error(true)
+        .pipelined(true)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<Vec<_>, ULogError>>()?;
+
+    assert!(pipelined.iter().any(|debug| is_corrupt(debug)));
+    assert_eq!(pipelined, parse(&log, true)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_recover_truncated_log() -> Result<(), Box<dyn std::error::Error>> {
+    let original = std::fs::read(PATH)?;
+    let expected = parse(&original, false)?;
+
+    // Cut the log in the middle of the message at 90% of the file.
+    let cut = original.len() * 9 / 10;
+    let bytes = &original[..cut];
+    let messages = parse(bytes, true)?;
+
+    let (marker, good) = messages.split_last().unwrap();
+    assert!(is_corrupt(marker));
+    assert!(good.len() > 100);
+    assert!(good == &expected[..good.len()]);
+
+    // The marker covers the rest of the file.
+    let mut last = None;
+    for msg_res in ULogParserBuilder::new(Cursor::new(bytes.to_vec()))
+        .recover_on_error(true)
+        .build()?
+    {
+        last = Some(msg_res?);
+    }
+    let Some(UlogMessage::Corrupt { offset, len }) = last else {
+        panic!("expected a corrupt marker, got {last:?}");
+    };
+    assert_eq!(offset + len, cut as u64);
+
+    Ok(())
+}
+
+#[test]
+fn test_recover_garbage_after_valid_data() -> Result<(), Box<dyn std::error::Error>> {
+    let original = std::fs::read(PATH)?;
+    let expected = parse(&original, false)?;
+
+    let cut = original.len() * 9 / 10;
+    let mut bytes = original[..cut].to_vec();
+    bytes.extend(garbage(original.len() / 10));
+
+    let messages = parse(&bytes, true)?;
+    assert!(messages.iter().any(|debug| is_corrupt(debug)));
+
+    // Every message of the valid part is kept.
+    let before = messages_before(&original, cut)?;
+    assert!(before > 100);
+    assert!(messages[..before] == expected[..before]);
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+fn log() -> SyntheticLog {
+    let x = |v: f32| v.to_le_bytes();
+
+    SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .format("accel:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "accel")
+        .data(0, &with_timestamp(100, &x(1.0)))
+        .data(1, &with_timestamp(150, &x(2.0)))
+        // REMOVE_SUBSCRIPTION of gyro.
+        .message(b'R', &0u16.to_le_bytes())
+        .data(1, &with_timestamp(250, &x(3.0)))
+}
+
+#[test]
+fn test_per_subscription_after_remove_subscription() -> Result<(), ULogError> {
+    // Records logged before their subscription was removed are still attributed to it.
+    let bytes = log().builder().build()?.bytes_per_subscription()?;
+    assert_eq!(bytes.len(), 2);
+    assert_eq!(bytes["gyro"], 12);
+    assert_eq!(bytes["accel"], 24);
+
+    let coverage = log().builder().build()?.coverage()?;
+    assert_eq!(coverage["gyro"].count, 1);
+    assert_eq!(coverage["gyro"].last_timestamp, 100);
+    assert_eq!(coverage["accel"].count, 2);
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("first:uint64_t timestamp;")
+        .format("second:uint64_t timestamp;")
+        .subscription(0, 0, "first")
+        .subscription(0, 1, "second")
+        .data(0, &with_timestamp(100, &[]))
+        .data(1, &with_timestamp(150, &[]))
+        // Equal timestamps are sorted.
+        .data(0, &with_timestamp(150, &[]))
+}
+
+#[test]
+fn test_require_sorted() -> Result<(), ULogError> {
+    let sorted = log();
+    let offset = sorted.len() as u64;
+    // Each topic is sorted on its own, but not the log as a whole.
+    let unsorted = sorted
+        .data(1, &with_timestamp(120, &[]))
+        .data(0, &with_timestamp(200, &[]));
+
+    let mut parser = unsorted.builder().require_sorted(true).build()?;
+    assert!(parser.config().require_sorted);
+
+    let mut records = 0;
+    let err = loop {
+        match parser.next() {
+            Some(Ok(UlogMessage::LoggedData(_))) => records += 1,
+            Some(Ok(_)) => {}
+            Some(Err(err)) => break err,
+            None => panic!("expected an out of order error"),
+        }
+    };
+
+    assert_eq!(records, 3);
+    assert!(
+        matches!(
+            err,
+            ULogError::OutOfOrder {
+                prev: 150,
+                cur: 120,
+                offset: o,
+            } if o == offset
+        ),
+        "{err:?}"
+    );
+
+    // Parsing stops at the error.
+    assert!(parser.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_require_sorted_accepts_sorted_log() -> Result<(), ULogError> {
+    let parser = log()
+        .data(1, &with_timestamp(200, &[]))
+        .builder()
+        .require_sorted(true)
+        .build()?;
+
+    let messages = parser.collect::<Result<Vec<_>, _>>()?;
+    let records = messages
+        .iter()
+        .filter(|msg| matches!(msg, UlogMessage::LoggedData(_)))
+        .count();
+    assert_eq!(records, 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_require_sorted_pipelined() -> Result<(), ULogError> {
+    // The pipeline reads ahead of the record yielded, which is still the one reported.
+    let sorted = log();
+    let offset = sorted.len() as u64;
+    let mut unsorted = sorted.data(1, &with_timestamp(120, &[]));
+    for i in 0..100 {
+        unsorted = unsorted.data(0, &with_timestamp(200 + i, &[]));
+    }
+
+    let mut parser = unsorted
+        .builder()
+        .require_sorted(true)
+        .pipelined(true)
+        .build()?;
+
+    let err = loop {
+        match parser.next() {
+            Some(Ok(_)) => {}
+            Some(Err(err)) => break err,
+            None => panic!("expected an out of order error"),
+        }
+    };
+
+    assert!(
+        matches!(
+            err,
+            ULogError::OutOfOrder {
+                prev: 150,
+                cur: 120,
+                offset: o,
+            } if o == offset
+        ),
+        "{err:?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+fn record(gyro: i16, current: f32, raw: [u8; 2]) -> Vec<u8> {
+    let mut record = gyro.to_le_bytes().to_vec();
+    record.extend(current.to_le_bytes());
+    record.extend(raw);
+    record
+}
+
+fn log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("sensor:uint64_t timestamp;int16_t gyro;float current;uint8_t[2] raw;")
+        .subscription(0, 0, "sensor")
+        .subscription(1, 1, "sensor")
+        .data(0, &with_timestamp(100, &record(i16::MAX, 1.0, [0, 7])))
+        .data(0, &with_timestamp(200, &record(12, f32::MAX, [255, 7])))
+        .data(1, &with_timestamp(250, &record(i16::MAX, 1.0, [7, 7])))
+        .data(0, &with_timestamp(300, &record(i16::MAX, 1.0, [7, 7])))
+        .data(0, &with_timestamp(400, &record(i16::MIN, 1.0, [255, 0])))
+        .data(0, &with_timestamp(500, &record(i16::MAX, 1.0, [7, 7])))
+}
+
+#[test]
+fn test_saturation_report() -> Result<(), ULogError> {
+    let report = log().builder().build()?.saturation_report(&[
+        "sensor.00/gyro",
+        "sensor.00/current",
+        "sensor.00/raw",
+        "sensor.01/gyro",
+        "sensor.00/no_such_field",
+    ])?;
+
+    assert_eq!(report.len(), 5);
+    assert_eq!(report["sensor.00/gyro"], 4);
+    assert_eq!(report["sensor.00/current"], 1);
+    // Each element of an array counts.
+    assert_eq!(report["sensor.00/raw"], 4);
+    assert_eq!(report["sensor.01/gyro"], 1);
+    assert_eq!(report["sensor.00/no_such_field"], 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_saturation_report_requires_subscription() -> Result<(), ULogError> {
+    let result = log().builder().build()?.saturation_report(&["gyro"]);
+    assert!(matches!(result, Err(ULogError::InvalidConfiguration(_))));
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::def::BaseType;
+use yule_log::model::inst::{ArrayMode, FieldValue};
+use yule_log::model::msg::UlogMessage;
+use yule_log::scalar_buffer::ScalarBuffer;
+
+#[test]
+fn test_scalar_buffer_round_trip() -> Result<(), ULogError> {
+    let mut payload = Vec::new();
+    payload.push(200u8);
+    payload.extend((-3i8).to_le_bytes());
+    payload.extend(60_000u16.to_le_bytes());
+    payload.extend((-30_000i16).to_le_bytes());
+    payload.extend(4_000_000_000u32.to_le_bytes());
+    payload.extend((-2_000_000_000i32).to_le_bytes());
+    payload.extend(u64::MAX.to_le_bytes());
+    payload.extend(i64::MIN.to_le_bytes());
+    payload.extend((-1.5f32).to_le_bytes());
+    payload.extend(std::f64::consts::PI.to_le_bytes());
+    payload.extend([1, b'x']);
+    // The array, which is left out, and the nested format.
+    payload.extend([7, 8]);
+    payload.extend(0.25f32.to_le_bytes());
+
+    let mut parser = SyntheticLog::new(0)
+        .format("inner:float z;")
+        .format(
+            "all:uint64_t timestamp;uint8_t a;int8_t b;uint16_t c;int16_t d;uint32_t e;int32_t f;\
+             uint64_t g;int64_t h;float i;double j;bool k;char l;uint8_t[2] m;inner n;",
+        )
+        .subscription(0, 0, "all")
+        .data(0, &with_timestamp(100, &payload))
+        .builder()
+        .include_timestamp(true)
+        .build()?;
+
+    let data = loop {
+        match parser.next() {
+            Some(Ok(UlogMessage::LoggedData(data))) => break data,
+            Some(Ok(_)) => {}
+            other => panic!("expected a record, got {other:?}"),
+        }
+    };
+
+    let buffer = ScalarBuffer::from_format(&data.data);
+
+    let expected: Vec<FieldValue> = data
+        .data
+        .flatten_fields(ArrayMode::Nested)
+        .into_iter()
+        .map(|(_, value)| value)
+        .filter(|value| !matches!(value, FieldValue::ArrayU8(_)))
+        .collect();
+    assert_eq!(buffer.len(), 14);
+    assert_eq!(buffer.iter().collect::<Vec<_>>(), expected);
+
+    assert_eq!(buffer.types[1], BaseType::UINT8);
+    assert_eq!(buffer.get(8), Some(FieldValue::ScalarI64(i64::MIN)));
+    assert_eq!(buffer.get_f64(2), Some(-3.0));
+    assert_eq!(buffer.get_f64(9), Some(-1.5));
+    assert_eq!(buffer.get_f64(13), Some(0.25));
+    assert_eq!(buffer.get_f64(11), None);
+    assert_eq!(buffer.get(14), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_scalar_buffer_reuse() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read("../../tests/fixtures/test_data/input/sample_log_small.ulg")?;
+
+    let mut buffer = ScalarBuffer::new();
+    let mut checked = 0;
+    for msg_res in ULogParserBuilder::new(input.as_slice())
+        .set_subscription_allow_list(["vehicle_status"])
+        .build()?
+    {
+        let UlogMessage::LoggedData(data) = msg_res? else {
+            continue;
+        };
+
+        buffer.fill(&data.data);
+        assert_eq!(buffer.len(), data.data.fields.len());
+        for (index, field) in data.data.fields.iter().enumerate() {
+            // Compared as text, since some values are NaN.
+            assert_eq!(
+                format!("{:?}", buffer.get(index)),
+                format!("{:?}", Some(&field.value))
+            );
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+
+    Ok(())
+}
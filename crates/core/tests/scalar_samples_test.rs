@@ -0,0 +1,37 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+
+fn log() -> SyntheticLog {
+    // Each record holds 1 + 3 + 2 * 2 = 8 values besides its timestamp.
+    let mut record = vec![7];
+    record.extend([1.0f32, 2.0, 3.0].iter().flat_map(|v| v.to_le_bytes()));
+    record.extend([1u16, 2, 3, 4].iter().flat_map(|v| v.to_le_bytes()));
+
+    SyntheticLog::new(0)
+        .format("pair:uint16_t a;uint16_t b;")
+        .format("sample:uint64_t timestamp;uint8_t id;float[3] xyz;pair[2] pairs;")
+        .format("other:uint64_t timestamp;int32_t value;")
+        .subscription(0, 0, "sample")
+        .subscription(0, 1, "other")
+        .data(0, &with_timestamp(100, &record))
+        .data(1, &with_timestamp(150, &5i32.to_le_bytes()))
+        .data(0, &with_timestamp(200, &record))
+}
+
+#[test]
+fn test_total_scalar_samples() -> Result<(), ULogError> {
+    assert_eq!(log().builder().build()?.total_scalar_samples()?, 8 + 1 + 8);
+
+    let with_timestamps = log().builder().include_timestamp(true).build()?;
+    assert_eq!(with_timestamps.total_scalar_samples()?, 9 + 2 + 9);
+
+    let allowed = log()
+        .builder()
+        .set_subscription_allow_list(["other"])
+        .build()?;
+    assert_eq!(allowed.total_scalar_samples()?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_skip_arrays_larger_than() -> Result<(), ULogError> {
+    let mut fields = 7u16.to_le_bytes().to_vec();
+    fields.extend_from_slice(&[0xAB; 10_000]);
+    fields.extend_from_slice(&[1, 2, 3]);
+    fields.extend_from_slice(&2.5f32.to_le_bytes());
+
+    let parser = SyntheticLog::new(0)
+        .format(
+            "camera:uint64_t timestamp;uint16_t id;uint8_t[10000] image;uint8_t[3] rgb;float gain;",
+        )
+        .subscription(0, 0, "camera")
+        .data(0, &with_timestamp(100, &fields))
+        .builder()
+        .skip_arrays_larger_than(16)
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let values: Vec<&FieldValue> = records[0].data.fields.iter().map(|f| &f.value).collect();
+    assert_eq!(
+        values,
+        vec![
+            &FieldValue::ScalarU16(7),
+            &FieldValue::Skipped(10_000),
+            &FieldValue::ArrayU8(vec![1, 2, 3]),
+            &FieldValue::ScalarF32(2.5),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_skip_arrays_recursive_format() -> Result<(), ULogError> {
+    // A format which contains itself has no size, which is reported instead of overflowing.
+    let log = || {
+        SyntheticLog::new(0)
+            .format("link:uint8_t value;link[8] next;")
+            .format("chain:uint64_t timestamp;link[8] links;")
+            .subscription(0, 0, "chain")
+            .data(0, &with_timestamp(100, &[0; 8]))
+    };
+
+    let result = log()
+        .builder()
+        .skip_arrays_larger_than(4)
+        .build()?
+        .collect::<Result<Vec<_>, _>>();
+    assert!(matches!(result, Err(ULogError::ParseError(_))));
+
+    let mut parser = log().builder().build()?;
+    for msg_res in parser.by_ref() {
+        if let UlogMessage::AddSubscription(_) = msg_res? {
+            break;
+        }
+    }
+    let format = parser.formats["chain"].clone();
+    assert!(matches!(
+        parser.format_byte_size(&format),
+        Err(ULogError::ParseError(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("sensor_gyro:uint64_t timestamp;float x;")
+        .format("sensor_gyro_1:uint64_t timestamp;float x;")
+        .format("sensor_accel2:uint64_t timestamp;float x;")
+        .subscription(0, 0, "sensor_gyro")
+        .subscription(0, 1, "sensor_gyro_1")
+        .subscription(0, 2, "sensor_accel2")
+        .data(0, &with_timestamp(10, &1.0f32.to_le_bytes()))
+        .data(1, &with_timestamp(20, &2.0f32.to_le_bytes()))
+        .data(2, &with_timestamp(30, &3.0f32.to_le_bytes()))
+}
+
+fn subscriptions(
+    parser: impl Iterator<Item = Result<UlogMessage, ULogError>>,
+) -> Result<Vec<(String, u8)>, ULogError> {
+    let mut subs = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::AddSubscription(sub) = msg_res? {
+            subs.push((sub.message_name, sub.multi_id));
+        }
+    }
+    Ok(subs)
+}
+
+#[test]
+fn test_split_name_instance() -> Result<(), ULogError> {
+    let subs = subscriptions(log().builder().split_name_instance(true).build()?)?;
+    assert_eq!(
+        subs,
+        vec![
+            ("sensor_gyro".to_string(), 0),
+            ("sensor_gyro".to_string(), 1),
+            ("sensor_accel2".to_string(), 0),
+        ]
+    );
+
+    // Split instances merge with the topic logged under its base name.
+    let bytes = log()
+        .builder()
+        .split_name_instance(true)
+        .build()?
+        .bytes_per_subscription()?;
+    assert_eq!(bytes["sensor_gyro.00"], 12);
+    assert_eq!(bytes["sensor_gyro.01"], 12);
+
+    Ok(())
+}
+
+#[test]
+fn test_split_name_instance_custom_pattern() -> Result<(), ULogError> {
+    let parser = log()
+        .builder()
+        .split_name_instance(true)
+        .name_instance_pattern(r"^(sensor_accel)(\d+)$")
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data.data.to_string());
+        }
+    }
+
+    // The format is only defined under the unsplit name, and is registered under the base name.
+    assert_eq!(
+        records,
+        vec!["sensor_gyro", "sensor_gyro_1", "sensor_accel.02"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_split_name_instance_mismatched_format() -> Result<(), ULogError> {
+    // `sensor_mag_2` is a different message which happens to share the suffix pattern.
+    let log = SyntheticLog::new(0)
+        .format("sensor_mag:uint64_t timestamp;float x;")
+        .format("sensor_mag_2:uint64_t timestamp;float x;float y;")
+        .subscription(0, 0, "sensor_mag")
+        .subscription(0, 1, "sensor_mag_2")
+        .data(0, &with_timestamp(10, &1.0f32.to_le_bytes()))
+        .data(
+            1,
+            &with_timestamp(20, &[2.0f32.to_le_bytes(), 3.0f32.to_le_bytes()].concat()),
+        );
+
+    let subs = subscriptions(log.builder().split_name_instance(true).build()?)?;
+    assert_eq!(
+        subs,
+        vec![
+            ("sensor_mag".to_string(), 0),
+            ("sensor_mag_2".to_string(), 0),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_split_name_instance_invalid_pattern() {
+    let result = log()
+        .builder()
+        .split_name_instance(true)
+        .name_instance_pattern(r"^(.+)_\d+$")
+        .build();
+
+    assert!(matches!(result, Err(ULogError::InvalidConfiguration(_))));
+}
+
+#[test]
+fn test_split_name_instance_disabled() -> Result<(), ULogError> {
+    let subs = subscriptions(log().builder().build()?)?;
+    assert_eq!(subs[1], ("sensor_gyro_1".to_string(), 0));
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "rusqlite")]
+
+use std::fs::File;
+use std::io::BufReader;
+
+use rusqlite::Connection;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::sqlite::export_sqlite;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "../../tests/fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+#[test]
+fn test_export_sqlite() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("sample_log_small.db");
+
+    export_sqlite(ULogParserBuilder::new(open_sample_log()?).build()?, &path)?;
+
+    let coverage = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .coverage()?;
+
+    let conn = Connection::open(&path)?;
+    let tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+        [],
+        |row| row.get(0),
+    )?;
+    assert_eq!(tables as usize, coverage.len());
+
+    for (name, topic) in &coverage {
+        let count: i64 =
+            conn.query_row(&format!("SELECT COUNT(*) FROM \"{name}\""), [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(count as u64, topic.count, "{name}");
+    }
+
+    // Columns are typed, and named by their path.
+    let (first, last): (i64, f64) = conn.query_row(
+        "SELECT MIN(timestamp), MAX(\"output.00\") FROM \"actuator_outputs.01\"",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!(
+        first as u64,
+        coverage["actuator_outputs.01"].first_timestamp
+    );
+    assert!(last.is_finite());
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "futures")]
+
+use std::fs::File;
+use std::io::BufReader;
+
+use futures::executor::block_on;
+use futures::StreamExt;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const SAMPLE_LOG: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn parser() -> Result<yule_log::parser::ULogParser<BufReader<File>>, ULogError> {
+    ULogParserBuilder::new(BufReader::new(File::open(SAMPLE_LOG)?)).build()
+}
+
+#[test]
+fn test_stream_matches_iteration() -> Result<(), ULogError> {
+    let expected = parser()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect::<Result<Vec<_>, ULogError>>()?;
+
+    let streamed = block_on(
+        parser()?
+            .into_stream()
+            .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+            .collect::<Vec<_>>(),
+    )
+    .into_iter()
+    .collect::<Result<Vec<_>, ULogError>>()?;
+
+    assert!(!expected.is_empty());
+    assert_eq!(streamed, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_combinators() -> Result<(), ULogError> {
+    let expected = parser()?
+        .filter(|msg_res| matches!(msg_res, Ok(UlogMessage::LoggedData(_))))
+        .count();
+
+    let records = block_on(
+        parser()?
+            .into_stream()
+            .filter(|msg_res| {
+                futures::future::ready(matches!(msg_res, Ok(UlogMessage::LoggedData(_))))
+            })
+            .count(),
+    );
+    assert_eq!(records, expected);
+
+    // Dropping the stream early stops the parsing thread.
+    let first = block_on(parser()?.into_stream().take(3).collect::<Vec<_>>());
+    assert_eq!(first.len(), 3);
+
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_stream_in_tokio_runtime() -> Result<(), ULogError> {
+    let expected = parser()?.count();
+
+    // Parsed on the runtime's blocking pool.
+    let streamed = parser()?.into_stream().collect::<Vec<_>>().await;
+    assert_eq!(streamed.len(), expected);
+
+    Ok(())
+}
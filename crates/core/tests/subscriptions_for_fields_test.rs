@@ -0,0 +1,110 @@
+mod common;
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use common::SyntheticLog;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn subscriptions_for_fields(log: SyntheticLog, fields: &[&str]) -> Result<Vec<String>, ULogError> {
+    let mut topics: Vec<_> = log
+        .builder()
+        .build()?
+        .subscriptions_for_fields(fields)?
+        .into_iter()
+        .collect();
+    topics.sort();
+
+    Ok(topics)
+}
+
+#[test]
+fn test_subscriptions_for_fields() -> Result<(), ULogError> {
+    let log = || {
+        SyntheticLog::new(0)
+            .format("vec3:float x;float y;float z;")
+            .format("sensor_accel:uint64_t timestamp;vec3 accel;float temperature;")
+            .format("sensor_gyro:uint64_t timestamp;vec3 accel;")
+            .format("battery_status:uint64_t timestamp;float voltage;float temperature;")
+            .format("vehicle_status:uint64_t timestamp;uint8_t nav_state;")
+            .format("unsubscribed:uint64_t timestamp;uint8_t nav_state;float voltage;")
+            .subscription(0, 1, "sensor_accel")
+            .subscription(0, 2, "sensor_gyro")
+            .subscription(0, 3, "battery_status")
+            .subscription(0, 4, "vehicle_status")
+    };
+
+    // `sensor_accel` alone has both `accel/x` and `temperature`.
+    assert_eq!(
+        subscriptions_for_fields(log(), &["accel/x", "temperature"])?,
+        ["sensor_accel"]
+    );
+    assert_eq!(
+        subscriptions_for_fields(log(), &["voltage", "nav_state", "missing"])?,
+        ["battery_status", "vehicle_status"]
+    );
+    assert!(subscriptions_for_fields(log(), &["missing"])?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_subscriptions_for_fields_drops_redundant_topics() -> Result<(), ULogError> {
+    // `middle` has the most fields, so is picked first, but `left` and `right` are needed for
+    // `a` and `f` anyway, and cover everything `middle` does.
+    let log = SyntheticLog::new(0)
+        .format("left:uint64_t timestamp;uint8_t a;uint8_t b;uint8_t c;")
+        .format("middle:uint64_t timestamp;uint8_t b;uint8_t c;uint8_t d;uint8_t e;")
+        .format("right:uint64_t timestamp;uint8_t d;uint8_t e;uint8_t f;")
+        .subscription(0, 1, "left")
+        .subscription(0, 2, "middle")
+        .subscription(0, 3, "right");
+
+    assert_eq!(
+        subscriptions_for_fields(log, &["a", "b", "c", "d", "e", "f"])?,
+        ["left", "right"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_subscriptions_for_fields_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let parser = || -> Result<_, ULogError> {
+        ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()
+    };
+    let fields = ["nav_state", "arming_state", "voltage_v", "control"];
+
+    let topics = parser()?.subscriptions_for_fields(&fields)?;
+    assert!(!topics.is_empty());
+
+    // Every field is covered, and every topic covers a field no other topic of the set does.
+    let mut fields_by_topic: Vec<(String, HashSet<&str>)> = Vec::new();
+    for topic in &topics {
+        fields_by_topic.push((topic.clone(), HashSet::new()));
+    }
+    for field in fields {
+        let with_field = parser()?.topics_with_field(field)?;
+        assert!(topics.iter().any(|topic| with_field.contains(topic)) || with_field.is_empty());
+
+        for (topic, covered) in &mut fields_by_topic {
+            if with_field.contains(topic) {
+                covered.insert(field);
+            }
+        }
+    }
+
+    for (topic, covered) in &fields_by_topic {
+        let others: HashSet<&str> = fields_by_topic
+            .iter()
+            .filter(|(other, _)| other != topic)
+            .flat_map(|(_, covered)| covered.iter().copied())
+            .collect();
+        assert!(!covered.is_subset(&others), "{topic} is redundant");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn synthetic_log() -> SyntheticLog {
+    let mut log = SyntheticLog::new(0)
+        .format("a:uint64_t timestamp;uint32_t value;")
+        .format("b:uint64_t timestamp;")
+        .subscription(0, 1, "a")
+        .subscription(0, 2, "b");
+
+    for i in 1..=10u64 {
+        let value = (i as u32).to_le_bytes();
+        log = log
+            .data(1, &with_timestamp(i * 100, &value))
+            .data(2, &with_timestamp(i * 100 + 50, &[]));
+    }
+    log
+}
+
+fn in_windows(timestamp: u64) -> bool {
+    (200..=300).contains(&timestamp) || (700..=800).contains(&timestamp)
+}
+
+#[test]
+fn test_filter_time_windows() -> Result<(), ULogError> {
+    let parser = synthetic_log().builder().filter_time(in_windows).build()?;
+    assert!(parser.config().time_filter);
+
+    let records: Vec<_> = parser
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(Ok((data.msg_id, data.timestamp))),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(
+        records,
+        [(1, 200), (2, 250), (1, 300), (1, 700), (2, 750), (1, 800)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_time_ignored_records() -> Result<(), ULogError> {
+    // Records outside the allow list are filtered before being yielded as `Ignored`.
+    let parser = synthetic_log()
+        .builder()
+        .set_subscription_allow_list(["a"])
+        .filter_time(|timestamp| timestamp % 200 == 0)
+        .build()?;
+
+    let mut decoded = Vec::new();
+    let mut ignored = 0;
+    for msg_res in parser {
+        match msg_res? {
+            UlogMessage::LoggedData(data) => decoded.push(data.timestamp),
+            UlogMessage::Ignored { .. } => ignored += 1,
+            _ => {}
+        }
+    }
+
+    assert_eq!(decoded, [200, 400, 600, 800, 1000]);
+    assert_eq!(ignored, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_time_legacy_timestamp() -> Result<(), ULogError> {
+    let mut log = SyntheticLog::new(0)
+        .format("legacy_status:uint32_t timestamp;float x;")
+        .subscription(0, 1, "legacy_status");
+    for i in 1..=5u32 {
+        let mut payload = (i * 100).to_le_bytes().to_vec();
+        payload.extend_from_slice(&1.5f32.to_le_bytes());
+        log = log.data(1, &payload);
+    }
+
+    let timestamps: Vec<u64> = log
+        .builder()
+        .filter_time(|timestamp| timestamp >= 300)
+        .build()?
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::LoggedData(data)) => Some(Ok(data.timestamp)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(timestamps, [300, 400, 500]);
+
+    Ok(())
+}
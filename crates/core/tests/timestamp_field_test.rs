@@ -0,0 +1,37 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_timestamp_field() -> Result<(), ULogError> {
+    let mut fields = 1.5f32.to_le_bytes().to_vec();
+    fields.extend_from_slice(&950u64.to_le_bytes());
+    fields.extend_from_slice(&(-1i64).to_le_bytes());
+
+    let parser = SyntheticLog::new(0)
+        .format("sensor_gyro:uint64_t timestamp;float x;uint64_t timestamp_sample;int64_t offset;")
+        .subscription(0, 0, "sensor_gyro")
+        .data(0, &with_timestamp(1_000, &fields))
+        .builder()
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let record = &records[0];
+    assert_eq!(record.timestamp_field("timestamp"), Some(1_000));
+    assert_eq!(record.timestamp_field("timestamp_sample"), Some(950));
+
+    // Negative values and non-integer fields are not timestamps.
+    assert_eq!(record.timestamp_field("offset"), None);
+    assert_eq!(record.timestamp_field("x"), None);
+    assert_eq!(record.timestamp_field("missing"), None);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn synthetic_log() -> SyntheticLog {
+    SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("sensor_accel:uint64_t timestamp;vec3 accel;uint32_t value;")
+        .format("battery_status:uint64_t timestamp;uint32_t value;")
+        .format("vehicle_status:uint64_t timestamp;uint8_t nav_state;")
+        .format("unsubscribed:uint64_t timestamp;uint32_t value;")
+        .subscription(0, 1, "sensor_accel")
+        .subscription(0, 2, "battery_status")
+        .subscription(1, 3, "battery_status")
+        .data(2, &with_timestamp(100, &1u32.to_le_bytes()))
+        .subscription(0, 4, "vehicle_status")
+        .data(4, &with_timestamp(200, &[3]))
+}
+
+#[test]
+fn test_topics_with_field() -> Result<(), ULogError> {
+    let topics = synthetic_log()
+        .builder()
+        .build()?
+        .topics_with_field("value")?;
+    assert_eq!(topics, ["battery_status", "sensor_accel"]);
+
+    // Subscriptions added in the data section are included.
+    let topics = synthetic_log()
+        .builder()
+        .build()?
+        .topics_with_field("timestamp")?;
+    assert_eq!(topics, ["battery_status", "sensor_accel", "vehicle_status"]);
+
+    let topics = synthetic_log()
+        .builder()
+        .build()?
+        .topics_with_field("accel/y")?;
+    assert_eq!(topics, ["sensor_accel"]);
+
+    let topics = synthetic_log()
+        .builder()
+        .build()?
+        .topics_with_field("missing")?;
+    assert!(topics.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_topics_with_timestamp_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let reader = || -> std::io::Result<BufReader<File>> { Ok(BufReader::new(File::open(path)?)) };
+
+    let subscribed: BTreeSet<String> = ULogParserBuilder::new(reader()?)
+        .build()?
+        .filter_map(|msg_res| match msg_res {
+            Ok(UlogMessage::AddSubscription(sub)) => Some(sub.message_name),
+            _ => None,
+        })
+        .collect();
+
+    let topics = ULogParserBuilder::new(reader()?)
+        .build()?
+        .topics_with_field("timestamp")?;
+
+    assert_eq!(topics, subscribed.into_iter().collect::<Vec<_>>());
+
+    Ok(())
+}
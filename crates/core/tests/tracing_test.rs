@@ -0,0 +1,130 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use common::{with_timestamp, SyntheticLog};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Registry;
+use yule_log::errors::ULogError;
+
+/// An event captured by `CaptureLayer`, with its fields rendered as strings.
+#[derive(Debug)]
+struct CapturedEvent {
+    span: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl CapturedEvent {
+    fn message(&self) -> &str {
+        self.field("message").unwrap_or_default()
+    }
+
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor(Vec<(String, String)>);
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name().to_owned(), format!("{value:?}")));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_owned(), value.to_owned()));
+    }
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+    span_fields: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        self.span_fields.lock().unwrap().extend(visitor.0);
+    }
+
+    fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        self.span_fields.lock().unwrap().extend(visitor.0);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push(CapturedEvent {
+            span: ctx.event_span(event).map(|span| span.name().to_owned()),
+            fields: visitor.0,
+        });
+    }
+}
+
+#[test]
+fn test_tracing_events() -> Result<(), ULogError> {
+    let layer = CaptureLayer::default();
+    let subscriber = Registry::default().with(layer.clone());
+
+    let bytes = SyntheticLog::new(0)
+        .format("topic:uint64_t timestamp;")
+        .subscription(0, 7, "topic")
+        .data(7, &with_timestamp(100, &[]))
+        .dropout(42)
+        .data(0xFFFF, &with_timestamp(200, &[]))
+        .into_bytes();
+
+    tracing::subscriber::with_default(subscriber, || -> Result<(), ULogError> {
+        let parser =
+            yule_log::builder::ULogParserBuilder::new(std::io::Cursor::new(bytes)).build()?;
+        let (_, errors) = parser.parse_all();
+        assert_eq!(errors.len(), 1);
+        Ok(())
+    })?;
+
+    let events = layer.events.lock().unwrap();
+    assert!(events
+        .iter()
+        .all(|event| event.span.as_deref() == Some("ulog_file")));
+
+    let subscription = events
+        .iter()
+        .find(|event| event.message() == "subscription registered")
+        .expect("subscription event");
+    assert_eq!(subscription.field("msg_id"), Some("7"));
+    assert_eq!(subscription.field("message_name"), Some("topic"));
+
+    let dropout = events
+        .iter()
+        .find(|event| event.message() == "dropout")
+        .expect("dropout event");
+    assert_eq!(dropout.field("duration_ms"), Some("42"));
+
+    let failure = events
+        .iter()
+        .find(|event| event.message() == "failed to parse message")
+        .expect("parse failure event");
+    assert!(failure.field("offset").is_some());
+    assert!(failure.field("error").is_some());
+
+    let span_fields = layer.span_fields.lock().unwrap();
+    assert!(span_fields.contains(&("version".to_owned(), "1".to_owned())));
+    assert!(span_fields.iter().any(|(key, _)| key == "bytes_read"));
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::inst::FieldValue;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_transform_radians_to_degrees() -> Result<(), ULogError> {
+    let mut fields = Vec::new();
+    for value in [std::f32::consts::PI, std::f32::consts::FRAC_PI_2, 0.0] {
+        fields.extend_from_slice(&value.to_le_bytes());
+    }
+    fields.extend_from_slice(&std::f32::consts::FRAC_PI_2.to_le_bytes());
+    fields.extend_from_slice(&1500u16.to_le_bytes());
+    fields.extend_from_slice(&[0, 0]);
+
+    let parser = SyntheticLog::new(0)
+        .format("vec3:float x;float y;float z;")
+        .format("attitude:uint64_t timestamp;vec3 rate;float yaw;uint16_t altitude_mm;uint8_t[2] _padding0;")
+        .subscription(0, 0, "attitude")
+        .data(0, &with_timestamp(100, &fields))
+        .builder()
+        .transform("attitude", "yaw", f64::to_degrees)
+        .transform("attitude", "rate/x", f64::to_degrees)
+        .transform("attitude", "altitude_mm", |mm| mm / 1000.0)
+        .build()?;
+
+    let mut records = Vec::new();
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(data) = msg_res? {
+            records.push(data);
+        }
+    }
+
+    let value = |path| records[0].data.find_field(path).unwrap().value.clone();
+    assert_eq!(value("yaw"), FieldValue::ScalarF32(90.0));
+    assert_eq!(value("rate/x"), FieldValue::ScalarF32(180.0));
+    // Untransformed fields keep their logged values.
+    assert_eq!(
+        value("rate/y"),
+        FieldValue::ScalarF32(std::f32::consts::FRAC_PI_2)
+    );
+    // Integer fields become doubles.
+    assert_eq!(value("altitude_mm"), FieldValue::ScalarF64(1.5));
+
+    Ok(())
+}
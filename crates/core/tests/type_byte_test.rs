@@ -0,0 +1,108 @@
+mod common;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::writer::ULogWriter;
+
+/// The name of the variant of a message, from its `Debug` representation.
+fn variant(msg: &UlogMessage) -> String {
+    let debug = format!("{msg:?}");
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_type_byte() -> Result<(), ULogError> {
+    let mut tagged = vec![b'6'];
+    tagged.extend_from_slice(&7u16.to_le_bytes());
+    tagged.extend_from_slice(&200u64.to_le_bytes());
+    tagged.extend_from_slice(b"tagged");
+
+    let mut default_parameter = vec![1, 17];
+    default_parameter.extend_from_slice(b"int32_t SYS_COUNT");
+    default_parameter.extend_from_slice(&3i32.to_le_bytes());
+
+    let parser = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .format("accel:uint64_t timestamp;float x;")
+        .info("char[3] sys_name", b"PX4")
+        .multi_info(false, "char[2] perf", b"ok")
+        .parameter("int32_t SYS_AUTOSTART", &4001i32.to_le_bytes())
+        .message(b'Q', &default_parameter)
+        .subscription(0, 0, "gyro")
+        .subscription(0, 1, "accel")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .data(1, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .logging(b'6', 150, "hello")
+        .message(b'C', &tagged)
+        .dropout(10)
+        .message(b'S', &[0x2F, 0x73, 0x13, 0x20, 0x25, 0x0C, 0xBB, 0x12])
+        .builder()
+        .include_header(true)
+        .set_subscription_allow_list(["gyro"])
+        .build()?;
+
+    let mut seen = Vec::new();
+    for msg_res in parser {
+        let msg = msg_res?;
+        seen.push((variant(&msg), msg.type_byte()));
+    }
+
+    let expected = [
+        ("Header", None),
+        ("FlagBits", Some(b'B')),
+        ("FormatDefinition", Some(b'F')),
+        ("FormatDefinition", Some(b'F')),
+        ("Info", Some(b'I')),
+        ("MultiInfo", Some(b'M')),
+        ("Parameter", Some(b'P')),
+        ("DefaultParameter", Some(b'Q')),
+        ("AddSubscription", Some(b'A')),
+        ("AddSubscription", Some(b'A')),
+        ("LoggedData", Some(b'D')),
+        ("Ignored", Some(b'D')),
+        ("LoggedString", Some(b'L')),
+        ("LoggedString", Some(b'C')),
+        ("DropoutMark", Some(b'O')),
+        ("Unhandled", Some(b'S')),
+    ];
+    let expected: Vec<(String, Option<u8>)> = expected
+        .iter()
+        .map(|(name, byte)| ((*name).to_string(), *byte))
+        .collect();
+    assert_eq!(seen, expected);
+
+    let marker = UlogMessage::Corrupt { offset: 0, len: 1 };
+    assert_eq!(marker.type_byte(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_tagged_string_round_trip() -> Result<(), ULogError> {
+    let mut tagged = vec![b'4'];
+    tagged.extend_from_slice(&7u16.to_le_bytes());
+    tagged.extend_from_slice(&200u64.to_le_bytes());
+    tagged.extend_from_slice(b"tagged");
+
+    // Tagged strings are decoded as `LoggedString`, and written back with their tag.
+    let input = SyntheticLog::new(0).message(b'C', &tagged).into_bytes();
+
+    let mut writer = ULogWriter::new(Vec::new());
+    for msg_res in SyntheticLog::new(0)
+        .message(b'C', &tagged)
+        .builder()
+        .include_header(true)
+        .build()?
+    {
+        writer.write_message(&msg_res?)?;
+    }
+
+    assert_eq!(writer.finish()?, input);
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_type_timeline() -> Result<(), ULogError> {
+    let x = |v: f32| v.to_le_bytes();
+
+    let timeline = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &x(1.0)))
+        .logging(b'6', 150, "armed")
+        .data(0, &with_timestamp(900, &x(2.0)))
+        .parameter("float MC_ROLL_P", &4.5f32.to_le_bytes())
+        .dropout(20)
+        .data(0, &with_timestamp(2_100, &x(3.0)))
+        .data(0, &with_timestamp(2_500, &x(4.0)))
+        .logging(b'6', 2_600, "landed")
+        .builder()
+        .build()?
+        .type_timeline(1_000)?;
+
+    let expected = BTreeMap::from([
+        (
+            0,
+            BTreeMap::from([
+                (b'B', 1),
+                (b'F', 1),
+                (b'A', 1),
+                (b'D', 2),
+                (b'L', 1),
+                (b'P', 1),
+                (b'O', 1),
+            ]),
+        ),
+        // Nothing was logged between 1000 and 2000.
+        (2_000, BTreeMap::from([(b'D', 2), (b'L', 1)])),
+    ]);
+    assert_eq!(timeline, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_type_timeline_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read("../../tests/fixtures/test_data/input/sample_log_small.ulg")?;
+
+    let timeline = ULogParserBuilder::new(input.as_slice())
+        .build()?
+        .type_timeline(1_000_000)?;
+
+    let records: u64 = ULogParserBuilder::new(input.as_slice())
+        .build()?
+        .coverage()?
+        .values()
+        .map(|topic| topic.count)
+        .sum();
+    let counted: u64 = timeline
+        .values()
+        .filter_map(|counts| counts.get(&b'D'))
+        .sum();
+    assert_eq!(counted, records);
+    assert!(timeline.keys().all(|start| start % 1_000_000 == 0));
+    assert_eq!(
+        timeline
+            .values()
+            .filter_map(|counts| counts.get(&b'O'))
+            .sum::<u64>(),
+        1
+    );
+
+    assert!(matches!(
+        ULogParserBuilder::new(input.as_slice())
+            .build()?
+            .type_timeline(0),
+        Err(ULogError::InvalidConfiguration(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_type_timeline_legacy_timestamp() -> Result<(), ULogError> {
+    // A `uint32_t` timestamp is followed directly by the fields.
+    let record = |timestamp: u32, x: f32| [timestamp.to_le_bytes(), x.to_le_bytes()].concat();
+
+    let timeline = SyntheticLog::new(0)
+        .format("legacy_status:uint32_t timestamp;float x;")
+        .subscription(0, 0, "legacy_status")
+        .data(0, &record(100, 1.0))
+        .data(0, &record(1_500, 2.0))
+        .data(0, &record(1_700, 3.0))
+        .builder()
+        .build()?
+        .type_timeline(1_000)?;
+
+    let expected = BTreeMap::from([
+        (
+            0,
+            BTreeMap::from([(b'B', 1), (b'F', 1), (b'A', 1), (b'D', 1)]),
+        ),
+        (1_000, BTreeMap::from([(b'D', 2)])),
+    ]);
+    assert_eq!(timeline, expected);
+
+    Ok(())
+}
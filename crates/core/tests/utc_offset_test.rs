@@ -0,0 +1,48 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use common::SyntheticLog;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn utc_offset_us(log: SyntheticLog) -> Result<Option<i64>, ULogError> {
+    let mut parser = log.builder().build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    Ok(parser.utc_offset_us())
+}
+
+#[test]
+fn test_utc_offset_fixture() -> Result<(), ULogError> {
+    let path = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+    let mut parser = ULogParserBuilder::new(BufReader::new(File::open(path)?)).build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    // The log was recorded with the offset left at UTC.
+    assert_eq!(parser.utc_offset_us(), Some(0));
+
+    Ok(())
+}
+
+#[test]
+fn test_utc_offset_sign_and_magnitude() -> Result<(), ULogError> {
+    // UTC-5 and UTC+5:30, logged in seconds.
+    let west = SyntheticLog::new(0).info("int32_t time_ref_utc", &(-18_000i32).to_le_bytes());
+    assert_eq!(utc_offset_us(west)?, Some(-18_000_000_000));
+
+    let east = SyntheticLog::new(0).info("int32_t time_ref_utc", &19_800i32.to_le_bytes());
+    assert_eq!(utc_offset_us(east)?, Some(19_800_000_000));
+
+    assert_eq!(utc_offset_us(SyntheticLog::new(0))?, None);
+
+    let text = SyntheticLog::new(0).info("char[3] time_ref_utc", b"+05");
+    assert_eq!(utc_offset_us(text)?, None);
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use common::SyntheticLog;
+use yule_log::errors::ULogError;
+
+#[test]
+fn test_uuid_stored_as_bytes() -> Result<(), ULogError> {
+    let mut parser = SyntheticLog::new(0)
+        .info("uint8_t[4] log_uuid", &[0xde, 0xad, 0x00, 0x0f])
+        .info("char[5] sys_uuid", b"ab-12")
+        .builder()
+        .build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(parser.log_uuid().as_deref(), Some("dead000f"));
+    assert_eq!(parser.sys_uuid().as_deref(), Some("ab-12"));
+
+    Ok(())
+}
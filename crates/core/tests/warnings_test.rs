@@ -0,0 +1,126 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::parser::BadTimestampPolicy;
+use yule_log::warnings::WarningKind;
+
+const DATA_APPENDED: [u8; 8] = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// A log with a reused msg_id, an unknown message type, a record with an implausible timestamp,
+/// and a message cut off by appended data.  Returns the log and the offsets of the issues.
+fn lenient_log(appended_offset: u64) -> (Vec<u8>, [usize; 4]) {
+    let log = SyntheticLog::with_flag_bits(0, [0; 8], DATA_APPENDED, [appended_offset, 0, 0])
+        .format("a:uint64_t timestamp;")
+        .format("b:uint64_t timestamp;")
+        .subscription(0, 1, "a")
+        .data(1, &with_timestamp(100_000_000, &[]));
+    let duplicate = log.len();
+    let log = log.subscription(0, 1, "b");
+    let unknown = log.len();
+    let log = log.message(b'Z', &[1, 2, 3]);
+    let implausible = log.len();
+    let log = log.data(1, &with_timestamp(0, &[]));
+    let truncated = log.len();
+
+    let mut bytes = log.data(1, &with_timestamp(100_000_100, &[])).into_bytes();
+    bytes.truncate(truncated + 5);
+
+    (bytes, [duplicate, unknown, implausible, truncated])
+}
+
+#[test]
+fn test_warnings_lenient_issues() -> Result<(), ULogError> {
+    // The flag bits have a fixed size, so the offset can be found with a placeholder.
+    let appended_offset = lenient_log(0).0.len() as u64;
+    let (mut bytes, offsets) = lenient_log(appended_offset);
+    bytes.extend_from_slice(b"appended data");
+
+    let mut parser = ULogParserBuilder::new(Cursor::new(bytes))
+        .on_bad_timestamp(BadTimestampPolicy::Skip)
+        .collect_warnings(true)
+        .build()?;
+    assert!(parser.config().collect_warnings);
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let warnings: Vec<_> = parser
+        .warnings()
+        .iter()
+        .map(|warning| (warning.kind, warning.offset as usize))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (WarningKind::DuplicateSubscription, offsets[0]),
+            (WarningKind::UnknownMessageType, offsets[1]),
+            (WarningKind::ImplausibleTimestamp, offsets[2]),
+            (WarningKind::TruncatedMessage, offsets[3]),
+        ]
+    );
+
+    let duplicate = &parser.warnings()[0];
+    assert!(duplicate.description.contains("msg_id 1"));
+    assert_eq!(
+        duplicate.to_string(),
+        format!("{} (at offset {})", duplicate.description, offsets[0])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_warnings_not_collected_by_default() -> Result<(), ULogError> {
+    let (bytes, _) = lenient_log(0);
+
+    let mut parser = ULogParserBuilder::new(Cursor::new(bytes))
+        .on_bad_timestamp(BadTimestampPolicy::Skip)
+        .recover_on_error(true)
+        .build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert!(parser.warnings().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_warnings_skipped_bytes() -> Result<(), ULogError> {
+    let log = SyntheticLog::new(0)
+        .format("a:uint64_t timestamp;")
+        .subscription(0, 1, "a")
+        .data(1, &with_timestamp(100, &[]));
+    let corrupt = log.len();
+    let mut bytes = log.into_bytes();
+
+    let rest = SyntheticLog::new(0)
+        .data(1, &with_timestamp(200, &[]))
+        .into_bytes();
+    // Garbage, followed by the record of `rest`, after its 16 byte file header and 43 byte flag
+    // bits message.
+    bytes.extend_from_slice(&[0xFF, 0xFF, 0xEE, 0xDD, 0xCC]);
+    bytes.extend_from_slice(&rest[16 + 43..]);
+
+    let mut parser = ULogParserBuilder::new(Cursor::new(bytes))
+        .recover_on_error(true)
+        .collect_warnings(true)
+        .build()?;
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedBytes);
+    assert_eq!(warnings[0].offset as usize, corrupt);
+    assert_eq!(warnings[0].description, "Skipped 5 corrupt bytes");
+
+    Ok(())
+}
@@ -0,0 +1,433 @@
+mod common;
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use common::{with_timestamp, SyntheticLog};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+use yule_log::parser::BadTimestampPolicy;
+use yule_log::writer::{strip_fields, ULogWriter};
+
+const SAMPLE_LOG: &str = "../../tests/fixtures/test_data/input/sample_log_small.ulg";
+
+fn parse_debug(builder: ULogParserBuilder<impl std::io::Read>) -> Result<Vec<String>, ULogError> {
+    builder
+        .include_header(true)
+        .build()?
+        .map(|msg_res| msg_res.map(|msg| format!("{msg:?}")))
+        .collect()
+}
+
+fn write_all(messages: impl IntoIterator<Item = UlogMessage>) -> Result<Vec<u8>, ULogError> {
+    let mut writer = ULogWriter::new(Vec::new());
+    for msg in messages {
+        writer.write_message(&msg)?;
+    }
+    writer.finish()
+}
+
+#[test]
+fn test_writer_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read(SAMPLE_LOG)?;
+
+    let messages = ULogParserBuilder::new(input.as_slice())
+        .include_header(true)
+        .include_timestamp(true)
+        .include_padding(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = write_all(messages)?;
+
+    assert_eq!(output, input);
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_round_trip_default_options() -> Result<(), Box<dyn std::error::Error>> {
+    // The timestamps and padding left out by the parser are put back.
+    let input = std::fs::read(SAMPLE_LOG)?;
+
+    let messages = ULogParserBuilder::new(input.as_slice())
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = write_all(messages)?;
+
+    assert_eq!(output, input);
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_rejects_short_records() -> Result<(), ULogError> {
+    let messages = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;float y;")
+        .subscription(0, 0, "gyro")
+        .data(
+            0,
+            &with_timestamp(100, &[1.0f32.to_le_bytes(), 2.0f32.to_le_bytes()].concat()),
+        )
+        .builder()
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The same messages with a field missing from the record.
+    let truncated = messages.into_iter().map(|mut msg| {
+        if let UlogMessage::LoggedData(data) = &mut msg {
+            data.data.fields.retain(|field| field.name != "y");
+        }
+        msg
+    });
+    assert!(matches!(
+        write_all(truncated),
+        Err(ULogError::LayoutMismatch(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_round_trip_ignored() -> Result<(), Box<dyn std::error::Error>> {
+    // Records outside the allow list are written back from their raw bytes.
+    fn parse<R: std::io::Read>(reader: R) -> ULogParserBuilder<R> {
+        ULogParserBuilder::new(reader)
+            .set_subscription_allow_list(["vehicle_status"])
+            .include_timestamp(true)
+            .include_padding(true)
+    }
+
+    let messages = parse(BufReader::new(File::open(SAMPLE_LOG)?))
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+    let output = write_all(messages)?;
+
+    assert_eq!(
+        parse_debug(parse(output.as_slice()))?,
+        parse_debug(parse(BufReader::new(File::open(SAMPLE_LOG)?)))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_strips_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let mut stripped_ids = HashSet::new();
+    let mut messages = Vec::new();
+
+    for msg_res in ULogParserBuilder::new(BufReader::new(File::open(SAMPLE_LOG)?))
+        .include_header(true)
+        .include_timestamp(true)
+        .include_padding(true)
+        .build()?
+    {
+        match msg_res? {
+            UlogMessage::AddSubscription(sub) if sub.message_name == "actuator_outputs" => {
+                stripped_ids.insert(sub.msg_id);
+            }
+            UlogMessage::LoggedData(data) if stripped_ids.contains(&data.msg_id) => {}
+            msg => messages.push(msg),
+        }
+    }
+
+    let output = write_all(messages)?;
+
+    let mut expected = ULogParserBuilder::new(BufReader::new(File::open(SAMPLE_LOG)?))
+        .build()?
+        .coverage()?;
+    expected.retain(|name, _| !name.starts_with("actuator_outputs"));
+    assert_eq!(stripped_ids.len(), 2);
+
+    assert_eq!(
+        ULogParserBuilder::new(output.as_slice())
+            .build()?
+            .coverage()?,
+        expected
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_rejects_invalid_streams() -> Result<(), ULogError> {
+    let messages = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;")
+        .subscription(0, 0, "gyro")
+        .data(0, &with_timestamp(100, &1.0f32.to_le_bytes()))
+        .builder()
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The same messages without the subscription.
+    let without_subscription = messages
+        .into_iter()
+        .filter(|msg| !matches!(msg, UlogMessage::AddSubscription(_)));
+    assert!(matches!(
+        write_all(without_subscription),
+        Err(ULogError::UndefinedSubscription(0))
+    ));
+
+    let mut writer = ULogWriter::new(Vec::new());
+    let sync = UlogMessage::Unhandled {
+        msg_type: b'S',
+        message_contents: vec![0x2F, 0x73, 0x13, 0x20, 0x25, 0x0C, 0xBB, 0x12],
+    };
+    assert!(matches!(
+        writer.write_message(&sync),
+        Err(ULogError::InvalidMessageOrder(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_strip_fields() -> Result<(), ULogError> {
+    let input = SyntheticLog::new(0)
+        .format("gyro:uint64_t timestamp;float x;float y;float z;")
+        .subscription(0, 0, "gyro")
+        .data(
+            0,
+            &with_timestamp(
+                100,
+                &[1.0f32, 2.0, 3.0]
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes())
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .builder()
+        .build()?;
+
+    let output = strip_fields(input, ULogWriter::new(Vec::new()), &["gyro.y"])?;
+
+    let mut formats = Vec::new();
+    let mut records = Vec::new();
+    for msg_res in ULogParserBuilder::new(output.as_slice()).build()? {
+        match msg_res? {
+            UlogMessage::FormatDefinition(format) => formats.push(format),
+            UlogMessage::LoggedData(data) => records.push(data),
+            _ => {}
+        }
+    }
+
+    let field_names: Vec<&str> = formats[0].fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(field_names, ["timestamp", "x", "z"]);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].timestamp, 100);
+    let values: Vec<String> = records[0]
+        .data
+        .fields
+        .iter()
+        .map(|f| format!("{}={:?}", f.name, f.value))
+        .collect();
+    assert_eq!(values, ["x=ScalarF32(1.0)", "z=ScalarF32(3.0)"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_strip_fields_resets_decoding_options() -> Result<(), ULogError> {
+    let log = || {
+        SyntheticLog::new(0)
+            .format("gyro_1:uint64_t timestamp;float x;uint8_t[8] raw;float y;")
+            .subscription(0, 0, "gyro_1")
+            .data(
+                0,
+                &with_timestamp(
+                    100,
+                    &[&1.0f32.to_le_bytes()[..], &[7; 8], &2.0f32.to_le_bytes()].concat(),
+                ),
+            )
+    };
+
+    let plain = strip_fields(
+        log().builder().build()?,
+        ULogWriter::new(Vec::new()),
+        &["gyro_1.y"],
+    )?;
+
+    // The options of the parser do not leak into the copy.
+    let transformed = log()
+        .builder()
+        .transform("gyro_1", "x", |x| x * 10.0)
+        .skip_arrays_larger_than(4)
+        .split_name_instance(true)
+        .filter_time(|timestamp| timestamp > 1_000)
+        .add_index_field(true)
+        .build()?;
+    let output = strip_fields(transformed, ULogWriter::new(Vec::new()), &["gyro_1.y"])?;
+
+    assert_eq!(output, plain);
+
+    // Nor do the options which drop records or end parsing early.
+    let limited = log()
+        .data(0, &with_timestamp(0, &[0; 16]))
+        .builder()
+        .on_bad_timestamp(BadTimestampPolicy::Skip)
+        .max_records(0)
+        .recover_on_error(true)
+        .error_on_dropout(true)
+        .require_sorted(true)
+        .build()?;
+    let copied = strip_fields(limited, ULogWriter::new(Vec::new()), &["gyro_1.y"])?;
+
+    let records = ULogParserBuilder::new(copied.as_slice())
+        .build()?
+        .filter(|msg_res| matches!(msg_res, Ok(UlogMessage::LoggedData(_))))
+        .count();
+    assert_eq!(records, 2);
+
+    let expected = log().into_bytes();
+    assert_eq!(output.len(), expected.len() - 4 - "float y;".len());
+
+    Ok(())
+}
+
+#[test]
+fn test_strip_fields_fixture() -> Result<(), Box<dyn std::error::Error>> {
+    let parse = |bytes: &[u8]| -> Result<Vec<UlogMessage>, ULogError> {
+        ULogParserBuilder::new(bytes.to_vec().as_slice())
+            .include_timestamp(true)
+            .build()?
+            .collect()
+    };
+
+    let input = std::fs::read(SAMPLE_LOG)?;
+    let parser = ULogParserBuilder::new(input.as_slice()).build()?;
+    let output = strip_fields(
+        parser,
+        ULogWriter::new(Vec::new()),
+        &["vehicle_status.nav_state"],
+    )?;
+    assert!(output.len() < input.len());
+
+    let original = parse(&input)?;
+    let stripped = parse(&output)?;
+    assert_eq!(original.len(), stripped.len());
+
+    let mut checked = 0;
+    for (before, after) in original.iter().zip(&stripped) {
+        match (before, after) {
+            (UlogMessage::LoggedData(before), UlogMessage::LoggedData(after))
+                if before.data.name == "vehicle_status" =>
+            {
+                let mut expected = before.data.fields.clone();
+                expected.retain(|field| field.name != "nav_state");
+                // Compared as text, since some values are NaN.
+                assert_eq!(format!("{:?}", after.data.fields), format!("{expected:?}"));
+                assert_eq!(after.timestamp, before.timestamp);
+                checked += 1;
+            }
+            (UlogMessage::LoggedData(before), UlogMessage::LoggedData(after)) => {
+                assert_eq!(
+                    format!("{:?}", after.data.fields),
+                    format!("{:?}", before.data.fields)
+                );
+            }
+            _ => {}
+        }
+    }
+    assert!(checked > 0);
+
+    assert!(matches!(
+        strip_fields(
+            ULogParserBuilder::new(input.as_slice()).build()?,
+            ULogWriter::new(Vec::new()),
+            &["vehicle_status.timestamp"],
+        ),
+        Err(ULogError::InvalidConfiguration(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_backfills_header_timestamp() -> Result<(), ULogError> {
+    fn header_timestamp(bytes: &[u8]) -> Result<u64, ULogError> {
+        match ULogParserBuilder::new(bytes)
+            .include_header(true)
+            .build()?
+            .next()
+        {
+            Some(Ok(UlogMessage::Header(header))) => Ok(header.timestamp),
+            other => panic!("expected a header, got {other:?}"),
+        }
+    }
+
+    let log = |header_timestamp| {
+        SyntheticLog::new(header_timestamp)
+            .format("gyro:uint64_t timestamp;float x;")
+            .subscription(0, 0, "gyro")
+            .data(0, &with_timestamp(1_500, &1.0f32.to_le_bytes()))
+            .data(0, &with_timestamp(2_500, &2.0f32.to_le_bytes()))
+    };
+
+    for (input_timestamp, expected) in [(0, 1_500), (700, 700)] {
+        let messages = log(input_timestamp)
+            .builder()
+            .include_header(true)
+            .include_timestamp(true)
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut writer = ULogWriter::new(Vec::new()).backfill_header_timestamp(true);
+        for msg in &messages {
+            writer.write_message(msg)?;
+        }
+        let output = writer.finish()?;
+
+        assert_eq!(header_timestamp(&output)?, expected);
+
+        // Only the header differs.
+        let input = log(input_timestamp).into_bytes();
+        assert_eq!(output.len(), input.len());
+        assert_eq!(output[16..], input[16..]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_writer_backfills_header_timestamp_from_ignored() -> Result<(), ULogError> {
+    // Records outside the allow list have their `uint32_t` timestamp read from the raw bytes.
+    let record = |timestamp: u32, x: f32| [timestamp.to_le_bytes(), x.to_le_bytes()].concat();
+
+    let messages = SyntheticLog::new(0)
+        .format("legacy_status:uint32_t timestamp;float x;")
+        .subscription(0, 0, "legacy_status")
+        .data(0, &record(1_500, 1.0))
+        .builder()
+        .set_subscription_allow_list(Vec::<String>::new())
+        .drop_ignored(false)
+        .include_header(true)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(messages
+        .iter()
+        .any(|msg| matches!(msg, UlogMessage::Ignored { .. })));
+
+    let mut writer = ULogWriter::new(Vec::new()).backfill_header_timestamp(true);
+    for msg in &messages {
+        writer.write_message(msg)?;
+    }
+    let output = writer.finish()?;
+
+    match ULogParserBuilder::new(output.as_slice())
+        .include_header(true)
+        .build()?
+        .next()
+    {
+        Some(Ok(UlogMessage::Header(header))) => assert_eq!(header.timestamp, 1_500),
+        other => panic!("expected a header, got {other:?}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::fs::File;
+use std::io::BufReader;
+use yule_log::analysis::TopicCoverage;
+use yule_log::builder::ULogParserBuilder;
+
+fn open_log(name: &str) -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(format!(
+        "fixtures/test_data/input/{name}"
+    ))?))
+}
+
+#[test]
+fn test_bytes_per_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("trig_stats.ulg")?).build()?;
+    let bytes = parser.bytes_per_subscription()?;
+
+    assert_eq!(bytes.len(), 2);
+    assert_eq!(bytes["trigonometric"], 1600);
+    assert_eq!(bytes["statistical"], 1600);
+
+    Ok(())
+}
+
+#[test]
+fn test_bytes_per_subscription_multi_id() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("sample_log_small.ulg")?).build()?;
+    let bytes = parser.bytes_per_subscription()?;
+
+    assert_eq!(bytes.len(), 70);
+    assert_eq!(bytes.values().sum::<u64>(), 782_618);
+    assert_eq!(bytes["vehicle_status"], 714);
+    assert_eq!(bytes["actuator_outputs.00"], 4940);
+    assert_eq!(bytes["actuator_outputs.01"], 4940);
+
+    Ok(())
+}
+
+#[test]
+fn test_preview() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("trig_stats.ulg")?).build()?;
+    let preview = parser.preview()?;
+
+    let format_names: Vec<&str> = preview.formats.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(format_names, vec!["trigonometric", "statistical"]);
+
+    assert_eq!(preview.samples.len(), 2);
+    assert_eq!(preview.samples["trigonometric"].data.name, "trigonometric");
+    assert_eq!(preview.samples["statistical"].data.name, "statistical");
+
+    Ok(())
+}
+
+#[test]
+fn test_preview_stops_once_every_topic_has_a_sample() -> Result<(), Box<dyn std::error::Error>> {
+    let mut bytes = std::fs::read("fixtures/test_data/input/trig_stats.ulg")?;
+
+    // Append a data message for an undefined subscription, well after the first samples.  A full
+    // parse reports it, but the preview never reads that far.
+    bytes.extend_from_slice(&[2, 0, b'D', 0xFF, 0xFF]);
+
+    let full_parse = ULogParserBuilder::new(bytes.as_slice()).build()?;
+    assert!(full_parse.into_iter().any(|msg_res| msg_res.is_err()));
+
+    let preview = ULogParserBuilder::new(bytes.as_slice())
+        .build()?
+        .preview()?;
+    assert_eq!(preview.samples.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_coverage() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("trig_stats.ulg")?).build()?;
+    let coverage = parser.coverage()?;
+
+    assert_eq!(coverage.len(), 2);
+    assert_eq!(
+        coverage["trigonometric"],
+        TopicCoverage {
+            first_timestamp: 206_633_453_293,
+            last_timestamp: 206_634_642_073,
+            count: 100,
+        }
+    );
+    assert_eq!(
+        coverage["statistical"],
+        TopicCoverage {
+            first_timestamp: 206_633_453_304,
+            last_timestamp: 206_634_642_086,
+            count: 100,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_coverage_multi_id() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("sample_log_small.ulg")?).build()?;
+    let coverage = parser.coverage()?;
+
+    assert_eq!(
+        coverage["actuator_outputs.01"],
+        TopicCoverage {
+            first_timestamp: 20_329_151,
+            last_timestamp: 26_727_607,
+            count: 65,
+        }
+    );
+    assert_eq!(coverage["vehicle_status"].count, 14);
+
+    Ok(())
+}
+
+#[test]
+fn test_highest_rate_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_log("sample_log_small.ulg")?).build()?;
+    assert_eq!(
+        parser.highest_rate_subscription()?,
+        Some("actuator_controls_0".to_string())
+    );
+
+    // Both topics have 100 records, so the tie is broken by name.
+    let parser = ULogParserBuilder::new(open_log("trig_stats.ulg")?).build()?;
+    assert_eq!(
+        parser.highest_rate_subscription()?,
+        Some("statistical".to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+#[test]
+fn test_expired_deadline_times_out() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_sample_log()?)
+        .deadline(Instant::now())
+        .build()?;
+
+    let results: Vec<_> = parser.collect();
+
+    // The timeout ends the stream.
+    assert!(matches!(results.last(), Some(Err(ULogError::Timeout))));
+    assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_generous_deadline_parses_everything() -> Result<(), Box<dyn std::error::Error>> {
+    let parser = ULogParserBuilder::new(open_sample_log()?)
+        .deadline(Instant::now() + Duration::from_secs(3600))
+        .build()?;
+
+    for msg_res in parser {
+        msg_res?;
+    }
+
+    Ok(())
+}
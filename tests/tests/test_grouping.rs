@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::model::msg::UlogMessage;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+/// Returns the msg_id of each data message, in stream order.
+fn data_msg_ids(messages: &[UlogMessage]) -> Vec<u16> {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            UlogMessage::LoggedData(data) => Some(data.msg_id),
+            UlogMessage::Ignored {
+                msg_type: b'D',
+                message_contents,
+            } => Some(u16::from_le_bytes([
+                message_contents[0],
+                message_contents[1],
+            ])),
+            _ => None,
+        })
+        .collect()
+}
+
+fn assert_contiguous(msg_ids: &[u16]) {
+    let mut finished = HashSet::new();
+    for pair in msg_ids.windows(2) {
+        if pair[0] != pair[1] {
+            assert!(
+                finished.insert(pair[0]),
+                "msg_id {} is not contiguous",
+                pair[0]
+            );
+        }
+    }
+    assert!(!finished.contains(msg_ids.last().unwrap()));
+}
+
+#[test]
+fn test_group_by_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let ungrouped = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let grouped = ULogParserBuilder::new(open_sample_log()?)
+        .build()?
+        .group_by_subscription()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(grouped.len(), ungrouped.len());
+
+    let mut ungrouped_ids = data_msg_ids(&ungrouped);
+    let grouped_ids = data_msg_ids(&grouped);
+    assert_contiguous(&grouped_ids);
+
+    // Only the order changes.
+    let mut sorted_ids = grouped_ids.clone();
+    sorted_ids.sort_unstable();
+    ungrouped_ids.sort_unstable();
+    assert_eq!(sorted_ids, ungrouped_ids);
+
+    Ok(())
+}
+
+#[test]
+fn test_group_by_subscription_spilled_matches_buffered() -> Result<(), Box<dyn std::error::Error>> {
+    let build = || -> Result<_, Box<dyn std::error::Error>> {
+        Ok(ULogParserBuilder::new(open_sample_log()?)
+            .set_subscription_allow_list(vec![
+                "vehicle_status".to_string(),
+                "actuator_outputs".to_string(),
+            ])
+            .add_index_field(true)
+            .build()?)
+    };
+
+    let buffered = build()?
+        .group_by_subscription()?
+        .collect::<Result<Vec<_>, _>>()?;
+    let spilled = build()?
+        .group_by_subscription_spilled(tempfile::tempfile)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_contiguous(&data_msg_ids(&spilled));
+    assert_eq!(format!("{spilled:?}"), format!("{buffered:?}"));
+
+    Ok(())
+}
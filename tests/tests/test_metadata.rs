@@ -0,0 +1,94 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::model::msg::UlogMessage;
+
+fn open_sample_log() -> Result<BufReader<File>, std::io::Error> {
+    Ok(BufReader::new(File::open(
+        "fixtures/test_data/input/sample_log_small.ulg",
+    )?))
+}
+
+#[test]
+fn test_perf_counters() -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = ULogParserBuilder::new(open_sample_log()?).build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(parser.multi_info()["perf_counter_preflight"].len(), 89);
+
+    let counters = parser.perf_counters();
+    assert_eq!(counters.len(), 89);
+    assert_eq!(
+        counters[0],
+        (
+            "vehicle_imu: gyro data gap".to_string(),
+            "1 events".to_string()
+        )
+    );
+    assert!(counters
+        .iter()
+        .any(|(name, value)| name == "navigator" && value.starts_with("229 events")));
+
+    Ok(())
+}
+
+#[test]
+fn test_uuids() -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = ULogParserBuilder::new(open_sample_log()?).build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(
+        parser.sys_uuid().as_deref(),
+        Some("000600000000383638393239510d0035002d")
+    );
+    assert_eq!(parser.log_uuid(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_uuids_absent() -> Result<(), Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open("fixtures/test_data/input/trig_stats.ulg")?);
+    let mut parser = ULogParserBuilder::new(reader).build()?;
+
+    for msg_res in parser.by_ref() {
+        msg_res?;
+    }
+
+    assert_eq!(parser.sys_uuid(), None);
+    assert_eq!(parser.log_uuid(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_info_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = ULogParserBuilder::new(open_sample_log()?).build()?;
+
+    let mut expected = BTreeSet::new();
+    for msg_res in parser.by_ref() {
+        let key = match msg_res? {
+            UlogMessage::Info(info) => info.key,
+            UlogMessage::MultiInfo(multi_info) => multi_info.key,
+            _ => continue,
+        };
+        expected.insert(key);
+    }
+
+    let keys = parser.info_keys();
+    assert!(keys.contains(&"sys_uuid"));
+    assert!(keys.contains(&"perf_counter_preflight"));
+    assert_eq!(
+        keys,
+        expected.iter().map(String::as_str).collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
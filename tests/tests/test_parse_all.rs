@@ -0,0 +1,64 @@
+use std::fs;
+use std::io::Cursor;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const ULOG_HEADER_SIZE: usize = 16;
+const MSG_HEADER_SIZE: usize = 3;
+
+/// Returns the byte offsets of every message of the given type in a raw ULOG file.
+fn message_offsets(bytes: &[u8], msg_type: u8) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut pos = ULOG_HEADER_SIZE;
+
+    while pos + MSG_HEADER_SIZE <= bytes.len() {
+        let msg_size = u16::from_le_bytes([bytes[pos], bytes[pos + 1]]) as usize;
+        if bytes[pos + 2] == msg_type {
+            offsets.push(pos);
+        }
+        pos += MSG_HEADER_SIZE + msg_size;
+    }
+
+    offsets
+}
+
+#[test]
+fn test_parse_all_collects_messages_and_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut bytes = fs::read("fixtures/test_data/input/trig_stats.ulg")?;
+
+    // Corrupt a single LoggedData message by pointing it at a msg_id with no subscription.
+    let corrupt_offset = message_offsets(&bytes, b'D')[10];
+    bytes[corrupt_offset + MSG_HEADER_SIZE] = 0xFF;
+    bytes[corrupt_offset + MSG_HEADER_SIZE + 1] = 0xFF;
+
+    let parser = ULogParserBuilder::new(Cursor::new(bytes)).build()?;
+    let (messages, errors) = parser.parse_all();
+
+    let num_logged_data = messages
+        .iter()
+        .filter(|msg| matches!(msg, UlogMessage::LoggedData(_)))
+        .count();
+
+    assert_eq!(num_logged_data, 199);
+    assert_eq!(errors.len(), 1);
+
+    let (offset, err) = &errors[0];
+    assert_eq!(*offset, corrupt_offset as u64);
+    assert!(matches!(err, ULogError::ParseError(_)));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_all_clean_file_has_no_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = fs::read("fixtures/test_data/input/powers.ulg")?;
+
+    let parser = ULogParserBuilder::new(Cursor::new(bytes)).build()?;
+    let (messages, errors) = parser.parse_all();
+
+    assert!(errors.is_empty());
+    assert!(!messages.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::fs::File;
+use std::io::BufReader;
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+const SAMPLE_LOG: &str = "fixtures/test_data/input/sample_log_small.ulg";
+
+fn parse(range: Option<(u64, u64)>) -> Result<Vec<String>, ULogError> {
+    let mut parser = ULogParserBuilder::new(BufReader::new(File::open(SAMPLE_LOG)?))
+        .include_header(true)
+        .include_timestamp(true)
+        .include_padding(true)
+        .build()?;
+
+    if let Some((start, end)) = range {
+        parser = parser.parse_range(start, end)?;
+    }
+
+    parser
+        .map(|msg_res| msg_res.map(|msg: UlogMessage| format!("{msg:?}")))
+        .collect()
+}
+
+#[test]
+fn test_parse_range_union_is_whole_log() -> Result<(), Box<dyn std::error::Error>> {
+    let len = std::fs::metadata(SAMPLE_LOG)?.len();
+    let whole = parse(None)?;
+
+    // Split at arbitrary offsets, which do not fall on message boundaries.
+    let splits = [0, 1_000, len / 3, len / 2 + 7, len];
+    let mut union = Vec::new();
+    for range in splits.windows(2) {
+        let part = parse(Some((range[0], range[1])))?;
+        assert!(!part.is_empty());
+        union.extend(part);
+    }
+
+    assert_eq!(union.len(), whole.len());
+    assert!(union == whole);
+
+    Ok(())
+}
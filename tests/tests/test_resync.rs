@@ -0,0 +1,66 @@
+use yule_log::builder::ULogParserBuilder;
+use yule_log::errors::ULogError;
+use yule_log::model::msg::UlogMessage;
+
+fn count_logged_data(messages: &[UlogMessage]) -> usize {
+    messages
+        .iter()
+        .filter(|msg| matches!(msg, UlogMessage::LoggedData(_)))
+        .count()
+}
+
+/// Simulates chunks of a transfer going through a text mode channel, which turns every `\n` into
+/// `\r\n` within the given byte ranges.
+fn crlf_mangle(bytes: &[u8], ranges: &[std::ops::Range<usize>]) -> Vec<u8> {
+    let mut mangled = Vec::with_capacity(bytes.len());
+    for (offset, &byte) in bytes.iter().enumerate() {
+        if byte == b'\n' && ranges.iter().any(|range| range.contains(&offset)) {
+            mangled.push(b'\r');
+        }
+        mangled.push(byte);
+    }
+    mangled
+}
+
+#[test]
+fn test_resync_recovers_crlf_mangled_log() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read("fixtures/test_data/input/sample_log_small.ulg")?;
+
+    let (clean, clean_errors) = ULogParserBuilder::new(bytes.as_slice())
+        .build()?
+        .parse_all();
+    assert!(clean_errors.is_empty());
+    let total = count_logged_data(&clean);
+
+    // Leave the definitions intact, so the formats and subscriptions are known.
+    let chunk = |fraction: f64| {
+        let start = (bytes.len() as f64 * fraction) as usize;
+        start..start + 4096
+    };
+    let mangled = crlf_mangle(&bytes, &[chunk(0.3), chunk(0.55), chunk(0.8)]);
+
+    let (without_resync, _) = ULogParserBuilder::new(mangled.as_slice())
+        .build()?
+        .parse_all();
+
+    let (with_resync, errors) = ULogParserBuilder::new(mangled.as_slice())
+        .resync_on_error(true)
+        .build()?
+        .parse_all();
+
+    let resyncs: Vec<u64> = errors
+        .iter()
+        .filter_map(|(_, err)| match err {
+            ULogError::Resynchronized { skipped_bytes } => Some(*skipped_bytes),
+            _ => None,
+        })
+        .collect();
+
+    let recovered = count_logged_data(&with_resync);
+
+    assert!(!resyncs.is_empty());
+    assert!(recovered > count_logged_data(&without_resync));
+    assert!(recovered > total * 3 / 4);
+
+    Ok(())
+}
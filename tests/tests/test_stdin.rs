@@ -0,0 +1,75 @@
+use std::env;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use yule_log::builder::ULogParserBuilder;
+use yule_log::model::msg::UlogMessage;
+
+#[test]
+fn test_parse_from_piped_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    // A Cursor over the raw file stands in for stdin: both are plain `Read` sources
+    // that cannot be memory mapped or seeked by the parser.
+    let bytes = fs::read("fixtures/test_data/input/trig_stats.ulg")?;
+    let parser = ULogParserBuilder::new(Cursor::new(bytes)).build()?;
+
+    let mut num_logged_data = 0;
+    for msg_res in parser {
+        if let UlogMessage::LoggedData(_) = msg_res? {
+            num_logged_data += 1;
+        }
+    }
+
+    assert_eq!(num_logged_data, 200);
+
+    Ok(())
+}
+
+/// Set in the child process of `test_from_stdin_parses_piped_bytes`, which reads its stdin.
+const STDIN_CHILD_ENV: &str = "YULE_LOG_TEST_STDIN_CHILD";
+
+#[test]
+fn test_from_stdin_parses_piped_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    if env::var_os(STDIN_CHILD_ENV).is_some() {
+        let parser = ULogParserBuilder::from_stdin().build()?;
+
+        let mut num_logged_data = 0;
+        for msg_res in parser {
+            if let UlogMessage::LoggedData(_) = msg_res? {
+                num_logged_data += 1;
+            }
+        }
+
+        assert_eq!(num_logged_data, 200);
+        return Ok(());
+    }
+
+    // Runs this test again in a child process, with the log piped into its stdin.
+    let bytes = fs::read("fixtures/test_data/input/trig_stats.ulg")?;
+    let mut child = Command::new(env::current_exe()?)
+        .args([
+            "--exact",
+            "test_from_stdin_parses_piped_bytes",
+            "--test-threads=1",
+        ])
+        .env(STDIN_CHILD_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(&bytes)?;
+
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("1 passed"), "{stdout}");
+
+    Ok(())
+}